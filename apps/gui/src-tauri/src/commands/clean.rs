@@ -0,0 +1,194 @@
+// Name cleaning commands for tidy-app GUI
+// Command names use snake_case per architecture requirements
+//
+// A one-click "clean names" pass over a folder: strips dates and counters,
+// removes diacritics, applies a case style and sanitizes the result. No AI,
+// templates or metadata are involved, so the preview is instant.
+
+use serde::Deserialize;
+use std::path::Path;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::rename::{
+    clean_filename, finalize_preview, normalize_case, sanitize_filename, strip_diacritics,
+    CaseStyle, FileActionType, RenameError, RenamePreview, RenameProposal, RenameStatus,
+    ReorganizationMode,
+};
+use super::scanner::{scan_folder, FileInfo, ScanOptions};
+use super::security::join_proposed_path;
+
+// =============================================================================
+// Types
+// =============================================================================
+
+/// Options for cleaning the names of a folder
+#[derive(Debug, Clone, Deserialize, Default, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct CleanFolderOptions {
+    /// Case style for the cleaned names (default: kebab-case)
+    #[serde(default)]
+    pub case_style: Option<CaseStyle>,
+    /// Keep accented letters instead of converting them to ASCII (default: false)
+    #[serde(default)]
+    pub keep_diacritics: bool,
+    /// Include files in subdirectories (default: false)
+    #[serde(default)]
+    pub recursive: bool,
+}
+
+// =============================================================================
+// Cleaning
+// =============================================================================
+
+/// Compute the cleaned name of a file, extension lowercased
+fn clean_name(file: &FileInfo, options: &CleanFolderOptions) -> String {
+    let style = options.case_style.clone().unwrap_or(CaseStyle::KebabCase);
+    let prepare = |name: &str| {
+        if options.keep_diacritics {
+            name.to_string()
+        } else {
+            strip_diacritics(name)
+        }
+    };
+
+    let mut stem = normalize_case(&prepare(&clean_filename(&file.name)), &style);
+    // Names made only of a date or counter: keep them rather than emptying them
+    if stem.trim().is_empty() {
+        stem = normalize_case(&prepare(&file.name), &style);
+    }
+
+    let name = if file.extension.is_empty() {
+        stem
+    } else {
+        format!("{}.{}", stem, file.extension.to_lowercase())
+    };
+    sanitize_filename(&name, '_').sanitized
+}
+
+fn build_clean_proposal(file: &FileInfo, options: &CleanFolderOptions) -> RenameProposal {
+    let proposed_name = clean_name(file, options);
+
+    let proposed_path = join_proposed_path(Path::new(&file.path).parent(), &proposed_name);
+
+    let (status, action_type) = if proposed_name == file.full_name {
+        (RenameStatus::NoChange, FileActionType::NoChange)
+    } else {
+        (RenameStatus::Ready, FileActionType::Rename)
+    };
+
+    RenameProposal {
+        id: Uuid::new_v4().to_string(),
+        original_path: file.path.clone(),
+        original_name: file.full_name.clone(),
+        proposed_name,
+        proposed_path,
+        status,
+        issues: vec![],
+        metadata_sources: None,
+        is_folder_move: false,
+        destination_folder: None,
+        action_type,
+        conflict: None,
+    }
+}
+
+// =============================================================================
+// Tauri Commands
+// =============================================================================
+
+/// Propose cleaned names for every file in a folder
+///
+/// Strips dates and counters, converts accented letters to ASCII, applies a
+/// case style (kebab-case by default) and sanitizes each name. Hidden files
+/// are left alone. Names that end up identical are reported as conflicts.
+///
+/// Command name: clean_folder (snake_case per architecture)
+#[tauri::command]
+pub async fn clean_folder(
+    path: String,
+    options: Option<CleanFolderOptions>,
+) -> Result<RenamePreview, RenameError> {
+    let options = options.unwrap_or_default();
+    let scan_options = ScanOptions {
+        recursive: options.recursive,
+        skip_hidden: true,
+        ..Default::default()
+    };
+    let scan = scan_folder(path, Some(scan_options))
+        .await
+        .map_err(|e| RenameError::PreviewFailed(e.to_string()))?;
+
+    let proposals = scan
+        .files
+        .iter()
+        .map(|file| build_clean_proposal(file, &options))
+        .collect();
+
+    Ok(finalize_preview(proposals, String::new(), ReorganizationMode::RenameOnly))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn proposed_name(preview: &RenamePreview, original: &str) -> String {
+        preview
+            .proposals
+            .iter()
+            .find(|p| p.original_name == original)
+            .map(|p| p.proposed_name.clone())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_clean_folder_proposes_clean_kebab_names() {
+        let dir = TempDir::new().unwrap();
+        for name in ["2024-01-15_IMG (1).JPG", "Été à la Plage_003.png", "notes.txt"] {
+            fs::write(dir.path().join(name), b"x").unwrap();
+        }
+
+        let path = dir.path().to_string_lossy().to_string();
+        let preview = clean_folder(path, None).await.unwrap();
+
+        assert_eq!(preview.proposals.len(), 3);
+        assert_eq!(proposed_name(&preview, "2024-01-15_IMG (1).JPG"), "img.jpg");
+        assert_eq!(proposed_name(&preview, "Été à la Plage_003.png"), "ete-a-la-plage.png");
+        assert_eq!(proposed_name(&preview, "notes.txt"), "notes.txt");
+        assert_eq!(preview.summary.no_change, 1);
+    }
+
+    #[tokio::test]
+    async fn test_clean_folder_options() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("Café Menu 2024-03-01.PDF"), b"x").unwrap();
+
+        let options = CleanFolderOptions {
+            case_style: Some(CaseStyle::SnakeCase),
+            keep_diacritics: true,
+            recursive: false,
+        };
+        let path = dir.path().to_string_lossy().to_string();
+        let preview = clean_folder(path, Some(options)).await.unwrap();
+
+        assert_eq!(proposed_name(&preview, "Café Menu 2024-03-01.PDF"), "café_menu.pdf");
+    }
+
+    #[tokio::test]
+    async fn test_clean_folder_reports_identical_names_as_conflicts() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("IMG_001.jpg"), b"a").unwrap();
+        fs::write(dir.path().join("IMG_002.jpg"), b"b").unwrap();
+
+        let path = dir.path().to_string_lossy().to_string();
+        let preview = clean_folder(path, None).await.unwrap();
+
+        assert!(preview
+            .proposals
+            .iter()
+            .all(|p| p.status == RenameStatus::Conflict));
+    }
+}
@@ -6,8 +6,10 @@
 use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
-use std::sync::RwLock;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
 use thiserror::Error;
 use uuid::Uuid;
 
@@ -18,6 +20,10 @@ use uuid::Uuid;
 lazy_static! {
     /// In-memory config cache to avoid disk reads on every get_config() call
     static ref CONFIG_CACHE: RwLock<Option<AppConfig>> = RwLock::new(None);
+
+    /// Coalesces rapid save_config() calls into a single disk write
+    static ref CONFIG_WRITER: DebouncedConfigWriter =
+        DebouncedConfigWriter::new(get_config_path(), SAVE_DEBOUNCE);
 }
 
 /// Clear the config cache (used after saves and resets)
@@ -39,6 +45,119 @@ fn cache_config(config: &AppConfig) {
     }
 }
 
+// =============================================================================
+// Debounced Config Writer
+// =============================================================================
+
+/// Saves within this window of each other result in a single disk write
+const SAVE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+struct WriterState {
+    path: PathBuf,
+    delay: Duration,
+    /// Latest config not yet written to disk
+    pending: Mutex<Option<AppConfig>>,
+    /// Bumped on every schedule; a timer only writes if no newer save arrived
+    generation: AtomicU64,
+    /// Serializes disk writes so an older config never overwrites a newer one
+    write_lock: Mutex<()>,
+    /// Number of disk writes performed
+    writes: AtomicUsize,
+    /// Error of the last background write, until reported or superseded
+    last_error: Mutex<Option<String>>,
+}
+
+/// Delays config writes until saves stop arriving for a short window
+///
+/// Each scheduled save replaces the pending config and restarts the window,
+/// so a burst of settings edits costs one write instead of one per edit.
+#[derive(Clone)]
+struct DebouncedConfigWriter {
+    state: Arc<WriterState>,
+}
+
+impl DebouncedConfigWriter {
+    fn new(path: PathBuf, delay: Duration) -> Self {
+        Self {
+            state: Arc::new(WriterState {
+                path,
+                delay,
+                pending: Mutex::new(None),
+                generation: AtomicU64::new(0),
+                write_lock: Mutex::new(()),
+                writes: AtomicUsize::new(0),
+                last_error: Mutex::new(None),
+            }),
+        }
+    }
+
+    /// Queue a config for writing once the debounce window has passed
+    fn schedule(&self, config: AppConfig) {
+        if let Ok(mut pending) = self.state.pending.lock() {
+            *pending = Some(config);
+        }
+        let generation = self.state.generation.fetch_add(1, Ordering::SeqCst) + 1;
+
+        let writer = self.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(writer.state.delay).await;
+            if writer.state.generation.load(Ordering::SeqCst) != generation {
+                // A newer save restarted the window
+                return;
+            }
+            if let Err(e) = writer.flush() {
+                eprintln!("Warning: Failed to save config: {}", e);
+                if let Ok(mut last_error) = writer.state.last_error.lock() {
+                    *last_error = Some(e.to_string());
+                }
+            }
+        });
+    }
+
+    /// Write the pending config now, if any
+    ///
+    /// A config that fails to write stays pending (unless a newer one was
+    /// scheduled meanwhile), so the next flush retries it.
+    fn flush(&self) -> Result<(), ConfigError> {
+        let _guard = self.state.write_lock.lock().unwrap_or_else(|p| p.into_inner());
+        let pending = self.state.pending.lock().ok().and_then(|mut p| p.take());
+
+        let Some(config) = pending else {
+            return Ok(());
+        };
+        self.state.writes.fetch_add(1, Ordering::SeqCst);
+        match write_config_file(&config, &self.state.path) {
+            Ok(()) => {
+                if let Ok(mut last_error) = self.state.last_error.lock() {
+                    *last_error = None;
+                }
+                Ok(())
+            }
+            Err(e) => {
+                if let Ok(mut pending) = self.state.pending.lock() {
+                    pending.get_or_insert(config);
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Take the error of a failed background write, if any
+    fn take_error(&self) -> Option<ConfigError> {
+        let error = self.state.last_error.lock().ok()?.take()?;
+        Some(ConfigError::WriteError(error))
+    }
+
+    /// Drop the pending config without writing it
+    fn discard(&self) {
+        let _guard = self.state.write_lock.lock().unwrap_or_else(|p| p.into_inner());
+        if let Ok(mut pending) = self.state.pending.lock() {
+            *pending = None;
+        }
+        self.state.generation.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
 // =============================================================================
 // Error Types
 // =============================================================================
@@ -151,12 +270,34 @@ pub struct Preferences {
     /// Case normalization style for filenames (default: kebab-case)
     #[serde(default)]
     pub case_normalization: CaseStyle,
+    /// Scans, duplicate searches and analyses that may run at once; read when
+    /// the app starts (default: 8)
+    #[serde(default = "default_max_active_sessions")]
+    pub max_active_sessions: usize,
+    /// Organize previews warn when files would be placed more than this many
+    /// folder levels deep (default: 3)
+    #[serde(default = "default_max_destination_depth")]
+    pub max_destination_depth: usize,
 }
 
+/// Default cap on sessions running at once
+pub const DEFAULT_MAX_ACTIVE_SESSIONS: usize = 8;
+
+/// Default destination folder depth above which previews warn
+pub const DEFAULT_MAX_DESTINATION_DEPTH: usize = 3;
+
 fn default_true() -> bool {
     true
 }
 
+fn default_max_active_sessions() -> usize {
+    DEFAULT_MAX_ACTIVE_SESSIONS
+}
+
+fn default_max_destination_depth() -> usize {
+    DEFAULT_MAX_DESTINATION_DEPTH
+}
+
 impl Default for Preferences {
     fn default() -> Self {
         Preferences {
@@ -165,6 +306,8 @@ impl Default for Preferences {
             confirm_before_apply: true,
             recursive_scan: false,
             case_normalization: CaseStyle::KebabCase,
+            max_active_sessions: DEFAULT_MAX_ACTIVE_SESSIONS,
+            max_destination_depth: DEFAULT_MAX_DESTINATION_DEPTH,
         }
     }
 }
@@ -207,6 +350,45 @@ pub enum OfflineMode {
     Disabled,
 }
 
+/// Cache key strategy for LLM analysis results
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum CacheKeyStrategy {
+    /// Hash file contents (survives touches that leave the bytes unchanged)
+    Content,
+    /// Hash path, size and modified time (fast, invalidated by any write)
+    Metadata,
+}
+
+/// Cache key strategy per file type
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheKeyConfig {
+    /// Strategy for text files
+    #[serde(default = "default_text_cache_key")]
+    pub text: CacheKeyStrategy,
+    /// Strategy for images
+    #[serde(default = "default_image_cache_key")]
+    pub images: CacheKeyStrategy,
+}
+
+impl Default for CacheKeyConfig {
+    fn default() -> Self {
+        CacheKeyConfig {
+            text: default_text_cache_key(),
+            images: default_image_cache_key(),
+        }
+    }
+}
+
+fn default_text_cache_key() -> CacheKeyStrategy {
+    CacheKeyStrategy::Content
+}
+
+fn default_image_cache_key() -> CacheKeyStrategy {
+    CacheKeyStrategy::Metadata
+}
+
 /// LLM provider type
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
 #[serde(rename_all = "lowercase")]
@@ -257,6 +439,74 @@ impl Default for OpenAiConfig {
     }
 }
 
+/// Extra folder-name keywords used to pick relevant folders for each file type
+/// These extend the built-in multi-language keyword sets
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderKeywords {
+    /// Keywords for image folders
+    #[serde(default)]
+    pub images: Vec<String>,
+    /// Keywords for document folders
+    #[serde(default)]
+    pub documents: Vec<String>,
+    /// Keywords for code folders
+    #[serde(default)]
+    pub code: Vec<String>,
+}
+
+fn default_adaptive_min_batch_size() -> usize {
+    200
+}
+
+fn default_adaptive_min_populated_folders() -> usize {
+    8
+}
+
+fn default_adaptive_max_depth() -> usize {
+    3
+}
+
+fn default_adaptive_min_files_ratio() -> f32 {
+    0.01
+}
+
+/// Adaptive folder consolidation for large, well-structured batches
+///
+/// When enabled and a batch is large with many well-populated folders,
+/// consolidation keeps deeper folders and requires more files per folder.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdaptiveConsolidationConfig {
+    /// Whether adaptive consolidation is enabled (default: false)
+    #[serde(default)]
+    pub enabled: bool,
+    /// Minimum number of suggestions with folders before adapting
+    #[serde(default = "default_adaptive_min_batch_size")]
+    pub min_batch_size: usize,
+    /// Minimum number of distinct folders meeting the scaled file threshold
+    #[serde(default = "default_adaptive_min_populated_folders")]
+    pub min_populated_folders: usize,
+    /// Maximum folder depth when adapting
+    #[serde(default = "default_adaptive_max_depth")]
+    pub max_depth: usize,
+    /// Minimum files per folder as a fraction of the batch (never below the standard minimum)
+    #[serde(default = "default_adaptive_min_files_ratio")]
+    pub min_files_ratio: f32,
+}
+
+impl Default for AdaptiveConsolidationConfig {
+    fn default() -> Self {
+        AdaptiveConsolidationConfig {
+            enabled: false,
+            min_batch_size: default_adaptive_min_batch_size(),
+            min_populated_folders: default_adaptive_min_populated_folders(),
+            max_depth: default_adaptive_max_depth(),
+            min_files_ratio: default_adaptive_min_files_ratio(),
+        }
+    }
+}
+
 /// File type configuration for LLM analysis
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -302,6 +552,18 @@ fn default_health_timeout() -> u64 {
     5000
 }
 
+fn default_keep_original_threshold() -> f32 {
+    0.7
+}
+
+fn default_temperature() -> f32 {
+    0.3
+}
+
+fn default_max_tokens() -> u32 {
+    500
+}
+
 /// Complete Ollama configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -312,6 +574,10 @@ pub struct OllamaConfig {
     /// Which LLM provider to use
     #[serde(default)]
     pub provider: LlmProvider,
+    /// Provider to retry a file with when the primary one fails with an
+    /// authentication, rate limit or connection error (default: none)
+    #[serde(default)]
+    pub fallback_provider: Option<LlmProvider>,
     /// Ollama API base URL
     #[serde(default = "default_ollama_url")]
     pub base_url: String,
@@ -327,6 +593,9 @@ pub struct OllamaConfig {
     /// Enable vision model analysis
     #[serde(default)]
     pub vision_enabled: bool,
+    /// Analyze videos through their embedded thumbnail (requires vision_enabled)
+    #[serde(default)]
+    pub video_analysis_enabled: bool,
     /// Skip images with EXIF metadata
     #[serde(default = "default_true")]
     pub skip_images_with_exif: bool,
@@ -342,6 +611,39 @@ pub struct OllamaConfig {
     /// OpenAI configuration (used when provider is 'openai')
     #[serde(default)]
     pub openai: OpenAiConfig,
+    /// Keep well-named files unchanged when AI confidence is below this value (0.0 disables)
+    #[serde(default = "default_keep_original_threshold")]
+    pub keep_original_confidence_threshold: f32,
+    /// Suggestions below this confidence are hidden from the results view
+    /// (0.0-1.0, 0.0 shows everything); the analysis data is not changed
+    #[serde(default)]
+    pub display_confidence_floor: f32,
+    /// How analysis results are keyed in the cache, per file type
+    #[serde(default)]
+    pub cache_keys: CacheKeyConfig,
+    /// Additional folder keywords for matching existing folders to file types
+    #[serde(default)]
+    pub folder_keywords: FolderKeywords,
+    /// Sampling temperature for naming requests (0.0-2.0, higher is more creative)
+    #[serde(default = "default_temperature")]
+    pub temperature: f32,
+    /// Maximum tokens generated per request (50-4096)
+    #[serde(default = "default_max_tokens")]
+    pub max_tokens: u32,
+    /// Adaptive folder consolidation for large batches (opt-in)
+    #[serde(default)]
+    pub adaptive_consolidation: AdaptiveConsolidationConfig,
+    /// Terms whose casing is forced in suggested names (e.g., "NASA", "iPhone")
+    #[serde(default)]
+    pub name_dictionary: Vec<String>,
+    /// Extensions analyzed as text besides the built-in ones (e.g., "prompt",
+    /// "mdx"), stored lowercase without the leading dot
+    #[serde(default)]
+    pub extra_text_extensions: Vec<String>,
+    /// Give single-file analysis a reserved request slot so it doesn't wait
+    /// behind a running batch (default: true)
+    #[serde(default = "default_true")]
+    pub interactive_priority: bool,
 }
 
 impl Default for OllamaConfig {
@@ -349,16 +651,28 @@ impl Default for OllamaConfig {
         OllamaConfig {
             enabled: false,
             provider: LlmProvider::Ollama,
+            fallback_provider: None,
             base_url: default_ollama_url(),
             timeout: default_timeout(),
             models: OllamaModelsConfig::default(),
             file_types: LlmFileTypes::default(),
             vision_enabled: false,
+            video_analysis_enabled: false,
             skip_images_with_exif: true,
             max_image_size: default_max_image_size(),
             offline_mode: OfflineMode::Auto,
             health_check_timeout: default_health_timeout(),
             openai: OpenAiConfig::default(),
+            keep_original_confidence_threshold: default_keep_original_threshold(),
+            display_confidence_floor: 0.0,
+            cache_keys: CacheKeyConfig::default(),
+            folder_keywords: FolderKeywords::default(),
+            temperature: default_temperature(),
+            max_tokens: default_max_tokens(),
+            adaptive_consolidation: AdaptiveConsolidationConfig::default(),
+            name_dictionary: Vec::new(),
+            extra_text_extensions: Vec::new(),
+            interactive_priority: true,
         }
     }
 }
@@ -413,6 +727,10 @@ pub struct AppConfig {
     /// Ollama/LLM configuration
     #[serde(default)]
     pub ollama: OllamaConfig,
+    /// Path prefixes the app refuses to rename or move files in or into
+    /// (e.g., an archive folder that must stay untouched)
+    #[serde(default)]
+    pub protected_paths: Vec<String>,
 }
 
 // =============================================================================
@@ -547,6 +865,7 @@ fn default_config() -> AppConfig {
         preferences: Preferences::default(),
         recent_folders: Vec::new(),
         ollama: OllamaConfig::default(),
+        protected_paths: Vec::new(),
     }
 }
 
@@ -560,6 +879,35 @@ impl Default for AppConfig {
 // Config Validation (SEC-005)
 // =============================================================================
 
+/// Maximum number of protected path prefixes
+const MAX_PROTECTED_PATHS: usize = 100;
+
+/// Highest configurable cap on sessions running at once
+const MAX_ACTIVE_SESSIONS_LIMIT: usize = 64;
+
+/// Whether a path is absolute on any platform ("/x", "C:\x", "\\server\share")
+fn is_absolute_path(path: &str) -> bool {
+    let bytes = path.as_bytes();
+    path.starts_with('/')
+        || path.starts_with("\\\\")
+        || (bytes.len() >= 3
+            && bytes[0].is_ascii_alphabetic()
+            && bytes[1] == b':'
+            && (bytes[2] == b'\\' || bytes[2] == b'/'))
+}
+
+/// Normalize an extension to lowercase without the leading dot (".MDX" -> "mdx")
+fn normalize_extension(extension: &str) -> String {
+    extension.trim().trim_start_matches('.').to_lowercase()
+}
+
+/// Normalize the extra text extensions in place
+fn normalize_extra_text_extensions(config: &mut AppConfig) {
+    for extension in &mut config.ollama.extra_text_extensions {
+        *extension = normalize_extension(extension);
+    }
+}
+
 /// Validate configuration values for integrity and security
 fn validate_config(config: &AppConfig) -> Result<(), ConfigError> {
     // Validate version
@@ -617,6 +965,28 @@ fn validate_config(config: &AppConfig) -> Result<(), ConfigError> {
         ));
     }
 
+    // Validate generation parameters
+    if !(0.0..=2.0).contains(&config.ollama.temperature) {
+        return Err(ConfigError::ParseError(
+            "LLM temperature must be between 0.0 and 2.0".to_string()
+        ));
+    }
+    if !(50..=4096).contains(&config.ollama.max_tokens) {
+        return Err(ConfigError::ParseError(
+            "LLM max tokens must be between 50 and 4096".to_string()
+        ));
+    }
+    if !(0.0..=1.0).contains(&config.ollama.display_confidence_floor) {
+        return Err(ConfigError::ParseError(
+            "Display confidence floor must be between 0.0 and 1.0".to_string()
+        ));
+    }
+    if !(0.0..=1.0).contains(&config.ollama.keep_original_confidence_threshold) {
+        return Err(ConfigError::ParseError(
+            "Keep-original confidence threshold must be between 0.0 and 1.0".to_string()
+        ));
+    }
+
     // Validate base URL format
     if !config.ollama.base_url.starts_with("http://") && !config.ollama.base_url.starts_with("https://") {
         return Err(ConfigError::ParseError(
@@ -631,6 +1001,51 @@ fn validate_config(config: &AppConfig) -> Result<(), ConfigError> {
         ));
     }
 
+    // Validate the session cap: zero would refuse every scan
+    if !(1..=MAX_ACTIVE_SESSIONS_LIMIT).contains(&config.preferences.max_active_sessions) {
+        return Err(ConfigError::ParseError(
+            format!("Max active sessions must be between 1 and {}", MAX_ACTIVE_SESSIONS_LIMIT)
+        ));
+    }
+
+    // Validate protected paths: absolute, so they can't match by accident
+    if config.protected_paths.len() > MAX_PROTECTED_PATHS {
+        return Err(ConfigError::ParseError(
+            format!("Too many protected paths (max {})", MAX_PROTECTED_PATHS)
+        ));
+    }
+    for path in &config.protected_paths {
+        let trimmed = path.trim();
+        if trimmed.is_empty() {
+            return Err(ConfigError::ParseError("Protected path must not be empty".to_string()));
+        }
+        if !is_absolute_path(trimmed) {
+            return Err(ConfigError::ParseError(
+                format!("Protected path '{}' must be absolute", path)
+            ));
+        }
+    }
+
+    // Validate extra text extensions: normalized, single extensions
+    for extension in &config.ollama.extra_text_extensions {
+        if extension.is_empty() {
+            return Err(ConfigError::ParseError(
+                "Extra text extensions must not be empty".to_string()
+            ));
+        }
+        if *extension != normalize_extension(extension)
+            || extension.contains(['.', '/', '\\'])
+            || extension.contains(char::is_whitespace)
+        {
+            return Err(ConfigError::ParseError(
+                format!(
+                    "Extra text extension '{}' must be a lowercase extension without a dot",
+                    extension
+                )
+            ));
+        }
+    }
+
     // Validate recent folders count (prevent memory bloat)
     if config.recent_folders.len() > 100 {
         return Err(ConfigError::ParseError(
@@ -641,6 +1056,117 @@ fn validate_config(config: &AppConfig) -> Result<(), ConfigError> {
     Ok(())
 }
 
+// =============================================================================
+// Environment Overrides
+// =============================================================================
+
+/// Environment variable holding an OpenAI API key that overrides the stored one
+pub const OPENAI_API_KEY_ENV: &str = "TIDY_OPENAI_API_KEY";
+
+/// Config values that can be overridden by an environment variable
+#[derive(Debug, Clone, Copy)]
+enum EnvOverride {
+    LlmEnabled,
+    LlmProvider,
+    OllamaUrl,
+    OpenAiApiKey,
+    OpenAiUrl,
+    OpenAiModel,
+}
+
+/// Environment variables read at load time, for headless/CI use
+///
+/// Precedence is env > file > defaults. Overridden values are never persisted.
+const ENV_OVERRIDES: &[(&str, EnvOverride)] = &[
+    ("TIDY_LLM_ENABLED", EnvOverride::LlmEnabled),
+    ("TIDY_LLM_PROVIDER", EnvOverride::LlmProvider),
+    ("TIDY_OLLAMA_URL", EnvOverride::OllamaUrl),
+    (OPENAI_API_KEY_ENV, EnvOverride::OpenAiApiKey),
+    ("TIDY_OPENAI_URL", EnvOverride::OpenAiUrl),
+    ("TIDY_OPENAI_MODEL", EnvOverride::OpenAiModel),
+];
+
+impl EnvOverride {
+    /// Set the value from the environment; returns false if it can't be parsed
+    fn apply(self, config: &mut AppConfig, value: &str) -> bool {
+        let ollama = &mut config.ollama;
+        match self {
+            EnvOverride::LlmEnabled => match value.to_lowercase().as_str() {
+                "1" | "true" | "yes" => ollama.enabled = true,
+                "0" | "false" | "no" => ollama.enabled = false,
+                _ => return false,
+            },
+            EnvOverride::LlmProvider => match value.to_lowercase().as_str() {
+                "ollama" => ollama.provider = LlmProvider::Ollama,
+                "openai" => ollama.provider = LlmProvider::Openai,
+                _ => return false,
+            },
+            EnvOverride::OllamaUrl => ollama.base_url = value.to_string(),
+            EnvOverride::OpenAiApiKey => ollama.openai.api_key = value.to_string(),
+            EnvOverride::OpenAiUrl => ollama.openai.base_url = value.to_string(),
+            EnvOverride::OpenAiModel => ollama.openai.model = value.to_string(),
+        }
+        true
+    }
+
+    /// Copy the value from `source`
+    fn restore(self, config: &mut AppConfig, source: &AppConfig) {
+        let (ollama, source) = (&mut config.ollama, &source.ollama);
+        match self {
+            EnvOverride::LlmEnabled => ollama.enabled = source.enabled,
+            EnvOverride::LlmProvider => ollama.provider = source.provider.clone(),
+            EnvOverride::OllamaUrl => ollama.base_url = source.base_url.clone(),
+            EnvOverride::OpenAiApiKey => ollama.openai.api_key = source.openai.api_key.clone(),
+            EnvOverride::OpenAiUrl => ollama.openai.base_url = source.openai.base_url.clone(),
+            EnvOverride::OpenAiModel => ollama.openai.model = source.openai.model.clone(),
+        }
+    }
+}
+
+/// Read a non-empty environment variable
+pub fn env_var(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|value| !value.trim().is_empty())
+}
+
+/// Apply environment overrides on top of a loaded config
+///
+/// Unparseable values are ignored with a warning. If the result fails
+/// validation, the config is returned without overrides.
+fn apply_env_overrides(config: AppConfig, env: impl Fn(&str) -> Option<String>) -> AppConfig {
+    let mut overridden = config.clone();
+    for (name, field) in ENV_OVERRIDES {
+        if let Some(value) = env(name) {
+            if !field.apply(&mut overridden, value.trim()) {
+                eprintln!("Warning: Ignoring invalid value for {}", name);
+            }
+        }
+    }
+
+    match validate_config(&overridden) {
+        Ok(()) => overridden,
+        Err(e) => {
+            eprintln!("Warning: Ignoring environment overrides: {}", e);
+            config
+        }
+    }
+}
+
+/// Replace values overridden by the environment with their file values
+///
+/// Keeps env-sourced values (API keys in particular) out of the saved file.
+fn strip_env_overrides(
+    mut config: AppConfig,
+    file_config: &AppConfig,
+    env: impl Fn(&str) -> Option<String>,
+) -> AppConfig {
+    for (name, field) in ENV_OVERRIDES {
+        if env(name).is_some() {
+            field.restore(&mut config, file_config);
+        }
+    }
+    config
+}
+
 // =============================================================================
 // Path Utilities
 // =============================================================================
@@ -673,9 +1199,18 @@ fn get_config_path() -> PathBuf {
 /// - Config file is invalid JSON
 /// - Config file fails validation
 ///
+/// `TIDY_*` environment variables then override LLM settings (env > file >
+/// defaults); see `ENV_OVERRIDES`. Overridden values are not persisted.
+///
 /// Command name: get_config (snake_case per architecture)
 #[tauri::command]
 pub async fn get_config() -> Result<AppConfig, ConfigError> {
+    let config = load_file_config().await?;
+    Ok(apply_env_overrides(config, env_var))
+}
+
+/// Load the configuration as stored on disk, without environment overrides
+async fn load_file_config() -> Result<AppConfig, ConfigError> {
     // Check cache first (PERF-007)
     if let Some(cached) = get_cached_config() {
         return Ok(cached);
@@ -726,6 +1261,8 @@ pub async fn get_config() -> Result<AppConfig, ConfigError> {
         }
     }
 
+    normalize_extra_text_extensions(&mut config);
+
     // Validate config integrity and security (SEC-005)
     if let Err(e) = validate_config(&config) {
         eprintln!("Config validation failed: {}", e);
@@ -741,24 +1278,13 @@ pub async fn get_config() -> Result<AppConfig, ConfigError> {
     Ok(config)
 }
 
-/// Save application configuration to disk
+/// Write a config file, creating its directory if needed
 ///
-/// Creates config directory if it doesn't exist.
 /// Sets restrictive file permissions (0600) on Unix systems (SEC-003).
-/// Updates the in-memory cache after saving.
-///
-/// Command name: save_config (snake_case per architecture)
-#[tauri::command]
-pub async fn save_config(config: AppConfig) -> Result<(), ConfigError> {
-    // Validate config before saving (SEC-005)
-    validate_config(&config)?;
-
-    let config_dir = get_config_dir();
-    let config_path = get_config_path();
-
+fn write_config_file(config: &AppConfig, config_path: &Path) -> Result<(), ConfigError> {
     // Create directory if needed
-    if !config_dir.exists() {
-        fs::create_dir_all(&config_dir).map_err(|e| {
+    if let Some(config_dir) = config_path.parent().filter(|dir| !dir.exists()) {
+        fs::create_dir_all(config_dir).map_err(|e| {
             ConfigError::WriteError(format!(
                 "Failed to create config directory {}: {}",
                 config_dir.display(),
@@ -771,16 +1297,16 @@ pub async fn save_config(config: AppConfig) -> Result<(), ConfigError> {
         {
             use std::os::unix::fs::PermissionsExt;
             let perms = fs::Permissions::from_mode(0o700);
-            let _ = fs::set_permissions(&config_dir, perms);
+            let _ = fs::set_permissions(config_dir, perms);
         }
     }
 
     // Serialize with pretty formatting
-    let content = serde_json::to_string_pretty(&config)
+    let content = serde_json::to_string_pretty(config)
         .map_err(|e| ConfigError::WriteError(format!("Failed to serialize config: {}", e)))?;
 
     // Write to file
-    fs::write(&config_path, &content).map_err(|e| {
+    fs::write(config_path, &content).map_err(|e| {
         ConfigError::WriteError(format!("Failed to write {}: {}", config_path.display(), e))
     })?;
 
@@ -789,13 +1315,69 @@ pub async fn save_config(config: AppConfig) -> Result<(), ConfigError> {
     {
         use std::os::unix::fs::PermissionsExt;
         let perms = fs::Permissions::from_mode(0o600);
-        let _ = fs::set_permissions(&config_path, perms);
+        let _ = fs::set_permissions(config_path, perms);
     }
 
+    Ok(())
+}
+
+/// Save application configuration
+///
+/// Updates the in-memory cache immediately; the disk write is debounced so
+/// rapid edits within a short window are coalesced into a single write.
+/// Call `flush_config` to force the write (e.g. before exit).
+/// Values overridden by environment variables keep their file value.
+///
+/// Since the write happens later, `Ok` only means the config was accepted.
+/// If an earlier background write failed, its error is returned here (and by
+/// `flush_config`); the config stays pending and the write is retried.
+///
+/// Command name: save_config (snake_case per architecture)
+#[tauri::command]
+pub async fn save_config(mut config: AppConfig) -> Result<(), ConfigError> {
+    normalize_extra_text_extensions(&mut config);
+
+    // Validate config before saving (SEC-005)
+    validate_config(&config)?;
+
+    // Never write env-sourced values (e.g. API keys) back to the file
+    let file_config = load_file_config().await.unwrap_or_else(|_| default_config());
+    let config = strip_env_overrides(config, &file_config, env_var);
+
     // Update cache with saved config (PERF-007)
     cache_config(&config);
 
-    Ok(())
+    CONFIG_WRITER.schedule(config);
+
+    match CONFIG_WRITER.take_error() {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
+
+/// Write any pending configuration changes to disk immediately
+///
+/// Fails if the write fails, or if a background write failed and nothing was
+/// left to retry.
+///
+/// Command name: flush_config (snake_case per architecture)
+#[tauri::command]
+pub async fn flush_config() -> Result<(), ConfigError> {
+    CONFIG_WRITER.flush()?;
+    match CONFIG_WRITER.take_error() {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
+
+/// Write any pending configuration changes before the app exits
+///
+/// Called from the run loop on exit, where there is no caller to report
+/// a failure to, so write errors are logged instead.
+pub fn flush_pending_config() {
+    if let Err(e) = CONFIG_WRITER.flush() {
+        eprintln!("Warning: Failed to save config on exit: {}", e);
+    }
 }
 
 /// Reset configuration to defaults
@@ -808,6 +1390,9 @@ pub async fn save_config(config: AppConfig) -> Result<(), ConfigError> {
 pub async fn reset_config() -> Result<AppConfig, ConfigError> {
     let config_path = get_config_path();
 
+    // A pending save must not recreate the file after the reset
+    CONFIG_WRITER.discard();
+
     // Delete existing config if it exists
     if config_path.exists() {
         fs::remove_file(&config_path).map_err(|e| {
@@ -938,4 +1523,235 @@ mod tests {
             "\"plain\""
         );
     }
+
+    #[test]
+    fn test_validate_display_confidence_floor() {
+        let mut config = default_config();
+        assert_eq!(config.ollama.display_confidence_floor, 0.0);
+
+        config.ollama.display_confidence_floor = 0.6;
+        assert!(validate_config(&config).is_ok());
+        config.ollama.display_confidence_floor = 1.5;
+        assert!(validate_config(&config).is_err());
+        config.ollama.display_confidence_floor = -0.1;
+        assert!(validate_config(&config).is_err());
+    }
+
+    #[test]
+    fn test_validate_keep_original_confidence_threshold() {
+        let mut config = default_config();
+        assert_eq!(config.ollama.keep_original_confidence_threshold, 0.7);
+
+        config.ollama.keep_original_confidence_threshold = 1.0;
+        assert!(validate_config(&config).is_ok());
+        config.ollama.keep_original_confidence_threshold = 1.2;
+        assert!(validate_config(&config).is_err());
+        config.ollama.keep_original_confidence_threshold = -0.5;
+        assert!(validate_config(&config).is_err());
+    }
+
+    #[test]
+    fn test_validate_generation_parameters() {
+        let mut config = default_config();
+        assert_eq!(config.ollama.temperature, 0.3);
+        assert_eq!(config.ollama.max_tokens, 500);
+        assert!(validate_config(&config).is_ok());
+
+        config.ollama.temperature = 2.5;
+        assert!(validate_config(&config).is_err());
+        config.ollama.temperature = -0.1;
+        assert!(validate_config(&config).is_err());
+        config.ollama.temperature = 2.0;
+        assert!(validate_config(&config).is_ok());
+
+        config.ollama.max_tokens = 49;
+        assert!(validate_config(&config).is_err());
+        config.ollama.max_tokens = 4097;
+        assert!(validate_config(&config).is_err());
+        config.ollama.max_tokens = 4096;
+        assert!(validate_config(&config).is_ok());
+    }
+
+    #[test]
+    fn test_extra_text_extensions_normalized_and_validated() {
+        let mut config = default_config();
+        config.ollama.extra_text_extensions = vec![".Prompt".to_string(), " MDX ".to_string()];
+        normalize_extra_text_extensions(&mut config);
+        assert_eq!(config.ollama.extra_text_extensions, vec!["prompt", "mdx"]);
+        assert!(validate_config(&config).is_ok());
+
+        config.ollama.extra_text_extensions = vec![" . ".to_string()];
+        normalize_extra_text_extensions(&mut config);
+        assert!(validate_config(&config).is_err());
+        config.ollama.extra_text_extensions = vec!["tar.gz".to_string()];
+        assert!(validate_config(&config).is_err());
+        config.ollama.extra_text_extensions = vec!["TF".to_string()];
+        assert!(validate_config(&config).is_err());
+    }
+
+    #[test]
+    fn test_validate_max_active_sessions() {
+        let mut config = default_config();
+        assert_eq!(config.preferences.max_active_sessions, DEFAULT_MAX_ACTIVE_SESSIONS);
+
+        config.preferences.max_active_sessions = 2;
+        assert!(validate_config(&config).is_ok());
+        config.preferences.max_active_sessions = 0;
+        assert!(validate_config(&config).is_err());
+        config.preferences.max_active_sessions = MAX_ACTIVE_SESSIONS_LIMIT + 1;
+        assert!(validate_config(&config).is_err());
+
+        let preferences: Preferences = serde_json::from_str("{}").unwrap();
+        assert_eq!(preferences.max_active_sessions, DEFAULT_MAX_ACTIVE_SESSIONS);
+    }
+
+    #[test]
+    fn test_validate_protected_paths() {
+        let mut config = default_config();
+        config.protected_paths = vec!["/srv/archive".to_string(), "D:\\Records".to_string()];
+        assert!(validate_config(&config).is_ok());
+
+        config.protected_paths = vec!["archive".to_string()];
+        assert!(validate_config(&config).is_err());
+        config.protected_paths = vec!["  ".to_string()];
+        assert!(validate_config(&config).is_err());
+    }
+
+    fn fake_env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: std::collections::HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        move |name| vars.get(name).cloned()
+    }
+
+    #[test]
+    fn test_env_overrides_apply_to_loaded_config() {
+        let env = fake_env(&[
+            ("TIDY_OLLAMA_URL", "http://gpu-box:11434"),
+            ("TIDY_LLM_PROVIDER", "OpenAI"),
+            ("TIDY_OPENAI_API_KEY", "sk-from-env"),
+            ("TIDY_LLM_ENABLED", "true"),
+        ]);
+
+        let config = apply_env_overrides(default_config(), env);
+
+        assert_eq!(config.ollama.base_url, "http://gpu-box:11434");
+        assert_eq!(config.ollama.provider, LlmProvider::Openai);
+        assert_eq!(config.ollama.openai.api_key, "sk-from-env");
+        assert!(config.ollama.enabled);
+        // Not overridden: file value kept
+        assert_eq!(config.ollama.openai.model, default_openai_model());
+    }
+
+    #[test]
+    fn test_env_overrides_cleared_or_invalid() {
+        let config = apply_env_overrides(default_config(), fake_env(&[]));
+        assert_eq!(config.ollama.base_url, default_ollama_url());
+
+        let env = fake_env(&[("TIDY_LLM_PROVIDER", "claude"), ("TIDY_LLM_ENABLED", "maybe")]);
+        let config = apply_env_overrides(default_config(), env);
+        assert_eq!(config.ollama.provider, LlmProvider::Ollama);
+        assert!(!config.ollama.enabled);
+    }
+
+    #[test]
+    fn test_env_overrides_are_not_persisted() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("config.json");
+        let env = || fake_env(&[("TIDY_OPENAI_API_KEY", "sk-from-env")]);
+
+        // The UI edits the returned config and saves it back
+        let mut edited = apply_env_overrides(default_config(), env());
+        edited.ollama.openai.model = "gpt-4.1-mini".to_string();
+        let saved = strip_env_overrides(edited, &default_config(), env());
+        write_config_file(&saved, &path).unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(!content.contains("sk-from-env"));
+        assert!(content.contains("gpt-4.1-mini"));
+
+        // Without the variable, edits to the same field are saved
+        let mut edited = default_config();
+        edited.ollama.openai.api_key = "sk-typed".to_string();
+        let saved = strip_env_overrides(edited, &default_config(), fake_env(&[]));
+        assert_eq!(saved.ollama.openai.api_key, "sk-typed");
+    }
+
+    #[test]
+    fn test_env_var_reads_process_environment() {
+        let name = "TIDY_TEST_ENV_VAR_READ";
+        std::env::set_var(name, "value");
+        assert_eq!(env_var(name).as_deref(), Some("value"));
+        std::env::set_var(name, "  ");
+        assert_eq!(env_var(name), None);
+        std::env::remove_var(name);
+        assert_eq!(env_var(name), None);
+    }
+
+    #[tokio::test]
+    async fn test_debounced_writer_coalesces_rapid_saves() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("tidy-app").join("config.json");
+        let writer = DebouncedConfigWriter::new(path.clone(), Duration::from_millis(50));
+        // Paused time only moves forward when every task waits, so the
+        // timers fire in order however slow the machine is
+        tokio::time::pause();
+
+        for threshold in [0.1, 0.2, 0.3, 0.4, 0.5] {
+            let mut config = default_config();
+            config.ollama.keep_original_confidence_threshold = threshold;
+            writer.schedule(config);
+        }
+        assert!(!path.exists());
+
+        tokio::time::sleep(Duration::from_millis(300)).await;
+
+        assert_eq!(writer.state.writes.load(Ordering::SeqCst), 1);
+        let saved: AppConfig = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(saved.ollama.keep_original_confidence_threshold, 0.5);
+    }
+
+    #[tokio::test]
+    async fn test_debounced_writer_flush_and_discard() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("config.json");
+        let writer = DebouncedConfigWriter::new(path.clone(), Duration::from_millis(50));
+        tokio::time::pause();
+
+        writer.schedule(default_config());
+        writer.flush().unwrap();
+        assert!(path.exists());
+
+        fs::remove_file(&path).unwrap();
+        writer.schedule(default_config());
+        writer.discard();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        // The flushed timer and the discarded save both write nothing more
+        assert_eq!(writer.state.writes.load(Ordering::SeqCst), 1);
+        assert!(!path.exists());
+    }
+
+    #[tokio::test]
+    async fn test_debounced_writer_reports_failed_background_write() {
+        let dir = tempfile::TempDir::new().unwrap();
+        // The config directory can't be created where a file already is
+        fs::write(dir.path().join("blocked"), "").unwrap();
+        let path = dir.path().join("blocked").join("config.json");
+        let writer = DebouncedConfigWriter::new(path.clone(), Duration::from_millis(50));
+        tokio::time::pause();
+
+        writer.schedule(default_config());
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        assert!(matches!(writer.take_error(), Some(ConfigError::WriteError(_))));
+        assert!(writer.take_error().is_none());
+        // The config is kept and the retry fails the same way
+        assert!(writer.flush().is_err());
+
+        fs::remove_file(dir.path().join("blocked")).unwrap();
+        writer.flush().unwrap();
+        assert!(path.exists());
+    }
 }
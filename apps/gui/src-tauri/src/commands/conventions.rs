@@ -0,0 +1,531 @@
+// Naming convention analysis commands for tidy-app GUI
+// Command names use snake_case per architecture requirements
+//
+// Detects the mix of naming conventions and date formats in a set of files,
+// so a messy folder can be given a target case style and template, and scores
+// how tidy a folder's names are before and after a rename.
+
+use lazy_static::lazy_static;
+use regex_lite::Regex;
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::path::Path;
+use ts_rs::TS;
+
+use super::llm::needs_ai_analysis;
+use super::rename::{clean_filename, normalize_case, CaseStyle, RenamePreview, RenameStatus};
+use super::scanner::FileInfo;
+
+/// Points of the cleanliness score given to descriptive names
+const DESCRIPTIVE_NAMES_WEIGHT: f32 = 50.0;
+
+/// Points of the cleanliness score given to consistent casing
+const CONSISTENT_CASING_WEIGHT: f32 = 30.0;
+
+/// Points of the cleanliness score given to consistent date formats
+const CONSISTENT_DATES_WEIGHT: f32 = 20.0;
+
+/// Case styles tried in order when classifying a name
+///
+/// Lowercase, title case and capitalize all mean words separated by spaces.
+const DETECTABLE_STYLES: &[CaseStyle] = &[
+    CaseStyle::KebabCase,
+    CaseStyle::SnakeCase,
+    CaseStyle::CamelCase,
+    CaseStyle::PascalCase,
+    CaseStyle::Lowercase,
+    CaseStyle::TitleCase,
+    CaseStyle::Capitalize,
+    CaseStyle::Uppercase,
+];
+
+lazy_static! {
+    /// YYYY-MM-DD with "-", "_" or "." separators
+    static ref YEAR_FIRST_DATE: Regex =
+        Regex::new(r"(?:^|[^0-9])(?:19|20)\d{2}([-_.])\d{2}([-_.])\d{2}(?:[^0-9]|$)").unwrap();
+
+    /// YYYYMMDD
+    static ref COMPACT_DATE: Regex = Regex::new(
+        r"(?:^|[^0-9])(?:19|20)\d{2}(?:0[1-9]|1[0-2])(?:0[1-9]|[12]\d|3[01])(?:[^0-9]|$)"
+    )
+    .unwrap();
+
+    /// DD-MM-YYYY or MM-DD-YYYY
+    static ref YEAR_LAST_DATE: Regex =
+        Regex::new(r"(?:^|[^0-9])(\d{2})([-_.])(\d{2})([-_.])(?:19|20)\d{2}(?:[^0-9]|$)").unwrap();
+}
+
+// =============================================================================
+// Types
+// =============================================================================
+
+/// Number of files using one case style
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct ConventionCount {
+    pub style: CaseStyle,
+    pub count: usize,
+}
+
+/// Number of files using one date format
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct DateFormatCount {
+    /// Format in template syntax (e.g., "YYYY-MM-DD"), usable as `dateFormat`
+    pub format: String,
+    pub count: usize,
+}
+
+/// A file that doesn't follow the dominant convention
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct NamingOutlier {
+    pub path: String,
+    pub name: String,
+    /// Detected case style, `None` for names mixing several conventions
+    pub style: Option<CaseStyle>,
+}
+
+/// Naming conventions found in a set of files
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct NamingConventionReport {
+    pub total_files: usize,
+    /// Case styles found, most files first
+    pub conventions: Vec<ConventionCount>,
+    /// Most common case style, recommended as the target `CaseStyle`
+    pub dominant: Option<CaseStyle>,
+    /// Multi-word names not following the dominant style
+    pub outliers: Vec<NamingOutlier>,
+    /// Names mixing several conventions (e.g., "My_photo-Final")
+    pub mixed_count: usize,
+    /// Single-word names, which fit any convention and are not counted
+    pub ambiguous_count: usize,
+    /// Date formats found, most files first
+    pub date_formats: Vec<DateFormatCount>,
+    /// Most common date format, recommended for the template
+    pub dominant_date_format: Option<String>,
+}
+
+/// Parts of a cleanliness score, each a share from 0.0 to 1.0
+///
+/// The score is `50 * descriptive_names + 30 * consistent_casing +
+/// 20 * consistent_dates`, rounded.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct CleanlinessBreakdown {
+    /// Files whose name needs no AI renaming (`needs_ai_analysis`: not a
+    /// low-quality name, at least 10 characters, a date or known document word)
+    pub descriptive_names: f32,
+    /// Multi-word names in the dominant case style (1.0 without multi-word names)
+    pub consistent_casing: f32,
+    /// Dated names using the dominant date format (1.0 without dated names)
+    pub consistent_dates: f32,
+}
+
+/// How tidy a set of filenames is, from 0 (messy) to 100 (tidy)
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct CleanlinessScore {
+    pub score: u32,
+    pub total_files: usize,
+    pub breakdown: CleanlinessBreakdown,
+}
+
+/// Cleanliness of a preview's files before and after renaming
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct CleanlinessComparison {
+    pub before: CleanlinessScore,
+    /// Score with the proposed names of ready proposals
+    pub after: CleanlinessScore,
+}
+
+// =============================================================================
+// Detection
+// =============================================================================
+
+enum Classification {
+    Style(CaseStyle),
+    Mixed,
+    Ambiguous,
+}
+
+/// Classify the case style of a filename stem
+///
+/// Dates and counters are ignored, so "2024-01-15_beach_sunset" is snake case.
+fn classify_name(stem: &str) -> Classification {
+    let cleaned = clean_filename(stem);
+    let name = cleaned.trim_start_matches('.');
+
+    // A single word reads the same in every style
+    if !normalize_case(name, &CaseStyle::KebabCase).contains('-') {
+        return Classification::Ambiguous;
+    }
+
+    DETECTABLE_STYLES
+        .iter()
+        .find(|style| normalize_case(name, style) == name)
+        .map_or(Classification::Mixed, |style| Classification::Style(style.clone()))
+}
+
+/// Detect the date format used in a filename stem, if any
+fn detect_date_format(stem: &str) -> Option<String> {
+    if let Some(caps) = YEAR_FIRST_DATE.captures(stem) {
+        let (first, second) = (&caps[1], &caps[2]);
+        return (first == second).then(|| format!("YYYY{}MM{}DD", first, second));
+    }
+
+    if COMPACT_DATE.is_match(stem) {
+        return Some("YYYYMMDD".to_string());
+    }
+
+    let caps = YEAR_LAST_DATE.captures(stem)?;
+    let (a, b): (u32, u32) = (caps[1].parse().ok()?, caps[3].parse().ok()?);
+    let (sep1, sep2) = (&caps[2], &caps[4]);
+    if sep1 != sep2 {
+        return None;
+    }
+    // Both parts up to 12 could be either order: don't guess
+    match (a > 12, b > 12) {
+        (true, false) => Some(format!("DD{}MM{}YYYY", sep1, sep2)),
+        (false, true) => Some(format!("MM{}DD{}YYYY", sep1, sep2)),
+        _ => None,
+    }
+}
+
+fn increment<T: PartialEq>(counts: &mut Vec<(T, usize)>, key: T) {
+    match counts.iter_mut().find(|(k, _)| *k == key) {
+        Some((_, count)) => *count += 1,
+        None => counts.push((key, 1)),
+    }
+}
+
+/// Sort counts most common first, keeping first-seen order on ties
+fn sort_counts<T>(counts: &mut [(T, usize)]) {
+    counts.sort_by_key(|(_, count)| Reverse(*count));
+}
+
+fn build_report(files: &[FileInfo]) -> NamingConventionReport {
+    let mut styles: Vec<(CaseStyle, usize)> = Vec::new();
+    let mut formats: Vec<(String, usize)> = Vec::new();
+    let mut classified: Vec<(&FileInfo, Option<CaseStyle>)> = Vec::new();
+    let mut mixed_count = 0;
+    let mut ambiguous_count = 0;
+
+    for file in files {
+        match classify_name(&file.name) {
+            Classification::Style(style) => {
+                increment(&mut styles, style.clone());
+                classified.push((file, Some(style)));
+            }
+            Classification::Mixed => {
+                mixed_count += 1;
+                classified.push((file, None));
+            }
+            Classification::Ambiguous => ambiguous_count += 1,
+        }
+
+        if let Some(format) = detect_date_format(&file.name) {
+            increment(&mut formats, format);
+        }
+    }
+
+    sort_counts(&mut styles);
+    sort_counts(&mut formats);
+    let dominant = styles.first().map(|(style, _)| style.clone());
+
+    let outliers = classified
+        .into_iter()
+        .filter(|(_, style)| style.is_none() || *style != dominant)
+        .map(|(file, style)| NamingOutlier {
+            path: file.path.clone(),
+            name: file.full_name.clone(),
+            style,
+        })
+        .collect();
+
+    NamingConventionReport {
+        total_files: files.len(),
+        conventions: styles
+            .into_iter()
+            .map(|(style, count)| ConventionCount { style, count })
+            .collect(),
+        dominant,
+        outliers,
+        mixed_count,
+        ambiguous_count,
+        dominant_date_format: formats.first().map(|(format, _)| format.clone()),
+        date_formats: formats
+            .into_iter()
+            .map(|(format, count)| DateFormatCount { format, count })
+            .collect(),
+    }
+}
+
+// =============================================================================
+// Cleanliness Score
+// =============================================================================
+
+/// Share of `count` in `total`, 1.0 when there is nothing to measure
+fn share(count: usize, total: usize) -> f32 {
+    if total == 0 {
+        1.0
+    } else {
+        count as f32 / total as f32
+    }
+}
+
+/// Score a set of filenames (with extensions)
+fn score_names<S: AsRef<str>>(names: &[S]) -> CleanlinessScore {
+    let mut styles: Vec<(CaseStyle, usize)> = Vec::new();
+    let mut formats: Vec<(String, usize)> = Vec::new();
+    let mut styled_count = 0;
+    let mut dated_count = 0;
+    let mut descriptive_count = 0;
+
+    for name in names {
+        let name = name.as_ref();
+        if !needs_ai_analysis(name).0 {
+            descriptive_count += 1;
+        }
+
+        let stem = Path::new(name).file_stem().and_then(|s| s.to_str()).unwrap_or(name);
+        match classify_name(stem) {
+            Classification::Style(style) => {
+                increment(&mut styles, style);
+                styled_count += 1;
+            }
+            Classification::Mixed => styled_count += 1,
+            Classification::Ambiguous => {}
+        }
+        if let Some(format) = detect_date_format(stem) {
+            increment(&mut formats, format);
+            dated_count += 1;
+        }
+    }
+
+    sort_counts(&mut styles);
+    sort_counts(&mut formats);
+    let dominant_style = styles.first().map_or(0, |(_, count)| *count);
+    let dominant_format = formats.first().map_or(0, |(_, count)| *count);
+
+    let breakdown = CleanlinessBreakdown {
+        descriptive_names: share(descriptive_count, names.len()),
+        consistent_casing: share(dominant_style, styled_count),
+        consistent_dates: share(dominant_format, dated_count),
+    };
+    let score = DESCRIPTIVE_NAMES_WEIGHT * breakdown.descriptive_names
+        + CONSISTENT_CASING_WEIGHT * breakdown.consistent_casing
+        + CONSISTENT_DATES_WEIGHT * breakdown.consistent_dates;
+
+    CleanlinessScore {
+        score: score.round() as u32,
+        total_files: names.len(),
+        breakdown,
+    }
+}
+
+/// Score a preview's original names and the names it would leave
+fn compare_preview_cleanliness(preview: &RenamePreview) -> CleanlinessComparison {
+    let before: Vec<&str> = preview.proposals.iter().map(|p| p.original_name.as_str()).collect();
+    let after: Vec<&str> = preview
+        .proposals
+        .iter()
+        .map(|p| match p.status {
+            RenameStatus::Ready => p.proposed_name.as_str(),
+            _ => p.original_name.as_str(),
+        })
+        .collect();
+
+    CleanlinessComparison {
+        before: score_names(&before),
+        after: score_names(&after),
+    }
+}
+
+// =============================================================================
+// Tauri Commands
+// =============================================================================
+
+/// Detect the naming conventions used by a set of files
+///
+/// Reports the dominant case style and date format, plus the files that
+/// don't follow them, to help pick a target `CaseStyle` and template.
+///
+/// Command name: analyze_naming_conventions (snake_case per architecture)
+#[tauri::command]
+pub async fn analyze_naming_conventions(
+    files: Vec<FileInfo>,
+) -> Result<NamingConventionReport, String> {
+    Ok(build_report(&files))
+}
+
+/// Score how tidy a folder's filenames are, from 0 to 100
+///
+/// Half the points go to descriptive names, the rest to a consistent case
+/// style (30) and date format (20). The breakdown gives each share so the UI
+/// can explain the score.
+///
+/// Command name: folder_cleanliness_score (snake_case per architecture)
+#[tauri::command]
+pub async fn folder_cleanliness_score(files: Vec<FileInfo>) -> Result<CleanlinessScore, String> {
+    let names: Vec<&str> = files.iter().map(|f| f.full_name.as_str()).collect();
+    Ok(score_names(&names))
+}
+
+/// Score a preview's files before and after renaming
+///
+/// The projected score uses the proposed names of ready proposals; other
+/// files keep their current name.
+///
+/// Command name: preview_cleanliness_score (snake_case per architecture)
+#[tauri::command]
+pub async fn preview_cleanliness_score(
+    preview: RenamePreview,
+) -> Result<CleanlinessComparison, String> {
+    Ok(compare_preview_cleanliness(&preview))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::scanner::test_file_info;
+
+    fn file(name: &str) -> FileInfo {
+        test_file_info(&format!("/messy/{}.pdf", name), 1024)
+    }
+
+    #[test]
+    fn test_classify_name() {
+        assert!(matches!(
+            classify_name("beach-sunset"),
+            Classification::Style(CaseStyle::KebabCase)
+        ));
+        assert!(matches!(
+            classify_name("2024-01-15_beach_sunset"),
+            Classification::Style(CaseStyle::SnakeCase)
+        ));
+        assert!(matches!(
+            classify_name("Beach Sunset"),
+            Classification::Style(CaseStyle::TitleCase)
+        ));
+        assert!(matches!(
+            classify_name("beachSunset"),
+            Classification::Style(CaseStyle::CamelCase)
+        ));
+        assert!(matches!(classify_name("My_photo-Final"), Classification::Mixed));
+        assert!(matches!(classify_name("invoice"), Classification::Ambiguous));
+    }
+
+    #[test]
+    fn test_detect_date_format() {
+        assert_eq!(detect_date_format("2024-01-15-beach").as_deref(), Some("YYYY-MM-DD"));
+        assert_eq!(detect_date_format("scan_20240115").as_deref(), Some("YYYYMMDD"));
+        assert_eq!(detect_date_format("report 25.12.2023").as_deref(), Some("DD.MM.YYYY"));
+        assert_eq!(detect_date_format("report 03.04.2023"), None);
+        assert_eq!(detect_date_format("beach"), None);
+    }
+
+    #[test]
+    fn test_report_on_mixed_folder() {
+        let files = vec![
+            file("2024-01-15-beach-sunset"),
+            file("2024-02-03-family-dinner"),
+            file("tax-return-2023"),
+            file("meeting-notes"),
+            file("Project Plan"),
+            file("budget_final"),
+            file("My_photo-Final"),
+            file("invoice"),
+        ];
+
+        let report = build_report(&files);
+
+        assert_eq!(report.total_files, 8);
+        assert_eq!(report.dominant, Some(CaseStyle::KebabCase));
+        assert_eq!(report.conventions[0].count, 4);
+        assert_eq!(report.outliers.len(), 3);
+        assert_eq!(report.mixed_count, 1);
+        assert_eq!(report.ambiguous_count, 1);
+        assert_eq!(report.dominant_date_format.as_deref(), Some("YYYY-MM-DD"));
+        assert_eq!(report.date_formats[0].count, 2);
+    }
+
+    #[test]
+    fn test_messy_folder_scores_low() {
+        let names = [
+            "IMG_4821.JPG",
+            "Screenshot 2024-01-15 at 10.32.11.png",
+            "document_a8f3b2c1.pdf",
+            "My_photo-Final.jpg",
+            "scan0001.pdf",
+            "Copy of budget_v2 FINAL.xlsx",
+            "15.01.2024 notes.txt",
+        ];
+
+        let score = score_names(&names);
+
+        assert!(score.score < 40, "messy folder scored {}", score.score);
+        assert!(score.breakdown.descriptive_names < 0.3);
+        // Good names are the ones AI analysis would skip
+        let good = names.iter().filter(|name| !needs_ai_analysis(name).0).count();
+        assert_eq!(score.breakdown.descriptive_names, good as f32 / names.len() as f32);
+    }
+
+    #[test]
+    fn test_well_named_folder_scores_high() {
+        let names = [
+            "2024-01-15-invoice-acme-corp.pdf",
+            "2024-02-03-meeting-notes-budget.md",
+            "2024-03-10-project-proposal-draft.docx",
+            "2024-04-22-quarterly-report-sales.xlsx",
+        ];
+
+        let score = score_names(&names);
+
+        assert_eq!(score.score, 100);
+        assert_eq!(score.total_files, 4);
+        assert_eq!(score.breakdown.consistent_casing, 1.0);
+        assert_eq!(score.breakdown.consistent_dates, 1.0);
+    }
+
+    #[test]
+    fn test_preview_cleanliness_projects_after_score() {
+        use crate::commands::rename::{finalize_preview, FileActionType, RenameProposal};
+        use crate::commands::rename::ReorganizationMode;
+
+        let proposal = |from: &str, to: &str| RenameProposal {
+            id: from.to_string(),
+            original_path: format!("/messy/{}", from),
+            original_name: from.to_string(),
+            proposed_name: to.to_string(),
+            proposed_path: format!("/messy/{}", to),
+            status: RenameStatus::Ready,
+            issues: vec![],
+            metadata_sources: None,
+            is_folder_move: false,
+            destination_folder: None,
+            action_type: FileActionType::Rename,
+            conflict: None,
+        };
+        let proposals = vec![
+            proposal("IMG_4821.JPG", "2024-01-15-beach-sunset-trip.jpg"),
+            proposal("scan0001.pdf", "2024-02-03-invoice-acme-corp.pdf"),
+        ];
+        let preview = finalize_preview(proposals, String::new(), ReorganizationMode::RenameOnly);
+
+        let comparison = compare_preview_cleanliness(&preview);
+
+        assert_eq!(comparison.before.breakdown.descriptive_names, 0.0);
+        assert!(comparison.before.score <= 50);
+        assert_eq!(comparison.after.score, 100);
+    }
+}
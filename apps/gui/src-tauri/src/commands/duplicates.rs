@@ -0,0 +1,436 @@
+// Duplicate detection commands for tidy-app GUI
+// Command names use snake_case per architecture requirements
+//
+// Finds files with identical content: files are grouped by size first, and only
+// size collisions are hashed (in parallel with rayon)
+
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Read;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tauri::Emitter;
+use ts_rs::TS;
+
+use super::scanner::{
+    should_report_progress, CancellationToken, FileInfo, ScanError, ScanState, SessionKind,
+};
+
+/// Read buffer size for hashing (64KB)
+const HASH_BUFFER_SIZE: usize = 64 * 1024;
+
+// =============================================================================
+// Types
+// =============================================================================
+
+/// A group of files with identical content
+#[derive(Debug, Clone, Serialize, PartialEq, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateGroup {
+    /// SHA-256 of the file contents (hex)
+    pub hash: String,
+    /// Size of each file in bytes
+    pub size: u64,
+    /// Paths of the duplicate files, sorted
+    pub paths: Vec<String>,
+    /// Bytes reclaimable by keeping a single copy: size × (count − 1)
+    pub wasted_bytes: u64,
+}
+
+/// Order of duplicate groups in the result
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "kebab-case")]
+pub enum DuplicateSort {
+    /// Largest files first (default)
+    #[default]
+    Size,
+    /// Most reclaimable bytes first
+    WastedSpace,
+}
+
+/// Result of a duplicate detection run
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateScanResult {
+    /// Duplicate groups, ordered by size (largest first) then hash, unless
+    /// another order was requested
+    pub groups: Vec<DuplicateGroup>,
+    /// Bytes reclaimable across all groups
+    #[serde(default)]
+    pub total_wasted_bytes: u64,
+    /// Number of files that had to be hashed (size collisions)
+    pub files_hashed: usize,
+    /// Session ID (for cancellation via cancel_scan)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    /// Whether detection was cancelled (groups are empty when cancelled)
+    #[serde(default)]
+    pub cancelled: bool,
+}
+
+/// Progress event payload for duplicate detection
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateProgress {
+    /// Session ID (for cancellation via cancel_scan)
+    pub session_id: String,
+    /// Number of files hashed so far
+    pub hashed: usize,
+    /// Number of files that need hashing
+    pub total: usize,
+}
+
+// =============================================================================
+// Hashing
+// =============================================================================
+
+/// Hash a file's contents, checking for cancellation between chunks
+///
+/// Returns None if the file can't be read or the token was cancelled.
+pub fn hash_file(path: &str, cancel_token: Option<&CancellationToken>) -> Option<String> {
+    let mut file = File::open(path).ok()?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; HASH_BUFFER_SIZE];
+
+    loop {
+        if cancel_token.is_some_and(|t| t.is_cancelled()) {
+            return None;
+        }
+
+        let bytes_read = file.read(&mut buffer).ok()?;
+        if bytes_read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..bytes_read]);
+    }
+
+    Some(
+        hasher
+            .finalize()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect(),
+    )
+}
+
+/// Select files whose size collides with at least one other file
+///
+/// Empty files are ignored. The result is sorted by path so the hashing
+/// stage sees the same input regardless of scan order.
+fn size_collision_candidates(files: &[FileInfo]) -> Vec<(String, u64)> {
+    let mut by_size: BTreeMap<u64, Vec<&str>> = BTreeMap::new();
+    for file in files.iter().filter(|f| f.size > 0) {
+        by_size.entry(file.size).or_default().push(&file.path);
+    }
+
+    let mut candidates: Vec<(String, u64)> = by_size
+        .into_iter()
+        .filter(|(_, paths)| paths.len() > 1)
+        .flat_map(|(size, paths)| paths.into_iter().map(move |p| (p.to_string(), size)))
+        .collect();
+
+    candidates.sort();
+    candidates
+}
+
+/// Build duplicate groups from hashed files
+///
+/// Uses ordered maps and sorted paths so the output is deterministic.
+fn group_by_hash(hashed: Vec<(String, u64, String)>) -> Vec<DuplicateGroup> {
+    let mut by_hash: BTreeMap<(u64, String), Vec<String>> = BTreeMap::new();
+    for (path, size, hash) in hashed {
+        by_hash.entry((size, hash)).or_default().push(path);
+    }
+
+    let mut groups: Vec<DuplicateGroup> = by_hash
+        .into_iter()
+        .filter(|(_, paths)| paths.len() > 1)
+        .map(|((size, hash), mut paths)| {
+            paths.sort();
+            let wasted_bytes = size * (paths.len() as u64 - 1);
+            DuplicateGroup { hash, size, paths, wasted_bytes }
+        })
+        .collect();
+
+    sort_duplicate_groups(&mut groups, DuplicateSort::Size);
+    groups
+}
+
+/// Sort groups in the requested order, breaking ties by hash
+fn sort_duplicate_groups(groups: &mut [DuplicateGroup], sort: DuplicateSort) {
+    match sort {
+        DuplicateSort::Size => {
+            groups.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.hash.cmp(&b.hash)));
+        }
+        DuplicateSort::WastedSpace => {
+            groups.sort_by(|a, b| {
+                b.wasted_bytes
+                    .cmp(&a.wasted_bytes)
+                    .then_with(|| b.size.cmp(&a.size))
+                    .then_with(|| a.hash.cmp(&b.hash))
+            });
+        }
+    }
+}
+
+/// Find groups of duplicate files
+///
+/// Hashing runs in parallel; `cancel_token` is checked before each file and
+/// between read chunks. `progress_callback` receives the number of files
+/// hashed so far and the number to hash, at `progress_interval` (see
+/// [`should_report_progress`]).
+fn find_duplicates_internal(
+    files: &[FileInfo],
+    cancel_token: Option<&CancellationToken>,
+    progress_interval: Option<usize>,
+    progress_callback: Option<&(dyn Fn(usize, usize) + Sync)>,
+) -> DuplicateScanResult {
+    let candidates = size_collision_candidates(files);
+    let hashed_count = AtomicUsize::new(0);
+
+    let hashed: Vec<(String, u64, String)> = candidates
+        .par_iter()
+        .filter_map(|(path, size)| {
+            if cancel_token.is_some_and(|t| t.is_cancelled()) {
+                return None;
+            }
+
+            let hash = hash_file(path, cancel_token)?;
+
+            let count = hashed_count.fetch_add(1, Ordering::SeqCst) + 1;
+            if let Some(callback) = progress_callback {
+                if should_report_progress(count, progress_interval) {
+                    callback(count, candidates.len());
+                }
+            }
+
+            Some((path.clone(), *size, hash))
+        })
+        .collect();
+
+    let files_hashed = hashed.len();
+
+    // Partial hashes would produce misleading groups - report nothing
+    if cancel_token.is_some_and(|t| t.is_cancelled()) {
+        return DuplicateScanResult {
+            groups: Vec::new(),
+            total_wasted_bytes: 0,
+            files_hashed,
+            session_id: None,
+            cancelled: true,
+        };
+    }
+
+    let groups = group_by_hash(hashed);
+    DuplicateScanResult {
+        total_wasted_bytes: groups.iter().map(|g| g.wasted_bytes).sum(),
+        groups,
+        files_hashed,
+        session_id: None,
+        cancelled: false,
+    }
+}
+
+// =============================================================================
+// Tauri Commands
+// =============================================================================
+
+/// Find files with identical content among scanned files
+///
+/// Registers a scan session so the operation can be cancelled with `cancel_scan`.
+/// Emits "duplicates-progress" events every `progress_interval` hashed files
+/// (0 disables them; default: adaptive). Groups are ordered by `sort`
+/// (default: largest files first).
+///
+/// Command name: find_duplicates (snake_case per architecture)
+#[tauri::command]
+pub async fn find_duplicates(
+    window: tauri::Window,
+    scan_state: tauri::State<'_, ScanState>,
+    files: Vec<FileInfo>,
+    progress_interval: Option<usize>,
+    sort: Option<DuplicateSort>,
+) -> Result<DuplicateScanResult, ScanError> {
+    // Report the folder of the first file; duplicates are usually searched per folder
+    let folder = files
+        .first()
+        .and_then(|f| std::path::Path::new(&f.path).parent())
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let (session_id, cancel_token) = scan_state
+        .create_session(SessionKind::Duplicates, &folder)?;
+
+    // Hashing blocks on disk reads and rayon: keep it off the async runtime
+    let progress_session_id = session_id.clone();
+    let hashing = tauri::async_runtime::spawn_blocking(move || {
+        let progress_callback = |hashed: usize, total: usize| {
+            let _ = window.emit("duplicates-progress", DuplicateProgress {
+                session_id: progress_session_id.clone(),
+                hashed,
+                total,
+            });
+        };
+
+        find_duplicates_internal(
+            &files,
+            Some(&cancel_token),
+            progress_interval,
+            Some(&progress_callback),
+        )
+    })
+    .await;
+
+    scan_state.remove_session(&session_id);
+
+    let mut result = hashing.map_err(|e| ScanError::InternalError(e.to_string()))?;
+    result.session_id = Some(session_id);
+    sort_duplicate_groups(&mut result.groups, sort.unwrap_or_default());
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::scanner::test_file_info;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn create_file(dir: &TempDir, name: &str, content: &[u8]) -> FileInfo {
+        let path = dir.path().join(name);
+        fs::write(&path, content).unwrap();
+        test_file_info(&path.to_string_lossy(), content.len() as u64)
+    }
+
+    /// 60 files of the same size: 3 duplicate groups of 10, plus 30 unique files
+    fn create_same_size_files(dir: &TempDir) -> Vec<FileInfo> {
+        (0..60)
+            .map(|i| {
+                let content = if i < 30 {
+                    format!("group-{:04}", i % 3)
+                } else {
+                    format!("uniqu-{:04}", i)
+                };
+                create_file(dir, &format!("file{:02}.bin", i), content.as_bytes())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_size_collision_candidates_skips_unique_sizes() {
+        let dir = TempDir::new().unwrap();
+        let files = vec![
+            create_file(&dir, "a.bin", b"same"),
+            create_file(&dir, "b.bin", b"same"),
+            create_file(&dir, "c.bin", b"different size"),
+            create_file(&dir, "empty1.bin", b""),
+            create_file(&dir, "empty2.bin", b""),
+        ];
+
+        let candidates = size_collision_candidates(&files);
+
+        assert_eq!(candidates.len(), 2);
+        assert!(candidates.iter().all(|(_, size)| *size == 4));
+    }
+
+    #[test]
+    fn test_find_duplicates_matches_serial_computation() {
+        let dir = TempDir::new().unwrap();
+        let files = create_same_size_files(&dir);
+
+        let result = find_duplicates_internal(&files, None, None, None);
+
+        // Serial reference computation
+        let serial_hashed: Vec<(String, u64, String)> = size_collision_candidates(&files)
+            .into_iter()
+            .map(|(path, size)| {
+                let hash = hash_file(&path, None).unwrap();
+                (path, size, hash)
+            })
+            .collect();
+        let serial_groups = group_by_hash(serial_hashed);
+
+        assert!(!result.cancelled);
+        assert_eq!(result.files_hashed, 60);
+        assert_eq!(result.groups.len(), 3);
+        assert!(result.groups.iter().all(|g| g.paths.len() == 10));
+        assert_eq!(result.groups, serial_groups);
+
+        // Deterministic across runs
+        let again = find_duplicates_internal(&files, None, None, None);
+        assert_eq!(again.groups, result.groups);
+    }
+
+    #[test]
+    fn test_duplicate_groups_sorted_by_wasted_space() {
+        let dir = TempDir::new().unwrap();
+        // 2 copies of 100 bytes, 5 copies of 40 bytes, 3 copies of 10 bytes
+        let mut files: Vec<FileInfo> = Vec::new();
+        for (size, copies) in [(100, 2), (40, 5), (10, 3)] {
+            for i in 0..copies {
+                let name = format!("{}-{}.bin", size, i);
+                files.push(create_file(&dir, &name, &vec![b'x'; size]));
+            }
+        }
+
+        let mut result = find_duplicates_internal(&files, None, None, None);
+
+        let wasted: Vec<(u64, u64)> =
+            result.groups.iter().map(|g| (g.size, g.wasted_bytes)).collect();
+        assert_eq!(wasted, vec![(100, 100), (40, 160), (10, 20)]);
+        assert_eq!(result.total_wasted_bytes, 280);
+
+        sort_duplicate_groups(&mut result.groups, DuplicateSort::WastedSpace);
+        let sizes: Vec<u64> = result.groups.iter().map(|g| g.size).collect();
+        assert_eq!(sizes, vec![40, 100, 10]);
+    }
+
+    #[test]
+    fn test_find_duplicates_cancellation_mid_hash() {
+        let dir = TempDir::new().unwrap();
+        let files = create_same_size_files(&dir);
+
+        let token = CancellationToken::new();
+        let cancel_after_five = |count: usize, _total: usize| {
+            if count >= 5 {
+                token.cancel();
+            }
+        };
+
+        // A single worker makes the cancellation point exact
+        let pool = rayon::ThreadPoolBuilder::new().num_threads(1).build().unwrap();
+        let result = pool.install(|| {
+            find_duplicates_internal(&files, Some(&token), None, Some(&cancel_after_five))
+        });
+
+        assert!(result.cancelled);
+        assert!(result.groups.is_empty());
+        assert_eq!(result.files_hashed, 5);
+    }
+
+    #[test]
+    fn test_find_duplicates_progress_interval() {
+        let dir = TempDir::new().unwrap();
+        let files = create_same_size_files(&dir);
+
+        let count_reports = |interval: Option<usize>| {
+            let calls = AtomicUsize::new(0);
+            let callback = |_: usize, total: usize| {
+                assert_eq!(total, 60);
+                calls.fetch_add(1, Ordering::SeqCst);
+            };
+            find_duplicates_internal(&files, None, interval, Some(&callback));
+            calls.load(Ordering::SeqCst)
+        };
+
+        assert_eq!(count_reports(None), 60);
+        assert_eq!(count_reports(Some(7)), 8);
+        assert_eq!(count_reports(Some(0)), 0);
+    }
+}
@@ -0,0 +1,383 @@
+// Scan exclusion rules for tidy-app GUI
+//
+// Evaluates hidden-file, .tidyignore, .gitignore and glob exclusions so the
+// scanner can report which rule omitted each file
+
+use std::fs;
+use std::path::Path;
+
+use super::scanner::{ScanOptions, SkipReason};
+
+const GITIGNORE_FILENAME: &str = ".gitignore";
+const TIDYIGNORE_FILENAME: &str = ".tidyignore";
+
+// =============================================================================
+// Glob Matching
+// =============================================================================
+
+/// One element of a parsed glob pattern
+enum GlobToken {
+    /// `**/`: zero or more whole directories
+    AnyDirs,
+    /// `**`: any characters, including `/`
+    AnyPath,
+    /// `*`: any characters except `/`
+    Any,
+    /// `?`: one character except `/`
+    One,
+    /// `[...]`: one character (except `/`) accepted by the class
+    Class(Box<dyn Fn(char) -> bool>),
+    Literal(char),
+}
+
+fn tokenize_glob(pattern: &[char]) -> Vec<GlobToken> {
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < pattern.len() {
+        match pattern[i] {
+            '*' if pattern.get(i + 1) == Some(&'*') => {
+                if pattern.get(i + 2) == Some(&'/') {
+                    tokens.push(GlobToken::AnyDirs);
+                    i += 3;
+                } else {
+                    tokens.push(GlobToken::AnyPath);
+                    i += 2;
+                }
+            }
+            '*' => {
+                tokens.push(GlobToken::Any);
+                i += 1;
+            }
+            '?' => {
+                tokens.push(GlobToken::One);
+                i += 1;
+            }
+            '[' => match parse_char_class(&pattern[i + 1..]) {
+                Some((matches_char, consumed)) => {
+                    tokens.push(GlobToken::Class(Box::new(matches_char)));
+                    i += 1 + consumed;
+                }
+                // No closing bracket - treat '[' literally
+                None => {
+                    tokens.push(GlobToken::Literal('['));
+                    i += 1;
+                }
+            },
+            c => {
+                tokens.push(GlobToken::Literal(c));
+                i += 1;
+            }
+        }
+    }
+    tokens
+}
+
+/// Match a path against a glob pattern
+///
+/// Supports `*` (any characters except `/`), `**` (any characters including
+/// `/`), `?` (one character except `/`) and `[abc]` / `[a-z]` / `[!abc]` classes.
+///
+/// Runs in O(pattern × path) time: `matched[j]` holds whether the rest of the
+/// pattern matches `path[j..]`, computed from the last token backwards, so
+/// patterns with many `*` can't backtrack exponentially.
+pub fn glob_match(pattern: &str, path: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = path.chars().collect();
+    let len = text.len();
+
+    // No tokens left: only the end of the path matches
+    let mut next = vec![false; len + 1];
+    next[len] = true;
+
+    for token in tokenize_glob(&pattern).iter().rev() {
+        let mut matched = vec![false; len + 1];
+        // For "**/": a '/' at or after j where the rest matches right after it
+        let mut dir_boundary_ahead = false;
+        for j in (0..=len).rev() {
+            let c = text.get(j).copied();
+            matched[j] = match token {
+                GlobToken::AnyDirs => {
+                    dir_boundary_ahead |= c == Some('/') && next[j + 1];
+                    next[j] || dir_boundary_ahead
+                }
+                GlobToken::AnyPath => next[j] || (j < len && matched[j + 1]),
+                GlobToken::Any => next[j] || (c.is_some_and(|c| c != '/') && matched[j + 1]),
+                GlobToken::One => c.is_some_and(|c| c != '/') && next[j + 1],
+                GlobToken::Class(matches_char) => {
+                    c.is_some_and(|c| c != '/' && matches_char(c)) && next[j + 1]
+                }
+                GlobToken::Literal(literal) => c == Some(*literal) && next[j + 1],
+            };
+        }
+        next = matched;
+    }
+
+    next[0]
+}
+
+/// Parse a character class body (after '['), returning a matcher and the
+/// number of pattern characters consumed including the closing ']'
+fn parse_char_class(p: &[char]) -> Option<(impl Fn(char) -> bool, usize)> {
+    let negated = matches!(p.first(), Some('!') | Some('^'));
+    let start = usize::from(negated);
+    let end = start + p[start..].iter().skip(1).position(|c| *c == ']')? + 1;
+
+    let body: Vec<char> = p[start..end].to_vec();
+    let matcher = move |c: char| {
+        let mut i = 0;
+        let mut found = false;
+        while i < body.len() {
+            if i + 2 < body.len() && body[i + 1] == '-' {
+                found |= body[i] <= c && c <= body[i + 2];
+                i += 3;
+            } else {
+                found |= body[i] == c;
+                i += 1;
+            }
+        }
+        found != negated
+    };
+
+    Some((matcher, end + 1))
+}
+
+// =============================================================================
+// Ignore Files (.gitignore / .tidyignore)
+// =============================================================================
+
+/// A single pattern from an ignore file
+#[derive(Debug, Clone)]
+struct IgnorePattern {
+    /// Original line (for reporting)
+    source: String,
+    pattern: String,
+    negated: bool,
+    dir_only: bool,
+    /// Pattern contains a slash, so it matches relative to the scan root
+    anchored: bool,
+}
+
+impl IgnorePattern {
+    fn parse(line: &str) -> Option<Self> {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+
+        let (negated, rest) = match line.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+        let (dir_only, rest) = match rest.strip_suffix('/') {
+            Some(rest) => (true, rest),
+            None => (false, rest),
+        };
+        let anchored = rest.contains('/');
+        let pattern = rest.trim_start_matches('/').to_string();
+
+        if pattern.is_empty() {
+            return None;
+        }
+
+        Some(IgnorePattern {
+            source: line.to_string(),
+            pattern,
+            negated,
+            dir_only,
+            anchored,
+        })
+    }
+
+    /// Check a file's relative path, including each of its parent directories
+    fn matches(&self, relative_path: &str) -> bool {
+        let components: Vec<&str> = relative_path.split('/').collect();
+        (1..=components.len()).any(|len| {
+            let is_dir = len < components.len();
+            if self.dir_only && !is_dir {
+                return false;
+            }
+            if self.anchored {
+                glob_match(&self.pattern, &components[..len].join("/"))
+            } else {
+                glob_match(&self.pattern, components[len - 1])
+            }
+        })
+    }
+}
+
+/// Patterns loaded from an ignore file at the scan root
+///
+/// Supports a subset of gitignore syntax: comments, negation (`!`),
+/// directory-only patterns (trailing `/`), anchored patterns and globs.
+/// Nested ignore files are not read.
+#[derive(Debug, Clone, Default)]
+pub struct IgnoreFile {
+    patterns: Vec<IgnorePattern>,
+}
+
+impl IgnoreFile {
+    pub fn parse(contents: &str) -> Self {
+        IgnoreFile {
+            patterns: contents.lines().filter_map(IgnorePattern::parse).collect(),
+        }
+    }
+
+    /// Load an ignore file, returning None if it doesn't exist or can't be read
+    pub fn load(path: &Path) -> Option<Self> {
+        fs::read_to_string(path).ok().map(|contents| Self::parse(&contents))
+    }
+
+    /// Return the pattern that excludes this path, if any (last match wins)
+    pub fn excluding_pattern(&self, relative_path: &str) -> Option<&str> {
+        self.patterns
+            .iter()
+            .rev()
+            .find(|p| p.matches(relative_path))
+            .filter(|p| !p.negated)
+            .map(|p| p.source.as_str())
+    }
+}
+
+// =============================================================================
+// Exclusion Rules
+// =============================================================================
+
+/// The set of exclusion rules active for a scan
+#[derive(Debug, Clone, Default)]
+pub struct ExclusionRules {
+    skip_hidden: bool,
+    tidyignore: Option<IgnoreFile>,
+    gitignore: Option<IgnoreFile>,
+    globs: Vec<String>,
+}
+
+impl ExclusionRules {
+    /// Build the rules for a scan rooted at `root`
+    pub fn from_options(root: &Path, options: &ScanOptions) -> Self {
+        ExclusionRules {
+            skip_hidden: options.skip_hidden,
+            tidyignore: if options.respect_tidyignore {
+                IgnoreFile::load(&root.join(TIDYIGNORE_FILENAME))
+            } else {
+                None
+            },
+            gitignore: if options.respect_gitignore {
+                IgnoreFile::load(&root.join(GITIGNORE_FILENAME))
+            } else {
+                None
+            },
+            globs: options.exclude_patterns.clone().unwrap_or_default(),
+        }
+    }
+
+    /// Check whether a file is excluded
+    ///
+    /// `relative_path` uses `/` separators and is relative to the scan root.
+    /// Returns the reason and, for pattern-based rules, the matching pattern.
+    /// Rules are checked in order: hidden, tidyignore, gitignore, glob.
+    pub fn check(&self, relative_path: &str) -> Option<(SkipReason, Option<String>)> {
+        if self.skip_hidden && relative_path.split('/').any(|c| c.starts_with('.')) {
+            return Some((SkipReason::Hidden, None));
+        }
+
+        if let Some(pattern) = self
+            .tidyignore
+            .as_ref()
+            .and_then(|f| f.excluding_pattern(relative_path))
+        {
+            return Some((SkipReason::Tidyignore, Some(pattern.to_string())));
+        }
+
+        if let Some(pattern) = self
+            .gitignore
+            .as_ref()
+            .and_then(|f| f.excluding_pattern(relative_path))
+        {
+            return Some((SkipReason::Gitignore, Some(pattern.to_string())));
+        }
+
+        let file_name = relative_path.rsplit('/').next().unwrap_or(relative_path);
+        if let Some(glob) = self
+            .globs
+            .iter()
+            .find(|g| glob_match(g, relative_path) || (!g.contains('/') && glob_match(g, file_name)))
+        {
+            return Some((SkipReason::Glob, Some(glob.clone())));
+        }
+
+        None
+    }
+
+    /// Check whether a directory is excluded
+    ///
+    /// Like `check`, but directory-only ignore patterns (`build/`) also match
+    /// the directory itself.
+    pub fn check_dir(&self, relative_path: &str) -> Option<(SkipReason, Option<String>)> {
+        self.check(relative_path)
+            .or_else(|| self.check(&format!("{}/", relative_path)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_match_basic() {
+        assert!(glob_match("*.log", "debug.log"));
+        assert!(!glob_match("*.log", "logs/debug.log"));
+        assert!(glob_match("**/*.log", "logs/debug.log"));
+        assert!(glob_match("**/*.log", "debug.log"));
+        assert!(glob_match("file?.txt", "file1.txt"));
+        assert!(!glob_match("file?.txt", "file10.txt"));
+        assert!(glob_match("img[0-9].jpg", "img7.jpg"));
+        assert!(!glob_match("img[!0-9].jpg", "img7.jpg"));
+        assert!(glob_match("build/**", "build/out/app.js"));
+    }
+
+    #[test]
+    fn test_glob_match_many_stars_is_fast() {
+        // Exponential for a backtracking matcher
+        let pattern = "*a*a*a*a*a*a*a*a*a*a*a*a*a*a*a*a*b";
+        let path = "a".repeat(200);
+        assert!(!glob_match(pattern, &path));
+        assert!(glob_match(pattern, &format!("{}b", path)));
+        assert!(!glob_match("**a**a**a**a**a**a**a**a**b", &"a/".repeat(100)));
+    }
+
+    #[test]
+    fn test_glob_match_double_star_requires_directory_boundary() {
+        assert!(glob_match("**/foo", "a/b/foo"));
+        assert!(!glob_match("**/foo", "afoo"));
+    }
+
+    #[test]
+    fn test_ignore_file_patterns() {
+        let ignore = IgnoreFile::parse("# comment\n*.tmp\nbuild/\n!keep.tmp\n/docs/*.md\n");
+
+        assert_eq!(ignore.excluding_pattern("notes.tmp"), Some("*.tmp"));
+        assert_eq!(ignore.excluding_pattern("sub/notes.tmp"), Some("*.tmp"));
+        assert_eq!(ignore.excluding_pattern("keep.tmp"), None);
+        assert_eq!(ignore.excluding_pattern("build/app.js"), Some("build/"));
+        assert_eq!(ignore.excluding_pattern("build"), None); // dir-only pattern, file named build
+        assert_eq!(ignore.excluding_pattern("docs/readme.md"), Some("/docs/*.md"));
+        assert_eq!(ignore.excluding_pattern("other/docs/readme.md"), None);
+        assert_eq!(ignore.excluding_pattern("photo.jpg"), None);
+    }
+
+    #[test]
+    fn test_exclusion_rules_order() {
+        let rules = ExclusionRules {
+            skip_hidden: true,
+            tidyignore: Some(IgnoreFile::parse("*.bak")),
+            gitignore: Some(IgnoreFile::parse("*.bak\n*.o")),
+            globs: vec!["*.o".to_string(), "cache/**".to_string()],
+        };
+
+        assert!(matches!(rules.check(".env"), Some((SkipReason::Hidden, None))));
+        assert!(matches!(rules.check("old.bak"), Some((SkipReason::Tidyignore, _))));
+        assert!(matches!(rules.check("main.o"), Some((SkipReason::Gitignore, _))));
+        assert!(matches!(rules.check("cache/a/b.bin"), Some((SkipReason::Glob, Some(_)))));
+        assert!(rules.check("photo.jpg").is_none());
+    }
+}
@@ -0,0 +1,316 @@
+// EXIF metadata reading for tidy-app GUI
+//
+// Minimal reader for the capture date and camera of JPEG and TIFF images. Only
+// a handful of ASCII tags are decoded, so no external EXIF dependency is needed.
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use std::fs::File;
+use std::io::Read;
+
+/// Maximum number of bytes read when looking for EXIF data (256KB)
+const MAX_EXIF_SCAN_BYTES: u64 = 256 * 1024;
+
+/// IFD0 tag: camera manufacturer
+const TAG_MAKE: u16 = 0x010F;
+/// IFD0 tag: camera model
+const TAG_MODEL: u16 = 0x0110;
+/// IFD0 tag: DateTime (last modification, used as a fallback)
+const TAG_DATE_TIME: u16 = 0x0132;
+/// IFD0 tag: pointer to the Exif sub-IFD
+const TAG_EXIF_IFD_POINTER: u16 = 0x8769;
+/// Exif tag: DateTimeOriginal (when the photo was taken)
+const TAG_DATE_TIME_ORIGINAL: u16 = 0x9003;
+/// Exif tag: DateTimeDigitized
+const TAG_DATE_TIME_DIGITIZED: u16 = 0x9004;
+
+/// TIFF field type for ASCII strings
+const TYPE_ASCII: u16 = 2;
+
+// =============================================================================
+// Public API
+// =============================================================================
+
+/// Key EXIF fields of an image
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExifSummary {
+    /// When the photo was taken (see `read_capture_date`)
+    pub capture_date: Option<DateTime<Utc>>,
+    /// Camera manufacturer (e.g. "Canon")
+    pub camera_make: Option<String>,
+    /// Camera model (e.g. "Canon EOS R6")
+    pub camera_model: Option<String>,
+}
+
+/// Read the capture date of an image from its EXIF metadata
+///
+/// Prefers DateTimeOriginal, then DateTimeDigitized, then DateTime.
+/// EXIF dates carry no timezone, so they are interpreted as UTC.
+/// Returns None if the file can't be read or has no usable date.
+pub fn read_capture_date(path: &str) -> Option<DateTime<Utc>> {
+    read_exif_summary(path)?.capture_date
+}
+
+/// Read the capture date and camera of an image
+///
+/// Returns None if the file can't be read or isn't a JPEG/TIFF with EXIF data.
+pub fn read_exif_summary(path: &str) -> Option<ExifSummary> {
+    let file = File::open(path).ok()?;
+    let mut data = Vec::new();
+    file.take(MAX_EXIF_SCAN_BYTES).read_to_end(&mut data).ok()?;
+
+    let tiff = if data.starts_with(&[0xFF, 0xD8]) {
+        find_jpeg_exif_segment(&data)?
+    } else if data.starts_with(b"II*\0") || data.starts_with(b"MM\0*") {
+        &data[..]
+    } else {
+        return None;
+    };
+
+    let reader = TiffReader::new(tiff)?;
+    let ifd0 = reader.u32_at(4)? as usize;
+    let text = |tag| {
+        let entry = reader.find_entry(ifd0, tag)?;
+        let value = reader.ascii_value(entry)?.trim();
+        (!value.is_empty()).then(|| value.to_string())
+    };
+
+    Some(ExifSummary {
+        capture_date: parse_tiff_capture_date(&reader, ifd0).map(|naive| naive.and_utc()),
+        camera_make: text(TAG_MAKE),
+        camera_model: text(TAG_MODEL),
+    })
+}
+
+// =============================================================================
+// JPEG / TIFF Parsing
+// =============================================================================
+
+/// Locate the TIFF data inside a JPEG APP1 "Exif" segment
+fn find_jpeg_exif_segment(data: &[u8]) -> Option<&[u8]> {
+    let mut pos = 2;
+
+    while pos + 4 <= data.len() {
+        if data[pos] != 0xFF {
+            return None;
+        }
+        let marker = data[pos + 1];
+
+        // Start of scan / end of image: no more metadata segments
+        if marker == 0xDA || marker == 0xD9 {
+            return None;
+        }
+
+        let length = u16::from_be_bytes([data[pos + 2], data[pos + 3]]) as usize;
+        let segment_end = (pos + 2 + length).min(data.len());
+        let segment = data.get(pos + 4..segment_end)?;
+
+        if marker == 0xE1 && segment.starts_with(b"Exif\0\0") {
+            return Some(&segment[6..]);
+        }
+
+        pos += 2 + length;
+    }
+
+    None
+}
+
+/// Byte-order aware reader over TIFF data
+struct TiffReader<'a> {
+    data: &'a [u8],
+    little_endian: bool,
+}
+
+impl<'a> TiffReader<'a> {
+    fn new(data: &'a [u8]) -> Option<Self> {
+        let little_endian = match data.get(0..2)? {
+            b"II" => true,
+            b"MM" => false,
+            _ => return None,
+        };
+        let reader = TiffReader { data, little_endian };
+        (reader.u16_at(2)? == 42).then_some(reader)
+    }
+
+    fn u16_at(&self, offset: usize) -> Option<u16> {
+        let bytes: [u8; 2] = self.data.get(offset..offset + 2)?.try_into().ok()?;
+        Some(if self.little_endian {
+            u16::from_le_bytes(bytes)
+        } else {
+            u16::from_be_bytes(bytes)
+        })
+    }
+
+    fn u32_at(&self, offset: usize) -> Option<u32> {
+        let bytes: [u8; 4] = self.data.get(offset..offset + 4)?.try_into().ok()?;
+        Some(if self.little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    }
+
+    /// Find an entry in the IFD at `ifd_offset`, returning the offset of the entry
+    fn find_entry(&self, ifd_offset: usize, tag: u16) -> Option<usize> {
+        let count = self.u16_at(ifd_offset)? as usize;
+        (0..count)
+            .map(|i| ifd_offset + 2 + i * 12)
+            .find(|&entry| self.u16_at(entry) == Some(tag))
+    }
+
+    /// Read an ASCII value from an IFD entry
+    fn ascii_value(&self, entry: usize) -> Option<&'a str> {
+        if self.u16_at(entry + 2)? != TYPE_ASCII {
+            return None;
+        }
+        let count = self.u32_at(entry + 4)? as usize;
+        // Values of 4 bytes or less are stored inline in the offset field
+        let start = if count <= 4 {
+            entry + 8
+        } else {
+            self.u32_at(entry + 8)? as usize
+        };
+        let bytes = self.data.get(start..start + count)?;
+        std::str::from_utf8(bytes).ok().map(|s| s.trim_end_matches('\0'))
+    }
+
+    fn date_value(&self, ifd_offset: usize, tag: u16) -> Option<NaiveDateTime> {
+        let entry = self.find_entry(ifd_offset, tag)?;
+        parse_exif_datetime(self.ascii_value(entry)?)
+    }
+}
+
+/// Extract the best available capture date from TIFF data
+fn parse_tiff_capture_date(reader: &TiffReader, ifd0: usize) -> Option<NaiveDateTime> {
+    let exif_date = reader
+        .find_entry(ifd0, TAG_EXIF_IFD_POINTER)
+        .and_then(|entry| reader.u32_at(entry + 8))
+        .and_then(|exif_ifd| {
+            let exif_ifd = exif_ifd as usize;
+            reader
+                .date_value(exif_ifd, TAG_DATE_TIME_ORIGINAL)
+                .or_else(|| reader.date_value(exif_ifd, TAG_DATE_TIME_DIGITIZED))
+        });
+
+    exif_date.or_else(|| reader.date_value(ifd0, TAG_DATE_TIME))
+}
+
+/// Parse an EXIF date string ("YYYY:MM:DD HH:MM:SS")
+fn parse_exif_datetime(value: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(value.trim(), "%Y:%m:%d %H:%M:%S").ok()
+}
+
+/// Build a minimal little-endian JPEG whose Exif IFD holds DateTimeOriginal
+#[cfg(test)]
+pub fn jpeg_with_capture_date(date: &str) -> Vec<u8> {
+    jpeg_with_exif(&[], &[(TAG_DATE_TIME_ORIGINAL, date)])
+}
+
+/// Build a minimal little-endian JPEG with ASCII tags in IFD0 and the Exif IFD
+#[cfg(test)]
+pub fn jpeg_with_exif(ifd0_tags: &[(u16, &str)], exif_tags: &[(u16, &str)]) -> Vec<u8> {
+    // Layout: header (8) | IFD0 | Exif IFD | strings
+    let ifd_size = |count: usize| 2 + 12 * count as u32 + 4;
+    let exif_ifd_offset = 8 + ifd_size(ifd0_tags.len() + 1);
+    let mut data_offset = exif_ifd_offset + ifd_size(exif_tags.len());
+    let mut strings = Vec::new();
+
+    let mut write_ifd = |tiff: &mut Vec<u8>, entries: &[(u16, &str)], pointer: Option<u32>| {
+        let count = entries.len() + usize::from(pointer.is_some());
+        tiff.extend_from_slice(&(count as u16).to_le_bytes());
+        for (tag, value) in entries {
+            let mut bytes = value.as_bytes().to_vec();
+            bytes.push(0);
+            tiff.extend_from_slice(&tag.to_le_bytes());
+            tiff.extend_from_slice(&TYPE_ASCII.to_le_bytes());
+            tiff.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+            if bytes.len() <= 4 {
+                bytes.resize(4, 0);
+                tiff.extend_from_slice(&bytes);
+            } else {
+                tiff.extend_from_slice(&data_offset.to_le_bytes());
+                data_offset += bytes.len() as u32;
+                strings.extend_from_slice(&bytes);
+            }
+        }
+        if let Some(offset) = pointer {
+            tiff.extend_from_slice(&TAG_EXIF_IFD_POINTER.to_le_bytes());
+            tiff.extend_from_slice(&4u16.to_le_bytes());
+            tiff.extend_from_slice(&1u32.to_le_bytes());
+            tiff.extend_from_slice(&offset.to_le_bytes());
+        }
+        tiff.extend_from_slice(&0u32.to_le_bytes());
+    };
+
+    let mut tiff = Vec::new();
+    tiff.extend_from_slice(b"II*\0");
+    tiff.extend_from_slice(&8u32.to_le_bytes());
+    write_ifd(&mut tiff, ifd0_tags, Some(exif_ifd_offset));
+    write_ifd(&mut tiff, exif_tags, None);
+    tiff.extend_from_slice(&strings);
+
+    let mut jpeg = vec![0xFF, 0xD8, 0xFF, 0xE1];
+    jpeg.extend_from_slice(&((2 + 6 + tiff.len()) as u16).to_be_bytes());
+    jpeg.extend_from_slice(b"Exif\0\0");
+    jpeg.extend_from_slice(&tiff);
+    jpeg.extend_from_slice(&[0xFF, 0xD9]);
+    jpeg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Datelike, Timelike};
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_read_capture_date_from_jpeg() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("photo.jpg");
+        fs::write(&path, jpeg_with_capture_date("2019:07:04 10:30:15")).unwrap();
+
+        let date = read_capture_date(path.to_str().unwrap()).unwrap();
+
+        assert_eq!((date.year(), date.month(), date.day()), (2019, 7, 4));
+        assert_eq!((date.hour(), date.minute(), date.second()), (10, 30, 15));
+    }
+
+    #[test]
+    fn test_read_capture_date_without_exif() {
+        let dir = TempDir::new().unwrap();
+        let plain_jpeg = dir.path().join("plain.jpg");
+        fs::write(&plain_jpeg, [0xFF, 0xD8, 0xFF, 0xD9]).unwrap();
+        let text = dir.path().join("notes.txt");
+        fs::write(&text, "not an image").unwrap();
+
+        assert!(read_capture_date(plain_jpeg.to_str().unwrap()).is_none());
+        assert!(read_capture_date(text.to_str().unwrap()).is_none());
+        assert!(read_capture_date("/nonexistent/photo.jpg").is_none());
+    }
+
+    #[test]
+    fn test_read_exif_summary_camera() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("photo.jpg");
+        let jpeg = jpeg_with_exif(
+            &[(TAG_MAKE, "Canon"), (TAG_MODEL, "Canon EOS R6")],
+            &[(TAG_DATE_TIME_ORIGINAL, "2024:06:01 09:15:00")],
+        );
+        fs::write(&path, jpeg).unwrap();
+
+        let summary = read_exif_summary(path.to_str().unwrap()).unwrap();
+
+        assert_eq!(summary.camera_make.as_deref(), Some("Canon"));
+        assert_eq!(summary.camera_model.as_deref(), Some("Canon EOS R6"));
+        assert_eq!(summary.capture_date.unwrap().day(), 1);
+    }
+
+    #[test]
+    fn test_read_capture_date_rejects_invalid_date() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("photo.jpg");
+        fs::write(&path, jpeg_with_capture_date("0000:00:00 00:00:00")).unwrap();
+
+        assert!(read_capture_date(path.to_str().unwrap()).is_none());
+    }
+}
@@ -5,11 +5,16 @@
 
 use serde::{Deserialize, Serialize};
 use std::fs;
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 use ts_rs::TS;
 
-use crate::commands::rename::{PreviewSummary, RenamePreview, RenameProposal};
-use crate::commands::scanner::{FileCategory, FileInfo};
+use crate::commands::rename::{
+    cycle_temp_path, plan_rename_groups, will_rename, PreviewSummary, RenameChainKind,
+    RenamePreview, RenameProposal,
+};
+use crate::commands::scanner::{FileCategory, FileInfo, SkippedFile};
+use crate::commands::security::validate_scan_path;
 
 // =============================================================================
 // Export Format Types
@@ -27,6 +32,18 @@ pub enum ExportFormat {
     Csv,
 }
 
+/// Target shell for rename script export
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, TS, Default, PartialEq)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "lowercase")]
+pub enum ScriptShell {
+    /// Bash script with `mkdir -p` and `mv` (default)
+    #[default]
+    Bash,
+    /// PowerShell script with `New-Item` and `Move-Item`
+    Powershell,
+}
+
 // =============================================================================
 // Error Types
 // =============================================================================
@@ -67,6 +84,9 @@ pub struct ExportScanResult {
     pub files: Vec<FileInfo>,
     pub statistics: ExportStatistics,
     pub scanned_at: String,
+    /// Files skipped by the scan, with reasons (only when requested)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skipped: Option<Vec<SkippedFile>>,
 }
 
 /// Preview section of export
@@ -99,6 +119,12 @@ pub struct ExportInput {
     /// Export format (default: JSON)
     #[serde(default)]
     pub format: ExportFormat,
+    /// Files skipped by the scan
+    #[serde(default)]
+    pub skipped: Vec<SkippedFile>,
+    /// Include skipped files and their skip reasons in the export (default: false)
+    #[serde(default)]
+    pub include_skipped: bool,
 }
 
 /// Result of save dialog
@@ -111,6 +137,26 @@ pub struct ExportResult {
     pub size: u64,
 }
 
+/// One line of a rename map: move `from` to `to`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RenameMapEntry {
+    pub from: String,
+    pub to: String,
+}
+
+/// Files written by `export_rename_map`
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct RenameMapExport {
+    /// JSON lines map of the planned renames
+    pub map_path: String,
+    /// JSON lines map undoing them, for manual undo
+    pub inverse_path: String,
+    /// Number of renames in each map
+    pub entries: usize,
+}
+
 // =============================================================================
 // Helper Functions
 // =============================================================================
@@ -133,6 +179,26 @@ fn compute_statistics(files: &[FileInfo]) -> ExportStatistics {
     }
 }
 
+/// Build the JSON export structure from the command input
+fn build_export_data(input: &ExportInput) -> ExportData {
+    ExportData {
+        scan_result: ExportScanResult {
+            folder: input.folder.clone(),
+            files: input.files.clone(),
+            statistics: compute_statistics(&input.files),
+            scanned_at: current_timestamp(),
+            skipped: input.include_skipped.then(|| input.skipped.clone()),
+        },
+        preview: input.preview.as_ref().map(|p| ExportPreview {
+            proposals: p.proposals.clone(),
+            summary: p.summary.clone(),
+            template_used: p.template_used.clone(),
+        }),
+        exported_at: current_timestamp(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+    }
+}
+
 /// Get current timestamp as ISO string
 fn current_timestamp() -> String {
     chrono::Utc::now().to_rfc3339()
@@ -148,16 +214,23 @@ fn csv_escape(s: &str) -> String {
 }
 
 /// Generate CSV content for files (FEAT-003)
-fn generate_files_csv(files: &[FileInfo]) -> String {
+///
+/// When skipped files are given, a Skip Reason and Error column is added and
+/// each skipped file gets its own row.
+fn generate_files_csv(files: &[FileInfo], skipped: Option<&[SkippedFile]>) -> String {
     let mut csv = String::new();
 
     // Header
-    csv.push_str("Path,Name,Extension,Size (bytes),Category,Created,Modified\n");
+    csv.push_str("Path,Name,Extension,Size (bytes),Category,Created,Modified");
+    if skipped.is_some() {
+        csv.push_str(",Skip Reason,Error");
+    }
+    csv.push('\n');
 
     // Data rows
     for file in files {
         csv.push_str(&format!(
-            "{},{},{},{},{},{},{}\n",
+            "{},{},{},{},{},{},{}",
             csv_escape(&file.path),
             csv_escape(&file.full_name),
             csv_escape(&file.extension),
@@ -166,6 +239,25 @@ fn generate_files_csv(files: &[FileInfo]) -> String {
             csv_escape(&file.created_at.to_rfc3339()),
             csv_escape(&file.modified_at.to_rfc3339()),
         ));
+        if skipped.is_some() {
+            csv.push_str(",,");
+        }
+        csv.push('\n');
+    }
+
+    // Skipped rows: only the path is known, plus why it was skipped
+    for file in skipped.unwrap_or_default() {
+        let path = std::path::Path::new(&file.path);
+        let name = path.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+        let extension = path.extension().map(|e| e.to_string_lossy()).unwrap_or_default();
+        csv.push_str(&format!(
+            "{},{},{},,,,,{},{}\n",
+            csv_escape(&file.path),
+            csv_escape(&name),
+            csv_escape(&extension),
+            csv_escape(file.reason.as_str()),
+            csv_escape(file.error.as_deref().unwrap_or_default()),
+        ));
     }
 
     csv
@@ -194,10 +286,216 @@ fn generate_preview_csv(preview: &RenamePreview) -> String {
     csv
 }
 
+/// Quote a path for the target shell
+///
+/// Both shells use single quotes, where no other character is special:
+/// bash closes and reopens the quote around an escaped quote, PowerShell
+/// doubles it.
+fn shell_quote(value: &str, shell: ScriptShell) -> String {
+    match shell {
+        ScriptShell::Bash => format!("'{}'", value.replace('\'', "'\\''")),
+        ScriptShell::Powershell => format!("'{}'", value.replace('\'', "''")),
+    }
+}
+
+fn script_mkdir(dir: &str, shell: ScriptShell) -> String {
+    match shell {
+        ScriptShell::Bash => format!("mkdir -p -- {}", shell_quote(dir, shell)),
+        ScriptShell::Powershell => format!(
+            "New-Item -ItemType Directory -Force -Path {} | Out-Null",
+            shell_quote(dir, shell)
+        ),
+    }
+}
+
+fn script_move(from: &str, to: &str, shell: ScriptShell) -> String {
+    match shell {
+        ScriptShell::Bash => format!("mv -- {} {}", shell_quote(from, shell), shell_quote(to, shell)),
+        ScriptShell::Powershell => format!(
+            "Move-Item -LiteralPath {} -Destination {}",
+            shell_quote(from, shell),
+            shell_quote(to, shell)
+        ),
+    }
+}
+
+/// Generate a shell script performing the ready renames of a preview
+///
+/// Directories that don't exist yet are created first. Chains run in
+/// dependency order and cycles (swaps) go through a temporary name, matching
+/// `execute_rename`.
+fn generate_rename_script(preview: &RenamePreview, shell: ScriptShell) -> String {
+    let proposals = &preview.proposals;
+    let mut lines: Vec<String> = Vec::new();
+
+    match shell {
+        ScriptShell::Bash => {
+            lines.push("#!/usr/bin/env bash".to_string());
+            lines.push("set -e".to_string());
+        }
+        ScriptShell::Powershell => {
+            lines.push("#!/usr/bin/env pwsh".to_string());
+            lines.push("$ErrorActionPreference = 'Stop'".to_string());
+        }
+    }
+    let count = proposals.iter().filter(|p| will_rename(p, None)).count();
+    lines.push(format!(
+        "# Generated by tidy-app on {}: {} rename(s)",
+        current_timestamp(),
+        count
+    ));
+
+    let new_dirs: std::collections::BTreeSet<String> = proposals
+        .iter()
+        .filter(|p| will_rename(p, None) && p.is_folder_move)
+        .filter_map(|p| std::path::Path::new(&p.proposed_path).parent())
+        .filter(|dir| !dir.as_os_str().is_empty() && !dir.exists())
+        .map(|dir| dir.to_string_lossy().to_string())
+        .collect();
+    if !new_dirs.is_empty() {
+        lines.push(String::new());
+        lines.extend(new_dirs.iter().map(|dir| script_mkdir(dir, shell)));
+    }
+
+    lines.push(String::new());
+    for entry in build_rename_map(preview) {
+        lines.push(script_move(&entry.from, &entry.to, shell));
+    }
+
+    lines.join("\n") + "\n"
+}
+
+/// Build the rename map of the ready proposals, in execution order
+///
+/// Chains are listed so each target is vacated before it is used. A cycle
+/// (swap) goes through a temporary name, matching `execute_rename`, so the map
+/// and its inverse can both be replayed as plain moves.
+fn build_rename_map(preview: &RenamePreview) -> Vec<RenameMapEntry> {
+    let proposals = &preview.proposals;
+    let entry = |from: &str, to: &str| RenameMapEntry {
+        from: from.to_string(),
+        to: to.to_string(),
+    };
+    let mut map = Vec::new();
+    for group in plan_rename_groups(proposals, None) {
+        let group_proposals: Vec<&RenameProposal> = group
+            .order
+            .iter()
+            .map(|&i| &proposals[i])
+            .filter(|p| will_rename(p, None))
+            .collect();
+
+        match (group.kind, group_proposals.split_first()) {
+            (Some(RenameChainKind::Cycle), Some((first, rest))) => {
+                let temp_path = cycle_temp_path(&first.original_path);
+                map.push(entry(&first.original_path, &temp_path));
+                map.extend(rest.iter().map(|p| entry(&p.original_path, &p.proposed_path)));
+                map.push(entry(&temp_path, &first.proposed_path));
+            }
+            _ => {
+                for proposal in group_proposals {
+                    map.push(entry(&proposal.original_path, &proposal.proposed_path));
+                }
+            }
+        }
+    }
+    map
+}
+
+/// Map undoing `map`: every entry swapped, in reverse order
+fn invert_rename_map(map: &[RenameMapEntry]) -> Vec<RenameMapEntry> {
+    map.iter()
+        .rev()
+        .map(|entry| RenameMapEntry {
+            from: entry.to.clone(),
+            to: entry.from.clone(),
+        })
+        .collect()
+}
+
+/// Serialize a rename map as JSON lines
+fn rename_map_lines(map: &[RenameMapEntry]) -> Result<String, ExportError> {
+    map.iter()
+        .map(|entry| {
+            serde_json::to_string(entry)
+                .map(|line| line + "\n")
+                .map_err(|e| ExportError::SerializeError(e.to_string()))
+        })
+        .collect()
+}
+
+/// "plan.jsonl" -> "plan.inverse.jsonl"
+fn inverse_map_path(path: &Path) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    match path.extension() {
+        Some(ext) => path.with_file_name(format!("{}.inverse.{}", stem, ext.to_string_lossy())),
+        None => path.with_file_name(format!("{}.inverse", stem)),
+    }
+}
+
+/// Resolve the map file path inside an existing, valid directory
+fn resolve_map_path(path: &str) -> Result<PathBuf, ExportError> {
+    let path = Path::new(path);
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| ExportError::WriteError(format!("Invalid map path: {}", path.display())))?;
+    let dir = path
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .ok_or_else(|| ExportError::WriteError("Map path must be absolute".to_string()))?;
+    let dir = validate_scan_path(&dir.to_string_lossy())
+        .map_err(|e| ExportError::WriteError(format!("Invalid output directory: {}", e)))?;
+    Ok(dir.join(file_name))
+}
+
 // =============================================================================
 // Tauri Commands
 // =============================================================================
 
+/// Write a rename map for external tools, plus its inverse for manual undo
+///
+/// Planning only: nothing is renamed. The map lists the ready proposals as
+/// JSON lines of `{"from", "to"}` in execution order; the inverse, written
+/// next to it as "<name>.inverse.<ext>", restores the original names when
+/// applied in its own order. The output directory must already exist.
+///
+/// Command name: export_rename_map (snake_case per architecture)
+#[tauri::command]
+pub async fn export_rename_map(
+    preview: RenamePreview,
+    path: String,
+) -> Result<RenameMapExport, ExportError> {
+    let map_path = resolve_map_path(&path)?;
+    let inverse_path = inverse_map_path(&map_path);
+    let map = build_rename_map(&preview);
+
+    for (target, entries) in [(&map_path, map.clone()), (&inverse_path, invert_rename_map(&map))] {
+        fs::write(target, rename_map_lines(&entries)?).map_err(|e| {
+            ExportError::WriteError(format!("Failed to write {}: {}", target.display(), e))
+        })?;
+    }
+
+    Ok(RenameMapExport {
+        map_path: map_path.to_string_lossy().to_string(),
+        inverse_path: inverse_path.to_string_lossy().to_string(),
+        entries: map.len(),
+    })
+}
+
+/// Generate a bash or PowerShell script equivalent of a rename batch
+///
+/// Export-only: the script is returned for review or version control and
+/// nothing is executed. Only ready proposals are included.
+///
+/// Command name: export_rename_script (snake_case per architecture)
+#[tauri::command]
+pub async fn export_rename_script(
+    preview: RenamePreview,
+    shell: Option<ScriptShell>,
+) -> Result<String, ExportError> {
+    Ok(generate_rename_script(&preview, shell.unwrap_or_default()))
+}
+
 /// Export scan results and preview to a file (JSON or CSV)
 ///
 /// Opens native file save dialog and writes export data.
@@ -216,21 +514,7 @@ pub async fn export_results(
     let (content, default_filename, file_filter) = match input.format {
         ExportFormat::Json => {
             // Build export data
-            let export_data = ExportData {
-                scan_result: ExportScanResult {
-                    folder: input.folder.clone(),
-                    files: input.files.clone(),
-                    statistics: compute_statistics(&input.files),
-                    scanned_at: current_timestamp(),
-                },
-                preview: input.preview.map(|p| ExportPreview {
-                    proposals: p.proposals,
-                    summary: p.summary,
-                    template_used: p.template_used,
-                }),
-                exported_at: current_timestamp(),
-                version: env!("CARGO_PKG_VERSION").to_string(),
-            };
+            let export_data = build_export_data(&input);
 
             let json_content = serde_json::to_string_pretty(&export_data)
                 .map_err(|e| ExportError::SerializeError(e.to_string()))?;
@@ -247,7 +531,8 @@ pub async fn export_results(
             let csv_content = if let Some(ref preview) = input.preview {
                 generate_preview_csv(preview)
             } else {
-                generate_files_csv(&input.files)
+                let skipped = input.include_skipped.then_some(input.skipped.as_slice());
+                generate_files_csv(&input.files, skipped)
             };
 
             let filename = format!(
@@ -307,7 +592,7 @@ pub async fn export_results(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::commands::scanner::MetadataCapability;
+    use crate::commands::scanner::{MetadataCapability, SkipReason};
     use chrono::Utc;
 
     fn mock_file(name: &str, category: FileCategory, size: u64) -> FileInfo {
@@ -323,6 +608,9 @@ mod tests {
             category,
             metadata_supported: true,
             metadata_capability: MetadataCapability::Full,
+            has_valid_dates: true,
+            metadata: None,
+            stable_id: None,
         }
     }
 
@@ -353,6 +641,7 @@ mod tests {
                 files: files.clone(),
                 statistics: stats,
                 scanned_at: "2026-01-01T12:00:00Z".to_string(),
+                skipped: None,
             },
             preview: None,
             exported_at: "2026-01-01T12:00:00Z".to_string(),
@@ -397,7 +686,7 @@ mod tests {
             mock_file("doc.pdf", FileCategory::Document, 5000),
         ];
 
-        let csv = generate_files_csv(&files);
+        let csv = generate_files_csv(&files, None);
 
         // Check header
         assert!(csv.starts_with("Path,Name,Extension,Size (bytes),Category,Created,Modified\n"));
@@ -411,6 +700,205 @@ mod tests {
         assert!(csv.contains("5000"));
     }
 
+    fn skipped_input(include_skipped: bool) -> ExportInput {
+        ExportInput {
+            folder: "/test".to_string(),
+            files: vec![mock_file("image1.jpg", FileCategory::Image, 1000)],
+            preview: None,
+            format: ExportFormat::Json,
+            skipped: vec![SkippedFile {
+                path: "/test/locked.pdf".to_string(),
+                reason: SkipReason::PermissionDenied,
+                error: Some("Permission denied (os error 13)".to_string()),
+                rule: None,
+            }],
+            include_skipped,
+        }
+    }
+
+    #[test]
+    fn test_export_includes_skipped_when_requested() {
+        let json = serde_json::to_string(&build_export_data(&skipped_input(true))).unwrap();
+
+        assert!(json.contains("\"skipped\":"));
+        assert!(json.contains("/test/locked.pdf"));
+        assert!(json.contains("\"permissionDenied\""));
+        assert!(json.contains("Permission denied (os error 13)"));
+
+        let input = skipped_input(true);
+        let csv = generate_files_csv(&input.files, Some(&input.skipped));
+        assert!(csv.starts_with(
+            "Path,Name,Extension,Size (bytes),Category,Created,Modified,Skip Reason,Error\n"
+        ));
+        assert!(csv.contains(
+            "/test/locked.pdf,locked.pdf,pdf,,,,,permissionDenied,Permission denied (os error 13)\n"
+        ));
+    }
+
+    #[test]
+    fn test_export_omits_skipped_by_default() {
+        let json = serde_json::to_string(&build_export_data(&skipped_input(false))).unwrap();
+
+        assert!(!json.contains("\"skipped\":"));
+        assert!(!json.contains("/test/locked.pdf"));
+
+        let input: ExportInput =
+            serde_json::from_str(r#"{"folder": "/test", "files": [], "preview": null}"#).unwrap();
+        assert!(!input.include_skipped);
+        assert!(input.skipped.is_empty());
+    }
+
+    fn script_preview(moves: &[(&str, &str)]) -> RenamePreview {
+        use crate::commands::rename::{FileActionType, ReorganizationMode, RenameStatus};
+        let name = |path: &str| path.rsplit('/').next().unwrap().to_string();
+        let proposals = moves
+            .iter()
+            .enumerate()
+            .map(|(i, (from, to))| RenameProposal {
+                id: i.to_string(),
+                original_path: from.to_string(),
+                original_name: name(from),
+                proposed_name: name(to),
+                proposed_path: to.to_string(),
+                status: RenameStatus::Ready,
+                issues: vec![],
+                metadata_sources: None,
+                is_folder_move: name(from) == name(to) || to.contains("/new dir/"),
+                destination_folder: None,
+                action_type: FileActionType::Rename,
+                conflict: None,
+            })
+            .collect();
+        crate::commands::rename::finalize_preview(proposals, String::new(), ReorganizationMode::RenameOnly)
+    }
+
+    #[test]
+    fn test_rename_script_bash_quoting() {
+        let preview = script_preview(&[(
+            "/tmp/tidy-nonexistent/it's a $file (1).jpg",
+            "/tmp/tidy-nonexistent/new dir/it's \"ok\" `now`.jpg",
+        )]);
+
+        let script = generate_rename_script(&preview, ScriptShell::Bash);
+
+        assert!(script.starts_with("#!/usr/bin/env bash\nset -e\n"));
+        assert!(script.contains("mkdir -p -- '/tmp/tidy-nonexistent/new dir'\n"));
+        assert!(script.contains(
+            "mv -- '/tmp/tidy-nonexistent/it'\\''s a $file (1).jpg' '/tmp/tidy-nonexistent/new dir/it'\\''s \"ok\" `now`.jpg'\n"
+        ));
+    }
+
+    #[test]
+    fn test_rename_script_powershell_quoting() {
+        let preview = script_preview(&[("C:/Photos/it's $(x).jpg", "C:/Photos/it's [1].jpg")]);
+
+        let script = generate_rename_script(&preview, ScriptShell::Powershell);
+
+        assert!(script.contains("$ErrorActionPreference = 'Stop'\n"));
+        assert!(script.contains(
+            "Move-Item -LiteralPath 'C:/Photos/it''s $(x).jpg' -Destination 'C:/Photos/it''s [1].jpg'\n"
+        ));
+    }
+
+    #[test]
+    fn test_rename_script_swap_uses_temporary_name() {
+        let preview = script_preview(&[("/tmp/x/a.jpg", "/tmp/x/b.jpg"), ("/tmp/x/b.jpg", "/tmp/x/a.jpg")]);
+
+        let script = generate_rename_script(&preview, ScriptShell::Bash);
+
+        let moves: Vec<&str> = script.lines().filter(|l| l.starts_with("mv ")).collect();
+        assert_eq!(moves.len(), 3);
+        assert!(moves[0].starts_with("mv -- '/tmp/x/a.jpg' '/tmp/x/.tidy-rename-"));
+        assert_eq!(moves[1], "mv -- '/tmp/x/b.jpg' '/tmp/x/a.jpg'");
+        assert!(moves[2].ends_with(" '/tmp/x/b.jpg'"));
+    }
+
+    /// Apply a rename map to a set of paths, like a tool running plain moves
+    fn apply_rename_map(paths: &mut std::collections::BTreeSet<String>, map: &[RenameMapEntry]) {
+        for entry in map {
+            assert!(paths.remove(&entry.from), "missing source {}", entry.from);
+            assert!(paths.insert(entry.to.clone()), "occupied target {}", entry.to);
+        }
+    }
+
+    #[test]
+    fn test_rename_map_and_inverse_round_trip() {
+        let mut preview = script_preview(&[
+            ("/tmp/x/a.jpg", "/tmp/x/b.jpg"),
+            ("/tmp/x/b.jpg", "/tmp/x/c.jpg"),
+            ("/tmp/x/d.jpg", "/tmp/x/sorted/d.jpg"),
+            ("/tmp/x/e.jpg", "/tmp/x/f.jpg"),
+        ]);
+        preview.proposals[3].status = crate::commands::rename::RenameStatus::Conflict;
+
+        let map = build_rename_map(&preview);
+        let inverse = invert_rename_map(&map);
+
+        // Only actionable proposals; the chain frees b.jpg before it is reused
+        assert_eq!(map.len(), 3);
+        assert!(map.iter().all(|entry| entry.from != "/tmp/x/e.jpg"));
+        let original: std::collections::BTreeSet<String> =
+            ["a", "b", "d"].iter().map(|n| format!("/tmp/x/{}.jpg", n)).collect();
+        let mut paths = original.clone();
+        apply_rename_map(&mut paths, &map);
+        assert!(paths.contains("/tmp/x/c.jpg") && paths.contains("/tmp/x/sorted/d.jpg"));
+        apply_rename_map(&mut paths, &inverse);
+        assert_eq!(paths, original);
+    }
+
+    #[test]
+    fn test_rename_map_swap_round_trip() {
+        let preview = script_preview(&[("/tmp/x/a.jpg", "/tmp/x/b.jpg"), ("/tmp/x/b.jpg", "/tmp/x/a.jpg")]);
+
+        let map = build_rename_map(&preview);
+        let inverse = invert_rename_map(&map);
+
+        // The swap hops through a temporary name, so no move lands on an occupied path
+        assert_eq!(map.len(), 3);
+        assert!(map[0].to.starts_with("/tmp/x/.tidy-rename-"));
+        assert_eq!(map[2].from, map[0].to);
+        // Track which file sits at each path while replaying plain moves
+        type Files = std::collections::HashMap<String, &'static str>;
+        let replay = |files: &mut Files, map: &[RenameMapEntry]| {
+            for entry in map {
+                let file = files.remove(&entry.from).expect("missing source");
+                let previous = files.insert(entry.to.clone(), file);
+                assert!(previous.is_none(), "occupied target {}", entry.to);
+            }
+        };
+        let original: Files =
+            Files::from([("/tmp/x/a.jpg".to_string(), "a"), ("/tmp/x/b.jpg".to_string(), "b")]);
+        let mut files = original.clone();
+        replay(&mut files, &map);
+        assert_eq!(files["/tmp/x/a.jpg"], "b");
+        assert_eq!(files["/tmp/x/b.jpg"], "a");
+        replay(&mut files, &inverse);
+        assert_eq!(files, original);
+    }
+
+    #[tokio::test]
+    async fn test_export_rename_map_writes_json_lines() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let preview = script_preview(&[("/tmp/x/a.jpg", "/tmp/x/b.jpg")]);
+        let path = dir.path().join("plan.jsonl").to_string_lossy().to_string();
+
+        let export = export_rename_map(preview, path).await.unwrap();
+
+        assert_eq!(export.entries, 1);
+        assert!(export.inverse_path.ends_with("plan.inverse.jsonl"));
+        let read = |path: &str| -> Vec<RenameMapEntry> {
+            let content = fs::read_to_string(path).unwrap();
+            content.lines().map(|line| serde_json::from_str(line).unwrap()).collect()
+        };
+        let map = read(&export.map_path);
+        assert_eq!(map[0].from, "/tmp/x/a.jpg");
+        assert_eq!(read(&export.inverse_path), invert_rename_map(&map));
+
+        let missing = dir.path().join("missing/plan.jsonl").to_string_lossy().to_string();
+        let preview = script_preview(&[]);
+        assert!(export_rename_map(preview, missing).await.is_err());
+    }
+
     #[test]
     fn test_export_format_default() {
         // Default should be JSON
@@ -0,0 +1,616 @@
+// Extension repair commands for tidy-app GUI
+// Command names use snake_case per architecture requirements
+//
+// Proposes extension-only renames: canonical spellings (".JPEG" -> ".jpg") and
+// corrections for files whose content doesn't match their extension. The name
+// part of each file is never touched. The same sniffing also corrects file
+// categories for organize mode.
+
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::rename::{
+    finalize_preview, FileActionType, RenameError, RenamePreview, RenameProposal, RenameStatus,
+    ReorganizationMode,
+};
+use super::scanner::{get_category_for_extension, FileCategory, FileInfo};
+use super::security::join_proposed_path;
+
+/// Number of leading bytes read for content sniffing
+const SNIFF_BYTES: u64 = 16;
+
+/// Largest file parsed whole to check for JSON content
+const MAX_JSON_SNIFF_BYTES: u64 = 1024 * 1024;
+
+/// Extensions that say little about the content; such files are also checked
+/// for text formats (JSON) that have no binary signature
+const GENERIC_EXTENSIONS: &[&str] = &["", "txt", "dat", "bin", "tmp", "download", "part"];
+
+/// Confidence of a correction from a format-specific signature (PNG, PDF...)
+const SIGNATURE_CONFIDENCE: f32 = 0.95;
+
+/// Confidence of a correction from a container signature (ZIP, MP4...) or
+/// parsed text, which back several kinds of files
+const CONTAINER_CONFIDENCE: f32 = 0.6;
+
+/// Alternative spellings mapped to their canonical extension
+const EXTENSION_ALIASES: &[(&str, &str)] = &[("jpeg", "jpg"), ("jpe", "jpg"), ("jfif", "jpg")];
+
+// =============================================================================
+// Types
+// =============================================================================
+
+/// Options for extension fixing
+#[derive(Debug, Clone, Deserialize, Default, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct FixExtensionsOptions {
+    /// Don't read file contents; only normalize the existing extension (default: false)
+    #[serde(default)]
+    pub skip_content_check: bool,
+    /// Keep alternative spellings such as ".jpeg" instead of canonicalizing them (default: false)
+    #[serde(default)]
+    pub keep_aliases: bool,
+}
+
+/// A file whose content belongs to another category than its extension says
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct CategoryCorrection {
+    pub path: String,
+    /// Category derived from the extension
+    pub previous_category: FileCategory,
+    /// Category derived from the content
+    pub category: FileCategory,
+    /// Extension matching the detected content (e.g., "png", "json")
+    pub detected_type: String,
+    /// How reliable the detection is (0.0 - 1.0)
+    pub confidence: f32,
+}
+
+/// Number and total size of the files with one extension
+#[derive(Debug, Clone, Serialize, PartialEq, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct ExtensionStats {
+    /// Lowercase extension without the dot ("" for files without one)
+    pub extension: String,
+    pub count: usize,
+    /// Total size in bytes
+    pub total_size: u64,
+}
+
+/// A file type recognized from its leading bytes
+#[derive(Debug, PartialEq)]
+struct ContentType {
+    /// Extension proposed when the current one doesn't match
+    canonical: &'static str,
+    /// Extensions that are valid for this content
+    extensions: &'static [&'static str],
+    /// Whether a mismatched extension is replaced, or only a missing one added.
+    /// Container formats (ZIP, MP4) back many specialized formats, so an
+    /// unknown extension on them is more likely right than wrong.
+    replaces_existing: bool,
+}
+
+impl ContentType {
+    fn accepts(&self, extension: &str) -> bool {
+        let extension = extension.to_lowercase();
+        self.extensions.contains(&extension.as_str())
+    }
+}
+
+// =============================================================================
+// Content Sniffing
+// =============================================================================
+
+const JPEG: ContentType = ContentType {
+    canonical: "jpg",
+    extensions: &["jpg", "jpeg", "jpe", "jfif"],
+    replaces_existing: true,
+};
+const PNG: ContentType = ContentType {
+    canonical: "png",
+    extensions: &["png"],
+    replaces_existing: true,
+};
+const GIF: ContentType = ContentType {
+    canonical: "gif",
+    extensions: &["gif"],
+    replaces_existing: true,
+};
+const WEBP: ContentType = ContentType {
+    canonical: "webp",
+    extensions: &["webp"],
+    replaces_existing: true,
+};
+const TIFF: ContentType = ContentType {
+    canonical: "tif",
+    // Camera raw formats are TIFF containers
+    extensions: &["tif", "tiff", "dng", "cr2", "nef", "arw"],
+    replaces_existing: true,
+};
+const HEIC: ContentType = ContentType {
+    canonical: "heic",
+    extensions: &["heic", "heif"],
+    replaces_existing: true,
+};
+const PDF: ContentType = ContentType {
+    canonical: "pdf",
+    extensions: &["pdf"],
+    replaces_existing: true,
+};
+const WAV: ContentType = ContentType {
+    canonical: "wav",
+    extensions: &["wav"],
+    replaces_existing: true,
+};
+const FLAC: ContentType = ContentType {
+    canonical: "flac",
+    extensions: &["flac"],
+    replaces_existing: true,
+};
+const MP3: ContentType = ContentType {
+    canonical: "mp3",
+    extensions: &["mp3"],
+    replaces_existing: false,
+};
+const MP4: ContentType = ContentType {
+    canonical: "mp4",
+    extensions: &["mp4", "m4v", "m4a", "mov", "3gp"],
+    replaces_existing: false,
+};
+const ZIP: ContentType = ContentType {
+    canonical: "zip",
+    extensions: &["zip", "docx", "xlsx", "pptx", "odt", "ods", "odp", "epub", "jar", "apk"],
+    replaces_existing: false,
+};
+const GZIP: ContentType = ContentType {
+    canonical: "gz",
+    extensions: &["gz", "tgz"],
+    replaces_existing: false,
+};
+const SEVEN_ZIP: ContentType = ContentType {
+    canonical: "7z",
+    extensions: &["7z"],
+    replaces_existing: true,
+};
+const RAR: ContentType = ContentType {
+    canonical: "rar",
+    extensions: &["rar"],
+    replaces_existing: true,
+};
+
+/// Identify a file type from its leading bytes
+fn detect_content_type(header: &[u8]) -> Option<&'static ContentType> {
+    let riff_form = header.get(8..12).filter(|_| header.starts_with(b"RIFF"));
+    let ftyp_brand = header.get(8..12).filter(|_| header.get(4..8) == Some(b"ftyp"));
+
+    let content_type = if header.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        &JPEG
+    } else if header.starts_with(&[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A]) {
+        &PNG
+    } else if header.starts_with(b"GIF87a") || header.starts_with(b"GIF89a") {
+        &GIF
+    } else if riff_form == Some(b"WEBP") {
+        &WEBP
+    } else if riff_form == Some(b"WAVE") {
+        &WAV
+    } else if header.starts_with(b"II*\0") || header.starts_with(b"MM\0*") {
+        &TIFF
+    } else if let Some(brand) = ftyp_brand {
+        match brand {
+            b"heic" | b"heix" | b"mif1" | b"msf1" => &HEIC,
+            _ => &MP4,
+        }
+    } else if header.starts_with(b"%PDF-") {
+        &PDF
+    } else if header.starts_with(b"fLaC") {
+        &FLAC
+    } else if header.starts_with(b"ID3") {
+        &MP3
+    } else if header.starts_with(b"PK\x03\x04") {
+        &ZIP
+    } else if header.starts_with(&[0x1F, 0x8B]) {
+        &GZIP
+    } else if header.starts_with(&[b'7', b'z', 0xBC, 0xAF, 0x27, 0x1C]) {
+        &SEVEN_ZIP
+    } else if header.starts_with(b"Rar!\x1A\x07") {
+        &RAR
+    } else {
+        return None;
+    };
+
+    Some(content_type)
+}
+
+/// Read the leading bytes of a file and identify its type
+fn sniff_content_type(path: &str) -> Option<&'static ContentType> {
+    let file = File::open(path).ok()?;
+    let mut header = Vec::with_capacity(SNIFF_BYTES as usize);
+    file.take(SNIFF_BYTES).read_to_end(&mut header).ok()?;
+    detect_content_type(&header)
+}
+
+/// Whether a file holds a JSON document (objects and arrays only)
+fn is_json_file(path: &str) -> bool {
+    let Ok(file) = File::open(path) else {
+        return false;
+    };
+    let mut content = Vec::new();
+    if file.take(MAX_JSON_SNIFF_BYTES + 1).read_to_end(&mut content).is_err()
+        || content.len() as u64 > MAX_JSON_SNIFF_BYTES
+    {
+        return false;
+    }
+
+    let starts_like_json = content
+        .iter()
+        .find(|b| !b.is_ascii_whitespace())
+        .is_some_and(|b| *b == b'{' || *b == b'[');
+    starts_like_json && serde_json::from_slice::<serde_json::Value>(&content).is_ok()
+}
+
+// =============================================================================
+// Category Correction
+// =============================================================================
+
+/// Compare a file's extension-based category with its content
+fn correct_category(file: &FileInfo) -> Option<CategoryCorrection> {
+    let previous_category = get_category_for_extension(&file.extension);
+
+    let (detected_type, confidence) = match sniff_content_type(&file.path) {
+        Some(content_type) if content_type.accepts(&file.extension) => return None,
+        Some(content_type) => {
+            let confidence = if content_type.replaces_existing {
+                SIGNATURE_CONFIDENCE
+            } else {
+                CONTAINER_CONFIDENCE
+            };
+            (content_type.canonical, confidence)
+        }
+        None if GENERIC_EXTENSIONS.contains(&file.extension.to_lowercase().as_str())
+            && is_json_file(&file.path) =>
+        {
+            ("json", CONTAINER_CONFIDENCE)
+        }
+        None => return None,
+    };
+
+    let category = get_category_for_extension(detected_type);
+    (category != previous_category).then(|| CategoryCorrection {
+        path: file.path.clone(),
+        previous_category,
+        category,
+        detected_type: detected_type.to_string(),
+        confidence,
+    })
+}
+
+// =============================================================================
+// Extension Resolution
+// =============================================================================
+
+/// Lowercase an extension and map known aliases to their canonical spelling
+fn canonical_extension(extension: &str, keep_aliases: bool) -> String {
+    let lower = extension.to_lowercase();
+    if keep_aliases {
+        return lower;
+    }
+    EXTENSION_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == lower)
+        .map(|(_, canonical)| canonical.to_string())
+        .unwrap_or(lower)
+}
+
+/// Decide the extension a file should have
+///
+/// Returns the extension and whether it was derived from the file content.
+fn resolve_extension(file: &FileInfo, options: &FixExtensionsOptions) -> (String, bool) {
+    let sniffed = if options.skip_content_check {
+        None
+    } else {
+        sniff_content_type(&file.path)
+    };
+
+    match sniffed {
+        Some(content_type)
+            if !content_type.accepts(&file.extension)
+                && (file.extension.is_empty() || content_type.replaces_existing) =>
+        {
+            (content_type.canonical.to_string(), true)
+        }
+        _ => (canonical_extension(&file.extension, options.keep_aliases), false),
+    }
+}
+
+/// Build an extension-only proposal for a file
+fn build_extension_proposal(file: &FileInfo, options: &FixExtensionsOptions) -> RenameProposal {
+    let (extension, from_content) = resolve_extension(file, options);
+
+    let proposed_name = if extension.is_empty() {
+        file.full_name.clone()
+    } else if file.extension.is_empty() {
+        format!("{}.{}", file.full_name, extension)
+    } else {
+        format!("{}.{}", file.name, extension)
+    };
+
+    let proposed_path = join_proposed_path(Path::new(&file.path).parent(), &proposed_name);
+
+    let (status, action_type) = if proposed_name == file.full_name {
+        (RenameStatus::NoChange, FileActionType::NoChange)
+    } else {
+        (RenameStatus::Ready, FileActionType::Rename)
+    };
+
+    RenameProposal {
+        id: Uuid::new_v4().to_string(),
+        original_path: file.path.clone(),
+        original_name: file.full_name.clone(),
+        proposed_name,
+        proposed_path,
+        status,
+        issues: vec![],
+        metadata_sources: from_content.then(|| vec!["content".to_string()]),
+        is_folder_move: false,
+        destination_folder: None,
+        action_type,
+        conflict: None,
+    }
+}
+
+/// Count files and sum their sizes per extension, most common first
+///
+/// Extensions are compared case-insensitively; ties are ordered by total size,
+/// then by extension.
+fn build_extension_histogram(files: &[FileInfo]) -> Vec<ExtensionStats> {
+    let mut by_extension: std::collections::HashMap<String, ExtensionStats> =
+        std::collections::HashMap::new();
+    for file in files {
+        let extension = file.extension.to_lowercase();
+        let stats = by_extension.entry(extension.clone()).or_insert(ExtensionStats {
+            extension,
+            count: 0,
+            total_size: 0,
+        });
+        stats.count += 1;
+        stats.total_size += file.size;
+    }
+
+    let mut histogram: Vec<ExtensionStats> = by_extension.into_values().collect();
+    histogram.sort_by(|a, b| {
+        b.count
+            .cmp(&a.count)
+            .then(b.total_size.cmp(&a.total_size))
+            .then(a.extension.cmp(&b.extension))
+    });
+    histogram
+}
+
+// =============================================================================
+// Tauri Commands
+// =============================================================================
+
+/// Count files and total sizes per extension, sorted by count
+///
+/// Describes what a scanned folder contains, e.g. to pick extension filters.
+/// Nothing is read from disk.
+///
+/// Command name: extension_histogram (snake_case per architecture)
+#[tauri::command]
+pub async fn extension_histogram(files: Vec<FileInfo>) -> Result<Vec<ExtensionStats>, RenameError> {
+    Ok(build_extension_histogram(&files))
+}
+
+/// Propose extension-only renames for files
+///
+/// Lowercases extensions, canonicalizes aliases (".jpeg" -> ".jpg") and, unless
+/// disabled, corrects wrong or missing extensions based on the file content.
+/// The result is a standard preview that can be passed to `execute_rename`.
+///
+/// Command name: fix_extensions (snake_case per architecture)
+#[tauri::command]
+pub async fn fix_extensions(
+    files: Vec<FileInfo>,
+    options: Option<FixExtensionsOptions>,
+) -> Result<RenamePreview, RenameError> {
+    let options = options.unwrap_or_default();
+
+    let proposals = files
+        .iter()
+        .map(|file| build_extension_proposal(file, &options))
+        .collect();
+
+    // No template is involved; the preview only changes extensions
+    Ok(finalize_preview(proposals, String::new(), ReorganizationMode::RenameOnly))
+}
+
+/// Find files whose content belongs to another category than their extension
+///
+/// Sniffs each file's leading bytes (and parses JSON for generic extensions
+/// such as ".txt" or ".dat") and returns only the files whose category
+/// changes, so organize mode can pick better destination folders.
+///
+/// Command name: recategorize_by_content (snake_case per architecture)
+#[tauri::command]
+pub async fn recategorize_by_content(
+    files: Vec<FileInfo>,
+) -> Result<Vec<CategoryCorrection>, RenameError> {
+    Ok(files.iter().filter_map(correct_category).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::exif::jpeg_with_capture_date;
+    use crate::commands::scanner::test_file_info;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn create_file(dir: &TempDir, full_name: &str, content: &[u8]) -> FileInfo {
+        let path = dir.path().join(full_name);
+        fs::write(&path, content).unwrap();
+        test_file_info(&path.to_string_lossy(), content.len() as u64)
+    }
+
+    #[tokio::test]
+    async fn test_fix_extensions_corrects_mislabeled_file() {
+        let dir = TempDir::new().unwrap();
+        let jpeg = jpeg_with_capture_date("2024:01:01 12:00:00");
+        let files = vec![
+            create_file(&dir, "holiday photo.png", &jpeg),
+            create_file(&dir, "scan", b"%PDF-1.7\n"),
+        ];
+
+        let preview = fix_extensions(files, None).await.unwrap();
+
+        assert_eq!(preview.proposals[0].proposed_name, "holiday photo.jpg");
+        assert_eq!(preview.proposals[0].metadata_sources, Some(vec!["content".to_string()]));
+        assert_eq!(preview.proposals[1].proposed_name, "scan.pdf");
+        assert_eq!(preview.summary.ready, 2);
+    }
+
+    #[tokio::test]
+    async fn test_fix_extensions_canonicalizes_jpeg() {
+        let dir = TempDir::new().unwrap();
+        let files = vec![create_file(&dir, "IMG_0042.JPEG", b"not really a jpeg")];
+
+        let preview = fix_extensions(files, None).await.unwrap();
+
+        let proposal = &preview.proposals[0];
+        assert_eq!(proposal.proposed_name, "IMG_0042.jpg");
+        assert!(proposal.proposed_path.ends_with("/IMG_0042.jpg"));
+        assert_eq!(proposal.status, RenameStatus::Ready);
+        assert!(proposal.metadata_sources.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_fix_extensions_keeps_correct_and_container_extensions() {
+        let dir = TempDir::new().unwrap();
+        let files = vec![
+            create_file(&dir, "notes.txt", b"plain text"),
+            create_file(&dir, "report.docx", b"PK\x03\x04rest"),
+            create_file(&dir, "package.whl", b"PK\x03\x04rest"),
+        ];
+
+        let preview = fix_extensions(files, None).await.unwrap();
+
+        assert_eq!(preview.summary.no_change, 3);
+    }
+
+    #[tokio::test]
+    async fn test_fix_extensions_options() {
+        let dir = TempDir::new().unwrap();
+        let jpeg = jpeg_with_capture_date("2024:01:01 12:00:00");
+        let files = vec![
+            create_file(&dir, "photo.png", &jpeg),
+            create_file(&dir, "photo.JPEG", b""),
+        ];
+        let options = FixExtensionsOptions {
+            skip_content_check: true,
+            keep_aliases: true,
+        };
+
+        let preview = fix_extensions(files, Some(options)).await.unwrap();
+
+        assert_eq!(preview.proposals[0].proposed_name, "photo.png");
+        assert_eq!(preview.proposals[1].proposed_name, "photo.jpeg");
+    }
+
+    #[tokio::test]
+    async fn test_fix_extensions_detects_conflicts() {
+        let dir = TempDir::new().unwrap();
+        create_file(&dir, "photo.jpg", b"existing");
+        let files = vec![create_file(&dir, "photo.jpeg", b"")];
+
+        let preview = fix_extensions(files, None).await.unwrap();
+
+        assert_eq!(preview.proposals[0].status, RenameStatus::Conflict);
+    }
+
+    #[test]
+    fn test_detect_content_type() {
+        assert_eq!(detect_content_type(b"\x89PNG\r\n\x1a\n...."), Some(&PNG));
+        assert_eq!(detect_content_type(b"RIFF\0\0\0\0WEBPVP8 "), Some(&WEBP));
+        assert_eq!(detect_content_type(b"\0\0\0\x18ftypheic"), Some(&HEIC));
+        assert_eq!(detect_content_type(b"\0\0\0\x18ftypisom"), Some(&MP4));
+        assert_eq!(detect_content_type(b"hello world"), None);
+        assert_eq!(detect_content_type(b""), None);
+    }
+
+    #[tokio::test]
+    async fn test_extension_histogram_counts_and_sizes() {
+        let dir = TempDir::new().unwrap();
+        let files = vec![
+            create_file(&dir, "a.jpg", &[0; 100]),
+            create_file(&dir, "b.JPG", &[0; 50]),
+            create_file(&dir, "c.jpg", &[0; 10]),
+            create_file(&dir, "notes.txt", &[0; 5]),
+            create_file(&dir, "report.pdf", &[0; 400]),
+            create_file(&dir, "draft.pdf", &[0; 100]),
+            create_file(&dir, "Makefile", &[0; 7]),
+        ];
+
+        let histogram = extension_histogram(files).await.unwrap();
+
+        let stats = |extension: &str, count: usize, total_size: u64| ExtensionStats {
+            extension: extension.to_string(),
+            count,
+            total_size,
+        };
+        assert_eq!(
+            histogram,
+            vec![
+                stats("jpg", 3, 160),
+                stats("pdf", 2, 500),
+                stats("", 1, 7),
+                stats("txt", 1, 5),
+            ]
+        );
+        assert!(extension_histogram(vec![]).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_recategorize_by_content_mismatch() {
+        let dir = TempDir::new().unwrap();
+        let files = vec![
+            create_file(&dir, "export.dat", b"\x89PNG\r\n\x1a\n...."),
+            create_file(&dir, "notes.txt", b"  {\"title\": \"Budget\", \"items\": [1, 2]}"),
+        ];
+
+        let corrections = recategorize_by_content(files).await.unwrap();
+
+        assert_eq!(corrections.len(), 2);
+        assert_eq!(corrections[0].previous_category, FileCategory::Other);
+        assert_eq!(corrections[0].category, FileCategory::Image);
+        assert_eq!(corrections[0].detected_type, "png");
+        assert_eq!(corrections[0].confidence, SIGNATURE_CONFIDENCE);
+        assert_eq!(corrections[1].previous_category, FileCategory::Document);
+        assert_eq!(corrections[1].category, FileCategory::Code);
+        assert_eq!(corrections[1].detected_type, "json");
+    }
+
+    #[tokio::test]
+    async fn test_recategorize_by_content_matching() {
+        let dir = TempDir::new().unwrap();
+        let jpeg = jpeg_with_capture_date("2024:01:01 12:00:00");
+        let files = vec![
+            create_file(&dir, "photo.jpg", &jpeg),
+            // Same category as the extension says: not a correction
+            create_file(&dir, "photo.png", &jpeg),
+            create_file(&dir, "report.docx", b"PK\x03\x04rest"),
+            create_file(&dir, "notes.txt", b"{ not json"),
+        ];
+
+        let corrections = recategorize_by_content(files).await.unwrap();
+
+        assert!(corrections.is_empty());
+    }
+}
@@ -0,0 +1,229 @@
+// Heuristic folder suggestion commands for tidy-app GUI
+// Command names use snake_case per architecture requirements
+//
+// Proposes a folder structure from the files themselves (dates, categories,
+// extensions and shared name prefixes), for users who don't want any file
+// sent to a model. The result is deterministic for a given set of files.
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+use ts_rs::TS;
+
+use super::rename::{apply_folder_pattern, FolderDateSource};
+use super::scanner::FileInfo;
+
+/// Minimum number of files for a folder to be worth suggesting
+const MIN_FOLDER_FILES: usize = 2;
+
+/// Minimum length of a shared name prefix ("img", "invoice", ...)
+const MIN_PREFIX_LEN: usize = 3;
+
+// =============================================================================
+// Types
+// =============================================================================
+
+/// How files were grouped into a suggested folder
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, PartialOrd, Ord, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "kebab-case")]
+pub enum FolderGrouping {
+    Year,
+    YearMonth,
+    Category,
+    Extension,
+    /// Files whose names start with the same word
+    NamePrefix,
+}
+
+/// A folder proposed for a group of files
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct FolderSuggestion {
+    /// Folder path relative to the destination root (e.g., "2024/03")
+    pub folder: String,
+    pub grouping: FolderGrouping,
+    /// Folder pattern producing this folder, usable as `folderPattern`
+    /// (`None` for name prefixes)
+    pub pattern: Option<String>,
+    /// Paths of the files that would go into the folder
+    pub files: Vec<String>,
+}
+
+// =============================================================================
+// Grouping
+// =============================================================================
+
+/// Folder patterns tried in order, with the grouping they stand for
+const FOLDER_PATTERNS: &[(FolderGrouping, &str)] = &[
+    (FolderGrouping::Year, "{year}"),
+    (FolderGrouping::YearMonth, "{year}/{month}"),
+    (FolderGrouping::Category, "{category}"),
+    (FolderGrouping::Extension, "{ext}"),
+];
+
+/// Leading word of a filename, lowercased ("IMG_0042" -> "img")
+fn name_prefix(name: &str) -> Option<String> {
+    let prefix = name
+        .chars()
+        .take_while(|c| c.is_alphabetic())
+        .collect::<String>()
+        .to_lowercase();
+    (prefix.chars().count() >= MIN_PREFIX_LEN).then_some(prefix)
+}
+
+/// Turn file groups into suggestions, skipping groups that are too small
+fn suggestions_from_groups(
+    groups: BTreeMap<String, Vec<String>>,
+    grouping: FolderGrouping,
+    pattern: Option<&str>,
+) -> impl Iterator<Item = FolderSuggestion> + '_ {
+    groups
+        .into_iter()
+        .filter(|(_, files)| files.len() >= MIN_FOLDER_FILES)
+        .map(move |(folder, files)| FolderSuggestion {
+            folder,
+            grouping,
+            pattern: pattern.map(str::to_string),
+            files,
+        })
+}
+
+/// Suggest folders for a set of files without any model
+///
+/// A pattern is only suggested when it splits the files into at least two
+/// folders; putting everything in "2024" organizes nothing. Suggestions are
+/// ordered by grouping, then by size (largest first) and folder name.
+fn build_heuristic_suggestions(files: &[FileInfo]) -> Vec<FolderSuggestion> {
+    let mut suggestions = Vec::new();
+
+    for &(grouping, pattern) in FOLDER_PATTERNS {
+        let mut groups: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for file in files {
+            let folder = apply_folder_pattern(file, pattern, FolderDateSource::Modified);
+            if !folder.is_empty() {
+                groups.entry(folder).or_default().push(file.path.clone());
+            }
+        }
+        if groups.len() >= 2 {
+            suggestions.extend(suggestions_from_groups(groups, grouping, Some(pattern)));
+        }
+    }
+
+    let mut prefixes: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for file in files {
+        if let Some(prefix) = name_prefix(&file.name) {
+            prefixes.entry(prefix).or_default().push(file.path.clone());
+        }
+    }
+    suggestions.extend(suggestions_from_groups(prefixes, FolderGrouping::NamePrefix, None));
+
+    suggestions.sort_by(|a, b| {
+        a.grouping
+            .cmp(&b.grouping)
+            .then(b.files.len().cmp(&a.files.len()))
+            .then_with(|| a.folder.cmp(&b.folder))
+    });
+    suggestions
+}
+
+// =============================================================================
+// Tauri Commands
+// =============================================================================
+
+/// Suggest a folder structure from file dates, categories, extensions and
+/// shared name prefixes (no AI)
+///
+/// Each suggestion lists the files that would go into the folder and, for
+/// date, category and extension groupings, the folder pattern to organize
+/// them with.
+///
+/// Command name: suggest_folders_heuristic (snake_case per architecture)
+#[tauri::command]
+pub async fn suggest_folders_heuristic(
+    files: Vec<FileInfo>,
+) -> Result<Vec<FolderSuggestion>, String> {
+    Ok(build_heuristic_suggestions(&files))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::scanner::test_file_info;
+    use chrono::{DateTime, TimeZone, Utc};
+
+    fn file(name: &str, modified_at: DateTime<Utc>) -> FileInfo {
+        FileInfo {
+            created_at: modified_at,
+            modified_at,
+            ..test_file_info(&format!("/photos/{}.jpg", name), 1024)
+        }
+    }
+
+    fn date(year: i32, month: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(year, month, 10, 12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_name_prefix() {
+        assert_eq!(name_prefix("IMG_0042"), Some("img".to_string()));
+        assert_eq!(name_prefix("invoice-march"), Some("invoice".to_string()));
+        assert_eq!(name_prefix("2024-03-01"), None);
+        assert_eq!(name_prefix("ab_1"), None);
+    }
+
+    #[test]
+    fn test_date_clustered_files_yield_year_folders() {
+        let files = vec![
+            file("beach", date(2023, 7)),
+            file("sunset", date(2023, 8)),
+            file("party", date(2024, 1)),
+            file("snow", date(2024, 1)),
+            file("lake", date(2024, 5)),
+        ];
+
+        let suggestions = build_heuristic_suggestions(&files);
+
+        let years: Vec<_> = suggestions
+            .iter()
+            .filter(|s| s.grouping == FolderGrouping::Year)
+            .map(|s| (s.folder.as_str(), s.files.len()))
+            .collect();
+        assert_eq!(years, vec![("2024", 3), ("2023", 2)]);
+        assert!(suggestions
+            .iter()
+            .filter(|s| s.grouping == FolderGrouping::Year)
+            .all(|s| s.pattern.as_deref() == Some("{year}")));
+
+        // Only "2024/01" has enough files to be its own folder
+        let months: Vec<_> = suggestions
+            .iter()
+            .filter(|s| s.grouping == FolderGrouping::YearMonth)
+            .map(|s| s.folder.as_str())
+            .collect();
+        assert_eq!(months, vec!["2024/01"]);
+
+        // Every file is a JPEG image: splitting by category or extension is pointless
+        assert!(!suggestions
+            .iter()
+            .any(|s| matches!(s.grouping, FolderGrouping::Category | FolderGrouping::Extension)));
+    }
+
+    #[test]
+    fn test_shared_name_prefixes_yield_folders() {
+        let files = vec![
+            file("IMG_0001", date(2024, 1)),
+            file("IMG_0002", date(2024, 1)),
+            file("Invoice-march", date(2024, 1)),
+            file("vacation", date(2024, 1)),
+        ];
+
+        let suggestions = build_heuristic_suggestions(&files);
+
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].grouping, FolderGrouping::NamePrefix);
+        assert_eq!(suggestions[0].folder, "img");
+        assert_eq!(suggestions[0].pattern, None);
+        assert_eq!(suggestions[0].files.len(), 2);
+    }
+}
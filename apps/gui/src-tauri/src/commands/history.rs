@@ -3,18 +3,21 @@
 //
 // Command names use snake_case per architecture requirements
 
-use chrono::Utc;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
 use fs2::FileExt;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs::{self, File, OpenOptions};
 use std::io::{Read, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 use ts_rs::TS;
 use uuid::Uuid;
 
+use super::config::{get_config, Template};
 use super::error::{ErrorCategory, ErrorResponse};
 use super::rename::{BatchRenameResult, FileRenameResult, RenameOutcome};
+use super::scanner::FileInfo;
 
 // =============================================================================
 // Error Types
@@ -140,8 +143,15 @@ pub struct OperationHistoryEntry {
     pub files: Vec<FileHistoryRecord>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub directories_created: Option<Vec<String>>,
+    /// Existing files moved to the system trash to be replaced; undo doesn't
+    /// bring them back, they have to be restored from the trash
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trashed_files: Option<Vec<String>>,
     #[serde(default)]
     pub undone: bool,
+    /// Template pattern applied in this operation (if known)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template_used: Option<String>,
 }
 
 /// The history store containing all entries
@@ -176,6 +186,76 @@ pub struct UndoResult {
     pub errors: Vec<String>,
 }
 
+/// Result of undoing several operations at once
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct MultiUndoResult {
+    /// Whether every selected operation was fully undone
+    pub success: bool,
+    /// Per-operation results, newest first, up to and including a failure
+    pub results: Vec<UndoResult>,
+    /// Operation whose undo failed; older operations were left untouched
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stopped_at: Option<String>,
+    /// Number of selected operations not attempted after the failure
+    pub remaining: usize,
+}
+
+/// A scanned file with the operation that renamed or moved it to its current path
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct FileWithHistory {
+    pub file: FileInfo,
+    /// Whether the app previously renamed or moved the file to its current path
+    pub handled: bool,
+    /// Path of the file before that operation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_path: Option<String>,
+    /// History entry of the operation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub operation_id: Option<String>,
+    /// When the operation ran (RFC 3339)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub handled_at: Option<String>,
+}
+
+/// Files processed on the busiest day
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct BusiestDay {
+    /// Day in UTC (YYYY-MM-DD)
+    pub date: String,
+    pub files: usize,
+}
+
+/// Usage statistics aggregated across all history entries
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryStats {
+    pub total_operations: usize,
+    pub rename_operations: usize,
+    pub move_operations: usize,
+    pub undone_operations: usize,
+    /// Files successfully renamed in place
+    pub files_renamed: usize,
+    /// Files successfully moved to another folder
+    pub files_moved: usize,
+    pub files_failed: usize,
+    /// Successful files per lowercase extension ("" for none)
+    pub by_extension: HashMap<String, usize>,
+    /// Share of attempted files that succeeded (0.0 - 1.0)
+    pub success_rate: f64,
+    /// Share of attempted files that failed (0.0 - 1.0)
+    pub failure_rate: f64,
+    /// Day with the most files processed (earliest on ties)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub busiest_day: Option<BusiestDay>,
+}
+
 // =============================================================================
 // History File Path
 // =============================================================================
@@ -209,14 +289,17 @@ fn get_history_path() -> Result<PathBuf, HistoryError> {
 /// Uses shared lock to allow concurrent reads
 #[tauri::command]
 pub async fn load_history() -> Result<HistoryStore, HistoryError> {
-    let path = get_history_path()?;
+    read_history_at(&get_history_path()?)
+}
 
+/// Read the history file at `path` under a shared lock
+fn read_history_at(path: &Path) -> Result<HistoryStore, HistoryError> {
     if !path.exists() {
         return Ok(HistoryStore::default());
     }
 
     // Open file and acquire shared lock for reading
-    let file = File::open(&path)?;
+    let file = File::open(path)?;
     file.lock_shared()
         .map_err(|e| HistoryError::LockFailed(format!("Shared lock: {}", e)))?;
 
@@ -255,15 +338,21 @@ fn with_locked_history<F, T>(modify_fn: F) -> Result<T, HistoryError>
 where
     F: FnOnce(&mut HistoryStore) -> Result<T, HistoryError>,
 {
-    let path = get_history_path()?;
+    with_locked_history_at(&get_history_path()?, modify_fn)
+}
 
+/// [`with_locked_history`] on the history file at `path`
+fn with_locked_history_at<F, T>(path: &Path, modify_fn: F) -> Result<T, HistoryError>
+where
+    F: FnOnce(&mut HistoryStore) -> Result<T, HistoryError>,
+{
     // Open or create the file with read+write access
     let mut file = OpenOptions::new()
         .read(true)
         .write(true)
         .create(true)
         .truncate(false)
-        .open(&path)?;
+        .open(path)?;
 
     // Acquire exclusive lock for read-modify-write
     file.lock_exclusive()
@@ -310,23 +399,32 @@ fn determine_operation_type(_results: &[FileRenameResult]) -> OperationType {
     OperationType::Rename
 }
 
+/// History record of a single rename result
+fn history_record(r: &FileRenameResult) -> FileHistoryRecord {
+    FileHistoryRecord {
+        original_path: r.original_path.clone(),
+        new_path: r.new_path.clone(),
+        is_move_operation: false, // Will be updated when move tracking is added
+        success: r.outcome == RenameOutcome::Success,
+        error: r.error.clone(),
+    }
+}
+
 /// Create a history entry from a batch rename result
 pub fn create_entry_from_result(result: &BatchRenameResult) -> OperationHistoryEntry {
     let id = Uuid::new_v4().to_string();
     let timestamp = Utc::now().to_rfc3339();
 
-    let files: Vec<FileHistoryRecord> = result.results.iter().map(|r| {
-        FileHistoryRecord {
-            original_path: r.original_path.clone(),
-            new_path: r.new_path.clone(),
-            is_move_operation: false, // Will be updated when move tracking is added
-            success: r.outcome == RenameOutcome::Success,
-            error: r.error.clone(),
-        }
-    }).collect();
+    let files: Vec<FileHistoryRecord> = result.results.iter().map(history_record).collect();
 
     let operation_type = determine_operation_type(&result.results);
 
+    let directories_created = if result.directories_created.is_empty() {
+        None
+    } else {
+        Some(result.directories_created.clone())
+    };
+
     OperationHistoryEntry {
         id,
         timestamp,
@@ -336,12 +434,14 @@ pub fn create_entry_from_result(result: &BatchRenameResult) -> OperationHistoryE
             succeeded: result.summary.succeeded,
             skipped: result.summary.skipped,
             failed: result.summary.failed,
-            directories_created: None,
+            directories_created: directories_created.as_ref().map(Vec::len),
         },
         duration_ms: result.duration_ms,
         files,
-        directories_created: None,
+        directories_created,
+        trashed_files: (!result.trashed_files.is_empty()).then(|| result.trashed_files.clone()),
         undone: false,
+        template_used: None,
     }
 }
 
@@ -351,27 +451,124 @@ pub fn create_entry_from_result(result: &BatchRenameResult) -> OperationHistoryE
 #[tauri::command]
 pub async fn record_operation(
     result: BatchRenameResult,
+    template_used: Option<String>,
 ) -> Result<OperationHistoryEntry, HistoryError> {
     // Create new entry before acquiring lock
-    let entry = create_entry_from_result(&result);
+    let mut entry = create_entry_from_result(&result);
+    entry.template_used = template_used;
     let entry_clone = entry.clone();
 
     // Use atomic read-modify-write with file locking
     with_locked_history(move |store| {
-        // Prepend to entries (newest first)
-        store.entries.insert(0, entry_clone);
-
-        // MEM-P2-002: Prune old entries if we exceed the limit
-        if store.entries.len() > MAX_HISTORY_ENTRIES {
-            store.entries.truncate(MAX_HISTORY_ENTRIES);
-        }
-
+        upsert_entry(store, entry_clone);
         Ok(())
     })?;
 
     Ok(entry)
 }
 
+/// Replace the entry with the same ID, or prepend it (newest first)
+fn upsert_entry(store: &mut HistoryStore, entry: OperationHistoryEntry) {
+    if let Some(existing) = store.entries.iter_mut().find(|e| e.id == entry.id) {
+        *existing = entry;
+        return;
+    }
+    store.entries.insert(0, entry);
+
+    // MEM-P2-002: Prune old entries if we exceed the limit
+    if store.entries.len() > MAX_HISTORY_ENTRIES {
+        store.entries.truncate(MAX_HISTORY_ENTRIES);
+    }
+}
+
+/// History entry kept up to date while a batch runs
+///
+/// `execute_rename` with `record_incrementally` flushes the results after each
+/// file it moves, staging moves included, so a crash mid-batch still leaves an
+/// undoable entry.
+/// The entry is rewritten in place under the history file lock and completed
+/// by [`HistoryJournal::finish`].
+#[derive(Debug, Clone)]
+pub struct HistoryJournal {
+    entry_id: String,
+    started_at: DateTime<Utc>,
+    path: PathBuf,
+    template_used: Option<String>,
+}
+
+impl HistoryJournal {
+    /// Start a journal on the app's history file
+    pub fn open(template_used: Option<String>) -> Result<Self, HistoryError> {
+        Ok(Self::at(get_history_path()?, template_used))
+    }
+
+    /// Start a journal on the history file at `path`
+    pub fn at(path: PathBuf, template_used: Option<String>) -> Self {
+        Self {
+            entry_id: Uuid::new_v4().to_string(),
+            started_at: Utc::now(),
+            path,
+            template_used,
+        }
+    }
+
+    /// ID of the history entry written by this journal
+    pub fn entry_id(&self) -> &str {
+        &self.entry_id
+    }
+
+    /// Write the results processed so far as a partial entry
+    pub fn record(
+        &self,
+        results: &[FileRenameResult],
+        directories_created: Vec<String>,
+        trashed_files: Vec<String>,
+    ) -> Result<(), HistoryError> {
+        let count =
+            |outcome: RenameOutcome| results.iter().filter(|r| r.outcome == outcome).count();
+        let directories_created = (!directories_created.is_empty()).then_some(directories_created);
+        let entry = OperationHistoryEntry {
+            id: self.entry_id.clone(),
+            timestamp: self.started_at.to_rfc3339(),
+            operation_type: determine_operation_type(results),
+            file_count: results.len(),
+            summary: OperationSummary {
+                succeeded: count(RenameOutcome::Success),
+                skipped: count(RenameOutcome::Skipped),
+                failed: count(RenameOutcome::Failed),
+                directories_created: directories_created.as_ref().map(Vec::len),
+            },
+            duration_ms: (Utc::now() - self.started_at).num_milliseconds().max(0) as u64,
+            files: results.iter().map(history_record).collect(),
+            directories_created,
+            trashed_files: (!trashed_files.is_empty()).then_some(trashed_files),
+            undone: false,
+            template_used: self.template_used.clone(),
+        };
+        with_locked_history_at(&self.path, move |store| {
+            upsert_entry(store, entry);
+            Ok(())
+        })
+    }
+
+    /// Replace the partial entry with the entry for the completed batch
+    pub fn finish(
+        &self,
+        result: &BatchRenameResult,
+    ) -> Result<OperationHistoryEntry, HistoryError> {
+        let mut entry = create_entry_from_result(result);
+        entry.id = self.entry_id.clone();
+        entry.timestamp = self.started_at.to_rfc3339();
+        entry.template_used = self.template_used.clone();
+        let entry_clone = entry.clone();
+        with_locked_history_at(&self.path, move |store| {
+            upsert_entry(store, entry_clone);
+            Ok(())
+        })?;
+        Ok(entry)
+    }
+}
+
 // =============================================================================
 // Query Functions
 // =============================================================================
@@ -398,33 +595,13 @@ pub async fn get_history_count() -> Result<usize, HistoryError> {
 // Undo Functions
 // =============================================================================
 
-/// Undo an operation by restoring files to their original locations
-/// Uses file locking to prevent race conditions during the undone flag update
-#[tauri::command]
-pub async fn undo_operation(entry_id: String) -> Result<UndoResult, HistoryError> {
-    // Step 1: Load history and get entry info (with shared lock, released quickly)
-    let store = load_history().await?;
-
-    // Find the entry
-    let entry = store.entries
-        .iter()
-        .find(|e| e.id == entry_id)
-        .ok_or_else(|| HistoryError::EntryNotFound(entry_id.clone()))?;
-
-    // Check if already undone
-    if entry.undone {
-        return Err(HistoryError::UndoFailed("Operation already undone".to_string()));
-    }
-
-    // Clone file info so we can release the lock before file operations
-    let files_to_restore: Vec<_> = entry.files.clone();
-
-    // Step 2: Perform file operations (no lock held - potentially slow I/O)
+/// Restore the files of a history entry to their original locations
+fn restore_entry_files(entry: &OperationHistoryEntry) -> UndoResult {
     let mut files_restored = 0;
     let mut files_failed = 0;
     let mut errors: Vec<String> = Vec::new();
 
-    for file in &files_to_restore {
+    for file in &entry.files {
         if !file.success {
             // Skip files that weren't successfully renamed
             continue;
@@ -452,27 +629,140 @@ pub async fn undo_operation(entry_id: String) -> Result<UndoResult, HistoryError
         }
     }
 
-    // Step 3: Atomically mark entry as undone if at least some files were restored
-    if files_restored > 0 {
-        let entry_id_for_update = entry_id.clone();
-        with_locked_history(move |store| {
-            // Re-find the entry (store may have changed while we were doing file I/O)
-            if let Some(entry) = store.entries.iter_mut().find(|e| e.id == entry_id_for_update) {
-                entry.undone = true;
-            }
-            Ok(())
-        })?;
-    }
-
-    Ok(UndoResult {
+    UndoResult {
         success: files_failed == 0 && files_restored > 0,
-        entry_id,
+        entry_id: entry.id.clone(),
         files_restored,
         files_failed,
         errors,
+    }
+}
+
+/// Mark entries as undone
+fn mark_undone(entry_ids: Vec<String>) -> Result<(), HistoryError> {
+    if entry_ids.is_empty() {
+        return Ok(());
+    }
+    with_locked_history(move |store| {
+        // Re-find the entries (store may have changed while we were doing file I/O)
+        for entry in store.entries.iter_mut().filter(|e| entry_ids.contains(&e.id)) {
+            entry.undone = true;
+        }
+        Ok(())
     })
 }
 
+/// Undo an operation by restoring files to their original locations
+/// Uses file locking to prevent race conditions during the undone flag update
+#[tauri::command]
+pub async fn undo_operation(entry_id: String) -> Result<UndoResult, HistoryError> {
+    // Step 1: Load history and get entry info (with shared lock, released quickly)
+    let store = load_history().await?;
+
+    // Find the entry
+    let entry = store.entries
+        .iter()
+        .find(|e| e.id == entry_id)
+        .ok_or_else(|| HistoryError::EntryNotFound(entry_id.clone()))?;
+
+    // Check if already undone
+    if entry.undone {
+        return Err(HistoryError::UndoFailed("Operation already undone".to_string()));
+    }
+
+    // Step 2: Perform file operations (no lock held - potentially slow I/O)
+    let result = restore_entry_files(entry);
+
+    // Step 3: Atomically mark entry as undone if at least some files were restored
+    if result.files_restored > 0 {
+        mark_undone(vec![entry_id])?;
+    }
+
+    Ok(result)
+}
+
+/// Undo entries one by one, stopping at the first that doesn't fully succeed
+///
+/// Entries must be ordered newest first, so each undo sees the files as the
+/// following (older) operation left them. Stopping early avoids undoing older
+/// operations on top of a partially restored newer one.
+fn undo_entries(entries: &[OperationHistoryEntry]) -> MultiUndoResult {
+    let mut results = Vec::new();
+    let mut stopped_at = None;
+
+    for entry in entries {
+        let result = restore_entry_files(entry);
+        let failed = !result.success;
+        results.push(result);
+        if failed {
+            stopped_at = Some(entry.id.clone());
+            break;
+        }
+    }
+
+    MultiUndoResult {
+        success: stopped_at.is_none(),
+        remaining: entries.len() - results.len(),
+        results,
+        stopped_at,
+    }
+}
+
+/// Undo the selected entries and record which ones were undone
+async fn undo_selected(
+    select: impl Fn(&OperationHistoryEntry) -> bool,
+    limit: usize,
+) -> Result<MultiUndoResult, HistoryError> {
+    let store = load_history().await?;
+
+    // Entries are stored newest first
+    let entries: Vec<OperationHistoryEntry> = store
+        .entries
+        .into_iter()
+        .filter(|e| !e.undone && select(e))
+        .take(limit)
+        .collect();
+
+    let result = undo_entries(&entries);
+
+    // Same rule as undo_operation: an entry with restored files counts as undone
+    mark_undone(
+        result
+            .results
+            .iter()
+            .filter(|r| r.files_restored > 0)
+            .map(|r| r.entry_id.clone())
+            .collect(),
+    )?;
+
+    Ok(result)
+}
+
+/// Undo the `count` most recent operations not yet undone, newest first
+///
+/// Stops at the first operation that can't be fully undone and reports it,
+/// leaving older operations untouched.
+#[tauri::command]
+pub async fn undo_recent(count: usize) -> Result<MultiUndoResult, HistoryError> {
+    undo_selected(|_| true, count).await
+}
+
+/// Undo every operation recorded at or after `timestamp` (RFC 3339), newest first
+///
+/// Stops at the first operation that can't be fully undone and reports it,
+/// leaving older operations untouched.
+#[tauri::command]
+pub async fn undo_since(timestamp: String) -> Result<MultiUndoResult, HistoryError> {
+    let since = parse_timestamp(&timestamp)
+        .ok_or_else(|| HistoryError::UndoFailed(format!("Invalid timestamp: {}", timestamp)))?;
+
+    undo_selected(
+        |entry| parse_timestamp(&entry.timestamp).is_some_and(|t| t >= since),
+        usize::MAX,
+    )
+    .await
+}
+
 /// Check if an operation can be undone
 #[tauri::command]
 pub async fn can_undo_operation(entry_id: String) -> Result<bool, HistoryError> {
@@ -498,6 +788,196 @@ pub async fn clear_history() -> Result<(), HistoryError> {
     })
 }
 
+// =============================================================================
+// File Annotations
+// =============================================================================
+
+/// Mark files the app previously renamed or moved to their current path
+///
+/// Lets the UI show a "handled" badge and skip files when re-tidying a folder.
+/// Undone operations are ignored; the most recent operation wins.
+#[tauri::command]
+pub async fn annotate_with_history(files: Vec<FileInfo>) -> Result<Vec<FileWithHistory>, HistoryError> {
+    let store = load_history().await?;
+    Ok(annotate_files(files, &store.entries))
+}
+
+/// Annotate files from history entries (newest first, as stored)
+fn annotate_files(files: Vec<FileInfo>, entries: &[OperationHistoryEntry]) -> Vec<FileWithHistory> {
+    let mut by_new_path: HashMap<&str, (&OperationHistoryEntry, &FileHistoryRecord)> = HashMap::new();
+    for entry in entries.iter().filter(|e| !e.undone) {
+        for record in entry.files.iter().filter(|f| f.success) {
+            if let Some(new_path) = record.new_path.as_deref() {
+                by_new_path.entry(new_path).or_insert((entry, record));
+            }
+        }
+    }
+
+    files
+        .into_iter()
+        .map(|file| match by_new_path.get(file.path.as_str()) {
+            Some((entry, record)) => FileWithHistory {
+                handled: true,
+                previous_path: Some(record.original_path.clone()),
+                operation_id: Some(entry.id.clone()),
+                handled_at: Some(entry.timestamp.clone()),
+                file,
+            },
+            None => FileWithHistory {
+                file,
+                handled: false,
+                previous_path: None,
+                operation_id: None,
+                handled_at: None,
+            },
+        })
+        .collect()
+}
+
+// =============================================================================
+// Statistics
+// =============================================================================
+
+/// Aggregate usage statistics over the whole history
+///
+/// Undone operations are included; `undone_operations` counts them.
+#[tauri::command]
+pub async fn history_stats() -> Result<HistoryStats, HistoryError> {
+    let store = load_history().await?;
+    Ok(compute_history_stats(&store.entries))
+}
+
+/// Whether a record changed folder, not only name
+fn is_move(record: &FileHistoryRecord) -> bool {
+    let parent = |path: &str| Path::new(path).parent().map(Path::to_path_buf);
+    record.is_move_operation
+        || record
+            .new_path
+            .as_deref()
+            .is_some_and(|new_path| parent(new_path) != parent(&record.original_path))
+}
+
+/// Compute statistics from history entries
+fn compute_history_stats(entries: &[OperationHistoryEntry]) -> HistoryStats {
+    let mut stats = HistoryStats {
+        total_operations: entries.len(),
+        rename_operations: 0,
+        move_operations: 0,
+        undone_operations: entries.iter().filter(|e| e.undone).count(),
+        files_renamed: 0,
+        files_moved: 0,
+        files_failed: 0,
+        by_extension: HashMap::new(),
+        success_rate: 0.0,
+        failure_rate: 0.0,
+        busiest_day: None,
+    };
+    let mut files_per_day: BTreeMap<NaiveDate, usize> = BTreeMap::new();
+
+    for entry in entries {
+        match entry.operation_type {
+            OperationType::Rename => stats.rename_operations += 1,
+            OperationType::Move => stats.move_operations += 1,
+        }
+        if let Some(ts) = parse_timestamp(&entry.timestamp) {
+            *files_per_day.entry(ts.date_naive()).or_insert(0) += entry.file_count;
+        }
+
+        for record in &entry.files {
+            if !record.success {
+                stats.files_failed += 1;
+                continue;
+            }
+            if is_move(record) {
+                stats.files_moved += 1;
+            } else {
+                stats.files_renamed += 1;
+            }
+            let path = record.new_path.as_deref().unwrap_or(&record.original_path);
+            let extension = Path::new(path)
+                .extension()
+                .map(|ext| ext.to_string_lossy().to_lowercase())
+                .unwrap_or_default();
+            *stats.by_extension.entry(extension).or_insert(0) += 1;
+        }
+    }
+
+    let succeeded = stats.files_renamed + stats.files_moved;
+    let attempted = succeeded + stats.files_failed;
+    if attempted > 0 {
+        stats.success_rate = succeeded as f64 / attempted as f64;
+        stats.failure_rate = stats.files_failed as f64 / attempted as f64;
+    }
+
+    // max_by_key keeps the last maximum, so walk latest first to prefer the earliest day
+    stats.busiest_day = files_per_day
+        .iter()
+        .rev()
+        .max_by_key(|(_, files)| **files)
+        .map(|(date, files)| BusiestDay {
+            date: date.format("%Y-%m-%d").to_string(),
+            files: *files,
+        });
+
+    stats
+}
+
+// =============================================================================
+// Template Usage
+// =============================================================================
+
+/// Find templates that were never applied in recorded operations
+///
+/// Templates are matched against history by pattern (or ID). When `window_days`
+/// is set, only operations within that window count, and templates created
+/// inside the window are not reported since they haven't had time to be used.
+/// The default template is never reported.
+#[tauri::command]
+pub async fn find_unused_templates(window_days: Option<u32>) -> Result<Vec<Template>, HistoryError> {
+    let config = get_config()
+        .await
+        .map_err(|e| HistoryError::LoadFailed(format!("Failed to load config: {}", e)))?;
+    let store = load_history().await?;
+
+    let window_start = window_days.map(|days| Utc::now() - Duration::days(i64::from(days)));
+
+    Ok(compute_unused_templates(&config.templates, &store.entries, window_start))
+}
+
+/// Parse an RFC 3339 timestamp as stored in history and config
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+/// Compute unused templates from config templates and history entries
+fn compute_unused_templates(
+    templates: &[Template],
+    entries: &[OperationHistoryEntry],
+    window_start: Option<DateTime<Utc>>,
+) -> Vec<Template> {
+    let in_window = |timestamp: &str| match window_start {
+        Some(start) => parse_timestamp(timestamp).is_some_and(|ts| ts >= start),
+        None => true,
+    };
+
+    let used: HashSet<&str> = entries
+        .iter()
+        .filter(|entry| in_window(&entry.timestamp))
+        .filter_map(|entry| entry.template_used.as_deref())
+        .collect();
+
+    templates
+        .iter()
+        .filter(|t| !t.is_default)
+        .filter(|t| !used.contains(t.pattern.as_str()) && !used.contains(t.id.as_str()))
+        // Templates created within the window haven't had a chance to be used yet
+        .filter(|t| window_start.is_none() || !in_window(&t.created_at))
+        .cloned()
+        .collect()
+}
+
 // =============================================================================
 // Tests
 // =============================================================================
@@ -519,6 +999,8 @@ mod tests {
                     new_name: Some("renamed1.jpg".to_string()),
                     outcome: RenameOutcome::Success,
                     error: None,
+                    error_kind: None,
+                    symlink: None,
                 },
             ],
             summary: BatchRenameSummary {
@@ -530,6 +1012,11 @@ mod tests {
             started_at: Utc::now(),
             completed_at: Utc::now(),
             duration_ms: 100,
+            cancelled: false,
+            directories_created: vec![],
+            stopped_on_permission_error: false,
+            trashed_files: vec![],
+            history_entry_id: None,
         }
     }
 
@@ -544,6 +1031,18 @@ mod tests {
         assert!(!entry.undone);
     }
 
+    #[test]
+    fn test_create_entry_records_directories_created() {
+        let mut result = create_test_result();
+        assert!(create_entry_from_result(&result).directories_created.is_none());
+
+        result.directories_created = vec!["/tmp/2024".to_string(), "/tmp/2024/07".to_string()];
+        let entry = create_entry_from_result(&result);
+
+        assert_eq!(entry.summary.directories_created, Some(2));
+        assert_eq!(entry.directories_created, Some(result.directories_created.clone()));
+    }
+
     #[test]
     fn test_determine_operation_type() {
         let results = vec![
@@ -555,10 +1054,286 @@ mod tests {
                 new_name: Some("renamed1.jpg".to_string()),
                 outcome: RenameOutcome::Success,
                 error: None,
+                error_kind: None,
+                symlink: None,
             },
         ];
 
         let op_type = determine_operation_type(&results);
         assert_eq!(op_type, OperationType::Rename);
     }
+
+    fn create_test_template(id: &str, pattern: &str, created_at: DateTime<Utc>) -> Template {
+        Template {
+            id: id.to_string(),
+            name: id.to_string(),
+            pattern: pattern.to_string(),
+            file_types: None,
+            is_default: false,
+            created_at: created_at.to_rfc3339(),
+            updated_at: created_at.to_rfc3339(),
+        }
+    }
+
+    fn create_test_entry(template_used: &str, timestamp: DateTime<Utc>) -> OperationHistoryEntry {
+        let mut entry = create_entry_from_result(&create_test_result());
+        entry.template_used = Some(template_used.to_string());
+        entry.timestamp = timestamp.to_rfc3339();
+        entry
+    }
+
+    #[test]
+    fn test_compute_unused_templates() {
+        let long_ago = Utc::now() - Duration::days(365);
+        let templates = vec![
+            create_test_template("date-prefix", "{date}_{name}", long_ago),
+            create_test_template("year-only", "{year}_{name}", long_ago),
+            create_test_template("never-used", "{name}_copy", long_ago),
+        ];
+        let entries = vec![
+            create_test_entry("{date}_{name}", Utc::now()),
+            // Matched by ID as well as pattern
+            create_test_entry("year-only", Utc::now()),
+        ];
+
+        let unused = compute_unused_templates(&templates, &entries, None);
+
+        assert_eq!(unused.len(), 1);
+        assert_eq!(unused[0].id, "never-used");
+    }
+
+    #[test]
+    fn test_compute_unused_templates_with_window() {
+        let long_ago = Utc::now() - Duration::days(365);
+        let templates = vec![
+            create_test_template("old-use", "{date}_{name}", long_ago),
+            create_test_template("recent-use", "{year}_{name}", long_ago),
+            create_test_template("brand-new", "{name}_new", Utc::now() - Duration::days(2)),
+        ];
+        let entries = vec![
+            create_test_entry("{date}_{name}", Utc::now() - Duration::days(200)),
+            create_test_entry("{year}_{name}", Utc::now() - Duration::days(5)),
+        ];
+
+        let window_start = Some(Utc::now() - Duration::days(30));
+        let unused = compute_unused_templates(&templates, &entries, window_start);
+
+        // Used only outside the window -> unused; recently created -> not flagged
+        let ids: Vec<&str> = unused.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["old-use"]);
+    }
+
+    #[test]
+    fn test_compute_unused_templates_skips_default() {
+        let mut template = create_test_template("default", "{name}", Utc::now() - Duration::days(365));
+        template.is_default = true;
+
+        let unused = compute_unused_templates(&[template], &[], None);
+
+        assert!(unused.is_empty());
+    }
+
+    /// Rename `from` to `to` inside `dir` and return the matching history entry
+    fn create_renamed_entry(dir: &tempfile::TempDir, from: &str, to: &str) -> OperationHistoryEntry {
+        let original = dir.path().join(from);
+        let renamed = dir.path().join(to);
+        fs::write(&original, from).unwrap();
+        fs::rename(&original, &renamed).unwrap();
+
+        let mut entry = create_entry_from_result(&create_test_result());
+        entry.files = vec![FileHistoryRecord {
+            original_path: original.to_string_lossy().to_string(),
+            new_path: Some(renamed.to_string_lossy().to_string()),
+            is_move_operation: false,
+            success: true,
+            error: None,
+        }];
+        entry
+    }
+
+    #[test]
+    fn test_undo_entries_two_operations() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let older = create_renamed_entry(&dir, "a.txt", "b.txt");
+        // The newer operation renamed the result of the older one
+        let mut newer = create_renamed_entry(&dir, "c.txt", "d.txt");
+        fs::rename(dir.path().join("b.txt"), dir.path().join("e.txt")).unwrap();
+        newer.files.push(FileHistoryRecord {
+            original_path: dir.path().join("b.txt").to_string_lossy().to_string(),
+            new_path: Some(dir.path().join("e.txt").to_string_lossy().to_string()),
+            is_move_operation: false,
+            success: true,
+            error: None,
+        });
+
+        let result = undo_entries(&[newer.clone(), older.clone()]);
+
+        assert!(result.success);
+        assert_eq!(result.results.len(), 2);
+        assert_eq!(result.results[0].entry_id, newer.id);
+        assert_eq!(result.results[0].files_restored, 2);
+        assert_eq!(result.remaining, 0);
+        assert!(dir.path().join("a.txt").exists());
+        assert!(dir.path().join("c.txt").exists());
+        assert!(!dir.path().join("e.txt").exists());
+    }
+
+    #[test]
+    fn test_undo_entries_stops_when_blocked() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let oldest = create_renamed_entry(&dir, "a.txt", "b.txt");
+        let blocked = create_renamed_entry(&dir, "c.txt", "d.txt");
+        let newest = create_renamed_entry(&dir, "e.txt", "f.txt");
+        // The renamed file of the middle operation was deleted since
+        fs::remove_file(dir.path().join("d.txt")).unwrap();
+
+        let result = undo_entries(&[newest, blocked.clone(), oldest]);
+
+        assert!(!result.success);
+        assert_eq!(result.results.len(), 2);
+        assert!(result.results[0].success);
+        assert_eq!(result.results[1].files_failed, 1);
+        assert_eq!(result.stopped_at, Some(blocked.id));
+        assert_eq!(result.remaining, 1);
+        // The oldest operation was left untouched
+        assert!(dir.path().join("b.txt").exists());
+        assert!(!dir.path().join("a.txt").exists());
+    }
+
+    fn scanned_file(path: &str) -> FileInfo {
+        crate::commands::scanner::test_file_info(path, 1024)
+    }
+
+    #[test]
+    fn test_annotate_files_marks_renamed_files() {
+        let older = create_entry_from_result(&create_test_result());
+        let mut newer = create_entry_from_result(&create_test_result());
+        newer.files[0].original_path = "/tmp/old-name.jpg".to_string();
+        let mut undone = create_entry_from_result(&create_test_result());
+        undone.files[0].new_path = Some("/tmp/restored.jpg".to_string());
+        undone.undone = true;
+
+        let files = vec![
+            scanned_file("/tmp/renamed1.jpg"),
+            scanned_file("/tmp/untouched.jpg"),
+            scanned_file("/tmp/restored.jpg"),
+        ];
+        let entries = vec![newer.clone(), undone, older];
+
+        let annotated = annotate_files(files, &entries);
+
+        assert!(annotated[0].handled);
+        assert_eq!(annotated[0].previous_path.as_deref(), Some("/tmp/old-name.jpg"));
+        assert_eq!(annotated[0].operation_id.as_deref(), Some(newer.id.as_str()));
+        assert_eq!(annotated[0].handled_at.as_deref(), Some(newer.timestamp.as_str()));
+        assert!(!annotated[1].handled);
+        assert!(annotated[1].operation_id.is_none());
+        // Undone operations no longer apply
+        assert!(!annotated[2].handled);
+    }
+
+    fn create_stats_entry(timestamp: &str, files: &[(&str, Option<&str>, bool)]) -> OperationHistoryEntry {
+        let mut entry = create_entry_from_result(&create_test_result());
+        entry.timestamp = timestamp.to_string();
+        entry.files = files
+            .iter()
+            .map(|(from, to, success)| FileHistoryRecord {
+                original_path: from.to_string(),
+                new_path: to.map(str::to_string),
+                is_move_operation: false,
+                success: *success,
+                error: None,
+            })
+            .collect();
+        entry.file_count = entry.files.len();
+        entry
+    }
+
+    #[test]
+    fn test_compute_history_stats() {
+        let mut undone = create_stats_entry(
+            "2024-03-01T09:00:00+00:00",
+            &[("/p/a.JPG", Some("/p/2024/a.jpg"), true), ("/p/b.txt", None, false)],
+        );
+        undone.undone = true;
+        let entries = vec![
+            create_stats_entry(
+                "2024-03-02T10:00:00+00:00",
+                &[("/p/c.pdf", Some("/p/report.pdf"), true), ("/p/README", Some("/p/readme"), true)],
+            ),
+            create_stats_entry(
+                "2024-03-01T18:00:00+00:00",
+                &[("/p/d.jpg", Some("/p/photo.jpg"), true)],
+            ),
+            undone,
+        ];
+
+        let stats = compute_history_stats(&entries);
+
+        assert_eq!(stats.total_operations, 3);
+        assert_eq!(stats.rename_operations, 3);
+        assert_eq!(stats.undone_operations, 1);
+        assert_eq!(stats.files_renamed, 3);
+        assert_eq!(stats.files_moved, 1);
+        assert_eq!(stats.files_failed, 1);
+        assert_eq!(stats.by_extension.get("jpg"), Some(&2));
+        assert_eq!(stats.by_extension.get("pdf"), Some(&1));
+        assert_eq!(stats.by_extension.get(""), Some(&1));
+        assert!(!stats.by_extension.contains_key("txt"));
+        assert!((stats.success_rate - 0.8).abs() < f64::EPSILON);
+        assert!((stats.failure_rate - 0.2).abs() < f64::EPSILON);
+        let busiest = stats.busiest_day.unwrap();
+        assert_eq!(busiest.date, "2024-03-01");
+        assert_eq!(busiest.files, 3);
+    }
+
+    #[test]
+    fn test_compute_history_stats_empty() {
+        let stats = compute_history_stats(&[]);
+
+        assert_eq!(stats.total_operations, 0);
+        assert_eq!(stats.success_rate, 0.0);
+        assert!(stats.busiest_day.is_none());
+    }
+
+    #[test]
+    fn test_history_journal_partial_entry_is_undoable() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let history_path = dir.path().join("history.json");
+        let journal = HistoryJournal::at(history_path.clone(), None);
+
+        // Rename two of three files, flushing after each, then "crash"
+        let mut results = Vec::new();
+        for name in ["a", "b"] {
+            let from = dir.path().join(format!("{}.txt", name));
+            let to = dir.path().join(format!("{}-renamed.txt", name));
+            fs::write(&from, name).unwrap();
+            fs::rename(&from, &to).unwrap();
+            results.push(FileRenameResult {
+                proposal_id: name.to_string(),
+                original_path: from.to_string_lossy().to_string(),
+                original_name: format!("{}.txt", name),
+                new_path: Some(to.to_string_lossy().to_string()),
+                new_name: Some(format!("{}-renamed.txt", name)),
+                outcome: RenameOutcome::Success,
+                error: None,
+                error_kind: None,
+                symlink: None,
+            });
+            journal.record(&results, vec![], vec![]).unwrap();
+        }
+
+        let store = read_history_at(&history_path).unwrap();
+        assert_eq!(store.entries.len(), 1);
+        let entry = &store.entries[0];
+        assert_eq!(entry.id, journal.entry_id());
+        assert_eq!(entry.file_count, 2);
+        assert_eq!(entry.summary.succeeded, 2);
+
+        let undo = restore_entry_files(entry);
+        assert!(undo.success);
+        assert_eq!(undo.files_restored, 2);
+        assert!(dir.path().join("a.txt").exists());
+        assert!(dir.path().join("b.txt").exists());
+    }
 }
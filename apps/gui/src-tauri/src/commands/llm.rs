@@ -8,11 +8,22 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::{RwLock, Semaphore};
+use tokio::sync::{RwLock, Semaphore, SemaphorePermit};
 use lazy_static::lazy_static;
 use tauri::Emitter;
+use uuid::Uuid;
 
+use super::metadata::{extract_file_metadata, extract_pdf_text};
+use super::rename::{
+    finalize_preview, sanitize_filename, strip_diacritics, FileActionType, RenamePreview,
+    RenameProposal, RenameStatus, ReorganizationMode,
+};
 use super::secrets::retrieve_secret;
+use super::scanner::{
+    get_category_for_extension, should_report_progress, CancellationToken, FileCategory,
+    ScanState, SessionKind,
+};
+use super::security::join_proposed_path;
 
 /// Secret key identifier for OpenAI API key (SEC-004)
 const OPENAI_API_KEY_SECRET: &str = "openai_api_key";
@@ -20,6 +31,11 @@ const OPENAI_API_KEY_SECRET: &str = "openai_api_key";
 /// Retrieve OpenAI API key from secure storage (SEC-004)
 /// Falls back to config value for migration compatibility
 async fn get_openai_api_key(config_key: &str) -> String {
+    // An API key from the environment wins over stored ones (env > file)
+    if let Some(key) = env_var(OPENAI_API_KEY_ENV) {
+        return key;
+    }
+
     // Try to retrieve from secure storage first
     match retrieve_secret(OPENAI_API_KEY_SECRET.to_string()).await {
         Ok(key) if !key.is_empty() => key,
@@ -53,6 +69,14 @@ lazy_static! {
     static ref ANALYSIS_CACHE: RwLock<HashMap<String, CacheEntry>> = RwLock::new(HashMap::new());
     /// Semaphore to limit concurrent LLM requests (avoid overwhelming the server)
     static ref LLM_SEMAPHORE: Semaphore = Semaphore::new(3); // Max 3 concurrent requests
+    /// Permit reserved for interactive (single-file) requests, never used by batches
+    static ref INTERACTIVE_SEMAPHORE: Semaphore = Semaphore::new(1);
+    /// Ollama models confirmed installed this session, as (base URL, model)
+    static ref VERIFIED_OLLAMA_MODELS: RwLock<std::collections::HashSet<(String, String)>> =
+        RwLock::new(std::collections::HashSet::new());
+    /// Folder structure per base path, reused while the folders' mtimes are unchanged
+    static ref FOLDER_STRUCTURE_CACHE: std::sync::Mutex<HashMap<String, FolderStructureEntry>> =
+        std::sync::Mutex::new(HashMap::new());
 }
 
 /// Cache TTL (24 hours)
@@ -61,12 +85,51 @@ const CACHE_TTL_SECS: u64 = 24 * 60 * 60;
 /// Maximum content size to analyze (tokens ~ chars/4, target ~2000 tokens)
 const MAX_CONTENT_CHARS: usize = 8000;
 
+/// Bytes of a non-text file hashed for its content cache key
+const CONTENT_KEY_HEADER_BYTES: u64 = 64 * 1024;
+
+/// Marker between the path and the hash of a content cache key
+const CONTENT_KEY_TAG: &str = "content:";
+
+/// Maximum content read from a file before sampling it down to MAX_CONTENT_CHARS
+const MAX_SAMPLE_SOURCE_CHARS: usize = 32000;
+
+/// Text files larger than this are sampled from several places on disk
+/// instead of only their head (logs, CSV exports)
+const LARGE_TEXT_FILE_BYTES: u64 = MAX_SAMPLE_SOURCE_CHARS as u64 * 4;
+
+/// Relative positions of the windows read after the head of a large file
+const SAMPLE_WINDOW_POSITIONS: &[f64] = &[0.33, 0.66, 1.0];
+
+/// Files larger than this many times MAX_CONTENT_CHARS get a truncation warning
+/// (the prompt saw less than a quarter of the file)
+const HEAVY_TRUNCATION_FACTOR: u64 = 4;
+
+/// Rough prompt size (instructions, folder list) per request, in tokens
+const ESTIMATED_PROMPT_TOKENS: u64 = 400;
+
+/// Rough size of an image sent to a vision model, in tokens
+const ESTIMATED_IMAGE_TOKENS: u64 = 850;
+
+/// Suggestions below this confidence get a low-confidence warning
+const LOW_CONFIDENCE_WARNING_THRESHOLD: f32 = 0.5;
+
+/// Identical per-file errors seen this many times get one batch-level warning
+const REPEATED_ERROR_WARNING_MIN: usize = 3;
+
+/// Per-file error when no OpenAI API key is available
+const MISSING_API_KEY_ERROR: &str = "OpenAI API key not configured";
+
+/// Analysis sources whose suggestion was built from the (sampled) file content
+const CONTENT_SOURCES: &[&str] = &["ollama", "openai", "cache"];
+
 /// Maximum retries for rate-limited requests
 const MAX_RETRIES: u32 = 3;
 
 /// Base delay for exponential backoff (in milliseconds)
 const BASE_RETRY_DELAY_MS: u64 = 1000;
 
+
 // =============================================================================
 // Security: HTTPS Enforcement (SEC-001)
 // =============================================================================
@@ -111,11 +174,10 @@ fn validate_openai_url_security(url: &str) -> Result<(), String> {
 
 /// Check cache for existing result
 /// Uses read lock for better concurrency (multiple readers allowed)
-async fn get_cached_result(file_path: &str, content_hash: &str) -> Option<AiSuggestion> {
+async fn get_cached_result(key: &str) -> Option<AiSuggestion> {
     let cache = ANALYSIS_CACHE.read().await;
-    let key = format!("{}:{}", file_path, content_hash);
 
-    if let Some(entry) = cache.get(&key) {
+    if let Some(entry) = cache.get(key) {
         if entry.cached_at.elapsed().as_secs() < CACHE_TTL_SECS {
             return Some(entry.suggestion.clone());
         }
@@ -125,11 +187,10 @@ async fn get_cached_result(file_path: &str, content_hash: &str) -> Option<AiSugg
 
 /// Store result in cache
 /// Uses write lock (exclusive access required)
-async fn cache_result(file_path: &str, content_hash: &str, suggestion: &AiSuggestion) {
+async fn cache_result(key: &str, suggestion: &AiSuggestion) {
     let mut cache = ANALYSIS_CACHE.write().await;
-    let key = format!("{}:{}", file_path, content_hash);
 
-    cache.insert(key, CacheEntry {
+    cache.insert(key.to_string(), CacheEntry {
         suggestion: suggestion.clone(),
         cached_at: std::time::Instant::now(),
     });
@@ -166,6 +227,68 @@ fn hash_file_metadata(file_path: &str) -> Option<String> {
     Some(format!("{:x}", hasher.finish()))
 }
 
+/// Hash file contents for caching
+/// Text files hash the text analysis reads (see `read_analysis_text`), so any
+/// edit the model could see changes the key; other files hash their size and
+/// first bytes (images are never read whole just to build a key)
+fn hash_file_content(file_path: &str) -> Option<String> {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+    use std::io::Read;
+
+    if is_text_file(file_path) {
+        let (content, _) = read_analysis_text(file_path).ok()?;
+        return Some(hash_content(&content));
+    }
+
+    let file = std::fs::File::open(file_path).ok()?;
+    let size = file.metadata().ok()?.len();
+    let mut header = Vec::new();
+    file.take(CONTENT_KEY_HEADER_BYTES).read_to_end(&mut header).ok()?;
+
+    let mut hasher = DefaultHasher::new();
+    size.hash(&mut hasher);
+    header.hash(&mut hasher);
+    Some(format!("{:x}", hasher.finish()))
+}
+
+/// Content cache key of a path ("<path>:content:<hex hash>")
+fn content_cache_key(file_path: &str, hash: &str) -> String {
+    format!("{}:{}{}", file_path, CONTENT_KEY_TAG, hash)
+}
+
+/// Build the cache key for a file using the configured strategy
+///
+/// Both keys start with the path, so different files never share an entry.
+/// Content keys survive touches that leave the bytes alone; metadata keys
+/// change whenever the size or mtime changes. `remap_cache_paths` moves either
+/// kind to a file's new path.
+fn compute_cache_key(file_path: &str, strategy: &CacheKeyStrategy) -> Option<String> {
+    match strategy {
+        CacheKeyStrategy::Content => {
+            hash_file_content(file_path).map(|hash| content_cache_key(file_path, &hash))
+        }
+        CacheKeyStrategy::Metadata => {
+            hash_file_metadata(file_path).map(|hash| format!("{}:{}", file_path, hash))
+        }
+    }
+}
+
+/// Cache key of a file, using the configured strategy for its type
+///
+/// Only images and text files are cached; None for other files or when the
+/// file can't be read.
+fn analysis_cache_key(file_path: &str, config: &OllamaConfig) -> Option<String> {
+    let strategy = if is_image_file(file_path) {
+        &config.cache_keys.images
+    } else if is_analyzable_text(file_path, config) {
+        &config.cache_keys.text
+    } else {
+        return None;
+    };
+    compute_cache_key(file_path, strategy)
+}
+
 /// Calculate exponential backoff delay
 fn calculate_backoff_delay(attempt: u32) -> Duration {
     let delay_ms = BASE_RETRY_DELAY_MS * 2u64.pow(attempt);
@@ -220,10 +343,11 @@ const GOOD_FILENAME_PATTERNS: &[&str] = &[
     "presentation", "resume", "analyse", "revue", "bilan",
 ];
 
-/// Check if a filename appears to need renaming (pre-filter)
-/// Returns true if the file should be analyzed by AI, false if it can be skipped
-/// NOTE: This should NOT be used for images - images should always use vision model
-fn needs_ai_analysis(file_path: &str) -> (bool, Option<String>) {
+/// Check if a filename has clear signs of a bad name
+///
+/// Matches generic device/download names, UUIDs, random hex suffixes and
+/// mostly-numeric names. Names without these signals are not necessarily good.
+pub fn is_low_quality_filename(file_path: &str) -> bool {
     let filename = std::path::Path::new(file_path)
         .file_stem()
         .and_then(|s| s.to_str())
@@ -231,11 +355,9 @@ fn needs_ai_analysis(file_path: &str) -> (bool, Option<String>) {
 
     let filename_lower = filename.to_lowercase();
 
-    // Check for low-quality patterns - these NEED analysis
-    for pattern in LOW_QUALITY_PATTERNS {
-        if filename_lower.contains(pattern) {
-            return (true, None);
-        }
+    // Check for low-quality patterns
+    if LOW_QUALITY_PATTERNS.iter().any(|pattern| filename_lower.contains(pattern)) {
+        return true;
     }
 
     // Check for UUID-like patterns (xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx)
@@ -243,7 +365,7 @@ fn needs_ai_analysis(file_path: &str) -> (bool, Option<String>) {
         .map(|re| re.is_match(&filename_lower))
         .unwrap_or(false)
     {
-        return (true, None);
+        return true;
     }
 
     // Check for random hex/alphanumeric suffixes (e.g., "document_a8f3b2c1")
@@ -251,12 +373,117 @@ fn needs_ai_analysis(file_path: &str) -> (bool, Option<String>) {
         .map(|re| re.is_match(&filename_lower))
         .unwrap_or(false)
     {
-        return (true, None);
+        return true;
     }
 
     // Check for mostly digits (>50%)
     let digit_count = filename.chars().filter(|c| c.is_ascii_digit()).count();
-    if filename.len() > 4 && (digit_count as f32 / filename.len() as f32) > 0.5 {
+    filename.len() > 4 && (digit_count as f32 / filename.len() as f32) > 0.5
+}
+
+/// Minimum filename quality score for a file to be skipped in "only bad names" mode
+const GOOD_NAME_QUALITY_THRESHOLD: f32 = 0.6;
+
+/// Score how descriptive a filename is, from 0.0 (bad) to 1.0 (descriptive)
+///
+/// Low-quality names score 0. Other names start at 0.4 and gain points for
+/// length, several real words and known good naming patterns. Applies to
+/// every file type, images included.
+pub fn filename_quality_score(file_path: &str) -> f32 {
+    if is_low_quality_filename(file_path) {
+        return 0.0;
+    }
+
+    let filename = std::path::Path::new(file_path)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("");
+    let filename_lower = filename.to_lowercase();
+
+    let mut score: f32 = 0.4;
+    if filename.chars().count() >= 10 {
+        score += 0.2;
+    }
+    let word_count = filename
+        .split(|c: char| !c.is_alphabetic())
+        .filter(|word| word.chars().count() >= 3)
+        .count();
+    if word_count >= 2 {
+        score += 0.2;
+    }
+    if GOOD_FILENAME_PATTERNS.iter().any(|pattern| filename_lower.contains(pattern)) {
+        score += 0.2;
+    }
+
+    score.min(1.0)
+}
+
+/// Skip a file with a good name before any network call ("only bad names" mode)
+///
+/// Returns the skipped result when `only_bad_names` is set and the filename
+/// scores at or above the quality threshold, otherwise None.
+fn skip_good_name(file_path: &str, only_bad_names: bool) -> Option<FileAnalysisResult> {
+    if !only_bad_names || filename_quality_score(file_path) < GOOD_NAME_QUALITY_THRESHOLD {
+        return None;
+    }
+
+    Some(FileAnalysisResult::skipped(file_path, AnalysisSkipReason::GoodName, "good-name", None))
+}
+
+/// Result for a file not analyzed because LLM analysis is disabled
+fn disabled_result(file_path: String) -> FileAnalysisResult {
+    FileAnalysisResult::skipped(
+        file_path,
+        AnalysisSkipReason::Disabled,
+        "disabled",
+        Some("LLM analysis is disabled".to_string()),
+    )
+}
+
+/// Result for a file not analyzed because its batch was cancelled
+fn cancelled_result(file_path: String) -> FileAnalysisResult {
+    FileAnalysisResult::skipped(
+        file_path,
+        AnalysisSkipReason::Cancelled,
+        "cancelled",
+        Some("Analysis was cancelled".to_string()),
+    )
+}
+
+/// Skip a file whose extension is listed in `file_types.excluded_extensions`
+fn skip_excluded_type(file_path: &str, file_types: &LlmFileTypes) -> Option<FileAnalysisResult> {
+    let ext = std::path::Path::new(file_path)
+        .extension()
+        .and_then(|e| e.to_str())?;
+    let excluded = file_types
+        .excluded_extensions
+        .iter()
+        .any(|excluded| excluded.trim_start_matches('.').eq_ignore_ascii_case(ext));
+    if !excluded {
+        return None;
+    }
+
+    Some(FileAnalysisResult::skipped(
+        file_path,
+        AnalysisSkipReason::Filtered,
+        "filtered",
+        Some(format!("Extension .{} is excluded from analysis", ext)),
+    ))
+}
+
+/// Check if a filename appears to need renaming (pre-filter)
+/// Returns true if the file should be analyzed by AI, false if it can be skipped
+/// NOTE: This should NOT be used for images - images should always use vision model
+pub fn needs_ai_analysis(file_path: &str) -> (bool, Option<String>) {
+    let filename = std::path::Path::new(file_path)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("");
+
+    let filename_lower = filename.to_lowercase();
+
+    // Low-quality names NEED analysis
+    if is_low_quality_filename(file_path) {
         return (true, None);
     }
 
@@ -298,35 +525,150 @@ fn truncate_content_smart(content: &str, max_chars: usize) -> String {
     format!("{}\n\n[... truncated ...]\n\n{}", start, end)
 }
 
+/// Extensions sampled as source code
+const CODE_EXTENSIONS: &[&str] = &[
+    "js", "ts", "jsx", "tsx", "py", "rs", "go", "java", "kt", "swift", "c", "cpp",
+    "h", "hpp", "cs", "rb", "php", "sh", "bash", "zsh", "fish", "ps1", "sql",
+];
+
+/// Extensions sampled as Markdown-like prose
+const MARKDOWN_EXTENSIONS: &[&str] = &["md", "markdown", "rst"];
+
+/// Line prefixes of top-level definitions and doc comments kept when sampling code
+const CODE_OUTLINE_PREFIXES: &[&str] = &[
+    "///", "//!", "/**", "\"\"\"", "#[", "@",
+    "fn ", "pub ", "async ", "def ", "class ", "function ", "export ", "struct ",
+    "enum ", "impl ", "trait ", "interface ", "type ", "func ", "public ", "module ",
+];
+
+/// Sample content for the prompt based on the file type
+///
+/// Code keeps the top of the file (imports, module docs) plus top-level
+/// signatures; Markdown keeps the first heading and intro plus the outline.
+/// Other files use the generic head/tail truncation.
+fn sample_content(content: &str, file_path: &str, max_chars: usize) -> String {
+    let ext = std::path::Path::new(file_path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .unwrap_or_default();
+
+    if CODE_EXTENSIONS.contains(&ext.as_str()) {
+        sample_head_and_outline(content, max_chars, |line| {
+            !line.starts_with(char::is_whitespace)
+                && CODE_OUTLINE_PREFIXES.iter().any(|p| line.starts_with(p))
+        })
+    } else if MARKDOWN_EXTENSIONS.contains(&ext.as_str()) {
+        sample_head_and_outline(content, max_chars, |line| line.trim_start().starts_with('#'))
+    } else {
+        truncate_content_smart(content, max_chars)
+    }
+}
+
+/// Keep whole lines from the top of the content (up to 2/3 of the budget),
+/// then fill the rest with later lines matching `is_outline_line`
+fn sample_head_and_outline(
+    content: &str,
+    max_chars: usize,
+    is_outline_line: impl Fn(&str) -> bool,
+) -> String {
+    const SEPARATOR: &str = "\n\n[... truncated ...]\n\n";
+
+    if content.chars().count() <= max_chars {
+        return content.to_string();
+    }
+
+    let head_budget = max_chars * 2 / 3;
+    let mut lines = content.lines().peekable();
+    let mut head: Vec<&str> = Vec::new();
+    let mut used = 0;
+
+    while let Some(line) = lines.peek() {
+        let len = line.chars().count() + 1;
+        if used + len > head_budget {
+            break;
+        }
+        used += len;
+        head.push(line);
+        lines.next();
+    }
+
+    // A single huge line (e.g. minified code) has no structure to exploit
+    if head.is_empty() {
+        return truncate_content_smart(content, max_chars);
+    }
+
+    let mut outline_budget = max_chars.saturating_sub(used + SEPARATOR.len());
+    let mut outline: Vec<&str> = Vec::new();
+    for line in lines.filter(|line| is_outline_line(line)) {
+        let len = line.chars().count() + 1;
+        if len > outline_budget {
+            break;
+        }
+        outline_budget -= len;
+        outline.push(line);
+    }
+
+    format!("{}{}{}", head.join("\n"), SEPARATOR, outline.join("\n"))
+}
+
 // =============================================================================
 // Folder Context Filtering
 // =============================================================================
 
-/// Keywords for different file type categories
+/// Keywords for different file type categories (matched as lowercase substrings)
+/// Covers EN, FR, DE, ES, IT and PT; users can add more via config
 const IMAGE_FOLDER_KEYWORDS: &[&str] = &[
+    // EN
     "photo", "image", "picture", "screenshot", "capture", "wallpaper",
     "travel", "vacation", "event", "portrait", "gallery",
+    // FR
+    "voyage", "vacances", "fond d'écran", "galerie",
+    // DE
+    "bild", "foto", "urlaub", "reise", "hintergr",
+    // ES / IT / PT
+    "imagen", "immagini", "imagens", "viaje", "vacaciones", "viaggi", "vacanze",
+    "viagem", "férias", "galería", "galleria",
 ];
 
 const DOCUMENT_FOLDER_KEYWORDS: &[&str] = &[
+    // EN
     "document", "doc", "report", "letter", "invoice", "contract",
     "resume", "cv", "manual", "guide", "notes", "meeting",
+    // FR
+    "facture", "contrat", "rapport", "lettre", "courrier", "réunion",
+    // DE
+    "dokument", "rechnung", "vertrag", "bericht", "brief", "lebenslauf",
+    "anleitung", "notizen",
+    // ES / IT / PT
+    "factura", "fattura", "fatura", "contrato", "contratto", "informe",
+    "relatorio", "relatório", "rapporto", "carta", "notas", "appunti",
 ];
 
 const CODE_FOLDER_KEYWORDS: &[&str] = &[
+    // EN
     "project", "src", "source", "code", "lib", "app", "module",
     "component", "test", "spec", "util", "helper",
+    // FR / DE / ES / IT / PT
+    "projet", "projekt", "proyecto", "progetto", "projeto",
+    "quellcode", "código", "codigo", "codice", "entwicklung", "desarrollo",
 ];
 
 /// Filter existing folders to show only relevant ones based on file type
-fn filter_folders_for_file_type(existing_folders: &[String], file_path: &str) -> Vec<String> {
+///
+/// `custom_keywords` extends the built-in keyword sets for each category.
+fn filter_folders_for_file_type(
+    existing_folders: &[String],
+    file_path: &str,
+    custom_keywords: &FolderKeywords,
+) -> Vec<String> {
     // If few folders, return all
     if existing_folders.len() <= 10 {
         return existing_folders.to_vec();
     }
 
-    let keywords = if is_image_file(file_path) {
-        IMAGE_FOLDER_KEYWORDS
+    let (keywords, extra_keywords) = if is_image_file(file_path) {
+        (IMAGE_FOLDER_KEYWORDS, &custom_keywords.images)
     } else if is_text_file(file_path) {
         // Check if it's a code file
         let ext = std::path::Path::new(file_path)
@@ -337,9 +679,9 @@ fn filter_folders_for_file_type(existing_folders: &[String], file_path: &str) ->
 
         let code_extensions = &["js", "ts", "jsx", "tsx", "py", "rs", "go", "java", "kt", "swift", "c", "cpp", "rb", "php"];
         if code_extensions.contains(&ext.as_str()) {
-            CODE_FOLDER_KEYWORDS
+            (CODE_FOLDER_KEYWORDS, &custom_keywords.code)
         } else {
-            DOCUMENT_FOLDER_KEYWORDS
+            (DOCUMENT_FOLDER_KEYWORDS, &custom_keywords.documents)
         }
     } else {
         // Generic - return top-level folders only
@@ -355,6 +697,9 @@ fn filter_folders_for_file_type(existing_folders: &[String], file_path: &str) ->
         .filter(|folder| {
             let folder_lower = folder.to_lowercase();
             keywords.iter().any(|kw| folder_lower.contains(kw))
+                || extra_keywords
+                    .iter()
+                    .any(|kw| !kw.is_empty() && folder_lower.contains(&kw.to_lowercase()))
         })
         .cloned()
         .collect();
@@ -393,17 +738,9 @@ const MAX_SIMILARITY_DISTANCE: usize = 3;
 
 /// Normalize a folder name to kebab-case, lowercase, no accents
 fn normalize_folder_name(name: &str) -> String {
-    let normalized: String = name
+    let normalized: String = strip_diacritics(name)
         .chars()
         .map(|c| match c {
-            // Common accented characters to ASCII
-            'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'À' | 'Á' | 'Â' | 'Ã' | 'Ä' | 'Å' => 'a',
-            'è' | 'é' | 'ê' | 'ë' | 'È' | 'É' | 'Ê' | 'Ë' => 'e',
-            'ì' | 'í' | 'î' | 'ï' | 'Ì' | 'Í' | 'Î' | 'Ï' => 'i',
-            'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'Ò' | 'Ó' | 'Ô' | 'Õ' | 'Ö' => 'o',
-            'ù' | 'ú' | 'û' | 'ü' | 'Ù' | 'Ú' | 'Û' | 'Ü' => 'u',
-            'ñ' | 'Ñ' => 'n',
-            'ç' | 'Ç' => 'c',
             // Keep path separators
             '/' => '/',
             // Convert spaces and underscores to hyphens
@@ -497,14 +834,14 @@ fn folders_are_similar(folder1: &str, folder2: &str) -> bool {
 }
 
 /// Flatten a folder path to maximum allowed depth
-fn flatten_folder_path(path: &str) -> String {
+fn flatten_folder_path(path: &str, max_depth: usize) -> String {
     let parts: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
-    if parts.len() <= MAX_FOLDER_DEPTH {
+    if parts.len() <= max_depth {
         return path.to_string();
     }
 
-    // Keep first MAX_FOLDER_DEPTH parts
-    parts.iter().take(MAX_FOLDER_DEPTH).cloned().collect::<Vec<_>>().join("/")
+    // Keep first max_depth parts
+    parts.iter().take(max_depth).cloned().collect::<Vec<_>>().join("/")
 }
 
 /// Get parent folder (or empty string if root)
@@ -516,18 +853,86 @@ fn get_parent_folder(path: &str) -> String {
     }
 }
 
+/// Depth and size limits applied by folder consolidation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConsolidationLimits {
+    pub max_depth: usize,
+    pub min_files_per_folder: usize,
+}
+
+impl ConsolidationLimits {
+    /// Limits used for regular batches
+    pub const STANDARD: ConsolidationLimits = ConsolidationLimits {
+        max_depth: MAX_FOLDER_DEPTH,
+        min_files_per_folder: MIN_FILES_PER_FOLDER,
+    };
+}
+
+/// Choose consolidation limits for a batch
+///
+/// Uses the adaptive limits only when enabled, the batch has at least
+/// `min_batch_size` folder suggestions, and at least `min_populated_folders`
+/// distinct folders (at the adaptive depth) meet the scaled file threshold.
+/// The adaptive depth is never below the standard one.
+pub fn select_consolidation_limits(
+    results: &[FileAnalysisResult],
+    config: &AdaptiveConsolidationConfig,
+) -> ConsolidationLimits {
+    if !config.enabled {
+        return ConsolidationLimits::STANDARD;
+    }
+
+    // Folders are counted at the depth that would be returned
+    let max_depth = config.max_depth.max(MAX_FOLDER_DEPTH);
+    let mut folder_counts: HashMap<String, usize> = HashMap::new();
+    for folder in results
+        .iter()
+        .filter_map(|r| r.suggestion.as_ref()?.suggested_folder.as_deref())
+        .filter(|f| !f.is_empty())
+    {
+        let flattened = flatten_folder_path(&normalize_folder_name(folder), max_depth);
+        *folder_counts.entry(flattened).or_insert(0) += 1;
+    }
+
+    let batch_size: usize = folder_counts.values().sum();
+    if batch_size < config.min_batch_size {
+        return ConsolidationLimits::STANDARD;
+    }
+
+    let scaled_min_files = ((batch_size as f32 * config.min_files_ratio).ceil() as usize)
+        .max(MIN_FILES_PER_FOLDER);
+    let populated = folder_counts
+        .values()
+        .filter(|count| **count >= scaled_min_files)
+        .count();
+
+    if populated < config.min_populated_folders {
+        return ConsolidationLimits::STANDARD;
+    }
+
+    ConsolidationLimits {
+        max_depth,
+        min_files_per_folder: scaled_min_files,
+    }
+}
+
 /// Consolidate folder suggestions after batch analysis
 ///
 /// This function:
 /// 1. Normalizes all folder names
-/// 2. Flattens folders deeper than 2 levels
+/// 2. Flattens folders deeper than `limits.max_depth` (2 by default)
 /// 3. Merges similar folder names
-/// 4. Moves files from folders with < 3 files to parent folder
+/// 4. Moves files from folders with fewer than `limits.min_files_per_folder`
+///    files (3 by default) to parent folder
 /// 5. Prefers existing folders over new suggestions
+///
+/// Returns a warning for each file whose folder suggestion was dropped or moved
+/// to its parent folder.
 pub fn consolidate_folder_suggestions(
     results: &mut [FileAnalysisResult],
     existing_folders: &[String],
-) {
+    limits: ConsolidationLimits,
+) -> Vec<AnalysisWarning> {
     // Step 1: Normalize all existing folders for comparison
     let normalized_existing: Vec<(String, String)> = existing_folders
         .iter()
@@ -544,7 +949,7 @@ pub fn consolidate_folder_suggestions(
                 if !folder.is_empty() {
                     // Normalize and flatten
                     let normalized = normalize_folder_name(folder);
-                    let flattened = flatten_folder_path(&normalized);
+                    let flattened = flatten_folder_path(&normalized, limits.max_depth);
 
                     original_to_normalized.insert(folder.clone(), flattened.clone());
                     *folder_counts.entry(flattened).or_insert(0) += 1;
@@ -557,11 +962,12 @@ pub fn consolidate_folder_suggestions(
     let mut canonical_mapping: HashMap<String, String> = HashMap::new();
     let mut processed: std::collections::HashSet<String> = std::collections::HashSet::new();
 
-    // Sort folders by frequency (most common first)
+    // Sort folders by frequency (most common first), ties by name so the
+    // canonical choice doesn't depend on hash map order
     let mut sorted_folders: Vec<(String, usize)> = folder_counts.iter()
         .map(|(k, v)| (k.clone(), *v))
         .collect();
-    sorted_folders.sort_by(|a, b| b.1.cmp(&a.1));
+    sorted_folders.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
 
     for (folder, _) in &sorted_folders {
         if processed.contains(folder) {
@@ -598,7 +1004,7 @@ pub fn consolidate_folder_suggestions(
             if let Some(ref folder) = suggestion.suggested_folder {
                 if !folder.is_empty() {
                     let normalized = normalize_folder_name(folder);
-                    let flattened = flatten_folder_path(&normalized);
+                    let flattened = flatten_folder_path(&normalized, limits.max_depth);
                     if let Some(canonical) = canonical_mapping.get(&flattened) {
                         *canonical_counts.entry(canonical.clone()).or_insert(0) += 1;
                     }
@@ -609,17 +1015,18 @@ pub fn consolidate_folder_suggestions(
 
     // Step 5: Find folders that don't meet minimum threshold
     let small_folders: std::collections::HashSet<String> = canonical_counts.iter()
-        .filter(|(_, count)| **count < MIN_FILES_PER_FOLDER)
+        .filter(|(_, count)| **count < limits.min_files_per_folder)
         .map(|(folder, _)| folder.clone())
         .collect();
 
     // Step 6: Apply all transformations to results
+    let mut warnings = Vec::new();
     for result in results.iter_mut() {
         if let Some(ref mut suggestion) = result.suggestion {
             if let Some(ref folder) = suggestion.suggested_folder.clone() {
                 if !folder.is_empty() {
                     let normalized = normalize_folder_name(folder);
-                    let flattened = flatten_folder_path(&normalized);
+                    let flattened = flatten_folder_path(&normalized, limits.max_depth);
 
                     if let Some(canonical) = canonical_mapping.get(&flattened) {
                         // Check if this folder meets minimum threshold
@@ -627,9 +1034,28 @@ pub fn consolidate_folder_suggestions(
                             // Move to parent folder or clear if no parent
                             let parent = get_parent_folder(canonical);
                             if parent.is_empty() {
+                                warnings.push(AnalysisWarning {
+                                    file_path: result.file_path.clone(),
+                                    kind: AnalysisWarningKind::FolderConsolidated,
+                                    file_count: None,
+                                    message: format!(
+                                        "Folder suggestion \"{}\" dropped: fewer than {} files",
+                                        folder, limits.min_files_per_folder
+                                    ),
+                                });
                                 suggestion.suggested_folder = None;
                                 suggestion.folder_confidence = None;
                             } else {
+                                warnings.push(AnalysisWarning {
+                                    file_path: result.file_path.clone(),
+                                    kind: AnalysisWarningKind::FolderConsolidated,
+                                    file_count: None,
+                                    message: format!(
+                                        "Folder suggestion \"{}\" moved up to \"{}\": \
+                                         fewer than {} files",
+                                        folder, parent, limits.min_files_per_folder
+                                    ),
+                                });
                                 suggestion.suggested_folder = Some(parent);
                                 // Reduce confidence since we had to move it
                                 if let Some(conf) = suggestion.folder_confidence {
@@ -645,77 +1071,630 @@ pub fn consolidate_folder_suggestions(
             }
         }
     }
+
+    warnings
 }
 
-// =============================================================================
-// Response Types
-// =============================================================================
+/// Put results and existing folders in a stable order before consolidation
+///
+/// Results are sorted by file path and existing folders by name (they come
+/// from `read_dir`, whose order varies by platform), so the same input always
+/// consolidates to the same output.
+fn sort_for_reproducibility(results: &mut [FileAnalysisResult], existing_folders: &mut [String]) {
+    results.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+    existing_folders.sort();
+}
 
-/// Health status for Ollama connection
-#[derive(Debug, Serialize)]
+/// A folder name and the form folder consolidation compares it by
+#[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
-pub struct HealthStatus {
-    /// Whether Ollama is reachable and responding
-    pub available: bool,
-    /// Number of models installed
-    pub model_count: Option<u32>,
-    /// Timestamp of health check
-    pub checked_at: String,
+pub struct FolderNormalization {
+    pub input: String,
+    /// Kebab-case, lowercase, accents stripped; "/" separates levels
+    pub normalized: String,
+    /// Other inputs with the same normalized form (merged by consolidation)
+    pub same_as: Vec<String>,
 }
 
-/// Model information from Ollama
-#[derive(Debug, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct OllamaModel {
-    /// Model name with tag (e.g., 'mistral:latest')
-    pub name: String,
-    /// Model size in bytes
-    pub size: u64,
-    /// Model family (e.g., 'mistral', 'llama')
-    pub family: Option<String>,
+/// Preview how folder consolidation normalizes folder names
+///
+/// Shows why "Photos Été" and "photos-ete" end up in the same folder: both
+/// normalize to "photos-ete". Inputs are returned in order.
+///
+/// Command name: preview_folder_normalization (snake_case per architecture)
+#[tauri::command]
+pub async fn preview_folder_normalization(names: Vec<String>) -> Vec<FolderNormalization> {
+    let normalized: Vec<String> = names.iter().map(|name| normalize_folder_name(name)).collect();
+
+    names
+        .iter()
+        .enumerate()
+        .map(|(index, name)| FolderNormalization {
+            input: name.clone(),
+            normalized: normalized[index].clone(),
+            same_as: names
+                .iter()
+                .enumerate()
+                .filter(|&(other, _)| other != index && normalized[other] == normalized[index])
+                .map(|(_, other_name)| other_name.clone())
+                .collect(),
+        })
+        .collect()
+}
+
+/// Re-run folder consolidation on analysis results, adapting to large batches
+///
+/// Uses deeper folders and a higher per-folder minimum when `adaptive` is
+/// enabled and the batch qualifies (see [`select_consolidation_limits`]);
+/// otherwise applies the standard limits.
+///
+/// Command name: reconsolidate_folder_suggestions (snake_case per architecture)
+#[tauri::command]
+pub async fn reconsolidate_folder_suggestions(
+    mut results: Vec<FileAnalysisResult>,
+    base_path: Option<String>,
+    adaptive: AdaptiveConsolidationConfig,
+) -> Result<Vec<FileAnalysisResult>, String> {
+    let existing_folders = base_path
+        .as_ref()
+        .map(|p| scan_folder_structure(p))
+        .unwrap_or_default();
+
+    let limits = select_consolidation_limits(&results, &adaptive);
+    consolidate_folder_suggestions(&mut results, &existing_folders, limits);
+
+    Ok(results)
 }
 
 // =============================================================================
-// Ollama API Response Types
+// Organize from Suggestions
 // =============================================================================
 
-#[derive(Debug, Deserialize)]
-struct OllamaTagsResponse {
-    models: Vec<OllamaModelInfo>,
+/// Turn an AI folder suggestion into a safe relative path
+///
+/// Each segment is sanitized like a filename; empty, "." and ".." segments
+/// are dropped so a suggestion can never leave the base directory.
+fn sanitize_suggested_folder(folder: &str) -> Option<String> {
+    let segments: Vec<String> = folder
+        .split(['/', '\\'])
+        .map(str::trim)
+        .filter(|segment| !segment.is_empty() && *segment != "." && *segment != "..")
+        .map(|segment| sanitize_filename(segment, '_').sanitized)
+        .filter(|segment| !segment.is_empty())
+        .collect();
+
+    (!segments.is_empty()).then(|| segments.join("/"))
 }
 
-#[derive(Debug, Deserialize)]
-struct OllamaModelInfo {
-    name: String,
-    size: u64,
-    #[serde(default)]
-    details: Option<OllamaModelDetails>,
+/// Build a move proposal for one analyzed file, if it has a folder suggestion
+fn build_suggestion_proposal(
+    result: &FileAnalysisResult,
+    base_directory: &str,
+) -> Option<RenameProposal> {
+    let folder = result
+        .suggestion
+        .as_ref()
+        .and_then(|s| s.suggested_folder.as_deref())
+        .and_then(sanitize_suggested_folder)?;
+    let original_name = std::path::Path::new(&result.file_path)
+        .file_name()?
+        .to_string_lossy()
+        .to_string();
+
+    let dest_dir = format!("{}/{}", base_directory.trim_end_matches(['/', '\\']), folder);
+    let proposed_path = format!("{}/{}", dest_dir, original_name);
+    let is_folder_move =
+        std::path::Path::new(&proposed_path) != std::path::Path::new(&result.file_path);
+    let (status, action_type) = if is_folder_move {
+        (RenameStatus::Ready, FileActionType::Move)
+    } else {
+        (RenameStatus::NoChange, FileActionType::NoChange)
+    };
+
+    Some(RenameProposal {
+        id: Uuid::new_v4().to_string(),
+        original_path: result.file_path.clone(),
+        original_name: original_name.clone(),
+        proposed_name: original_name,
+        proposed_path,
+        status,
+        issues: vec![],
+        metadata_sources: Some(vec!["ai".to_string()]),
+        is_folder_move,
+        destination_folder: is_folder_move.then_some(folder),
+        action_type,
+        conflict: None,
+    })
 }
 
-#[derive(Debug, Deserialize)]
-struct OllamaModelDetails {
-    family: Option<String>,
+/// Turn AI folder suggestions into an organize preview
+///
+/// Each file with a `suggested_folder` (run `reconsolidate_folder_suggestions`
+/// first to use consolidated folders) is moved into that folder under
+/// `base_directory`, keeping its name. Files without a folder suggestion are
+/// left out. The result is a standard preview with conflict detection that
+/// can be passed to `execute_rename`.
+///
+/// Command name: organize_from_suggestions (snake_case per architecture)
+#[tauri::command]
+pub async fn organize_from_suggestions(
+    results: Vec<FileAnalysisResult>,
+    base_directory: String,
+) -> Result<RenamePreview, String> {
+    if base_directory.trim().is_empty() {
+        return Err("Base directory is required".to_string());
+    }
+
+    let proposals = results
+        .iter()
+        .filter_map(|result| build_suggestion_proposal(result, &base_directory))
+        .collect();
+
+    Ok(finalize_preview(proposals, String::new(), ReorganizationMode::Organize))
 }
 
 // =============================================================================
-// Tauri Commands
+// Merge Suggestions into a Preview
 // =============================================================================
 
-/// Check Ollama health status
-///
-/// Attempts to connect to Ollama API and verify it's responding.
-/// Returns availability status and model count.
+/// Conflict types set by batch conflict detection, re-evaluated after a merge
+const BATCH_CONFLICT_TYPES: [&str; 3] = ["duplicate-name", "case-collision", "file-exists"];
+
+/// Undo the batch conflict marking of a proposal so it can be detected again
+fn clear_batch_conflict(proposal: &mut RenameProposal) {
+    let is_batch_conflict = proposal
+        .conflict
+        .as_ref()
+        .is_some_and(|c| BATCH_CONFLICT_TYPES.contains(&c.conflict_type.as_str()));
+    if proposal.status != RenameStatus::Conflict || !is_batch_conflict {
+        return;
+    }
+
+    proposal.issues.retain(|issue| {
+        !matches!(issue.code.as_str(), "DUPLICATE_NAME" | "CASE_COLLISION" | "FILE_EXISTS")
+    });
+    proposal.conflict = None;
+    proposal.status = RenameStatus::Ready;
+    proposal.action_type = if proposal.is_folder_move {
+        FileActionType::Move
+    } else {
+        FileActionType::Rename
+    };
+}
+
+/// Replace a proposal's name with an AI suggestion, keeping its destination folder
 ///
-/// Command name: check_ollama_health (snake_case per architecture)
-#[tauri::command]
-pub async fn check_ollama_health(base_url: String, timeout_ms: u64) -> Result<HealthStatus, String> {
-    let client = Client::builder()
-        .timeout(Duration::from_millis(timeout_ms))
-        .build()
-        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+/// Only ready and unchanged proposals take a suggestion (batch conflicts are
+/// cleared to ready first): missing data, invalid names and other blocking
+/// issues can't be re-detected by `finalize_preview`, so those proposals keep
+/// their status and issues. Returns false (leaving the proposal untouched)
+/// for them, or when the suggestion sanitizes to an empty name.
+fn apply_suggested_name(proposal: &mut RenameProposal, suggestion: &AiSuggestion) -> bool {
+    if !matches!(proposal.status, RenameStatus::Ready | RenameStatus::NoChange) {
+        return false;
+    }
 
-    let url = format!("{}/api/tags", base_url.trim_end_matches('/'));
-    let checked_at = chrono::Utc::now().to_rfc3339();
+    let stem = sanitize_filename(suggestion.suggested_name.trim(), '_').sanitized;
+    if stem.trim().is_empty() {
+        return false;
+    }
+
+    let original = std::path::Path::new(&proposal.original_name);
+    let proposed_name = match original.extension() {
+        Some(ext) => format!("{}.{}", stem, ext.to_string_lossy()),
+        None => stem,
+    };
+    let proposed_path =
+        join_proposed_path(std::path::Path::new(&proposal.proposed_path).parent(), &proposed_name);
+
+    let unchanged = proposed_name == proposal.original_name && !proposal.is_folder_move;
+    let (status, action_type) = if unchanged {
+        (RenameStatus::NoChange, FileActionType::NoChange)
+    } else if proposal.is_folder_move {
+        (RenameStatus::Ready, FileActionType::Move)
+    } else {
+        (RenameStatus::Ready, FileActionType::Rename)
+    };
+
+    proposal.proposed_name = proposed_name;
+    proposal.proposed_path = proposed_path;
+    proposal.status = status;
+    proposal.action_type = action_type;
+    let sources = proposal.metadata_sources.get_or_insert_with(Vec::new);
+    if !sources.iter().any(|source| source == "ai") {
+        sources.push("ai".to_string());
+    }
+    true
+}
+
+/// Overlay confident AI name suggestions on an existing preview
+///
+/// For each proposal whose original path has an analysis result, the AI's
+/// `suggested_name` (plus the original extension) replaces the proposed name
+/// when the suggestion's confidence exceeds `min_confidence` and it does not
+/// ask to keep the original. Destination folders are kept, so an organize
+/// preview still moves files. Conflict detection runs again on the merged
+/// proposals.
+///
+/// Command name: merge_suggestions (snake_case per architecture)
+#[tauri::command]
+pub async fn merge_suggestions(
+    preview: RenamePreview,
+    results: Vec<FileAnalysisResult>,
+    min_confidence: f32,
+) -> Result<RenamePreview, String> {
+    let suggestions: HashMap<&str, &AiSuggestion> = results
+        .iter()
+        .filter_map(|r| r.suggestion.as_ref().map(|s| (r.file_path.as_str(), s)))
+        .collect();
+
+    let mut proposals = preview.proposals;
+    for proposal in &mut proposals {
+        clear_batch_conflict(proposal);
+        let Some(suggestion) = suggestions.get(proposal.original_path.as_str()) else {
+            continue;
+        };
+        if suggestion.keep_original || suggestion.confidence <= min_confidence {
+            continue;
+        }
+        apply_suggested_name(proposal, suggestion);
+    }
+
+    Ok(finalize_preview(proposals, preview.template_used, preview.reorganization_mode))
+}
+
+// =============================================================================
+// Confidence Display Filter
+// =============================================================================
+
+/// Keep only the results whose suggestion is at or above `floor`
+///
+/// A display helper: results without such a suggestion (low confidence,
+/// failed or skipped) are left out of the view, while the counts and warnings
+/// still describe the whole batch. The view's `display_confidence_floor` is
+/// set to `floor`.
+///
+/// Command name: filter_results_by_confidence (snake_case per architecture)
+#[tauri::command]
+pub async fn filter_results_by_confidence(
+    mut result: BatchAnalysisResult,
+    floor: f32,
+) -> Result<BatchAnalysisResult, String> {
+    if !(0.0..=1.0).contains(&floor) {
+        return Err("Confidence floor must be between 0.0 and 1.0".to_string());
+    }
+
+    result.results.retain(|r| r.suggestion.as_ref().is_some_and(|s| s.confidence >= floor));
+    result.display_confidence_floor = floor;
+    Ok(result)
+}
+
+// =============================================================================
+// Keep-Original Policy (Post-processing)
+// =============================================================================
+
+/// Keep the original filename when it already looks good and the AI is unsure
+///
+/// A file whose name passes the `needs_ai_analysis` pre-filter as descriptive
+/// keeps that name whenever the AI suggestion's confidence is below `threshold`.
+/// This prevents low-confidence suggestions from overriding a decent name.
+/// A threshold of 0.0 (or below) disables the policy.
+pub fn apply_keep_original_policy(results: &mut [FileAnalysisResult], threshold: f32) {
+    if threshold <= 0.0 {
+        return;
+    }
+
+    for result in results.iter_mut() {
+        let Some(suggestion) = result.suggestion.as_mut() else {
+            continue;
+        };
+
+        if suggestion.keep_original || suggestion.confidence >= threshold {
+            continue;
+        }
+
+        let (needs_analysis, _) = needs_ai_analysis(&result.file_path);
+        if needs_analysis {
+            continue;
+        }
+
+        let original_name = std::path::Path::new(&result.file_path)
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("unknown")
+            .to_string();
+
+        suggestion.reasoning = format!(
+            "Kept original filename: AI confidence {:.0}% is below the {:.0}% threshold",
+            suggestion.confidence * 100.0,
+            threshold * 100.0
+        );
+        suggestion.suggested_name = original_name;
+        suggestion.keep_original = true;
+    }
+}
+
+// =============================================================================
+// Name Dictionary (Post-processing)
+// =============================================================================
+
+/// Force the dictionary casing of whole words in a name
+///
+/// Words are runs of letters and digits; a word matching a dictionary term
+/// case-insensitively is replaced by the term ("nasa-report" -> "NASA-report").
+fn apply_dictionary_casing(name: &str, dictionary: &[String]) -> String {
+    let mut result = String::with_capacity(name.len());
+    let mut word = String::new();
+
+    let flush = |word: &mut String, result: &mut String| {
+        let lower = word.to_lowercase();
+        match dictionary.iter().find(|term| term.to_lowercase() == lower) {
+            Some(term) => result.push_str(term),
+            None => result.push_str(word),
+        }
+        word.clear();
+    };
+
+    for c in name.chars() {
+        if c.is_alphanumeric() {
+            word.push(c);
+        } else {
+            flush(&mut word, &mut result);
+            result.push(c);
+        }
+    }
+    flush(&mut word, &mut result);
+
+    result
+}
+
+/// Apply the user's name dictionary to AI suggested names
+///
+/// Deterministic fix-up for models that lowercase acronyms or mangle brand
+/// names. Kept original names are left untouched.
+pub fn apply_name_dictionary(results: &mut [FileAnalysisResult], dictionary: &[String]) {
+    if dictionary.is_empty() {
+        return;
+    }
+
+    for suggestion in results.iter_mut().filter_map(|r| r.suggestion.as_mut()) {
+        if !suggestion.keep_original {
+            suggestion.suggested_name =
+                apply_dictionary_casing(&suggestion.suggested_name, dictionary);
+        }
+    }
+}
+
+// =============================================================================
+// Analysis Warnings (Post-processing)
+// =============================================================================
+
+/// Warn when the prompt only saw a small part of a text file
+///
+/// Uses the lengths measured during analysis; cached results have none, so
+/// the file size is compared with the sample limit instead.
+fn truncation_warning(result: &FileAnalysisResult) -> Option<AnalysisWarning> {
+    if result.suggestion.is_none()
+        || !CONTENT_SOURCES.contains(&result.source.as_str())
+        || !is_text_file(&result.file_path)
+    {
+        return None;
+    }
+
+    let (size, used) = match result.content_length {
+        Some(_) if !result.content_truncated => return None,
+        Some(length) => (length.original, length.used),
+        None => (std::fs::metadata(&result.file_path).ok()?.len(), MAX_CONTENT_CHARS as u64),
+    };
+    if size <= used * HEAVY_TRUNCATION_FACTOR {
+        return None;
+    }
+
+    Some(AnalysisWarning {
+        file_path: result.file_path.clone(),
+        kind: AnalysisWarningKind::ContentTruncated,
+        file_count: None,
+        message: format!("Only about {} of {} bytes were sampled for analysis", used, size),
+    })
+}
+
+/// Warn about suggestions the AI itself was unsure of
+fn low_confidence_warning(result: &FileAnalysisResult) -> Option<AnalysisWarning> {
+    let suggestion = result.suggestion.as_ref()?;
+    if suggestion.keep_original || suggestion.confidence >= LOW_CONFIDENCE_WARNING_THRESHOLD {
+        return None;
+    }
+
+    Some(AnalysisWarning {
+        file_path: result.file_path.clone(),
+        kind: AnalysisWarningKind::LowConfidence,
+        file_count: None,
+        message: format!("AI confidence is only {:.0}%", suggestion.confidence * 100.0),
+    })
+}
+
+/// Collect per-file warnings (truncated content, low confidence)
+///
+/// Run before the keep-original policy, which turns low-confidence
+/// suggestions into "keep original" ones.
+pub fn collect_analysis_warnings(results: &[FileAnalysisResult]) -> Vec<AnalysisWarning> {
+    results
+        .iter()
+        .flat_map(|result| [truncation_warning(result), low_confidence_warning(result)])
+        .flatten()
+        .collect()
+}
+
+fn batch_warning(kind: AnalysisWarningKind, file_count: usize, message: String) -> AnalysisWarning {
+    AnalysisWarning {
+        file_path: String::new(),
+        kind,
+        file_count: Some(file_count),
+        message,
+    }
+}
+
+/// Summarize systemic issues shared by many files into batch-level warnings
+///
+/// Lets the UI show one banner rather than the same per-file error many times.
+/// Errors explained by a missing API key aren't repeated as `RepeatedError`.
+pub fn collect_batch_warnings(
+    results: &[FileAnalysisResult],
+    config: &OllamaConfig,
+) -> Vec<AnalysisWarning> {
+    let mut warnings = Vec::new();
+
+    if !config.vision_enabled {
+        let skipped_media = results
+            .iter()
+            .filter(|r| r.skip_reason == Some(AnalysisSkipReason::Unsupported))
+            .filter(|r| is_image_file(&r.file_path) || is_video_file(&r.file_path))
+            .count();
+        if skipped_media > 0 {
+            warnings.push(batch_warning(
+                AnalysisWarningKind::VisionDisabled,
+                skipped_media,
+                format!(
+                    "Vision is disabled, so {} image{} skipped",
+                    skipped_media,
+                    if skipped_media == 1 { " was" } else { "s were" }
+                ),
+            ));
+        }
+    }
+
+    let mut error_counts: Vec<(&str, usize)> = Vec::new();
+    for error in results.iter().filter(|r| !r.skipped).filter_map(|r| r.error.as_deref()) {
+        match error_counts.iter_mut().find(|(e, _)| *e == error) {
+            Some((_, count)) => *count += 1,
+            None => error_counts.push((error, 1)),
+        }
+    }
+
+    for (error, count) in error_counts {
+        if error == MISSING_API_KEY_ERROR {
+            warnings.push(batch_warning(
+                AnalysisWarningKind::ApiKeyMissing,
+                count,
+                format!("{}: {} file(s) could not be analyzed", MISSING_API_KEY_ERROR, count),
+            ));
+        } else if count >= REPEATED_ERROR_WARNING_MIN {
+            warnings.push(batch_warning(
+                AnalysisWarningKind::RepeatedError,
+                count,
+                format!("{} files failed with: {}", count, error),
+            ));
+        }
+    }
+
+    warnings
+}
+
+// =============================================================================
+// Response Types
+// =============================================================================
+
+/// Health status for Ollama connection
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthStatus {
+    /// Whether Ollama is reachable and responding
+    pub available: bool,
+    /// Number of models installed
+    pub model_count: Option<u32>,
+    /// Timestamp of health check
+    pub checked_at: String,
+}
+
+/// Model information from Ollama
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OllamaModel {
+    /// Model name with tag (e.g., 'mistral:latest')
+    pub name: String,
+    /// Model size in bytes
+    pub size: u64,
+    /// Model family (e.g., 'mistral', 'llama')
+    pub family: Option<String>,
+}
+
+// =============================================================================
+// Ollama API Response Types
+// =============================================================================
+
+#[derive(Debug, Deserialize)]
+struct OllamaTagsResponse {
+    models: Vec<OllamaModelInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OllamaModelInfo {
+    name: String,
+    size: u64,
+    #[serde(default)]
+    details: Option<OllamaModelDetails>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OllamaModelDetails {
+    family: Option<String>,
+}
+
+/// One line of the streamed `/api/pull` response
+#[derive(Debug, Deserialize)]
+struct OllamaPullStatus {
+    #[serde(default)]
+    status: String,
+    digest: Option<String>,
+    total: Option<u64>,
+    completed: Option<u64>,
+    error: Option<String>,
+}
+
+/// Progress event payload for model downloads
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelPullProgress {
+    /// Model being pulled
+    pub model: String,
+    /// Ollama status text (e.g., "pulling manifest", "success")
+    pub status: String,
+    /// Digest of the layer being downloaded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub layer: Option<String>,
+    /// Download progress of the current layer (0-100)
+    pub percent: u8,
+}
+
+/// Result of pulling a model
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelPullResult {
+    /// Model that was pulled
+    pub model: String,
+    /// Whether the model was already installed (nothing downloaded)
+    pub already_installed: bool,
+}
+
+// =============================================================================
+// Tauri Commands
+// =============================================================================
+
+/// Check Ollama health status
+///
+/// Attempts to connect to Ollama API and verify it's responding.
+/// Returns availability status and model count.
+///
+/// Command name: check_ollama_health (snake_case per architecture)
+#[tauri::command]
+pub async fn check_ollama_health(base_url: String, timeout_ms: u64) -> Result<HealthStatus, String> {
+    let client = Client::builder()
+        .timeout(Duration::from_millis(timeout_ms))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let url = format!("{}/api/tags", base_url.trim_end_matches('/'));
+    let checked_at = chrono::Utc::now().to_rfc3339();
 
     match client.get(&url).send().await {
         Ok(response) => {
@@ -810,58 +1789,272 @@ pub async fn list_ollama_models(
     Ok(models)
 }
 
-// =============================================================================
-// OpenAI API Types
-// =============================================================================
+/// Names of the models installed on an Ollama server
+async fn fetch_installed_models(client: &Client, base_url: &str) -> Result<Vec<String>, String> {
+    let url = format!("{}/api/tags", base_url);
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {}", e))?;
+    let data: OllamaTagsResponse = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse response: {}", e))?;
 
-#[derive(Debug, Deserialize)]
-struct OpenAiModelsResponse {
-    data: Vec<OpenAiModelInfo>,
+    Ok(data.models.into_iter().map(|m| m.name).collect())
 }
 
-#[derive(Debug, Deserialize)]
-#[allow(dead_code)]
-struct OpenAiModelInfo {
-    id: String,
-    owned_by: String,
+/// Whether `model` is in the installed list ("mistral" matches "mistral:latest")
+fn is_model_listed(installed: &[String], model: &str) -> bool {
+    let with_tag = format!("{}:latest", model);
+    installed.iter().any(|name| name == model || *name == with_tag)
 }
 
-#[derive(Debug, Deserialize)]
-struct OpenAiErrorResponse {
-    error: OpenAiErrorDetail,
+async fn is_ollama_model_installed(client: &Client, base_url: &str, model: &str) -> bool {
+    fetch_installed_models(client, base_url)
+        .await
+        .is_ok_and(|installed| is_model_listed(&installed, model))
 }
 
-#[derive(Debug, Deserialize)]
-#[allow(dead_code)]
-struct OpenAiErrorDetail {
-    message: String,
-    #[serde(rename = "type")]
-    error_type: Option<String>,
-}
+/// Check that the configured Ollama models are installed before analysis
+///
+/// Confirmed models are cached for the session; missing ones are checked
+/// again on every call so a model installed in the meantime is picked up.
+/// If the model list can't be fetched, the check is skipped and analysis
+/// reports the connection problem itself.
+async fn verify_ollama_models(client: &Client, config: &OllamaConfig) -> Result<(), String> {
+    let base_url = config.base_url.trim_end_matches('/');
+    let mut models: Vec<&str> = config.models.inference.iter().map(String::as_str).collect();
+    if config.vision_enabled {
+        models.extend(config.models.vision.as_deref());
+    }
 
-/// OpenAI model information
-#[derive(Debug, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct OpenAiModel {
-    /// Model ID (e.g., 'gpt-4o', 'gpt-4o-mini')
-    pub id: String,
-    /// Display name
-    pub name: String,
-    /// Whether this model supports vision
-    pub supports_vision: bool,
-}
+    let mut installed: Option<Vec<String>> = None;
+    for model in models {
+        let key = (base_url.to_string(), model.to_string());
+        if VERIFIED_OLLAMA_MODELS.read().await.contains(&key) {
+            continue;
+        }
 
-// =============================================================================
-// OpenAI Tauri Commands
-// =============================================================================
+        if installed.is_none() {
+            match fetch_installed_models(client, base_url).await {
+                Ok(names) => installed = Some(names),
+                Err(_) => return Ok(()),
+            }
+        }
+        let names = installed.as_deref().unwrap_or_default();
 
-/// Check OpenAI health status
-///
-/// Attempts to connect to OpenAI API and verify the API key works.
-/// Returns availability status.
-///
-/// Command name: check_openai_health (snake_case per architecture)
-#[tauri::command]
+        if !is_model_listed(names, model) {
+            let available = if names.is_empty() {
+                "none installed".to_string()
+            } else {
+                format!("available: {}", names.join(", "))
+            };
+            return Err(format!(
+                "Ollama model '{}' is not installed ({}). \
+                 Download it with pull_ollama_model or pick an installed model.",
+                model, available
+            ));
+        }
+        VERIFIED_OLLAMA_MODELS.write().await.insert(key);
+    }
+
+    Ok(())
+}
+
+/// Parse one line of the pull stream into a progress event
+///
+/// Returns None for blank lines and an error when Ollama reports a failure.
+fn parse_pull_line(model: &str, line: &[u8]) -> Result<Option<ModelPullProgress>, String> {
+    let line = String::from_utf8_lossy(line);
+    let line = line.trim();
+    if line.is_empty() {
+        return Ok(None);
+    }
+
+    let status: OllamaPullStatus = serde_json::from_str(line)
+        .map_err(|e| format!("Failed to parse pull progress: {}", e))?;
+    if let Some(error) = status.error {
+        return Err(format!("Failed to pull model '{}': {}", model, error));
+    }
+
+    let percent = match (status.completed, status.total) {
+        _ if status.status == "success" => 100,
+        (Some(completed), Some(total)) => progress_percent(completed as usize, total as usize),
+        _ => 0,
+    };
+
+    Ok(Some(ModelPullProgress {
+        model: model.to_string(),
+        status: status.status,
+        layer: status.digest,
+        percent,
+    }))
+}
+
+/// Pull a model, reporting each change of status or percent to `on_progress`
+async fn pull_ollama_model_internal(
+    client: &Client,
+    base_url: &str,
+    model: &str,
+    on_progress: &(dyn Fn(ModelPullProgress) + Sync),
+) -> Result<ModelPullResult, String> {
+    let model = model.trim();
+    if model.is_empty() {
+        return Err("Model name is required".to_string());
+    }
+    let base_url = base_url.trim_end_matches('/');
+
+    if is_ollama_model_installed(client, base_url, model).await {
+        on_progress(ModelPullProgress {
+            model: model.to_string(),
+            status: "success".to_string(),
+            layer: None,
+            percent: 100,
+        });
+        return Ok(ModelPullResult {
+            model: model.to_string(),
+            already_installed: true,
+        });
+    }
+
+    let mut response = client
+        .post(format!("{}/api/pull", base_url))
+        .json(&serde_json::json!({ "model": model, "stream": true }))
+        .send()
+        .await
+        .map_err(|e| {
+            if e.is_connect() {
+                "Cannot connect to Ollama. Is it running?".to_string()
+            } else {
+                format!("Request failed: {}", e)
+            }
+        })?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return match parse_pull_line(model, body.as_bytes()) {
+            Err(e) => Err(e),
+            Ok(_) => Err(format!("Ollama returned error: {}", status)),
+        };
+    }
+
+    // The body is newline-delimited JSON; chunks may split lines anywhere
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut last: Option<ModelPullProgress> = None;
+    let mut handle_line = |line: &[u8]| -> Result<(), String> {
+        if let Some(progress) = parse_pull_line(model, line)? {
+            if last.as_ref() != Some(&progress) {
+                on_progress(progress.clone());
+                last = Some(progress);
+            }
+        }
+        Ok(())
+    };
+
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Model download interrupted: {}", e))?
+    {
+        buffer.extend_from_slice(&chunk);
+        while let Some(pos) = buffer.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=pos).collect();
+            handle_line(&line)?;
+        }
+    }
+    handle_line(&buffer)?;
+
+    if last.is_none_or(|p| p.status != "success") {
+        return Err(format!("Pull of model '{}' ended before completing", model));
+    }
+
+    Ok(ModelPullResult {
+        model: model.to_string(),
+        already_installed: false,
+    })
+}
+
+/// Download an Ollama model
+///
+/// Streams `/api/pull` and emits "model-pull-progress" events (status, layer,
+/// percent) for a download bar. Completes immediately when the model is
+/// already installed.
+///
+/// Command name: pull_ollama_model (snake_case per architecture)
+#[tauri::command]
+pub async fn pull_ollama_model(
+    window: tauri::Window,
+    base_url: String,
+    model: String,
+) -> Result<ModelPullResult, String> {
+    // No overall timeout: models are several gigabytes
+    let client = Client::builder()
+        .connect_timeout(Duration::from_secs(10))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let on_progress = |progress: ModelPullProgress| {
+        let _ = window.emit("model-pull-progress", progress);
+    };
+
+    pull_ollama_model_internal(&client, &base_url, &model, &on_progress).await
+}
+
+// =============================================================================
+// OpenAI API Types
+// =============================================================================
+
+#[derive(Debug, Deserialize)]
+struct OpenAiModelsResponse {
+    data: Vec<OpenAiModelInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct OpenAiModelInfo {
+    id: String,
+    owned_by: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiErrorResponse {
+    error: OpenAiErrorDetail,
+}
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct OpenAiErrorDetail {
+    message: String,
+    #[serde(rename = "type")]
+    error_type: Option<String>,
+}
+
+/// OpenAI model information
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenAiModel {
+    /// Model ID (e.g., 'gpt-4o', 'gpt-4o-mini')
+    pub id: String,
+    /// Display name
+    pub name: String,
+    /// Whether this model supports vision
+    pub supports_vision: bool,
+}
+
+// =============================================================================
+// OpenAI Tauri Commands
+// =============================================================================
+
+/// Check OpenAI health status
+///
+/// Attempts to connect to OpenAI API and verify the API key works.
+/// Returns availability status.
+///
+/// Command name: check_openai_health (snake_case per architecture)
+#[tauri::command]
 pub async fn check_openai_health(
     api_key: String,
     base_url: String,
@@ -1001,7 +2194,7 @@ pub struct AiSuggestion {
 }
 
 /// Result of analyzing a single file
-#[derive(Debug, Serialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct FileAnalysisResult {
     /// Original file path
@@ -1016,10 +2209,170 @@ pub struct FileAnalysisResult {
     pub skipped: bool,
     /// Source of analysis (llm, vision, fallback)
     pub source: String,
+    /// Why the file was not sent to the AI, when it wasn't
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skip_reason: Option<AnalysisSkipReason>,
+    /// Whether the suggestion is based on a sample of the file's text
+    #[serde(default)]
+    pub content_truncated: bool,
+    /// Size of the text file and of the part sent to the AI, for text analyzed
+    /// in this run (not for cached results)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_length: Option<ContentLength>,
+    /// Kind of provider failure behind `error`, for retry and fallback decisions
+    #[serde(skip)]
+    pub failure: Option<ProviderFailure>,
+}
+
+/// Provider failures that warrant a retry or the fallback provider
+///
+/// Other errors (unparseable responses, unreadable files) would fail the same
+/// way with any provider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProviderFailure {
+    /// No API key is configured
+    MissingApiKey,
+    /// The API key was rejected (401)
+    Unauthorized,
+    /// Rate limit or quota exceeded (429)
+    RateLimited,
+    /// The service is temporarily down (502, 503)
+    Unavailable,
+    /// The request got no response (connection refused, timeout)
+    Connection,
+}
+
+impl ProviderFailure {
+    /// Classify an HTTP error status; other statuses aren't provider failures
+    fn from_status(status: reqwest::StatusCode) -> Option<Self> {
+        match status.as_u16() {
+            401 => Some(ProviderFailure::Unauthorized),
+            429 => Some(ProviderFailure::RateLimited),
+            502 | 503 => Some(ProviderFailure::Unavailable),
+            _ => None,
+        }
+    }
+
+    /// Whether the same provider may succeed after a short wait
+    fn is_transient(self) -> bool {
+        matches!(self, ProviderFailure::RateLimited | ProviderFailure::Unavailable)
+    }
+}
+
+impl FileAnalysisResult {
+    /// A suggestion produced by `source` ("ollama", "openai-vision", "cache", ...)
+    fn suggested(file_path: impl Into<String>, suggestion: AiSuggestion, source: &str) -> Self {
+        Self {
+            file_path: file_path.into(),
+            suggestion: Some(suggestion),
+            source: source.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// An analysis that failed with `error`
+    fn failed(file_path: impl Into<String>, error: impl Into<String>) -> Self {
+        Self {
+            file_path: file_path.into(),
+            error: Some(error.into()),
+            source: "error".to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// A request to the provider that failed, classified when it's a provider problem
+    fn provider_failed(
+        file_path: impl Into<String>,
+        error: impl Into<String>,
+        failure: Option<ProviderFailure>,
+    ) -> Self {
+        Self {
+            failure,
+            ..Self::failed(file_path, error)
+        }
+    }
+
+    /// A file that was not sent to the AI, with an optional explanation
+    fn skipped(
+        file_path: impl Into<String>,
+        reason: AnalysisSkipReason,
+        source: &str,
+        message: Option<String>,
+    ) -> Self {
+        Self {
+            file_path: file_path.into(),
+            error: message,
+            skipped: true,
+            source: source.to_string(),
+            skip_reason: Some(reason),
+            ..Default::default()
+        }
+    }
+}
+
+/// Size of a text file and of the content sent for analysis, in bytes
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContentLength {
+    pub original: u64,
+    pub used: u64,
+}
+
+/// Kind of non-fatal issue found during analysis
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum AnalysisWarningKind {
+    /// Only a small part of the file content was sent to the AI
+    ContentTruncated,
+    /// The folder suggestion was dropped or moved up during consolidation
+    FolderConsolidated,
+    /// The AI reported low confidence in its suggestion
+    LowConfidence,
+    /// Vision is disabled, so images (and videos) were skipped
+    VisionDisabled,
+    /// No OpenAI API key is configured, so every request failed
+    ApiKeyMissing,
+    /// Several files failed with the same error
+    RepeatedError,
+}
+
+/// Why a file was not sent to the AI
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum AnalysisSkipReason {
+    /// The pre-filter found the filename already descriptive (kept as-is)
+    AlreadyDescriptive,
+    /// The file type can't be analyzed
+    Unsupported,
+    /// The file has no content to analyze
+    Empty,
+    /// LLM analysis is disabled in the configuration
+    Disabled,
+    /// The extension is excluded by the LLM file type settings
+    Filtered,
+    /// The filename scored as good in "only bad names" mode
+    GoodName,
+    /// The batch was cancelled before the file's turn
+    Cancelled,
+}
+
+/// Advisory about a file, or about the whole batch, that didn't stop analysis
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnalysisWarning {
+    /// File the warning is about (empty for batch-level warnings)
+    pub file_path: String,
+    /// Kind of warning
+    pub kind: AnalysisWarningKind,
+    /// Number of files affected, for batch-level warnings
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_count: Option<usize>,
+    /// Human-readable details
+    pub message: String,
 }
 
 /// Batch analysis result
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct BatchAnalysisResult {
     /// Results for each file
@@ -1034,6 +2387,16 @@ pub struct BatchAnalysisResult {
     pub skipped: usize,
     /// Whether LLM was available
     pub llm_available: bool,
+    /// Non-fatal issues: batch-level ones (vision disabled, missing API key,
+    /// repeated errors) first, then per-file ones
+    pub warnings: Vec<AnalysisWarning>,
+    /// Confidence below which suggestions should be hidden when displayed
+    /// (annotation only, see `filter_results_by_confidence`)
+    #[serde(default)]
+    pub display_confidence_floor: f32,
+    /// Whether the batch was cancelled; files not started are skipped as "cancelled"
+    #[serde(default)]
+    pub cancelled: bool,
 }
 
 /// Request for OpenAI Chat Completion
@@ -1045,6 +2408,18 @@ struct OpenAiChatRequest {
     max_tokens: u32,
 }
 
+impl OpenAiChatRequest {
+    /// Build a chat request using the configured generation parameters
+    fn new(model: String, messages: Vec<OpenAiMessage>, config: &OllamaConfig) -> Self {
+        OpenAiChatRequest {
+            model,
+            messages,
+            temperature: config.temperature,
+            max_tokens: config.max_tokens,
+        }
+    }
+}
+
 #[derive(Debug, Serialize)]
 struct OpenAiMessage {
     role: String,
@@ -1082,6 +2457,15 @@ struct OllamaOptions {
     num_predict: u32,
 }
 
+impl OllamaOptions {
+    fn from_config(config: &OllamaConfig) -> Self {
+        OllamaOptions {
+            temperature: config.temperature,
+            num_predict: config.max_tokens,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 struct OllamaGenerateResponse {
     response: String,
@@ -1276,6 +2660,28 @@ fn is_image_file(path: &str) -> bool {
     IMAGE_EXTENSIONS.contains(&ext.as_str())
 }
 
+/// Video extensions whose embedded thumbnail can be analyzed (MP4/QuickTime)
+const VIDEO_EXTENSIONS: &[&str] = &["mp4", "m4v", "mov"];
+
+/// Largest "moov" box read when looking for a video thumbnail
+const MAX_VIDEO_METADATA_BYTES: u64 = 32 * 1024 * 1024;
+
+/// Check if file is a video with possible embedded thumbnail
+fn is_video_file(path: &str) -> bool {
+    let ext = std::path::Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .unwrap_or_default();
+    VIDEO_EXTENSIONS.contains(&ext.as_str())
+}
+
+/// Whether a file goes through the vision model with this config
+fn uses_vision(path: &str, config: &OllamaConfig) -> bool {
+    config.vision_enabled
+        && (is_image_file(path) || (config.video_analysis_enabled && is_video_file(path)))
+}
+
 /// Check if file is extractable text
 fn is_text_file(path: &str) -> bool {
     let ext = std::path::Path::new(path)
@@ -1286,80 +2692,472 @@ fn is_text_file(path: &str) -> bool {
     TEXT_EXTENSIONS.contains(&ext.as_str())
 }
 
-/// Extract text content from a file (limited)
-fn extract_file_content(path: &str, max_chars: usize) -> Result<String, String> {
+/// Check if file is text, including the configured `extra_text_extensions`
+/// and formats with a content extractor
+fn is_analyzable_text(path: &str, config: &OllamaConfig) -> bool {
+    if is_text_file(path) || has_content_extractor(path) {
+        return true;
+    }
+    let Some(ext) = std::path::Path::new(path).extension().and_then(|e| e.to_str()) else {
+        return false;
+    };
+    config
+        .extra_text_extensions
+        .iter()
+        .any(|extra| extra.trim_start_matches('.').eq_ignore_ascii_case(ext))
+}
+
+/// Text and metadata extracted from a file for analysis
+#[derive(Debug, Clone, Default)]
+struct ExtractedContent {
+    text: String,
+    /// Fields found in the file, keyed like `extract_file_metadata` ("title", ...)
+    metadata: HashMap<String, String>,
+    /// Whether the file holds more content than `text` (the read was cut short)
+    truncated: bool,
+}
+
+impl ExtractedContent {
+    /// Text sent to the model, led by the document's title, author and date when known
+    fn into_analysis_text(self) -> String {
+        const FIELDS: &[(&str, &str)] =
+            &[("title", "Title"), ("author", "Author"), ("creationDate", "Date")];
+        let header: Vec<String> = FIELDS
+            .iter()
+            .filter_map(|(key, label)| Some(format!("{}: {}", label, self.metadata.get(*key)?)))
+            .collect();
+        if header.is_empty() {
+            self.text
+        } else {
+            format!("{}\n\n{}", header.join("\n"), self.text)
+        }
+    }
+}
+
+/// Extracts up to about `max_chars` characters of content from a file
+type ContentExtractor = fn(&str, usize) -> Result<ExtractedContent, String>;
+
+/// Content extractors by lowercase extension; other files are read as text.
+/// New formats are added here.
+const CONTENT_EXTRACTORS: &[(&str, ContentExtractor)] = &[("pdf", extract_pdf_content)];
+
+/// Registered extractor for a file, if its extension has one
+fn registered_extractor(path: &str) -> Option<ContentExtractor> {
+    let ext = std::path::Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())?;
+    CONTENT_EXTRACTORS
+        .iter()
+        .find(|(registered, _)| *registered == ext)
+        .map(|&(_, extractor)| extractor)
+}
+
+/// Whether a file has a dedicated content extractor
+fn has_content_extractor(path: &str) -> bool {
+    registered_extractor(path).is_some()
+}
+
+/// Extract a file's content with the extractor registered for its extension,
+/// falling back to reading it as text
+fn extract_content(path: &str, max_chars: usize) -> Result<ExtractedContent, String> {
+    let extractor: ContentExtractor = registered_extractor(path).unwrap_or(extract_text_content);
+    extractor(path, max_chars)
+}
+
+/// Fallback extractor: the decoded start of the file
+///
+/// Reads at most `max_chars + 100` bytes, so `truncated` is decided by what
+/// was left unread or undecoded, not by comparing the file size to `max_chars`.
+fn extract_text_content(path: &str, max_chars: usize) -> Result<ExtractedContent, String> {
     use std::fs;
     use std::io::Read;
 
-    let mut file = fs::File::open(path)
+    let file = fs::File::open(path)
         .map_err(|e| format!("Failed to open file: {}", e))?;
+    let file_len = file.metadata().map(|m| m.len()).unwrap_or(0);
 
-    let mut buffer = vec![0u8; max_chars + 100];
-
-    let bytes_read = file.read(&mut buffer)
+    let mut buffer = Vec::with_capacity(max_chars + 100);
+    file.take((max_chars + 100) as u64)
+        .read_to_end(&mut buffer)
         .map_err(|e| format!("Failed to read file: {}", e))?;
 
-    // Try to convert to UTF-8
-    let content: String = String::from_utf8_lossy(&buffer[..bytes_read])
-        .chars()
-        .take(max_chars)
-        .collect();
+    // Detect BOM / encoding and convert to UTF-8
+    let decoded = decode_text_bytes(&buffer);
+    let mut chars = decoded.chars();
+    let text: String = chars.by_ref().take(max_chars).collect();
+    let truncated = chars.next().is_some() || file_len > buffer.len() as u64;
 
-    Ok(content)
+    Ok(ExtractedContent {
+        text,
+        metadata: HashMap::new(),
+        truncated,
+    })
 }
 
-/// Encode image to base64 for vision APIs
-fn encode_image_base64(path: &str) -> Result<String, String> {
-    use std::fs;
-    use base64::{Engine as _, engine::general_purpose::STANDARD};
-
-    let bytes = fs::read(path)
-        .map_err(|e| format!("Failed to read image: {}", e))?;
+/// PDF extractor: text of the content streams plus Info dictionary fields
+fn extract_pdf_content(path: &str, max_chars: usize) -> Result<ExtractedContent, String> {
+    let text = extract_pdf_text(path, max_chars).ok_or_else(|| "Not a valid PDF".to_string())?;
+    let metadata = extract_file_metadata(path, "pdf", &FileCategory::Document).unwrap_or_default();
+    // The text is cut at `max_chars`, so reaching it means there was more
+    let truncated = text.chars().count() >= max_chars;
+    Ok(ExtractedContent { text, metadata, truncated })
+}
 
-    Ok(STANDARD.encode(&bytes))
+/// Read the text analysis samples from, and whether reading cut it short
+///
+/// Large files are read in windows (head, middle and tail), others up to
+/// `MAX_SAMPLE_SOURCE_CHARS`. The content cache key hashes the same text.
+fn read_analysis_text(path: &str) -> Result<(String, bool), String> {
+    match extract_windowed_content(path, MAX_CONTENT_CHARS) {
+        // Only files too large to read whole are read in windows
+        Some(text) => Ok((text, true)),
+        None => extract_content(path, MAX_SAMPLE_SOURCE_CHARS).map(|content| {
+            let truncated = content.truncated;
+            (content.into_analysis_text(), truncated)
+        }),
+    }
 }
 
-/// Get MIME type for image
-fn get_image_mime_type(path: &str) -> &'static str {
+/// Read the head plus mid-file and tail windows of a large text file
+///
+/// Gives the model rows from the whole file, not only the header of a log or
+/// CSV export, without loading it. Windows after the head start at a line
+/// boundary and the result fits in `max_chars` bytes. Returns `None` for
+/// files up to `LARGE_TEXT_FILE_BYTES`, for code and Markdown (sampled by
+/// outline instead), for formats with a content extractor and for UTF-16
+/// text, which can't be read at any offset.
+fn extract_windowed_content(path: &str, max_chars: usize) -> Option<String> {
+    use std::fs;
+    use std::io::{Read, Seek, SeekFrom};
+
+    const SEPARATOR: &str = "\n\n[... truncated ...]\n\n";
+
     let ext = std::path::Path::new(path)
         .extension()
         .and_then(|e| e.to_str())
         .map(|e| e.to_lowercase())
         .unwrap_or_default();
+    if CODE_EXTENSIONS.contains(&ext.as_str())
+        || MARKDOWN_EXTENSIONS.contains(&ext.as_str())
+        || has_content_extractor(path)
+    {
+        return None;
+    }
 
-    match ext.as_str() {
-        "jpg" | "jpeg" => "image/jpeg",
-        "png" => "image/png",
-        "gif" => "image/gif",
-        "webp" => "image/webp",
-        _ => "image/jpeg",
+    let mut file = fs::File::open(path).ok()?;
+    let size = file.metadata().ok()?.len();
+    if size <= LARGE_TEXT_FILE_BYTES {
+        return None;
     }
-}
 
-// =============================================================================
-// LLM Analysis Commands
-// =============================================================================
+    // Half of the budget for the head, the rest shared by the other windows
+    let budget = max_chars.saturating_sub(SEPARATOR.len() * SAMPLE_WINDOW_POSITIONS.len());
+    let head_len = budget / 2;
+    let window_len = (budget - head_len) / SAMPLE_WINDOW_POSITIONS.len();
 
-use super::config::{OllamaConfig, LlmProvider};
+    let mut head = vec![0u8; head_len];
+    let read = file.read(&mut head).ok()?;
+    head.truncate(read);
+    if head.starts_with(&[0xFF, 0xFE]) || head.starts_with(&[0xFE, 0xFF]) || head.contains(&0) {
+        return None;
+    }
 
-/// Scan existing folder structure in a directory (max 2 levels deep)
-fn scan_folder_structure(base_path: &str) -> Vec<String> {
-    let mut folders = Vec::new();
-    let base = std::path::Path::new(base_path);
+    let mut parts = vec![decode_text_bytes(&head).into_owned()];
+    for position in SAMPLE_WINDOW_POSITIONS {
+        let offset = ((size - window_len as u64) as f64 * position) as u64;
+        file.seek(SeekFrom::Start(offset)).ok()?;
 
-    if !base.is_dir() {
-        return folders;
+        let mut window = vec![0u8; window_len];
+        let read = file.read(&mut window).ok()?;
+        window.truncate(read);
+
+        // Drop the partial first line (and any partial UTF-8 sequence with it)
+        let start = window.iter().position(|b| *b == b'\n').map_or(0, |i| i + 1);
+        parts.push(decode_text_bytes(&window[start..]).into_owned());
     }
 
-    // Scan first level
-    if let Ok(entries) = std::fs::read_dir(base) {
-        for entry in entries.filter_map(|e| e.ok()) {
-            let path = entry.path();
-            if path.is_dir() {
-                if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-                    // Skip hidden folders
-                    if !name.starts_with('.') {
-                        folders.push(name.to_string());
+    Some(parts.join(SEPARATOR))
+}
+
+/// Decode raw text bytes to UTF-8, handling BOMs and common non-UTF-8 encodings
+///
+/// Order of detection:
+/// 1. BOM (UTF-8, UTF-16LE, UTF-16BE) - BOM is stripped
+/// 2. BOM-less UTF-16, detected by the distribution of NUL bytes
+/// 3. Valid UTF-8 (fast path, tolerates a sequence cut off by the read buffer)
+/// 4. Latin-1 (decoded as Windows-1252, its superset)
+fn decode_text_bytes(bytes: &[u8]) -> std::borrow::Cow<'_, str> {
+    use encoding_rs::{Encoding, WINDOWS_1252};
+
+    if let Some((encoding, bom_len)) = Encoding::for_bom(bytes) {
+        let (decoded, _) = encoding.decode_without_bom_handling(&bytes[bom_len..]);
+        return decoded;
+    }
+
+    // NUL bytes are valid UTF-8 but never appear in real text files,
+    // so only pay for UTF-16 detection when there is one
+    if bytes.contains(&0) {
+        if let Some(encoding) = detect_utf16_without_bom(bytes) {
+            let (decoded, _) = encoding.decode_without_bom_handling(bytes);
+            return decoded;
+        }
+    }
+
+    match std::str::from_utf8(bytes) {
+        Ok(text) => return std::borrow::Cow::Borrowed(text),
+        // Only the trailing sequence is incomplete (truncated by the read buffer)
+        Err(e) if e.error_len().is_none() => {
+            return String::from_utf8_lossy(&bytes[..e.valid_up_to()]);
+        }
+        Err(_) => {}
+    }
+
+    let (decoded, _) = WINDOWS_1252.decode_without_bom_handling(bytes);
+    decoded
+}
+
+/// Guess UTF-16 byte order for text without a BOM
+///
+/// ASCII-heavy UTF-16 text has a NUL in every other byte: odd positions for
+/// little-endian, even positions for big-endian.
+fn detect_utf16_without_bom(bytes: &[u8]) -> Option<&'static encoding_rs::Encoding> {
+    if bytes.len() < 4 {
+        return None;
+    }
+
+    let pairs = bytes.len() / 2;
+    let even_nuls = bytes.iter().step_by(2).take(pairs).filter(|b| **b == 0).count();
+    let odd_nuls = bytes.iter().skip(1).step_by(2).filter(|b| **b == 0).count();
+
+    // Require most code units to have a NUL half, and the other half to be mostly non-NUL
+    let threshold = pairs * 7 / 10;
+    if odd_nuls >= threshold && even_nuls < pairs / 10 {
+        Some(encoding_rs::UTF_16LE)
+    } else if even_nuls >= threshold && odd_nuls < pairs / 10 {
+        Some(encoding_rs::UTF_16BE)
+    } else {
+        None
+    }
+}
+
+/// Encode image to base64 for vision APIs
+fn encode_image_base64(path: &str) -> Result<String, String> {
+    use std::fs;
+    use base64::{Engine as _, engine::general_purpose::STANDARD};
+
+    let bytes = fs::read(path)
+        .map_err(|e| format!("Failed to read image: {}", e))?;
+
+    Ok(STANDARD.encode(&bytes))
+}
+
+/// Find a box by type among sibling MP4/QuickTime boxes and return its payload
+///
+/// Box sizes come from the file, so a size smaller than its own header or
+/// larger than the bytes left ends the search instead of overflowing.
+fn find_mp4_box<'a>(data: &'a [u8], box_type: &[u8; 4]) -> Option<&'a [u8]> {
+    let mut offset = 0usize;
+    while data.len() - offset >= 8 {
+        let remaining = data.len() - offset;
+        let raw_size = u32::from_be_bytes(data[offset..offset + 4].try_into().ok()?);
+        let (header_len, size) = match raw_size {
+            0 => (8, remaining),
+            1 => {
+                let large = data.get(offset + 8..offset.checked_add(16)?)?;
+                (16, usize::try_from(u64::from_be_bytes(large.try_into().ok()?)).ok()?)
+            }
+            size => (8, usize::try_from(size).ok()?),
+        };
+        if size < header_len || size > remaining {
+            return None;
+        }
+        let end = offset.checked_add(size)?;
+        if &data[offset + 4..offset + 8] == box_type {
+            return Some(&data[offset + header_len..end]);
+        }
+        offset = end;
+    }
+    None
+}
+
+/// Read the payload of the top-level "moov" box without loading the media data
+fn read_mp4_moov(path: &str) -> Option<Vec<u8>> {
+    use std::io::{Read, Seek, SeekFrom};
+
+    let mut file = std::fs::File::open(path).ok()?;
+    let len = file.metadata().ok()?.len();
+    let mut offset = 0u64;
+
+    while len - offset >= 8 {
+        let remaining = len - offset;
+        let mut header = [0u8; 16];
+        file.seek(SeekFrom::Start(offset)).ok()?;
+        file.read_exact(&mut header[..8]).ok()?;
+
+        let (header_len, size) = match u32::from_be_bytes(header[..4].try_into().ok()?) {
+            0 => (8, remaining),
+            1 => {
+                file.read_exact(&mut header[8..]).ok()?;
+                (16, u64::from_be_bytes(header[8..].try_into().ok()?))
+            }
+            size => (8, u64::from(size)),
+        };
+        if size < header_len || size > remaining {
+            return None;
+        }
+
+        if &header[4..8] == b"moov" {
+            let payload_len = size - header_len;
+            if payload_len > MAX_VIDEO_METADATA_BYTES {
+                return None;
+            }
+            let mut payload = vec![0u8; payload_len as usize];
+            file.read_exact(&mut payload).ok()?;
+            return Some(payload);
+        }
+        offset = offset.checked_add(size)?;
+    }
+    None
+}
+
+/// MIME type of a thumbnail from its magic bytes (JPEG or PNG only)
+fn thumbnail_mime_type(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("image/jpeg")
+    } else if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("image/png")
+    } else {
+        None
+    }
+}
+
+/// Extract the cover image embedded in an MP4/QuickTime file
+///
+/// Reads moov/udta/meta/ilst/covr, where phones, editors and iTunes store a
+/// poster frame. Returns the image bytes and MIME type, or `None` when the
+/// video has no embedded JPEG or PNG thumbnail.
+fn extract_video_thumbnail(path: &str) -> Option<(Vec<u8>, &'static str)> {
+    let moov = read_mp4_moov(path)?;
+    let udta = find_mp4_box(&moov, b"udta")?;
+    let meta = find_mp4_box(udta, b"meta")?;
+    // ISO "meta" is a full box (version and flags); QuickTime's is not
+    let meta_children = if meta.get(4..8) == Some(b"hdlr".as_slice()) {
+        meta
+    } else {
+        meta.get(4..)?
+    };
+    let ilst = find_mp4_box(meta_children, b"ilst")?;
+    let covr = find_mp4_box(ilst, b"covr")?;
+    // "data" payload: 4-byte type indicator, 4-byte locale, then the image
+    let image = find_mp4_box(covr, b"data")?.get(8..)?;
+
+    let mime_type = thumbnail_mime_type(image)?;
+    Some((image.to_vec(), mime_type))
+}
+
+/// Get MIME type for image
+fn get_image_mime_type(path: &str) -> &'static str {
+    let ext = std::path::Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .unwrap_or_default();
+
+    match ext.as_str() {
+        "jpg" | "jpeg" => "image/jpeg",
+        "png" => "image/png",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        _ => "image/jpeg",
+    }
+}
+
+// =============================================================================
+// LLM Analysis Commands
+// =============================================================================
+
+use super::config::{
+    env_var, AdaptiveConsolidationConfig, CacheKeyStrategy, FolderKeywords, LlmFileTypes,
+    OllamaConfig, LlmProvider, OPENAI_API_KEY_ENV,
+};
+
+/// Cached folder structure of a base directory
+struct FolderStructureEntry {
+    signature: Vec<Option<std::time::SystemTime>>,
+    folders: Vec<String>,
+}
+
+/// Modification times of a base directory and its first-level folders
+///
+/// Adding, removing or renaming a folder updates its parent's mtime, so this
+/// changes whenever the two scanned levels change.
+fn folder_structure_signature(
+    base_path: &str,
+    folders: &[String],
+) -> Vec<Option<std::time::SystemTime>> {
+    let base = std::path::Path::new(base_path);
+    let modified = |path: &std::path::Path| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+
+    std::iter::once(modified(base))
+        .chain(
+            folders
+                .iter()
+                .filter(|folder| !folder.contains('/'))
+                .map(|folder| modified(&base.join(folder))),
+        )
+        .collect()
+}
+
+/// Folder structure of a base directory, walked only when it changed
+fn cached_folder_structure(base_path: &str, walk: impl Fn(&str) -> Vec<String>) -> Vec<String> {
+    let mut cache = FOLDER_STRUCTURE_CACHE
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    if let Some(entry) = cache.get(base_path) {
+        if entry.signature == folder_structure_signature(base_path, &entry.folders) {
+            return entry.folders.clone();
+        }
+    }
+
+    let folders = walk(base_path);
+    let signature = folder_structure_signature(base_path, &folders);
+    cache.insert(
+        base_path.to_string(),
+        FolderStructureEntry {
+            signature,
+            folders: folders.clone(),
+        },
+    );
+    folders
+}
+
+/// Scan existing folder structure in a directory (max 2 levels deep)
+///
+/// Cached per base path until a scanned folder changes.
+fn scan_folder_structure(base_path: &str) -> Vec<String> {
+    cached_folder_structure(base_path, walk_folder_structure)
+}
+
+/// Walk the folder structure of a directory (max 2 levels deep)
+fn walk_folder_structure(base_path: &str) -> Vec<String> {
+    let mut folders = Vec::new();
+    let base = std::path::Path::new(base_path);
+
+    if !base.is_dir() {
+        return folders;
+    }
+
+    // Scan first level
+    if let Ok(entries) = std::fs::read_dir(base) {
+        for entry in entries.filter_map(|e| e.ok()) {
+            let path = entry.path();
+            if path.is_dir() {
+                if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
+                    // Skip hidden folders
+                    if !name.starts_with('.') {
+                        folders.push(name.to_string());
 
                         // Scan second level
                         if let Ok(sub_entries) = std::fs::read_dir(&path) {
@@ -1400,17 +3198,144 @@ pub struct AnalysisProgress {
     pub phase: String,
 }
 
+/// Percentage of files processed (an empty batch is complete)
+fn progress_percent(processed: usize, total: usize) -> u8 {
+    if total == 0 {
+        return 100;
+    }
+    ((processed.min(total) as f64 / total as f64) * 100.0) as u8
+}
+
+/// Check whether the configured provider is reachable
+async fn check_provider_available(config: &OllamaConfig) -> bool {
+    let status = match config.provider {
+        LlmProvider::Ollama => {
+            check_ollama_health(config.base_url.clone(), config.health_check_timeout).await
+        }
+        LlmProvider::Openai => {
+            check_openai_health(
+                config.openai.api_key.clone(),
+                config.openai.base_url.clone(),
+                config.health_check_timeout,
+            )
+            .await
+        }
+    };
+
+    status.map(|s| s.available).unwrap_or(false)
+}
+
+/// Result for an empty batch, with `llm_available` from a real health check
+async fn empty_analysis_result(config: &OllamaConfig) -> BatchAnalysisResult {
+    BatchAnalysisResult {
+        results: Vec::new(),
+        total: 0,
+        analyzed: 0,
+        failed: 0,
+        skipped: 0,
+        llm_available: check_provider_available(config).await,
+        warnings: Vec::new(),
+        display_confidence_floor: config.display_confidence_floor,
+        cancelled: false,
+    }
+}
+
+/// Acquire a permit for one LLM request
+///
+/// Batch requests share the `batch` pool. Interactive requests take the
+/// `reserved` permit or any free batch permit, and only wait for the reserved
+/// one, so they never queue behind a saturated batch pool.
+async fn acquire_analysis_permit<'a>(
+    batch: &'a Semaphore,
+    reserved: &'a Semaphore,
+    interactive: bool,
+) -> Option<SemaphorePermit<'a>> {
+    if !interactive {
+        return batch.acquire().await.ok();
+    }
+    match reserved.try_acquire().or_else(|_| batch.try_acquire()) {
+        Ok(permit) => Some(permit),
+        Err(_) => reserved.acquire().await.ok(),
+    }
+}
+
 /// Analyze files with LLM to get naming suggestions
 ///
+/// Emits "analysis-progress" events as files start and finish. With
+/// `progress_interval` set, only every Nth completed file is reported
+/// (0 disables per-file events; starting/complete events are always sent).
+///
+/// With `only_bad_names`, every file (images included) is scored by filename
+/// and descriptive names are skipped with source "good-name" before any
+/// network call, to save cost.
+///
+/// With `deterministic`, results are returned sorted by file path and folder
+/// consolidation gives the same output on every run of the same input.
+///
+/// A request for a single file counts as interactive: with
+/// `interactive_priority` it uses a reserved permit instead of waiting for a
+/// large batch running at the same time.
+///
+/// The batch is listed by `list_active_sessions` while it runs and can be
+/// stopped with `cancel_scan`: files not started yet are skipped as
+/// "cancelled" and the result is marked `cancelled`.
+///
 /// Command name: analyze_files_with_llm (snake_case per architecture)
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn analyze_files_with_llm(
     window: tauri::Window,
+    scan_state: tauri::State<'_, ScanState>,
+    file_paths: Vec<String>,
+    config: OllamaConfig,
+    base_path: Option<String>,
+    progress_interval: Option<usize>,
+    only_bad_names: Option<bool>,
+    deterministic: Option<bool>,
+) -> Result<BatchAnalysisResult, String> {
+    let session_path = base_path
+        .clone()
+        .or_else(|| file_paths.first().cloned())
+        .unwrap_or_default();
+    let (session_id, token) = scan_state
+        .create_session(SessionKind::Analysis, &session_path)
+        .map_err(|e| e.to_string())?;
+
+    let result = analyze_files(
+        move |progress| {
+            let _ = window.emit("analysis-progress", progress);
+        },
+        token,
+        file_paths,
+        config,
+        base_path,
+        progress_interval,
+        only_bad_names,
+        deterministic,
+    )
+    .await;
+
+    scan_state.remove_session(&session_id);
+    result
+}
+
+/// Batch analysis behind [`analyze_files_with_llm`]
+///
+/// Progress is reported through `emit`. `token` is checked before each file
+/// is sent to the provider, so a cancelled batch stops between files.
+#[allow(clippy::too_many_arguments)]
+async fn analyze_files(
+    emit: impl Fn(AnalysisProgress) + Send + Sync + 'static,
+    token: CancellationToken,
     file_paths: Vec<String>,
     config: OllamaConfig,
     base_path: Option<String>,
+    progress_interval: Option<usize>,
+    only_bad_names: Option<bool>,
+    deterministic: Option<bool>,
 ) -> Result<BatchAnalysisResult, String> {
     let total = file_paths.len();
+    let only_bad_names = only_bad_names.unwrap_or(false);
 
     // Validate URL security for OpenAI provider (SEC-001)
     if config.provider == LlmProvider::Openai {
@@ -1418,7 +3343,7 @@ pub async fn analyze_files_with_llm(
     }
 
     // Emit initial progress
-    let _ = window.emit("analysis-progress", AnalysisProgress {
+    emit(AnalysisProgress {
         current_file: String::new(),
         processed: 0,
         total,
@@ -1437,19 +3362,13 @@ pub async fn analyze_files_with_llm(
         // Return all as skipped when LLM is disabled
         let results: Vec<FileAnalysisResult> = file_paths
             .into_iter()
-            .map(|file_path| FileAnalysisResult {
-                file_path,
-                suggestion: None,
-                error: Some("LLM analysis is disabled".to_string()),
-                skipped: true,
-                source: "disabled".to_string(),
-            })
+            .map(disabled_result)
             .collect();
 
         let skipped = results.len();
 
         // Emit completion
-        let _ = window.emit("analysis-progress", AnalysisProgress {
+        emit(AnalysisProgress {
             current_file: String::new(),
             processed: total,
             total,
@@ -1464,7 +3383,25 @@ pub async fn analyze_files_with_llm(
             failed: 0,
             skipped,
             llm_available: false,
+            warnings: Vec::new(),
+            display_confidence_floor: config.display_confidence_floor,
+            cancelled: false,
+        });
+    }
+
+    // Nothing to analyze: report whether the provider is actually reachable
+    if file_paths.is_empty() {
+        let result = empty_analysis_result(&config).await;
+
+        emit(AnalysisProgress {
+            current_file: String::new(),
+            processed: 0,
+            total: 0,
+            percent: 100,
+            phase: "complete".to_string(),
         });
+
+        return Ok(result);
     }
 
     let client = Arc::new(Client::builder()
@@ -1472,8 +3409,16 @@ pub async fn analyze_files_with_llm(
         .build()
         .map_err(|e| format!("Failed to create HTTP client: {}", e))?);
 
+    // Catch a mistyped or missing model before every file fails with a 404
+    if config.provider == LlmProvider::Ollama {
+        verify_ollama_models(&client, &config).await?;
+    }
+
     let config = Arc::new(config);
 
+    // A lone file is a user waiting on one result: don't queue it behind batches
+    let interactive = config.interactive_priority && total == 1;
+
     // Process files concurrently with semaphore-limited parallelism
     // Use a channel to track progress
     let (progress_tx, mut progress_rx) = tokio::sync::mpsc::channel::<(String, bool)>(total);
@@ -1484,11 +3429,25 @@ pub async fn analyze_files_with_llm(
         let config = Arc::clone(&config);
         let existing_folders = Arc::clone(&existing_folders);
         let progress_tx = progress_tx.clone();
+        let token = token.clone();
         let file_path_clone = file_path.clone();
 
         let handle = tokio::spawn(async move {
+            // "Only bad names" mode: skip descriptive names without waiting for a permit
+            if let Some(result) = skip_good_name(&file_path_clone, only_bad_names) {
+                let _ = progress_tx.send((file_path_clone, true)).await;
+                return result;
+            }
+
             // Acquire semaphore permit (limits concurrent requests)
-            let _permit = LLM_SEMAPHORE.acquire().await.ok();
+            let _permit =
+                acquire_analysis_permit(&LLM_SEMAPHORE, &INTERACTIVE_SEMAPHORE, interactive).await;
+
+            // Files still waiting for a permit when the batch is cancelled are not sent
+            if token.is_cancelled() {
+                let _ = progress_tx.send((file_path_clone.clone(), true)).await;
+                return cancelled_result(file_path_clone);
+            }
 
             // Emit progress before starting
             let _ = progress_tx.send((file_path_clone.clone(), false)).await;
@@ -1510,7 +3469,8 @@ pub async fn analyze_files_with_llm(
     drop(progress_tx);
 
     // Spawn a task to handle progress updates
-    let window_clone = window.clone();
+    let emit = Arc::new(emit);
+    let emit_progress = Arc::clone(&emit);
     let total_files = total;
     let progress_task = tokio::spawn(async move {
         let mut processed = 0;
@@ -1518,20 +3478,23 @@ pub async fn analyze_files_with_llm(
         while let Some((file, completed)) = progress_rx.recv().await {
             if completed {
                 processed += 1;
-                let percent = ((processed as f64 / total_files as f64) * 100.0) as u8;
-                let _ = window_clone.emit("analysis-progress", AnalysisProgress {
+                if !should_report_progress(processed, Some(progress_interval.unwrap_or(1))) {
+                    continue;
+                }
+                let percent = progress_percent(processed, total_files);
+                emit_progress(AnalysisProgress {
                     current_file: file.clone(),
                     processed,
                     total: total_files,
                     percent,
                     phase: if processed == total_files { "complete" } else { "analyzing" }.to_string(),
                 });
-            } else {
-                let _ = window_clone.emit("analysis-progress", AnalysisProgress {
+            } else if progress_interval.is_none_or(|n| n == 1) {
+                emit_progress(AnalysisProgress {
                     current_file: file.clone(),
                     processed,
                     total: total_files,
-                    percent: ((processed as f64 / total_files as f64) * 100.0) as u8,
+                    percent: progress_percent(processed, total_files),
                     phase: "analyzing".to_string(),
                 });
             }
@@ -1556,13 +3519,7 @@ pub async fn analyze_files_with_llm(
             }
             Err(e) => {
                 // Task panicked or was cancelled
-                results.push(FileAnalysisResult {
-                    file_path: "unknown".to_string(),
-                    suggestion: None,
-                    error: Some(format!("Task failed: {}", e)),
-                    skipped: false,
-                    source: "error".to_string(),
-                });
+                results.push(FileAnalysisResult::failed("unknown", format!("Task failed: {}", e)));
                 failed += 1;
             }
         }
@@ -1571,12 +3528,26 @@ pub async fn analyze_files_with_llm(
     // Wait for progress task to complete
     let _ = progress_task.await;
 
+    let mut existing_folders = existing_folders.to_vec();
+    if deterministic.unwrap_or(false) {
+        sort_for_reproducibility(&mut results, &mut existing_folders);
+    }
+
     // Post-processing: Consolidate folder suggestions to reduce fragmentation
     // This normalizes folder names, merges similar folders, and enforces minimum thresholds
-    consolidate_folder_suggestions(&mut results, &existing_folders);
+    let limits = select_consolidation_limits(&results, &config.adaptive_consolidation);
+    let mut warnings = collect_batch_warnings(&results, &config);
+    warnings.extend(consolidate_folder_suggestions(&mut results, &existing_folders, limits));
+    warnings.extend(collect_analysis_warnings(&results));
+
+    // Post-processing: Don't let low-confidence suggestions override good names
+    apply_keep_original_policy(&mut results, config.keep_original_confidence_threshold);
+
+    // Post-processing: Restore the casing of known acronyms and brand names
+    apply_name_dictionary(&mut results, &config.name_dictionary);
 
     // Emit final completion
-    let _ = window.emit("analysis-progress", AnalysisProgress {
+    emit(AnalysisProgress {
         current_file: String::new(),
         processed: total,
         total,
@@ -1591,135 +3562,328 @@ pub async fn analyze_files_with_llm(
         failed,
         skipped,
         llm_available: true,
+        warnings,
+        display_confidence_floor: config.display_confidence_floor,
+        cancelled: token.is_cancelled(),
     })
 }
 
-/// Analyze a single file with caching, pre-filtering, and retry support
-async fn analyze_single_file_with_cache(
-    client: &Client,
-    file_path: &str,
-    config: &OllamaConfig,
-    existing_folders: &[String],
-    _skip_prefilter: bool,
-) -> FileAnalysisResult {
-    // Filter folders based on file type for more relevant context
-    let filtered_folders = filter_folders_for_file_type(existing_folders, file_path);
+// =============================================================================
+// Sample Analysis
+// =============================================================================
 
-    // IMPORTANT: Never pre-filter images - they should always use vision model
-    // Pre-filter only applies to text files
-    let is_image = is_image_file(file_path);
+/// Result of analyzing a sample of a batch, with estimates for the full batch
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SampleAnalysisResult {
+    /// Results for the sampled files
+    pub results: Vec<FileAnalysisResult>,
+    /// Number of files in the full batch
+    pub total: usize,
+    /// Number of sampled files per category
+    pub sampled_by_category: HashMap<FileCategory, usize>,
+    /// Estimated time to analyze the full batch, extrapolated from the sample
+    pub estimated_duration_ms: u64,
+    /// Estimated tokens (input and output) to analyze the full batch, as a cost proxy
+    pub estimated_tokens: u64,
+}
 
-    // Pre-filter: Skip AI analysis for TEXT files with already descriptive names
-    // Images are NEVER pre-filtered - they always need vision analysis
-    if !is_image {
-        let (needs_analysis, skip_reason) = needs_ai_analysis(file_path);
-        if !needs_analysis {
-            // Return a "keep original" suggestion without calling AI
-            let original_name = std::path::Path::new(file_path)
-                .file_stem()
-                .and_then(|s| s.to_str())
-                .unwrap_or("unknown")
-                .to_string();
+fn category_of(file_path: &str) -> FileCategory {
+    let ext = std::path::Path::new(file_path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("");
+    get_category_for_extension(ext)
+}
 
-            return FileAnalysisResult {
-                file_path: file_path.to_string(),
-                suggestion: Some(AiSuggestion {
-                    suggested_name: original_name.clone(),
-                    confidence: 0.95,
-                    reasoning: skip_reason.unwrap_or_else(|| "Filename already descriptive".to_string()),
-                    keywords: vec![],
-                    keep_original: true,
-                    suggested_folder: None,
-                    folder_confidence: None,
-                }),
-                error: None,
-                skipped: false,
-                source: "prefilter".to_string(),
-            };
-        }
+/// Pick `sample_size` files, stratified by category
+///
+/// Each category gets a share proportional to its size (largest remainder),
+/// and every category is represented when the sample is large enough. Files
+/// are taken evenly spaced from each category's sorted list, so the sample
+/// is deterministic.
+fn stratified_sample(file_paths: &[String], sample_size: usize) -> Vec<String> {
+    let mut sorted: Vec<&String> = file_paths.iter().collect();
+    sorted.sort();
+    if sample_size >= sorted.len() {
+        return sorted.into_iter().cloned().collect();
     }
 
-    // For text files, check cache first
-    if is_text_file(file_path) {
-        if let Ok(content) = extract_file_content(file_path, MAX_CONTENT_CHARS) {
-            let content_hash = hash_content(&content);
-
-            // Check cache
-            if let Some(cached) = get_cached_result(file_path, &content_hash).await {
-                return FileAnalysisResult {
-                    file_path: file_path.to_string(),
-                    suggestion: Some(cached),
-                    error: None,
-                    skipped: false,
-                    source: "cache".to_string(),
-                };
-            }
-
-            // Analyze with retry and cache result
-            let result = analyze_with_retry(client, file_path, config, &filtered_folders).await;
-
-            // Cache successful results
-            if let Some(ref suggestion) = result.suggestion {
-                cache_result(file_path, &content_hash, suggestion).await;
-            }
-
-            return result;
+    let mut groups: Vec<(FileCategory, Vec<&String>)> = Vec::new();
+    for path in sorted {
+        let category = category_of(path);
+        match groups.iter_mut().find(|(c, _)| *c == category) {
+            Some((_, paths)) => paths.push(path),
+            None => groups.push((category, vec![path])),
         }
     }
 
-    // For images, check cache by file metadata
-    if is_image_file(file_path) {
-        if let Some(file_hash) = hash_file_metadata(file_path) {
-            // Check cache
-            if let Some(cached) = get_cached_result(file_path, &file_hash).await {
-                return FileAnalysisResult {
-                    file_path: file_path.to_string(),
-                    suggestion: Some(cached),
-                    error: None,
-                    skipped: false,
-                    source: "cache".to_string(),
-                };
-            }
-
-            // Analyze with retry and cache result
-            let result = analyze_with_retry(client, file_path, config, &filtered_folders).await;
-
-            // Cache successful results
-            if let Some(ref suggestion) = result.suggestion {
-                cache_result(file_path, &file_hash, suggestion).await;
-            }
+    // Proportional quotas, remainders going to the largest fractions first
+    let total = file_paths.len();
+    let mut quotas: Vec<usize> = groups
+        .iter()
+        .map(|(_, paths)| sample_size * paths.len() / total)
+        .collect();
+    let mut by_remainder: Vec<usize> = (0..groups.len()).collect();
+    by_remainder.sort_by_key(|&i| {
+        let len = groups[i].1.len();
+        std::cmp::Reverse((sample_size * len % total, len))
+    });
+    let assigned: usize = quotas.iter().sum();
+    for &i in by_remainder.iter().take(sample_size - assigned) {
+        quotas[i] += 1;
+    }
 
-            return result;
+    // Make sure small categories get at least one file
+    for i in 0..groups.len() {
+        if quotas[i] > 0 {
+            continue;
+        }
+        let largest = (0..groups.len()).max_by_key(|&j| quotas[j]).unwrap_or(i);
+        if quotas[largest] > 1 {
+            quotas[largest] -= 1;
+            quotas[i] = 1;
         }
     }
 
-    // Fallback: analyze without caching
-    analyze_with_retry(client, file_path, config, &filtered_folders).await
+    groups
+        .iter()
+        .zip(quotas)
+        .flat_map(|((_, paths), quota)| {
+            (0..quota).map(move |k| paths[k * paths.len() / quota].clone())
+        })
+        .collect()
 }
 
-/// Analyze a file with exponential backoff retry on rate limits
-async fn analyze_with_retry(
-    client: &Client,
-    file_path: &str,
-    config: &OllamaConfig,
+/// Rough token count for analyzing one file (0 when it wouldn't be sent)
+fn estimate_file_tokens(file_path: &str, config: &OllamaConfig) -> u64 {
+    let input = if uses_vision(file_path, config) {
+        ESTIMATED_IMAGE_TOKENS
+    } else if is_analyzable_text(file_path, config) {
+        let size = std::fs::metadata(file_path).map(|m| m.len()).unwrap_or(0);
+        size.min(MAX_CONTENT_CHARS as u64) / 4
+    } else {
+        return 0;
+    };
+
+    input + ESTIMATED_PROMPT_TOKENS + u64::from(config.max_tokens)
+}
+
+/// Analyze a representative sample before committing to a large batch
+///
+/// Picks `sample_size` files stratified by category, analyzes them like
+/// `analyze_files_with_llm` would, and extrapolates the time and token usage
+/// of the full set so the prompt and model can be checked cheaply.
+///
+/// Command name: sample_analysis (snake_case per architecture)
+#[tauri::command]
+pub async fn sample_analysis(
+    file_paths: Vec<String>,
+    config: OllamaConfig,
+    sample_size: usize,
+) -> Result<SampleAnalysisResult, String> {
+    if !config.enabled {
+        return Err("LLM analysis is disabled".to_string());
+    }
+    if config.provider == LlmProvider::Openai {
+        validate_openai_url_security(&config.openai.base_url)?;
+    }
+
+    let client = Arc::new(Client::builder()
+        .timeout(Duration::from_millis(config.timeout))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?);
+
+    if config.provider == LlmProvider::Ollama {
+        verify_ollama_models(&client, &config).await?;
+    }
+
+    let sample = stratified_sample(&file_paths, sample_size);
+    let mut sampled_by_category: HashMap<FileCategory, usize> = HashMap::new();
+    for path in &sample {
+        *sampled_by_category.entry(category_of(path)).or_insert(0) += 1;
+    }
+
+    let config = Arc::new(config);
+    let started = std::time::Instant::now();
+    let handles: Vec<_> = sample
+        .into_iter()
+        .map(|file_path| {
+            let client = Arc::clone(&client);
+            let config = Arc::clone(&config);
+            tokio::spawn(async move {
+                let _permit = LLM_SEMAPHORE.acquire().await.ok();
+                analyze_single_file_with_cache(&client, &file_path, &config, &[], false).await
+            })
+        })
+        .collect();
+
+    let mut results = Vec::with_capacity(handles.len());
+    for handle in handles {
+        match handle.await {
+            Ok(result) => results.push(result),
+            Err(e) => results.push(FileAnalysisResult::failed(
+                "unknown",
+                format!("Task failed: {}", e),
+            )),
+        }
+    }
+    let elapsed_ms = started.elapsed().as_millis() as u64;
+
+    let estimated_duration_ms = if results.is_empty() {
+        0
+    } else {
+        elapsed_ms * file_paths.len() as u64 / results.len() as u64
+    };
+    let estimated_tokens = file_paths
+        .iter()
+        .map(|path| estimate_file_tokens(path, &config))
+        .sum();
+
+    Ok(SampleAnalysisResult {
+        results,
+        total: file_paths.len(),
+        sampled_by_category,
+        estimated_duration_ms,
+        estimated_tokens,
+    })
+}
+
+/// Analyze a single file with caching, pre-filtering, and retry support
+async fn analyze_single_file_with_cache(
+    client: &Client,
+    file_path: &str,
+    config: &OllamaConfig,
+    existing_folders: &[String],
+    _skip_prefilter: bool,
+) -> FileAnalysisResult {
+    if let Some(filtered) = skip_excluded_type(file_path, &config.file_types) {
+        return filtered;
+    }
+
+    // Filter folders based on file type for more relevant context
+    let filtered_folders = filter_folders_for_file_type(existing_folders, file_path, &config.folder_keywords);
+
+    // IMPORTANT: Never pre-filter images - they should always use vision model
+    // Pre-filter only applies to text files
+    let is_image = is_image_file(file_path) || uses_vision(file_path, config);
+
+    // Pre-filter: Skip AI analysis for TEXT files with already descriptive names
+    // Images are NEVER pre-filtered - they always need vision analysis
+    if !is_image {
+        let (needs_analysis, skip_reason) = needs_ai_analysis(file_path);
+        if !needs_analysis {
+            // Return a "keep original" suggestion without calling AI
+            let original_name = std::path::Path::new(file_path)
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or("unknown")
+                .to_string();
+
+            return FileAnalysisResult {
+                file_path: file_path.to_string(),
+                suggestion: Some(AiSuggestion {
+                    suggested_name: original_name.clone(),
+                    confidence: 0.95,
+                    reasoning: skip_reason.unwrap_or_else(|| "Filename already descriptive".to_string()),
+                    keywords: vec![],
+                    keep_original: true,
+                    suggested_folder: None,
+                    folder_confidence: None,
+                }),
+                source: "prefilter".to_string(),
+                skip_reason: Some(AnalysisSkipReason::AlreadyDescriptive),
+                ..Default::default()
+            };
+        }
+    }
+
+    // Check cache using the configured key strategy for this file type
+    if let Some(cache_key) = analysis_cache_key(file_path, config) {
+        // Check cache
+        if let Some(cached) = get_cached_result(&cache_key).await {
+            return FileAnalysisResult::suggested(file_path, cached, "cache");
+        }
+
+        // Analyze with retry and cache result
+        let result = analyze_with_fallback(client, file_path, config, &filtered_folders).await;
+
+        // Cache successful results
+        if let Some(ref suggestion) = result.suggestion {
+            cache_result(&cache_key, suggestion).await;
+        }
+
+        return result;
+    }
+
+    // Fallback: analyze without caching
+    analyze_with_fallback(client, file_path, config, &filtered_folders).await
+}
+
+/// Whether a failed result should be retried with the fallback provider
+fn should_use_fallback(result: &FileAnalysisResult) -> bool {
+    result.suggestion.is_none() && result.failure.is_some()
+}
+
+/// Analyze a file with the primary provider, then with the fallback provider
+/// if the primary one still fails after its retries
+///
+/// The result's `source` records which provider produced the suggestion.
+async fn analyze_with_fallback(
+    client: &Client,
+    file_path: &str,
+    config: &OllamaConfig,
+    existing_folders: &[String],
+) -> FileAnalysisResult {
+    let result = analyze_with_retry(client, file_path, config, existing_folders).await;
+
+    let fallback = match &config.fallback_provider {
+        Some(provider) if *provider != config.provider && should_use_fallback(&result) => provider,
+        _ => return result,
+    };
+
+    // The batch only validated the primary provider's URL (SEC-001)
+    if *fallback == LlmProvider::Openai {
+        if let Err(e) = validate_openai_url_security(&config.openai.base_url) {
+            let mut result = result;
+            result.error = result
+                .error
+                .map(|primary| format!("{} (fallback provider not used: {})", primary, e));
+            return result;
+        }
+    }
+
+    let fallback_config = OllamaConfig {
+        provider: fallback.clone(),
+        ..config.clone()
+    };
+    let mut fallback_result =
+        analyze_with_retry(client, file_path, &fallback_config, existing_folders).await;
+
+    // Keep both errors when the fallback fails too
+    if let (Some(primary_error), Some(fallback_error)) = (&result.error, &fallback_result.error) {
+        fallback_result.error = Some(format!(
+            "{} (fallback provider also failed: {})",
+            primary_error, fallback_error
+        ));
+    }
+
+    fallback_result
+}
+
+/// Analyze a file with exponential backoff retry on rate limits
+async fn analyze_with_retry(
+    client: &Client,
+    file_path: &str,
+    config: &OllamaConfig,
     existing_folders: &[String],
 ) -> FileAnalysisResult {
     let mut last_result = analyze_single_file(client, file_path, config, existing_folders).await;
 
     // Check if we should retry
     for attempt in 0..MAX_RETRIES {
-        // Only retry on specific errors
-        let should_retry = match &last_result.error {
-            Some(err) => {
-                err.contains("429") ||
-                err.contains("rate limit") ||
-                err.contains("Rate limit") ||
-                err.contains("503") ||
-                err.contains("502") ||
-                err.contains("temporarily unavailable")
-            }
-            None => false,
-        };
+        // Only retry on rate limits and temporary outages
+        let should_retry = last_result.failure.is_some_and(ProviderFailure::is_transient);
 
         if !should_retry {
             break;
@@ -1748,43 +3912,47 @@ async fn analyze_single_file(
         return analyze_image_file(client, file_path, config, existing_folders).await;
     }
 
+    // Videos are named from their embedded thumbnail, like an image
+    if uses_vision(file_path, config) {
+        return analyze_video_file(client, file_path, config, existing_folders).await;
+    }
+
     // Check if it's a text file we can analyze
-    if !is_text_file(file_path) {
-        return FileAnalysisResult {
-            file_path: file_path.to_string(),
-            suggestion: None,
-            error: Some("File type not supported for analysis".to_string()),
-            skipped: true,
-            source: "unsupported".to_string(),
-        };
+    if !is_analyzable_text(file_path, config) {
+        return FileAnalysisResult::skipped(
+            file_path,
+            AnalysisSkipReason::Unsupported,
+            "unsupported",
+            Some("File type not supported for analysis".to_string()),
+        );
     }
 
-    // Extract content with smart truncation
-    let raw_content = match extract_file_content(file_path, MAX_CONTENT_CHARS) {
+    let (raw_content, read_truncated) = match read_analysis_text(file_path) {
         Ok(c) => c,
         Err(e) => {
-            return FileAnalysisResult {
-                file_path: file_path.to_string(),
-                suggestion: None,
-                error: Some(e),
-                skipped: false,
-                source: "error".to_string(),
-            };
+            return FileAnalysisResult::failed(file_path, e);
         }
     };
 
     if raw_content.trim().is_empty() {
-        return FileAnalysisResult {
-            file_path: file_path.to_string(),
-            suggestion: None,
-            error: Some("File is empty".to_string()),
-            skipped: true,
-            source: "empty".to_string(),
-        };
+        return FileAnalysisResult::skipped(
+            file_path,
+            AnalysisSkipReason::Empty,
+            "empty",
+            Some("File is empty".to_string()),
+        );
     }
 
-    // Apply smart truncation for token economy
-    let content = truncate_content_smart(&raw_content, MAX_CONTENT_CHARS);
+    // Apply file-type-aware sampling for token economy
+    let content = sample_content(&raw_content, file_path, MAX_CONTENT_CHARS);
+
+    // Large files are cut while reading, smaller ones while sampling
+    let original = std::fs::metadata(file_path).map(|m| m.len()).unwrap_or(0);
+    let content_truncated = read_truncated || content.len() < raw_content.len();
+    let content_length = ContentLength {
+        original,
+        used: content.len() as u64,
+    };
 
     // Get file extension
     let ext = std::path::Path::new(file_path)
@@ -1793,10 +3961,13 @@ async fn analyze_single_file(
         .unwrap_or("txt");
 
     // Call appropriate provider
-    match config.provider {
+    let mut result = match config.provider {
         LlmProvider::Openai => analyze_with_openai(client, &content, ext, file_path, config, existing_folders).await,
         LlmProvider::Ollama => analyze_with_ollama(client, &content, ext, file_path, config, existing_folders).await,
-    }
+    };
+    result.content_truncated = content_truncated;
+    result.content_length = Some(content_length);
+    result
 }
 
 /// Analyze an image file with vision model
@@ -1810,13 +3981,7 @@ async fn analyze_image_file(
     let base64_image = match encode_image_base64(file_path) {
         Ok(b) => b,
         Err(e) => {
-            return FileAnalysisResult {
-                file_path: file_path.to_string(),
-                suggestion: None,
-                error: Some(e),
-                skipped: false,
-                source: "error".to_string(),
-            };
+            return FileAnalysisResult::failed(file_path, e);
         }
     };
 
@@ -1828,6 +3993,34 @@ async fn analyze_image_file(
     }
 }
 
+/// Analyze a video through its embedded thumbnail with the vision model
+///
+/// Videos without a JPEG or PNG thumbnail are skipped.
+async fn analyze_video_file(
+    client: &Client,
+    file_path: &str,
+    config: &OllamaConfig,
+    existing_folders: &[String],
+) -> FileAnalysisResult {
+    use base64::{Engine as _, engine::general_purpose::STANDARD};
+
+    let Some((thumbnail, mime_type)) = extract_video_thumbnail(file_path) else {
+        return FileAnalysisResult::skipped(
+            file_path,
+            AnalysisSkipReason::Unsupported,
+            "unsupported",
+            Some("Video has no embedded thumbnail".to_string()),
+        );
+    };
+
+    let base64_image = STANDARD.encode(&thumbnail);
+
+    match config.provider {
+        LlmProvider::Openai => analyze_image_with_openai(client, &base64_image, mime_type, file_path, config, existing_folders).await,
+        LlmProvider::Ollama => analyze_image_with_ollama(client, &base64_image, file_path, config, existing_folders).await,
+    }
+}
+
 /// Analyze content with OpenAI
 async fn analyze_with_openai(
     client: &Client,
@@ -1840,13 +4033,11 @@ async fn analyze_with_openai(
     // Retrieve API key from secure storage (SEC-004)
     let api_key = get_openai_api_key(&config.openai.api_key).await;
     if api_key.is_empty() {
-        return FileAnalysisResult {
-            file_path: file_path.to_string(),
-            suggestion: None,
-            error: Some("OpenAI API key not configured".to_string()),
-            skipped: false,
-            source: "error".to_string(),
-        };
+        return FileAnalysisResult::provider_failed(
+            file_path,
+            MISSING_API_KEY_ERROR,
+            Some(ProviderFailure::MissingApiKey),
+        );
     }
 
     // Extract original filename (without extension) for the prompt
@@ -1858,9 +4049,9 @@ async fn analyze_with_openai(
     let url = format!("{}/chat/completions", config.openai.base_url.trim_end_matches('/'));
     let prompt = create_analysis_prompt(content, file_type, original_name, existing_folders);
 
-    let request = OpenAiChatRequest {
-        model: config.openai.model.clone(),
-        messages: vec![
+    let request = OpenAiChatRequest::new(
+        config.openai.model.clone(),
+        vec![
             OpenAiMessage {
                 role: "system".to_string(),
                 content: serde_json::Value::String(NAMING_SYSTEM_PROMPT.to_string()),
@@ -1870,9 +4061,8 @@ async fn analyze_with_openai(
                 content: serde_json::Value::String(prompt),
             },
         ],
-        temperature: 0.3,
-        max_tokens: 500,
-    };
+        config,
+    );
 
     let response = client
         .post(&url)
@@ -1889,30 +4079,19 @@ async fn analyze_with_openai(
                     Ok(data) => {
                         if let Some(choice) = data.choices.first() {
                             if let Some(suggestion) = parse_ai_suggestion(&choice.message.content) {
-                                return FileAnalysisResult {
-                                    file_path: file_path.to_string(),
-                                    suggestion: Some(suggestion),
-                                    error: None,
-                                    skipped: false,
-                                    source: "openai".to_string(),
-                                };
+                                return FileAnalysisResult::suggested(
+                                    file_path,
+                                    suggestion,
+                                    "openai",
+                                );
                             }
                         }
-                        FileAnalysisResult {
-                            file_path: file_path.to_string(),
-                            suggestion: None,
-                            error: Some("Failed to parse AI response".to_string()),
-                            skipped: false,
-                            source: "error".to_string(),
-                        }
+                        FileAnalysisResult::failed(file_path, "Failed to parse AI response")
                     }
-                    Err(e) => FileAnalysisResult {
-                        file_path: file_path.to_string(),
-                        suggestion: None,
-                        error: Some(format!("Failed to parse response: {}", e)),
-                        skipped: false,
-                        source: "error".to_string(),
-                    },
+                    Err(e) => FileAnalysisResult::failed(
+                        file_path,
+                        format!("Failed to parse response: {}", e),
+                    ),
                 }
             } else {
                 let status = resp.status();
@@ -1923,22 +4102,18 @@ async fn analyze_with_openai(
                 } else {
                     format!("API error: {}", status)
                 };
-                FileAnalysisResult {
-                    file_path: file_path.to_string(),
-                    suggestion: None,
-                    error: Some(error_msg),
-                    skipped: false,
-                    source: "error".to_string(),
-                }
+                FileAnalysisResult::provider_failed(
+                    file_path,
+                    error_msg,
+                    ProviderFailure::from_status(status),
+                )
             }
         }
-        Err(e) => FileAnalysisResult {
-            file_path: file_path.to_string(),
-            suggestion: None,
-            error: Some(format!("Request failed: {}", e)),
-            skipped: false,
-            source: "error".to_string(),
-        },
+        Err(e) => FileAnalysisResult::provider_failed(
+            file_path,
+            format!("Request failed: {}", e),
+            Some(ProviderFailure::Connection),
+        ),
     }
 }
 
@@ -1954,13 +4129,7 @@ async fn analyze_with_ollama(
     let model = match &config.models.inference {
         Some(m) => m.clone(),
         None => {
-            return FileAnalysisResult {
-                file_path: file_path.to_string(),
-                suggestion: None,
-                error: Some("No inference model configured".to_string()),
-                skipped: false,
-                source: "error".to_string(),
-            };
+            return FileAnalysisResult::failed(file_path, "No inference model configured");
         }
     };
 
@@ -1978,10 +4147,7 @@ async fn analyze_with_ollama(
         prompt,
         system: NAMING_SYSTEM_PROMPT.to_string(),
         stream: false,
-        options: OllamaOptions {
-            temperature: 0.3,
-            num_predict: 500,
-        },
+        options: OllamaOptions::from_config(config),
     };
 
     let response = client
@@ -1996,48 +4162,29 @@ async fn analyze_with_ollama(
                 match resp.json::<OllamaGenerateResponse>().await {
                     Ok(data) => {
                         if let Some(suggestion) = parse_ai_suggestion(&data.response) {
-                            FileAnalysisResult {
-                                file_path: file_path.to_string(),
-                                suggestion: Some(suggestion),
-                                error: None,
-                                skipped: false,
-                                source: "ollama".to_string(),
-                            }
+                            FileAnalysisResult::suggested(file_path, suggestion, "ollama")
                         } else {
-                            FileAnalysisResult {
-                                file_path: file_path.to_string(),
-                                suggestion: None,
-                                error: Some("Failed to parse AI response".to_string()),
-                                skipped: false,
-                                source: "error".to_string(),
-                            }
+                            FileAnalysisResult::failed(file_path, "Failed to parse AI response")
                         }
                     }
-                    Err(e) => FileAnalysisResult {
-                        file_path: file_path.to_string(),
-                        suggestion: None,
-                        error: Some(format!("Failed to parse response: {}", e)),
-                        skipped: false,
-                        source: "error".to_string(),
-                    },
+                    Err(e) => FileAnalysisResult::failed(
+                        file_path,
+                        format!("Failed to parse response: {}", e),
+                    ),
                 }
             } else {
-                FileAnalysisResult {
-                    file_path: file_path.to_string(),
-                    suggestion: None,
-                    error: Some(format!("Ollama error: {}", resp.status())),
-                    skipped: false,
-                    source: "error".to_string(),
-                }
+                FileAnalysisResult::provider_failed(
+                    file_path,
+                    format!("Ollama error: {}", resp.status()),
+                    ProviderFailure::from_status(resp.status()),
+                )
             }
         }
-        Err(e) => FileAnalysisResult {
-            file_path: file_path.to_string(),
-            suggestion: None,
-            error: Some(format!("Request failed: {}", e)),
-            skipped: false,
-            source: "error".to_string(),
-        },
+        Err(e) => FileAnalysisResult::provider_failed(
+            file_path,
+            format!("Request failed: {}", e),
+            Some(ProviderFailure::Connection),
+        ),
     }
 }
 
@@ -2053,13 +4200,11 @@ async fn analyze_image_with_openai(
     // Retrieve API key from secure storage (SEC-004)
     let api_key = get_openai_api_key(&config.openai.api_key).await;
     if api_key.is_empty() {
-        return FileAnalysisResult {
-            file_path: file_path.to_string(),
-            suggestion: None,
-            error: Some("OpenAI API key not configured".to_string()),
-            skipped: false,
-            source: "error".to_string(),
-        };
+        return FileAnalysisResult::provider_failed(
+            file_path,
+            MISSING_API_KEY_ERROR,
+            Some(ProviderFailure::MissingApiKey),
+        );
     }
 
     // Extract original filename (without extension) for the prompt
@@ -2085,9 +4230,9 @@ async fn analyze_image_with_openai(
         }
     ]);
 
-    let request = OpenAiChatRequest {
-        model: config.openai.vision_model.clone(),
-        messages: vec![
+    let request = OpenAiChatRequest::new(
+        config.openai.vision_model.clone(),
+        vec![
             OpenAiMessage {
                 role: "system".to_string(),
                 content: serde_json::Value::String(NAMING_SYSTEM_PROMPT.to_string()),
@@ -2097,9 +4242,8 @@ async fn analyze_image_with_openai(
                 content,
             },
         ],
-        temperature: 0.3,
-        max_tokens: 500,
-    };
+        config,
+    );
 
     let response = client
         .post(&url)
@@ -2116,30 +4260,19 @@ async fn analyze_image_with_openai(
                     Ok(data) => {
                         if let Some(choice) = data.choices.first() {
                             if let Some(suggestion) = parse_ai_suggestion(&choice.message.content) {
-                                return FileAnalysisResult {
-                                    file_path: file_path.to_string(),
-                                    suggestion: Some(suggestion),
-                                    error: None,
-                                    skipped: false,
-                                    source: "openai-vision".to_string(),
-                                };
+                                return FileAnalysisResult::suggested(
+                                    file_path,
+                                    suggestion,
+                                    "openai-vision",
+                                );
                             }
                         }
-                        FileAnalysisResult {
-                            file_path: file_path.to_string(),
-                            suggestion: None,
-                            error: Some("Failed to parse vision response".to_string()),
-                            skipped: false,
-                            source: "error".to_string(),
-                        }
+                        FileAnalysisResult::failed(file_path, "Failed to parse vision response")
                     }
-                    Err(e) => FileAnalysisResult {
-                        file_path: file_path.to_string(),
-                        suggestion: None,
-                        error: Some(format!("Failed to parse response: {}", e)),
-                        skipped: false,
-                        source: "error".to_string(),
-                    },
+                    Err(e) => FileAnalysisResult::failed(
+                        file_path,
+                        format!("Failed to parse response: {}", e),
+                    ),
                 }
             } else {
                 let status = resp.status();
@@ -2152,25 +4285,38 @@ async fn analyze_image_with_openai(
                 } else {
                     format!("Vision API error: {}", status)
                 };
-                FileAnalysisResult {
-                    file_path: file_path.to_string(),
-                    suggestion: None,
-                    error: Some(error_msg),
-                    skipped: false,
-                    source: "error".to_string(),
-                }
+                FileAnalysisResult::provider_failed(
+                    file_path,
+                    error_msg,
+                    ProviderFailure::from_status(status),
+                )
             }
         }
-        Err(e) => FileAnalysisResult {
-            file_path: file_path.to_string(),
-            suggestion: None,
-            error: Some(format!("Vision request failed: {}", e)),
-            skipped: false,
-            source: "error".to_string(),
-        },
+        Err(e) => FileAnalysisResult::provider_failed(
+            file_path,
+            format!("Vision request failed: {}", e),
+            Some(ProviderFailure::Connection),
+        ),
     }
 }
 
+/// Build an Ollama vision request body
+fn build_ollama_vision_request(
+    model: &str,
+    prompt: &str,
+    base64_image: &str,
+    config: &OllamaConfig,
+) -> serde_json::Value {
+    // Ollama vision request format
+    serde_json::json!({
+        "model": model,
+        "prompt": prompt,
+        "images": [base64_image],
+        "stream": false,
+        "options": OllamaOptions::from_config(config)
+    })
+}
+
 /// Analyze image with Ollama Vision
 async fn analyze_image_with_ollama(
     client: &Client,
@@ -2182,13 +4328,7 @@ async fn analyze_image_with_ollama(
     let model = match &config.models.vision {
         Some(m) => m.clone(),
         None => {
-            return FileAnalysisResult {
-                file_path: file_path.to_string(),
-                suggestion: None,
-                error: Some("No vision model configured".to_string()),
-                skipped: false,
-                source: "error".to_string(),
-            };
+            return FileAnalysisResult::failed(file_path, "No vision model configured");
         }
     };
 
@@ -2201,17 +4341,7 @@ async fn analyze_image_with_ollama(
     let url = format!("{}/api/generate", config.base_url.trim_end_matches('/'));
     let prompt = create_vision_prompt(original_name, existing_folders);
 
-    // Ollama vision request format
-    let request = serde_json::json!({
-        "model": model,
-        "prompt": prompt,
-        "images": [base64_image],
-        "stream": false,
-        "options": {
-            "temperature": 0.3,
-            "num_predict": 500
-        }
-    });
+    let request = build_ollama_vision_request(&model, &prompt, base64_image, config);
 
     let response = client
         .post(&url)
@@ -2225,48 +4355,32 @@ async fn analyze_image_with_ollama(
                 match resp.json::<OllamaGenerateResponse>().await {
                     Ok(data) => {
                         if let Some(suggestion) = parse_ai_suggestion(&data.response) {
-                            FileAnalysisResult {
-                                file_path: file_path.to_string(),
-                                suggestion: Some(suggestion),
-                                error: None,
-                                skipped: false,
-                                source: "ollama-vision".to_string(),
-                            }
+                            FileAnalysisResult::suggested(file_path, suggestion, "ollama-vision")
                         } else {
-                            FileAnalysisResult {
-                                file_path: file_path.to_string(),
-                                suggestion: None,
-                                error: Some("Failed to parse vision response".to_string()),
-                                skipped: false,
-                                source: "error".to_string(),
-                            }
+                            FileAnalysisResult::failed(
+                                file_path,
+                                "Failed to parse vision response",
+                            )
                         }
                     }
-                    Err(e) => FileAnalysisResult {
-                        file_path: file_path.to_string(),
-                        suggestion: None,
-                        error: Some(format!("Failed to parse response: {}", e)),
-                        skipped: false,
-                        source: "error".to_string(),
-                    },
+                    Err(e) => FileAnalysisResult::failed(
+                        file_path,
+                        format!("Failed to parse response: {}", e),
+                    ),
                 }
             } else {
-                FileAnalysisResult {
-                    file_path: file_path.to_string(),
-                    suggestion: None,
-                    error: Some(format!("Ollama vision error: {}", resp.status())),
-                    skipped: false,
-                    source: "error".to_string(),
-                }
+                FileAnalysisResult::provider_failed(
+                    file_path,
+                    format!("Ollama vision error: {}", resp.status()),
+                    ProviderFailure::from_status(resp.status()),
+                )
             }
         }
-        Err(e) => FileAnalysisResult {
-            file_path: file_path.to_string(),
-            suggestion: None,
-            error: Some(format!("Vision request failed: {}", e)),
-            skipped: false,
-            source: "error".to_string(),
-        },
+        Err(e) => FileAnalysisResult::provider_failed(
+            file_path,
+            format!("Vision request failed: {}", e),
+            Some(ProviderFailure::Connection),
+        ),
     }
 }
 
@@ -2286,6 +4400,20 @@ pub async fn clear_analysis_cache() -> Result<usize, String> {
     Ok(count)
 }
 
+/// Clear the cached folder structures of analyzed base directories
+///
+/// Returns the number of base directories that were cached.
+/// Command name: clear_folder_structure_cache (snake_case per architecture)
+#[tauri::command]
+pub async fn clear_folder_structure_cache() -> Result<usize, String> {
+    let mut cache = FOLDER_STRUCTURE_CACHE
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let count = cache.len();
+    cache.clear();
+    Ok(count)
+}
+
 /// Get cache statistics
 ///
 /// Returns the number of cached entries.
@@ -2314,35 +4442,163 @@ pub struct CacheStats {
     pub valid_entries: usize,
 }
 
-// =============================================================================
-// Tests
-// =============================================================================
+/// How a batch would use the analysis cache
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CachePreview {
+    pub total: usize,
+    /// Files answered from the cache (no API call)
+    pub hits: usize,
+    /// Cacheable files that would need an API call
+    pub misses: usize,
+    /// Files that are never cached (unsupported type or unreadable)
+    pub uncacheable: usize,
+}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Count cache hits and misses for a batch without calling the AI
+///
+/// Computes each file's cache key with the configured strategies (content
+/// hash or metadata hash), so users can tell whether re-running analysis
+/// would reuse earlier results.
+///
+/// Command name: analysis_cache_preview (snake_case per architecture)
+#[tauri::command]
+pub async fn analysis_cache_preview(
+    file_paths: Vec<String>,
+    config: OllamaConfig,
+) -> Result<CachePreview, String> {
+    let mut preview = CachePreview {
+        total: file_paths.len(),
+        hits: 0,
+        misses: 0,
+        uncacheable: 0,
+    };
 
-    #[test]
-    fn test_parse_ai_suggestion_valid() {
-        let json = r#"{"suggestedName": "my-document", "confidence": 0.9, "reasoning": "Document about X", "keywords": ["doc", "x"]}"#;
-        let suggestion = parse_ai_suggestion(json).unwrap();
-        assert_eq!(suggestion.suggested_name, "my-document");
-        assert!((suggestion.confidence - 0.9).abs() < 0.01);
-        assert_eq!(suggestion.reasoning, "Document about X");
-        assert_eq!(suggestion.keywords, vec!["doc", "x"]);
+    for file_path in &file_paths {
+        match analysis_cache_key(file_path, &config) {
+            Some(key) if get_cached_result(&key).await.is_some() => preview.hits += 1,
+            Some(_) => preview.misses += 1,
+            None => preview.uncacheable += 1,
+        }
     }
 
-    #[test]
-    fn test_parse_ai_suggestion_with_extra_text() {
-        let response = r#"Here's my suggestion:
-{"suggestedName": "test-file", "confidence": 0.85, "reasoning": "Test", "keywords": ["test"]}
-Hope this helps!"#;
-        let suggestion = parse_ai_suggestion(response).unwrap();
-        assert_eq!(suggestion.suggested_name, "test-file");
-    }
+    Ok(preview)
+}
 
-    #[test]
-    fn test_parse_ai_suggestion_invalid() {
+/// Old and new path of a file renamed or moved by the app
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CachePathMapping {
+    pub old_path: String,
+    pub new_path: String,
+}
+
+/// Whether a cache key is the metadata key of a path ("<path>:<hex hash>")
+fn is_metadata_key_of(key: &str, path: &str) -> bool {
+    key.strip_prefix(path)
+        .and_then(|rest| rest.strip_prefix(':'))
+        .is_some_and(|hash| !hash.is_empty() && hash.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// Content hash of a path's content key ("<path>:content:<hex hash>")
+fn content_key_hash<'a>(key: &'a str, path: &str) -> Option<&'a str> {
+    key.strip_prefix(path)
+        .and_then(|rest| rest.strip_prefix(':'))
+        .and_then(|rest| rest.strip_prefix(CONTENT_KEY_TAG))
+        .filter(|hash| !hash.is_empty() && hash.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// Move cache entries from old paths to new paths
+///
+/// Metadata keys are recomputed for the new path; content keys keep their
+/// hash, since a rename doesn't change the bytes. Returns the number of
+/// entries moved.
+async fn remap_cache_entries(mappings: &[CachePathMapping]) -> usize {
+    let mut cache = ANALYSIS_CACHE.write().await;
+    let mut remapped = 0;
+
+    for mapping in mappings {
+        let content_keys: Vec<(String, String)> = cache
+            .keys()
+            .filter_map(|key| {
+                content_key_hash(key, &mapping.old_path)
+                    .map(|hash| (key.clone(), content_cache_key(&mapping.new_path, hash)))
+            })
+            .collect();
+        for (old_key, new_key) in content_keys {
+            if let Some(entry) = cache.remove(&old_key) {
+                cache.insert(new_key, entry);
+                remapped += 1;
+            }
+        }
+
+        let old_keys: Vec<String> = cache
+            .keys()
+            .filter(|key| is_metadata_key_of(key, &mapping.old_path))
+            .cloned()
+            .collect();
+        if old_keys.is_empty() {
+            continue;
+        }
+        let Some(new_key) = compute_cache_key(&mapping.new_path, &CacheKeyStrategy::Metadata) else {
+            continue;
+        };
+
+        // Keep the newest entry if a path was cached more than once
+        let newest = old_keys
+            .iter()
+            .filter_map(|key| cache.remove(key))
+            .max_by_key(|entry| entry.cached_at);
+        if let Some(entry) = newest {
+            cache.insert(new_key, entry);
+            remapped += 1;
+        }
+    }
+
+    remapped
+}
+
+/// Re-key analysis cache entries after a successful rename batch
+///
+/// Cache keys include the file path, so renamed files would miss the cache
+/// when re-analyzed. Entries keep their suggestion and age. Returns the number
+/// of entries moved.
+///
+/// Command name: remap_cache_paths (snake_case per architecture)
+#[tauri::command]
+pub async fn remap_cache_paths(mappings: Vec<CachePathMapping>) -> Result<usize, String> {
+    Ok(remap_cache_entries(&mappings).await)
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ai_suggestion_valid() {
+        let json = r#"{"suggestedName": "my-document", "confidence": 0.9, "reasoning": "Document about X", "keywords": ["doc", "x"]}"#;
+        let suggestion = parse_ai_suggestion(json).unwrap();
+        assert_eq!(suggestion.suggested_name, "my-document");
+        assert!((suggestion.confidence - 0.9).abs() < 0.01);
+        assert_eq!(suggestion.reasoning, "Document about X");
+        assert_eq!(suggestion.keywords, vec!["doc", "x"]);
+    }
+
+    #[test]
+    fn test_parse_ai_suggestion_with_extra_text() {
+        let response = r#"Here's my suggestion:
+{"suggestedName": "test-file", "confidence": 0.85, "reasoning": "Test", "keywords": ["test"]}
+Hope this helps!"#;
+        let suggestion = parse_ai_suggestion(response).unwrap();
+        assert_eq!(suggestion.suggested_name, "test-file");
+    }
+
+    #[test]
+    fn test_parse_ai_suggestion_invalid() {
         let invalid = "not a json response";
         assert!(parse_ai_suggestion(invalid).is_none());
     }
@@ -2377,6 +4633,120 @@ Hope this helps!"#;
         assert_eq!(get_image_mime_type("/path/photo.webp"), "image/webp");
     }
 
+    fn mp4_box(box_type: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+        let mut bytes = ((payload.len() + 8) as u32).to_be_bytes().to_vec();
+        bytes.extend_from_slice(box_type);
+        bytes.extend_from_slice(payload);
+        bytes
+    }
+
+    /// A minimal MP4 with media data before "moov" and an optional cover image
+    fn write_test_video(dir: &tempfile::TempDir, name: &str, cover: Option<&[u8]>) -> String {
+        let mut udta = Vec::new();
+        if let Some(cover) = cover {
+            let mut data = vec![0, 0, 0, 13, 0, 0, 0, 0];
+            data.extend_from_slice(cover);
+            let ilst = mp4_box(b"ilst", &mp4_box(b"covr", &mp4_box(b"data", &data)));
+            let mut meta = vec![0, 0, 0, 0];
+            meta.extend(mp4_box(b"hdlr", &[0; 25]));
+            meta.extend(ilst);
+            udta = mp4_box(b"udta", &mp4_box(b"meta", &meta));
+        }
+
+        let mut file = mp4_box(b"ftyp", b"isom\0\0\x02\0");
+        file.extend(mp4_box(b"mdat", &[0xAB; 4096]));
+        let mut moov = mp4_box(b"mvhd", &[0; 100]);
+        moov.extend(udta);
+        file.extend(mp4_box(b"moov", &moov));
+
+        let path = dir.path().join(name);
+        std::fs::write(&path, file).unwrap();
+        path.to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn test_extract_video_thumbnail() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let jpeg = [0xFF, 0xD8, 0xFF, 0xE0, 1, 2, 3];
+        let png = b"\x89PNG\r\n\x1a\nrest";
+
+        let video = write_test_video(&dir, "clip.mp4", Some(&jpeg));
+        assert_eq!(extract_video_thumbnail(&video), Some((jpeg.to_vec(), "image/jpeg")));
+
+        let video = write_test_video(&dir, "clip.mov", Some(png));
+        assert_eq!(extract_video_thumbnail(&video).map(|(_, mime)| mime), Some("image/png"));
+
+        // No cover, or a cover in a format vision models don't take
+        let video = write_test_video(&dir, "bare.mp4", None);
+        assert!(extract_video_thumbnail(&video).is_none());
+        let video = write_test_video(&dir, "odd.mp4", Some(b"BM bitmap"));
+        assert!(extract_video_thumbnail(&video).is_none());
+    }
+
+    #[test]
+    fn test_mp4_boxes_with_bad_sizes_are_rejected() {
+        // A 64-bit size with its size field cut off
+        let mut truncated = 1u32.to_be_bytes().to_vec();
+        truncated.extend_from_slice(b"moov\0\0\0");
+        assert!(find_mp4_box(&truncated, b"moov").is_none());
+
+        // A 64-bit size that would overflow the offset, or runs past the data
+        let mut huge = 1u32.to_be_bytes().to_vec();
+        huge.extend_from_slice(b"free");
+        huge.extend_from_slice(&u64::MAX.to_be_bytes());
+        huge.extend(mp4_box(b"moov", &[]));
+        assert!(find_mp4_box(&huge, b"moov").is_none());
+
+        // Sizes smaller than the box header
+        let mut tiny = 4u32.to_be_bytes().to_vec();
+        tiny.extend_from_slice(b"free");
+        assert!(find_mp4_box(&tiny, b"moov").is_none());
+        let mut small_large = 1u32.to_be_bytes().to_vec();
+        small_large.extend_from_slice(b"free");
+        small_large.extend_from_slice(&8u64.to_be_bytes());
+        assert!(find_mp4_box(&small_large, b"free").is_none());
+
+        let dir = tempfile::TempDir::new().unwrap();
+        for (name, bytes) in [("cut.mp4", &truncated), ("huge.mp4", &huge)] {
+            let path = dir.path().join(name);
+            std::fs::write(&path, bytes).unwrap();
+            assert!(read_mp4_moov(&path.to_string_lossy()).is_none(), "{}", name);
+        }
+
+        let valid = mp4_box(b"moov", b"payload");
+        assert_eq!(find_mp4_box(&valid, b"moov"), Some(&b"payload"[..]));
+    }
+
+    #[test]
+    fn test_thumbnail_mime_type() {
+        assert_eq!(thumbnail_mime_type(&[0xFF, 0xD8, 0xFF, 0xDB]), Some("image/jpeg"));
+        assert_eq!(thumbnail_mime_type(b"\x89PNG\r\n\x1a\n"), Some("image/png"));
+        assert_eq!(thumbnail_mime_type(b"GIF89a"), None);
+        assert_eq!(thumbnail_mime_type(&[]), None);
+    }
+
+    #[tokio::test]
+    async fn test_video_without_thumbnail_is_skipped() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let video = write_test_video(&dir, "bare.mp4", None);
+        let config = OllamaConfig {
+            vision_enabled: true,
+            video_analysis_enabled: true,
+            ..Default::default()
+        };
+
+        assert!(uses_vision(&video, &config));
+        let result = analyze_single_file(&Client::new(), &video, &config, &[]).await;
+
+        assert!(result.skipped);
+        assert_eq!(result.skip_reason, Some(AnalysisSkipReason::Unsupported));
+        assert_eq!(result.error.as_deref(), Some("Video has no embedded thumbnail"));
+
+        // Without the flag, videos are not sent to the vision model at all
+        let disabled = OllamaConfig { vision_enabled: true, ..Default::default() };
+        assert!(!uses_vision(&video, &disabled));
+    }
+
     #[test]
     fn test_ai_suggestion_serialization() {
         let suggestion = AiSuggestion {
@@ -2397,9 +4767,9 @@ Hope this helps!"#;
 
     #[test]
     fn test_file_analysis_result_serialization() {
-        let result = FileAnalysisResult {
-            file_path: "/path/to/file.txt".to_string(),
-            suggestion: Some(AiSuggestion {
+        let result = FileAnalysisResult::suggested(
+            "/path/to/file.txt",
+            AiSuggestion {
                 suggested_name: "test".to_string(),
                 confidence: 0.9,
                 reasoning: "Test".to_string(),
@@ -2407,11 +4777,9 @@ Hope this helps!"#;
                 keep_original: false,
                 suggested_folder: None,
                 folder_confidence: None,
-            }),
-            error: None,
-            skipped: false,
-            source: "ollama".to_string(),
-        };
+            },
+            "ollama",
+        );
 
         let json = serde_json::to_string(&result).unwrap();
         assert!(json.contains("\"filePath\":\"/path/to/file.txt\""));
@@ -2465,6 +4833,115 @@ Hope this helps!"#;
         assert!(hash1.chars().all(|c| c.is_ascii_hexdigit()));
     }
 
+    #[tokio::test]
+    async fn test_content_cache_key_follows_remapped_move() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let original = temp_dir.path().join("IMG_0001.jpg");
+        let moved = temp_dir.path().join("moved").join("IMG_0001.jpg");
+        std::fs::write(&original, b"content-key-test image bytes").unwrap();
+
+        let original_key = compute_cache_key(original.to_str().unwrap(), &CacheKeyStrategy::Content).unwrap();
+        let suggestion = AiSuggestion {
+            suggested_name: "beach-sunset".to_string(),
+            confidence: 0.9,
+            reasoning: "test".to_string(),
+            keywords: vec![],
+            keep_original: false,
+            suggested_folder: None,
+            folder_confidence: None,
+        };
+        cache_result(&original_key, &suggestion).await;
+
+        std::fs::create_dir(moved.parent().unwrap()).unwrap();
+        std::fs::rename(&original, &moved).unwrap();
+
+        let moved_key = compute_cache_key(moved.to_str().unwrap(), &CacheKeyStrategy::Content).unwrap();
+        assert_ne!(original_key, moved_key);
+        assert!(get_cached_result(&moved_key).await.is_none());
+
+        let mappings = vec![CachePathMapping {
+            old_path: original.to_string_lossy().to_string(),
+            new_path: moved.to_string_lossy().to_string(),
+        }];
+        assert_eq!(remap_cache_entries(&mappings).await, 1);
+
+        let cached = get_cached_result(&moved_key).await.unwrap();
+        assert_eq!(cached.suggested_name, "beach-sunset");
+    }
+
+    #[test]
+    fn test_content_cache_key_reads_only_header() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let first = temp_dir.path().join("first.jpg");
+        let second = temp_dir.path().join("second.jpg");
+        let mut bytes = vec![7u8; CONTENT_KEY_HEADER_BYTES as usize + 16];
+        std::fs::write(&first, &bytes).unwrap();
+        // Same size, differs only past the header
+        *bytes.last_mut().unwrap() = 8;
+        std::fs::write(&second, &bytes).unwrap();
+
+        let first_hash = hash_file_content(first.to_str().unwrap()).unwrap();
+        let second_hash = hash_file_content(second.to_str().unwrap()).unwrap();
+        assert_eq!(first_hash, second_hash);
+    }
+
+    #[test]
+    fn test_content_cache_key_covers_the_sampled_text() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("notes.txt");
+        let text = "a".repeat(MAX_CONTENT_CHARS * 2);
+        std::fs::write(&path, &text).unwrap();
+        let before = hash_file_content(path.to_str().unwrap()).unwrap();
+
+        // An edit past the prompt size is still read and sampled by analysis
+        std::fs::write(&path, format!("{}b", &text[1..])).unwrap();
+
+        assert_ne!(hash_file_content(path.to_str().unwrap()).unwrap(), before);
+    }
+
+    #[test]
+    fn test_metadata_cache_key_changes_with_path() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let first = temp_dir.path().join("a.jpg");
+        let second = temp_dir.path().join("b.jpg");
+        std::fs::write(&first, b"same bytes").unwrap();
+        std::fs::write(&second, b"same bytes").unwrap();
+
+        let first_key = compute_cache_key(first.to_str().unwrap(), &CacheKeyStrategy::Metadata).unwrap();
+        let second_key = compute_cache_key(second.to_str().unwrap(), &CacheKeyStrategy::Metadata).unwrap();
+        assert_ne!(first_key, second_key);
+
+        // Identical files at different paths don't share a content entry either
+        let first_content = compute_cache_key(first.to_str().unwrap(), &CacheKeyStrategy::Content).unwrap();
+        let second_content = compute_cache_key(second.to_str().unwrap(), &CacheKeyStrategy::Content).unwrap();
+        assert_ne!(first_content, second_content);
+    }
+
+    #[test]
+    fn test_filename_quality_score() {
+        assert_eq!(filename_quality_score("/path/to/IMG_1234.jpg"), 0.0);
+        assert!(filename_quality_score("/path/to/cat.jpg") < GOOD_NAME_QUALITY_THRESHOLD);
+        assert!(filename_quality_score("/path/to/vacation-beach-sunset.jpg") >= GOOD_NAME_QUALITY_THRESHOLD);
+        assert_eq!(filename_quality_score("/path/to/invoice-client-january.pdf"), 1.0);
+    }
+
+    #[test]
+    fn test_skip_good_name_only_bad_names_mode() {
+        // Well-named images are skipped, overriding the image-always-analyze rule
+        let result = skip_good_name("/path/to/vacation-beach-sunset.jpg", true).unwrap();
+        assert!(result.skipped);
+        assert!(result.suggestion.is_none());
+        assert_eq!(result.source, "good-name");
+        assert_eq!(result.skip_reason, Some(AnalysisSkipReason::GoodName));
+
+        // Bad names are still analyzed
+        assert!(skip_good_name("/path/to/IMG_1234.jpg", true).is_none());
+        assert!(skip_good_name("/path/to/Screenshot 2024-01-01.png", true).is_none());
+
+        // Mode disabled: nothing is skipped
+        assert!(skip_good_name("/path/to/vacation-beach-sunset.jpg", false).is_none());
+    }
+
     #[test]
     fn test_needs_ai_analysis_low_quality_english() {
         // Low quality patterns should need analysis
@@ -2555,6 +5032,143 @@ Hope this helps!"#;
         assert!(needs, "unknown pattern should default to needing analysis");
     }
 
+    #[test]
+    fn test_extract_text_content_utf16le_with_bom() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("notes.txt");
+
+        let mut bytes = vec![0xFF, 0xFE];
+        for unit in "Meeting notes: café".encode_utf16() {
+            bytes.extend_from_slice(&unit.to_le_bytes());
+        }
+        std::fs::write(&path, bytes).unwrap();
+
+        let content = extract_text_content(path.to_str().unwrap(), MAX_CONTENT_CHARS).unwrap();
+        assert_eq!(content.text, "Meeting notes: café");
+    }
+
+    #[test]
+    fn test_extract_content_dispatches_by_extension() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let pdf = temp_dir.path().join("Report.PDF");
+        std::fs::write(
+            &pdf,
+            b"%PDF-1.4\n1 0 obj\n<< /Title (Q2 Report) >>\nendobj\n2 0 obj\n<< /Length 30 >>\nstream\nBT (Revenue grew) Tj ET\nendstream\nendobj\n",
+        )
+        .unwrap();
+        let pdf = pdf.to_str().unwrap();
+
+        assert!(has_content_extractor(pdf));
+        let content = extract_content(pdf, MAX_CONTENT_CHARS).unwrap();
+        assert_eq!(content.text, "Revenue grew");
+        assert_eq!(content.metadata["title"], "Q2 Report");
+        assert_eq!(content.into_analysis_text(), "Title: Q2 Report\n\nRevenue grew");
+        assert!(is_analyzable_text(pdf, &OllamaConfig::default()));
+    }
+
+    #[test]
+    fn test_extract_content_falls_back_to_text() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("notes.unknown");
+        std::fs::write(&path, "Plain notes").unwrap();
+        let path = path.to_str().unwrap();
+
+        assert!(!has_content_extractor(path));
+        let content = extract_content(path, MAX_CONTENT_CHARS).unwrap();
+        assert_eq!(content.text, "Plain notes");
+        assert!(content.metadata.is_empty());
+        assert!(!content.truncated);
+        assert_eq!(content.into_analysis_text(), "Plain notes");
+    }
+
+    #[test]
+    fn test_extract_text_content_reports_truncation() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        // More bytes than the character limit, but every character fits
+        let accented = temp_dir.path().join("accented.txt");
+        std::fs::write(&accented, "é".repeat(MAX_CONTENT_CHARS / 2 + 10)).unwrap();
+        let long = temp_dir.path().join("long.txt");
+        std::fs::write(&long, "a".repeat(MAX_CONTENT_CHARS * 2)).unwrap();
+
+        let content = extract_content(&accented.to_string_lossy(), MAX_CONTENT_CHARS).unwrap();
+        assert_eq!(content.text.chars().count(), MAX_CONTENT_CHARS / 2 + 10);
+        assert!(!content.truncated);
+
+        let content = extract_content(&long.to_string_lossy(), MAX_CONTENT_CHARS).unwrap();
+        assert_eq!(content.text.len(), MAX_CONTENT_CHARS);
+        assert!(content.truncated);
+    }
+
+    fn write_large_csv(dir: &tempfile::TempDir, name: &str) -> std::path::PathBuf {
+        let path = dir.path().join(name);
+        let mut csv = String::from("date,account,amount\n");
+        for row in 0..10_000 {
+            csv.push_str(&format!("2024-01-01,acct-{:05},{}.00\n", row, row % 97));
+        }
+        csv.push_str("TOTAL,all-accounts,123456.00\n");
+        std::fs::write(&path, csv).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_extract_windowed_content_includes_tail() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = write_large_csv(&temp_dir, "ledger.csv");
+
+        let content = extract_windowed_content(path.to_str().unwrap(), MAX_CONTENT_CHARS).unwrap();
+
+        assert!(content.len() <= MAX_CONTENT_CHARS);
+        assert!(content.starts_with("date,account,amount"));
+        assert!(content.contains("acct-03"), "mid-file rows sampled");
+        assert!(content.ends_with("TOTAL,all-accounts,123456.00\n"));
+        // Every window after the head starts on a full row
+        for window in content.split("\n\n[... truncated ...]\n\n").skip(1) {
+            assert!(window.starts_with("2024-01-01,") || window.starts_with("TOTAL"));
+        }
+        // Fits the prompt budget, so sampling keeps all windows
+        assert_eq!(sample_content(&content, path.to_str().unwrap(), MAX_CONTENT_CHARS), content);
+    }
+
+    #[test]
+    fn test_extract_windowed_content_skips_small_and_code_files() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let small = temp_dir.path().join("small.csv");
+        std::fs::write(&small, "date,amount\n2024-01-01,5\n").unwrap();
+        assert!(extract_windowed_content(small.to_str().unwrap(), MAX_CONTENT_CHARS).is_none());
+
+        let code = write_large_csv(&temp_dir, "fixtures.py");
+        assert!(extract_windowed_content(code.to_str().unwrap(), MAX_CONTENT_CHARS).is_none());
+    }
+
+    #[test]
+    fn test_decode_text_bytes_strips_utf8_bom() {
+        let bytes = b"\xEF\xBB\xBFhello world";
+        assert_eq!(decode_text_bytes(bytes), "hello world");
+    }
+
+    #[test]
+    fn test_decode_text_bytes_utf16_without_bom() {
+        let le: Vec<u8> = "quarterly report".encode_utf16().flat_map(|u| u.to_le_bytes()).collect();
+        let be: Vec<u8> = "quarterly report".encode_utf16().flat_map(|u| u.to_be_bytes()).collect();
+
+        assert_eq!(decode_text_bytes(&le), "quarterly report");
+        assert_eq!(decode_text_bytes(&be), "quarterly report");
+    }
+
+    #[test]
+    fn test_decode_text_bytes_latin1_fallback() {
+        // "café résumé" in ISO-8859-1
+        let bytes = b"caf\xE9 r\xE9sum\xE9";
+        assert_eq!(decode_text_bytes(bytes), "café résumé");
+    }
+
+    #[test]
+    fn test_decode_text_bytes_truncated_utf8() {
+        // "é" is 0xC3 0xA9; a buffer cut after 0xC3 must not fall back to Latin-1
+        let bytes = b"caf\xC3";
+        assert_eq!(decode_text_bytes(bytes), "caf");
+    }
+
     #[test]
     fn test_truncate_content_smart_short() {
         let content = "Short content";
@@ -2570,6 +5184,50 @@ Hope this helps!"#;
         assert!(truncated.contains("[... truncated ...]"));
     }
 
+    #[test]
+    fn test_sample_content_code_keeps_imports_and_drops_middle() {
+        let mut content = String::from(
+            "//! Invoice parser\nuse std::fs;\nuse serde::Deserialize;\n\npub fn parse_invoice() {\n",
+        );
+        for i in 0..2000 {
+            content.push_str(&format!("    let middle_{} = {};\n", i, i));
+        }
+        content.push_str("}\n\npub fn export_totals() {}\n");
+
+        let sampled = sample_content(&content, "invoice.rs", 1000);
+
+        assert!(sampled.chars().count() <= 1000);
+        assert!(sampled.starts_with("//! Invoice parser\nuse std::fs;\nuse serde::Deserialize;"));
+        assert!(!sampled.contains("middle_1999"));
+        assert!(sampled.contains("[... truncated ...]"));
+        // Later top-level signatures are kept
+        assert!(sampled.ends_with("pub fn export_totals() {}"));
+    }
+
+    #[test]
+    fn test_sample_content_markdown_keeps_intro_and_headings() {
+        let mut content = String::from("# Trip Planning\n\nNotes for the Lisbon trip.\n\n");
+        for i in 0..500 {
+            content.push_str(&format!("Paragraph line {} with details.\n", i));
+        }
+        content.push_str("## Budget\n");
+
+        let sampled = sample_content(&content, "notes.md", 1000);
+
+        assert!(sampled.starts_with("# Trip Planning\n\nNotes for the Lisbon trip."));
+        assert!(!sampled.contains("Paragraph line 499"));
+        assert!(sampled.ends_with("## Budget"));
+    }
+
+    #[test]
+    fn test_sample_content_other_uses_generic_truncation() {
+        let content = "a".repeat(10000);
+        assert_eq!(
+            sample_content(&content, "data.csv", 1000),
+            truncate_content_smart(&content, 1000)
+        );
+    }
+
     #[test]
     fn test_filter_folders_for_file_type_few_folders() {
         let folders = vec![
@@ -2578,7 +5236,7 @@ Hope this helps!"#;
             "Code".to_string(),
         ];
 
-        let filtered = filter_folders_for_file_type(&folders, "/path/to/image.jpg");
+        let filtered = filter_folders_for_file_type(&folders, "/path/to/image.jpg", &FolderKeywords::default());
         // Should return all when few folders
         assert_eq!(filtered.len(), 3);
     }
@@ -2597,7 +5255,7 @@ Hope this helps!"#;
             })
             .collect();
 
-        let filtered = filter_folders_for_file_type(&folders, "/path/to/image.jpg");
+        let filtered = filter_folders_for_file_type(&folders, "/path/to/image.jpg", &FolderKeywords::default());
 
         // Should include photo-related folders
         assert!(filtered.iter().any(|f| f.contains("Photo")));
@@ -2618,12 +5276,52 @@ Hope this helps!"#;
             })
             .collect();
 
-        let filtered = filter_folders_for_file_type(&folders, "/path/to/code.ts");
+        let filtered = filter_folders_for_file_type(&folders, "/path/to/code.ts", &FolderKeywords::default());
 
         // Should include code-related folders
         assert!(filtered.iter().any(|f| f.contains("Project") || f.contains("src") || f.contains("lib")));
     }
 
+    /// Nested folders so only keyword matches are returned (top-level folders are always added)
+    fn create_nested_folders(matching: &str) -> Vec<String> {
+        let mut folders: Vec<String> = (0..20).map(|i| format!("Misc/Other{}", i)).collect();
+        folders.push(matching.to_string());
+        folders
+    }
+
+    #[test]
+    fn test_filter_folders_for_file_type_spanish_images() {
+        let folders = create_nested_folders("Archivo/Fotos");
+
+        let filtered = filter_folders_for_file_type(&folders, "/path/to/image.jpg", &FolderKeywords::default());
+
+        assert_eq!(filtered, vec!["Archivo/Fotos".to_string()]);
+    }
+
+    #[test]
+    fn test_filter_folders_for_file_type_german_documents() {
+        let folders = create_nested_folders("Archiv/Dokumente");
+
+        let filtered = filter_folders_for_file_type(&folders, "/path/to/notes.txt", &FolderKeywords::default());
+
+        assert_eq!(filtered, vec!["Archiv/Dokumente".to_string()]);
+    }
+
+    #[test]
+    fn test_filter_folders_for_file_type_custom_keywords() {
+        let folders = create_nested_folders("Archief/Kiekjes");
+
+        let no_match = filter_folders_for_file_type(&folders, "/path/to/image.jpg", &FolderKeywords::default());
+        assert!(!no_match.contains(&"Archief/Kiekjes".to_string()));
+
+        let keywords = FolderKeywords {
+            images: vec!["Kiekjes".to_string()],
+            ..Default::default()
+        };
+        let filtered = filter_folders_for_file_type(&folders, "/path/to/image.jpg", &keywords);
+        assert_eq!(filtered, vec!["Archief/Kiekjes".to_string()]);
+    }
+
     #[test]
     fn test_cache_stats_serialization() {
         let stats = CacheStats {
@@ -2652,6 +5350,7 @@ Hope this helps!"#;
         assert_eq!(normalize_folder_name("Téléchargements"), "telechargements");
         assert_eq!(normalize_folder_name("Éléments"), "elements");
         assert_eq!(normalize_folder_name("Photos été 2024"), "photos-ete-2024");
+        assert_eq!(normalize_folder_name("Straße Øresund"), "strasse-oresund");
     }
 
     #[test]
@@ -2669,6 +5368,24 @@ Hope this helps!"#;
         assert_eq!(normalize_folder_name("-Documents-"), "documents");
     }
 
+    #[tokio::test]
+    async fn test_preview_folder_normalization() {
+        let names = ["Photos Été", "photos-ete", "Work / Été_2024", "My -- Documents!", "Notes"]
+            .iter()
+            .map(|n| n.to_string())
+            .collect();
+
+        let preview = preview_folder_normalization(names).await;
+
+        assert_eq!(preview[0].normalized, "photos-ete");
+        assert_eq!(preview[0].same_as, vec!["photos-ete".to_string()]);
+        assert_eq!(preview[1].same_as, vec!["Photos Été".to_string()]);
+        assert_eq!(preview[2].normalized, "work/ete-2024");
+        assert_eq!(preview[3].normalized, "my-documents");
+        assert_eq!(preview[4].input, "Notes");
+        assert!(preview[4].same_as.is_empty());
+    }
+
     #[test]
     fn test_levenshtein_distance_identical() {
         assert_eq!(levenshtein_distance("photos", "photos"), 0);
@@ -2717,15 +5434,15 @@ Hope this helps!"#;
 
     #[test]
     fn test_flatten_folder_path_under_limit() {
-        assert_eq!(flatten_folder_path("photos"), "photos");
-        assert_eq!(flatten_folder_path("photos/2024"), "photos/2024");
+        assert_eq!(flatten_folder_path("photos", MAX_FOLDER_DEPTH), "photos");
+        assert_eq!(flatten_folder_path("photos/2024", MAX_FOLDER_DEPTH), "photos/2024");
     }
 
     #[test]
     fn test_flatten_folder_path_over_limit() {
         // Should truncate to MAX_FOLDER_DEPTH (2) levels
-        assert_eq!(flatten_folder_path("photos/travel/europe/2024"), "photos/travel");
-        assert_eq!(flatten_folder_path("a/b/c/d/e"), "a/b");
+        assert_eq!(flatten_folder_path("photos/travel/europe/2024", MAX_FOLDER_DEPTH), "photos/travel");
+        assert_eq!(flatten_folder_path("a/b/c/d/e", MAX_FOLDER_DEPTH), "a/b");
     }
 
     #[test]
@@ -2738,9 +5455,9 @@ Hope this helps!"#;
     #[test]
     fn test_consolidate_folder_suggestions_normalizes() {
         let mut results = vec![
-            FileAnalysisResult {
-                file_path: "/path/file1.jpg".to_string(),
-                suggestion: Some(AiSuggestion {
+            FileAnalysisResult::suggested(
+                "/path/file1.jpg",
+                AiSuggestion {
                     suggested_name: "file1".to_string(),
                     confidence: 0.9,
                     reasoning: "test".to_string(),
@@ -2748,14 +5465,12 @@ Hope this helps!"#;
                     keep_original: false,
                     suggested_folder: Some("Photos été".to_string()),
                     folder_confidence: Some(0.8),
-                }),
-                error: None,
-                skipped: false,
-                source: "test".to_string(),
-            },
-            FileAnalysisResult {
-                file_path: "/path/file2.jpg".to_string(),
-                suggestion: Some(AiSuggestion {
+                },
+                "test",
+            ),
+            FileAnalysisResult::suggested(
+                "/path/file2.jpg",
+                AiSuggestion {
                     suggested_name: "file2".to_string(),
                     confidence: 0.9,
                     reasoning: "test".to_string(),
@@ -2763,14 +5478,12 @@ Hope this helps!"#;
                     keep_original: false,
                     suggested_folder: Some("photos-ete".to_string()),
                     folder_confidence: Some(0.8),
-                }),
-                error: None,
-                skipped: false,
-                source: "test".to_string(),
-            },
-            FileAnalysisResult {
-                file_path: "/path/file3.jpg".to_string(),
-                suggestion: Some(AiSuggestion {
+                },
+                "test",
+            ),
+            FileAnalysisResult::suggested(
+                "/path/file3.jpg",
+                AiSuggestion {
                     suggested_name: "file3".to_string(),
                     confidence: 0.9,
                     reasoning: "test".to_string(),
@@ -2778,14 +5491,12 @@ Hope this helps!"#;
                     keep_original: false,
                     suggested_folder: Some("Photos_été".to_string()),
                     folder_confidence: Some(0.8),
-                }),
-                error: None,
-                skipped: false,
-                source: "test".to_string(),
-            },
+                },
+                "test",
+            ),
         ];
 
-        consolidate_folder_suggestions(&mut results, &[]);
+        consolidate_folder_suggestions(&mut results, &[], ConsolidationLimits::STANDARD);
 
         // All should be normalized to same canonical name
         let folders: Vec<_> = results
@@ -2800,9 +5511,9 @@ Hope this helps!"#;
     #[test]
     fn test_consolidate_folder_suggestions_prefers_existing() {
         let mut results = vec![
-            FileAnalysisResult {
-                file_path: "/path/file1.jpg".to_string(),
-                suggestion: Some(AiSuggestion {
+            FileAnalysisResult::suggested(
+                "/path/file1.jpg",
+                AiSuggestion {
                     suggested_name: "file1".to_string(),
                     confidence: 0.9,
                     reasoning: "test".to_string(),
@@ -2810,14 +5521,12 @@ Hope this helps!"#;
                     keep_original: false,
                     suggested_folder: Some("photo".to_string()), // Missing 's'
                     folder_confidence: Some(0.8),
-                }),
-                error: None,
-                skipped: false,
-                source: "test".to_string(),
-            },
-            FileAnalysisResult {
-                file_path: "/path/file2.jpg".to_string(),
-                suggestion: Some(AiSuggestion {
+                },
+                "test",
+            ),
+            FileAnalysisResult::suggested(
+                "/path/file2.jpg",
+                AiSuggestion {
                     suggested_name: "file2".to_string(),
                     confidence: 0.9,
                     reasoning: "test".to_string(),
@@ -2825,14 +5534,12 @@ Hope this helps!"#;
                     keep_original: false,
                     suggested_folder: Some("photo".to_string()),
                     folder_confidence: Some(0.8),
-                }),
-                error: None,
-                skipped: false,
-                source: "test".to_string(),
-            },
-            FileAnalysisResult {
-                file_path: "/path/file3.jpg".to_string(),
-                suggestion: Some(AiSuggestion {
+                },
+                "test",
+            ),
+            FileAnalysisResult::suggested(
+                "/path/file3.jpg",
+                AiSuggestion {
                     suggested_name: "file3".to_string(),
                     confidence: 0.9,
                     reasoning: "test".to_string(),
@@ -2840,15 +5547,13 @@ Hope this helps!"#;
                     keep_original: false,
                     suggested_folder: Some("photo".to_string()),
                     folder_confidence: Some(0.8),
-                }),
-                error: None,
-                skipped: false,
-                source: "test".to_string(),
-            },
+                },
+                "test",
+            ),
         ];
 
         // Existing folder named "Photos" (with s)
-        consolidate_folder_suggestions(&mut results, &["Photos".to_string()]);
+        consolidate_folder_suggestions(&mut results, &["Photos".to_string()], ConsolidationLimits::STANDARD);
 
         // Should use existing folder name "Photos"
         for result in &results {
@@ -2864,9 +5569,9 @@ Hope this helps!"#;
     fn test_consolidate_folder_suggestions_removes_small() {
         let mut results = vec![
             // 3 files in "photos" - should keep
-            FileAnalysisResult {
-                file_path: "/path/file1.jpg".to_string(),
-                suggestion: Some(AiSuggestion {
+            FileAnalysisResult::suggested(
+                "/path/file1.jpg",
+                AiSuggestion {
                     suggested_name: "file1".to_string(),
                     confidence: 0.9,
                     reasoning: "test".to_string(),
@@ -2874,14 +5579,12 @@ Hope this helps!"#;
                     keep_original: false,
                     suggested_folder: Some("photos".to_string()),
                     folder_confidence: Some(0.8),
-                }),
-                error: None,
-                skipped: false,
-                source: "test".to_string(),
-            },
-            FileAnalysisResult {
-                file_path: "/path/file2.jpg".to_string(),
-                suggestion: Some(AiSuggestion {
+                },
+                "test",
+            ),
+            FileAnalysisResult::suggested(
+                "/path/file2.jpg",
+                AiSuggestion {
                     suggested_name: "file2".to_string(),
                     confidence: 0.9,
                     reasoning: "test".to_string(),
@@ -2889,14 +5592,12 @@ Hope this helps!"#;
                     keep_original: false,
                     suggested_folder: Some("photos".to_string()),
                     folder_confidence: Some(0.8),
-                }),
-                error: None,
-                skipped: false,
-                source: "test".to_string(),
-            },
-            FileAnalysisResult {
-                file_path: "/path/file3.jpg".to_string(),
-                suggestion: Some(AiSuggestion {
+                },
+                "test",
+            ),
+            FileAnalysisResult::suggested(
+                "/path/file3.jpg",
+                AiSuggestion {
                     suggested_name: "file3".to_string(),
                     confidence: 0.9,
                     reasoning: "test".to_string(),
@@ -2904,15 +5605,13 @@ Hope this helps!"#;
                     keep_original: false,
                     suggested_folder: Some("photos".to_string()),
                     folder_confidence: Some(0.8),
-                }),
-                error: None,
-                skipped: false,
-                source: "test".to_string(),
-            },
+                },
+                "test",
+            ),
             // 1 file in "random-folder" - should be removed (below threshold)
-            FileAnalysisResult {
-                file_path: "/path/file4.pdf".to_string(),
-                suggestion: Some(AiSuggestion {
+            FileAnalysisResult::suggested(
+                "/path/file4.pdf",
+                AiSuggestion {
                     suggested_name: "file4".to_string(),
                     confidence: 0.9,
                     reasoning: "test".to_string(),
@@ -2920,14 +5619,12 @@ Hope this helps!"#;
                     keep_original: false,
                     suggested_folder: Some("random-folder".to_string()),
                     folder_confidence: Some(0.8),
-                }),
-                error: None,
-                skipped: false,
-                source: "test".to_string(),
-            },
+                },
+                "test",
+            ),
         ];
 
-        consolidate_folder_suggestions(&mut results, &[]);
+        consolidate_folder_suggestions(&mut results, &[], ConsolidationLimits::STANDARD);
 
         // "photos" folder should remain (3 files)
         let photo_folders: Vec<_> = results
@@ -2942,11 +5639,25 @@ Hope this helps!"#;
         assert!(random_file.suggestion.as_ref().unwrap().suggested_folder.is_none());
     }
 
+    #[test]
+    fn test_consolidate_folder_suggestions_warns_dropped_folder() {
+        let mut results: Vec<_> = (0..3).map(|i| create_folder_result(i, "photos")).collect();
+        results.push(create_folder_result(3, "random-folder"));
+
+        let warnings =
+            consolidate_folder_suggestions(&mut results, &[], ConsolidationLimits::STANDARD);
+
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].file_path, "/path/file3.pdf");
+        assert_eq!(warnings[0].kind, AnalysisWarningKind::FolderConsolidated);
+        assert!(warnings[0].message.contains("random-folder"));
+    }
+
     #[test]
     fn test_flatten_folder_path_cleans_deep_paths() {
         // Test from prompt: MAX 2 levels
-        assert_eq!(flatten_folder_path("documents/work/projects/client"), "documents/work");
-        assert_eq!(flatten_folder_path("photos/travel/europe/2024"), "photos/travel");
+        assert_eq!(flatten_folder_path("documents/work/projects/client", MAX_FOLDER_DEPTH), "documents/work");
+        assert_eq!(flatten_folder_path("photos/travel/europe/2024", MAX_FOLDER_DEPTH), "photos/travel");
     }
 
     // =============================================================================
@@ -2961,26 +5672,248 @@ Hope this helps!"#;
         assert!(validate_openai_url_security("HTTPS://API.OPENAI.COM").is_ok());
     }
 
-    #[test]
-    fn test_validate_openai_url_security_localhost() {
-        // Localhost HTTP should be allowed for development
-        assert!(validate_openai_url_security("http://localhost:8080").is_ok());
-        assert!(validate_openai_url_security("http://localhost/v1").is_ok());
-        assert!(validate_openai_url_security("http://127.0.0.1:8080").is_ok());
-        assert!(validate_openai_url_security("http://127.0.0.1").is_ok());
-        assert!(validate_openai_url_security("http://[::1]:8080").is_ok());
+    fn create_policy_result(file_path: &str, confidence: f32) -> FileAnalysisResult {
+        FileAnalysisResult::suggested(
+            file_path,
+            AiSuggestion {
+                suggested_name: "ai-suggested-name".to_string(),
+                confidence,
+                reasoning: "test".to_string(),
+                keywords: vec![],
+                keep_original: false,
+                suggested_folder: None,
+                folder_confidence: None,
+            },
+            "llm",
+        )
     }
 
     #[test]
-    fn test_validate_openai_url_security_http_external_rejected() {
-        // HTTP to external hosts should be rejected
-        let result = validate_openai_url_security("http://api.openai.com");
-        assert!(result.is_err());
-        assert!(result.unwrap_err().contains("HTTPS"));
+    fn test_collect_analysis_warnings_truncated_content() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let large = dir.path().join("notes.txt");
+        let small = dir.path().join("short.txt");
+        std::fs::write(&large, "a".repeat(MAX_CONTENT_CHARS * 5)).unwrap();
+        std::fs::write(&small, "short note").unwrap();
 
-        let result = validate_openai_url_security("http://external-host.com/api");
-        assert!(result.is_err());
-    }
+        let mut results = vec![
+            create_policy_result(&large.to_string_lossy(), 0.9),
+            create_policy_result(&small.to_string_lossy(), 0.9),
+        ];
+        for result in results.iter_mut() {
+            result.source = "openai".to_string();
+        }
+
+        let warnings = collect_analysis_warnings(&results);
+
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].kind, AnalysisWarningKind::ContentTruncated);
+        assert_eq!(warnings[0].file_path, large.to_string_lossy());
+    }
+
+    #[test]
+    fn test_truncation_warning_uses_measured_lengths() {
+        let mut result = create_policy_result("/docs/notes.txt", 0.9);
+        result.source = "ollama".to_string();
+        result.content_truncated = true;
+        result.content_length = Some(ContentLength { original: 100_000, used: 6_000 });
+
+        let warning = truncation_warning(&result).unwrap();
+        assert!(warning.message.contains("6000 of 100000"));
+
+        // Sampled, but most of the file made it into the prompt
+        result.content_length = Some(ContentLength { original: 20_000, used: 6_000 });
+        assert!(truncation_warning(&result).is_none());
+        result.content_truncated = false;
+        result.content_length = Some(ContentLength { original: 100_000, used: 100_000 });
+        assert!(truncation_warning(&result).is_none());
+    }
+
+    #[test]
+    fn test_collect_analysis_warnings_low_confidence() {
+        let results = vec![
+            create_policy_result("/docs/scan.pdf", 0.3),
+            create_policy_result("/docs/other.pdf", 0.9),
+        ];
+
+        let warnings = collect_analysis_warnings(&results);
+
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].kind, AnalysisWarningKind::LowConfidence);
+        assert_eq!(warnings[0].file_path, "/docs/scan.pdf");
+    }
+
+    fn create_failed_result(path: &str, error: &str) -> FileAnalysisResult {
+        FileAnalysisResult::failed(path, error)
+    }
+
+    #[test]
+    fn test_collect_batch_warnings_vision_disabled() {
+        let config = OllamaConfig {
+            vision_enabled: false,
+            ..Default::default()
+        };
+        let unsupported = |path: &str| {
+            FileAnalysisResult::skipped(
+                path,
+                AnalysisSkipReason::Unsupported,
+                "unsupported",
+                Some("File type not supported for analysis".to_string()),
+            )
+        };
+        let results = vec![
+            unsupported("/photos/a.jpg"),
+            unsupported("/photos/b.png"),
+            unsupported("/misc/archive.zip"),
+            create_policy_result("/docs/notes.txt", 0.9),
+        ];
+
+        let warnings = collect_batch_warnings(&results, &config);
+
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].kind, AnalysisWarningKind::VisionDisabled);
+        assert_eq!(warnings[0].file_count, Some(2));
+        assert!(warnings[0].file_path.is_empty());
+        assert_eq!(warnings[0].message, "Vision is disabled, so 2 images were skipped");
+
+        let enabled = OllamaConfig {
+            vision_enabled: true,
+            ..Default::default()
+        };
+        assert!(collect_batch_warnings(&results, &enabled).is_empty());
+    }
+
+    #[test]
+    fn test_collect_batch_warnings_missing_api_key() {
+        let results: Vec<_> = (0..30)
+            .map(|i| create_failed_result(&format!("/docs/{}.txt", i), MISSING_API_KEY_ERROR))
+            .collect();
+
+        let warnings = collect_batch_warnings(&results, &OllamaConfig::default());
+
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].kind, AnalysisWarningKind::ApiKeyMissing);
+        assert_eq!(warnings[0].file_count, Some(30));
+    }
+
+    #[test]
+    fn test_collect_batch_warnings_repeated_error() {
+        let mut results: Vec<_> = (0..3)
+            .map(|i| create_failed_result(&format!("/docs/{}.txt", i), "Request timed out"))
+            .collect();
+        results.push(create_failed_result("/docs/x.txt", "Invalid JSON response"));
+
+        let warnings = collect_batch_warnings(&results, &OllamaConfig::default());
+
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].kind, AnalysisWarningKind::RepeatedError);
+        assert_eq!(warnings[0].message, "3 files failed with: Request timed out");
+    }
+
+    #[test]
+    fn test_keep_original_policy_good_name_low_confidence() {
+        let mut results = vec![create_policy_result("/docs/invoice_acme_march.pdf", 0.4)];
+
+        apply_keep_original_policy(&mut results, 0.7);
+
+        let suggestion = results[0].suggestion.as_ref().unwrap();
+        assert!(suggestion.keep_original);
+        assert_eq!(suggestion.suggested_name, "invoice_acme_march");
+    }
+
+    #[test]
+    fn test_keep_original_policy_good_name_high_confidence() {
+        let mut results = vec![create_policy_result("/docs/invoice_acme_march.pdf", 0.9)];
+
+        apply_keep_original_policy(&mut results, 0.7);
+
+        let suggestion = results[0].suggestion.as_ref().unwrap();
+        assert!(!suggestion.keep_original);
+        assert_eq!(suggestion.suggested_name, "ai-suggested-name");
+    }
+
+    #[test]
+    fn test_keep_original_policy_bad_name_low_confidence() {
+        let mut results = vec![create_policy_result("/photos/IMG_1234.jpg", 0.4)];
+
+        apply_keep_original_policy(&mut results, 0.7);
+
+        let suggestion = results[0].suggestion.as_ref().unwrap();
+        assert!(!suggestion.keep_original);
+        assert_eq!(suggestion.suggested_name, "ai-suggested-name");
+    }
+
+    #[test]
+    fn test_keep_original_policy_bad_name_high_confidence() {
+        let mut results = vec![create_policy_result("/photos/IMG_1234.jpg", 0.9)];
+
+        apply_keep_original_policy(&mut results, 0.7);
+
+        let suggestion = results[0].suggestion.as_ref().unwrap();
+        assert!(!suggestion.keep_original);
+        assert_eq!(suggestion.suggested_name, "ai-suggested-name");
+    }
+
+    #[test]
+    fn test_keep_original_policy_disabled_with_zero_threshold() {
+        let mut results = vec![create_policy_result("/docs/invoice_acme_march.pdf", 0.1)];
+
+        apply_keep_original_policy(&mut results, 0.0);
+
+        assert!(!results[0].suggestion.as_ref().unwrap().keep_original);
+    }
+
+    #[test]
+    fn test_apply_dictionary_casing() {
+        let dictionary = vec!["NASA".to_string(), "iPhone".to_string()];
+
+        assert_eq!(apply_dictionary_casing("nasa-report", &dictionary), "NASA-report");
+        assert_eq!(
+            apply_dictionary_casing("Iphone_photos_nasa", &dictionary),
+            "iPhone_photos_NASA"
+        );
+        // Only whole words are replaced
+        assert_eq!(apply_dictionary_casing("nasal-spray", &dictionary), "nasal-spray");
+    }
+
+    #[test]
+    fn test_apply_name_dictionary_skips_kept_originals() {
+        let dictionary = vec!["NASA".to_string()];
+        let mut results = vec![
+            create_policy_result("/docs/scan.pdf", 0.9),
+            create_policy_result("/docs/nasa_notes.pdf", 0.9),
+        ];
+        results[0].suggestion.as_mut().unwrap().suggested_name = "nasa-report".to_string();
+        let kept = results[1].suggestion.as_mut().unwrap();
+        kept.suggested_name = "nasa_notes".to_string();
+        kept.keep_original = true;
+
+        apply_name_dictionary(&mut results, &dictionary);
+
+        assert_eq!(results[0].suggestion.as_ref().unwrap().suggested_name, "NASA-report");
+        assert_eq!(results[1].suggestion.as_ref().unwrap().suggested_name, "nasa_notes");
+    }
+
+    #[test]
+    fn test_validate_openai_url_security_localhost() {
+        // Localhost HTTP should be allowed for development
+        assert!(validate_openai_url_security("http://localhost:8080").is_ok());
+        assert!(validate_openai_url_security("http://localhost/v1").is_ok());
+        assert!(validate_openai_url_security("http://127.0.0.1:8080").is_ok());
+        assert!(validate_openai_url_security("http://127.0.0.1").is_ok());
+        assert!(validate_openai_url_security("http://[::1]:8080").is_ok());
+    }
+
+    #[test]
+    fn test_validate_openai_url_security_http_external_rejected() {
+        // HTTP to external hosts should be rejected
+        let result = validate_openai_url_security("http://api.openai.com");
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("HTTPS"));
+
+        let result = validate_openai_url_security("http://external-host.com/api");
+        assert!(result.is_err());
+    }
 
     #[test]
     fn test_validate_openai_url_security_no_protocol() {
@@ -2989,4 +5922,1093 @@ Hope this helps!"#;
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("https://"));
     }
+
+    // =========================================================================
+    // Generation Parameter Tests
+    // =========================================================================
+
+    fn create_generation_config() -> OllamaConfig {
+        OllamaConfig {
+            temperature: 1.25,
+            max_tokens: 1024,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_openai_request_uses_configured_parameters() {
+        let config = create_generation_config();
+        let request = OpenAiChatRequest::new("gpt-4o-mini".to_string(), vec![], &config);
+
+        let body = serde_json::to_value(&request).unwrap();
+        assert_eq!(body["temperature"], 1.25);
+        assert_eq!(body["max_tokens"], 1024);
+    }
+
+    #[test]
+    fn test_ollama_request_uses_configured_parameters() {
+        let config = create_generation_config();
+        let request = OllamaGenerateRequest {
+            model: "mistral".to_string(),
+            prompt: String::new(),
+            system: String::new(),
+            stream: false,
+            options: OllamaOptions::from_config(&config),
+        };
+
+        let body = serde_json::to_value(&request).unwrap();
+        assert_eq!(body["options"]["temperature"], 1.25);
+        assert_eq!(body["options"]["num_predict"], 1024);
+    }
+
+    #[test]
+    fn test_ollama_vision_request_uses_configured_parameters() {
+        let config = create_generation_config();
+        let body = build_ollama_vision_request("llava", "describe", "aW1hZ2U=", &config);
+
+        assert_eq!(body["images"][0], "aW1hZ2U=");
+        assert_eq!(body["options"]["temperature"], 1.25);
+        assert_eq!(body["options"]["num_predict"], 1024);
+
+        // Defaults match the previous hardcoded values
+        let defaults = build_ollama_vision_request("llava", "describe", "", &OllamaConfig::default());
+        assert_eq!(defaults["options"]["num_predict"], 500);
+    }
+
+    // =========================================================================
+    // Empty Batch Tests
+    // =========================================================================
+
+    #[test]
+    fn test_progress_percent_handles_zero_total() {
+        assert_eq!(progress_percent(0, 0), 100);
+        assert_eq!(progress_percent(0, 4), 0);
+        assert_eq!(progress_percent(1, 3), 33);
+        assert_eq!(progress_percent(3, 3), 100);
+        assert_eq!(progress_percent(5, 3), 100);
+    }
+
+    #[tokio::test]
+    async fn test_empty_analysis_result_checks_provider() {
+        // Nothing listens on port 1, so the health check fails to connect
+        let config = OllamaConfig {
+            enabled: true,
+            base_url: "http://127.0.0.1:1".to_string(),
+            health_check_timeout: 1000,
+            ..Default::default()
+        };
+
+        let result = empty_analysis_result(&config).await;
+
+        assert!(result.results.is_empty());
+        assert_eq!(result.total, 0);
+        assert_eq!(result.analyzed + result.failed + result.skipped, 0);
+        assert!(!result.llm_available);
+    }
+
+    // =========================================================================
+    // Adaptive Consolidation Tests
+    // =========================================================================
+
+    fn create_folder_result(index: usize, folder: &str) -> FileAnalysisResult {
+        FileAnalysisResult::suggested(
+            format!("/path/file{}.pdf", index),
+            AiSuggestion {
+                suggested_name: format!("file{}", index),
+                confidence: 0.9,
+                reasoning: "test".to_string(),
+                keywords: vec![],
+                keep_original: false,
+                suggested_folder: Some(folder.to_string()),
+                folder_confidence: Some(0.8),
+            },
+            "test",
+        )
+    }
+
+    /// 720 results spread evenly over 36 three-level folders
+    fn create_deep_batch() -> Vec<FileAnalysisResult> {
+        let areas = ["finance", "travel", "health", "projects"];
+        let topics = ["invoices", "itineraries", "appointments"];
+        let leaves = ["quarterly-summaries", "summer-photos", "legal-contracts"];
+
+        let mut results = Vec::new();
+        for area in areas {
+            for topic in topics {
+                for leaf in leaves {
+                    for _ in 0..20 {
+                        let folder = format!("{}/{}/{}", area, topic, leaf);
+                        results.push(create_folder_result(results.len(), &folder));
+                    }
+                }
+            }
+        }
+        results
+    }
+
+    fn distinct_folders(results: &[FileAnalysisResult]) -> std::collections::HashSet<String> {
+        results
+            .iter()
+            .filter_map(|r| r.suggestion.as_ref()?.suggested_folder.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_standard_vs_adaptive_consolidation_on_deep_batch() {
+        let adaptive = AdaptiveConsolidationConfig {
+            enabled: true,
+            ..Default::default()
+        };
+
+        let mut standard_results = create_deep_batch();
+        consolidate_folder_suggestions(&mut standard_results, &[], ConsolidationLimits::STANDARD);
+
+        let mut adaptive_results = create_deep_batch();
+        let limits = select_consolidation_limits(&adaptive_results, &adaptive);
+        assert_eq!(limits, ConsolidationLimits { max_depth: 3, min_files_per_folder: 8 });
+        consolidate_folder_suggestions(&mut adaptive_results, &[], limits);
+
+        // Standard flattens to two levels, adaptive keeps the third
+        let standard_folders = distinct_folders(&standard_results);
+        assert_eq!(standard_folders.len(), 12);
+        assert!(standard_folders.contains("finance/invoices"));
+
+        let adaptive_folders = distinct_folders(&adaptive_results);
+        assert_eq!(adaptive_folders.len(), 36);
+        assert!(adaptive_folders.contains("finance/invoices/legal-contracts"));
+    }
+
+    #[test]
+    fn test_adaptive_consolidation_scales_min_files() {
+        let adaptive = AdaptiveConsolidationConfig {
+            enabled: true,
+            ..Default::default()
+        };
+
+        // A sparse folder below the scaled threshold (8) moves to its parent
+        let mut results = create_deep_batch();
+        for _ in 0..5 {
+            results.push(create_folder_result(results.len(), "finance/invoices/tax-refunds"));
+        }
+
+        let limits = select_consolidation_limits(&results, &adaptive);
+        consolidate_folder_suggestions(&mut results, &[], limits);
+
+        let folders = distinct_folders(&results);
+        assert!(!folders.contains("finance/invoices/tax-refunds"));
+        assert!(folders.contains("finance/invoices"));
+    }
+
+    #[test]
+    fn test_adaptive_consolidation_measures_at_returned_depth() {
+        // Four top-level folders would be too few, but twelve exist at depth 2
+        let adaptive = AdaptiveConsolidationConfig {
+            enabled: true,
+            max_depth: 1,
+            ..Default::default()
+        };
+
+        let limits = select_consolidation_limits(&create_deep_batch(), &adaptive);
+
+        assert_eq!(limits, ConsolidationLimits { max_depth: 2, min_files_per_folder: 8 });
+    }
+
+    #[test]
+    fn test_adaptive_consolidation_requires_opt_in_and_large_batch() {
+        let results = create_deep_batch();
+        assert_eq!(
+            select_consolidation_limits(&results, &AdaptiveConsolidationConfig::default()),
+            ConsolidationLimits::STANDARD
+        );
+
+        let adaptive = AdaptiveConsolidationConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            select_consolidation_limits(&results[..100], &adaptive),
+            ConsolidationLimits::STANDARD
+        );
+    }
+
+    // =========================================================================
+    // Model Pull Tests
+    // =========================================================================
+
+    /// Serve canned HTTP responses on a local port, one per connection in order
+    fn serve_responses(responses: Vec<String>) -> String {
+        serve_responses_with(responses, |_| {})
+    }
+
+    /// Like `serve_responses`, calling `on_request` with the index of each
+    /// request before answering it
+    fn serve_responses_with(
+        responses: Vec<String>,
+        on_request: impl Fn(usize) + Send + 'static,
+    ) -> String {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            for (index, response) in responses.into_iter().enumerate() {
+                let (mut stream, _) = listener.accept().unwrap();
+                // Read the whole request so closing the socket doesn't reset it
+                let mut request = Vec::new();
+                let mut buf = [0u8; 1024];
+                loop {
+                    let n = stream.read(&mut buf).unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                    let text = String::from_utf8_lossy(&request).to_lowercase();
+                    if let Some(end) = text.find("\r\n\r\n") {
+                        let length = text
+                            .lines()
+                            .find_map(|l| l.strip_prefix("content-length:"))
+                            .map(|l| l.trim().parse::<usize>().unwrap())
+                            .unwrap_or(0);
+                        if n == 0 || request.len() >= end + 4 + length {
+                            break;
+                        }
+                    }
+                }
+                on_request(index);
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+        url
+    }
+
+    fn json_response(body: &str) -> String {
+        format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        )
+    }
+
+    /// Chunked response whose chunk boundaries fall in the middle of lines
+    fn streamed_response(chunks: &[&str]) -> String {
+        let mut response = "HTTP/1.1 200 OK\r\nContent-Type: application/x-ndjson\r\n\
+                            Transfer-Encoding: chunked\r\nConnection: close\r\n\r\n"
+            .to_string();
+        for chunk in chunks {
+            response.push_str(&format!("{:x}\r\n{}\r\n", chunk.len(), chunk));
+        }
+        response.push_str("0\r\n\r\n");
+        response
+    }
+
+    async fn pull_with_events(
+        url: &str,
+        model: &str,
+    ) -> (Result<ModelPullResult, String>, Vec<ModelPullProgress>) {
+        let events = std::sync::Mutex::new(Vec::new());
+        let on_progress = |progress: ModelPullProgress| events.lock().unwrap().push(progress);
+        let result = pull_ollama_model_internal(&Client::new(), url, model, &on_progress).await;
+        (result, events.into_inner().unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_pull_ollama_model_streams_progress() {
+        let url = serve_responses(vec![
+            json_response(r#"{"models":[]}"#),
+            streamed_response(&[
+                "{\"status\":\"pulling manifest\"}\n{\"status\":\"pulling abc\",\"dig",
+                "est\":\"sha256:abc\",\"total\":200,\"completed\":100}\n",
+                "{\"status\":\"pulling abc\",\"digest\":\"sha256:abc\",\"total\":200,\"completed\":100}\n",
+                "{\"status\":\"pulling abc\",\"digest\":\"sha256:abc\",\"total\":200,\"completed\":200}\n",
+                "{\"status\":\"success\"}",
+            ]),
+        ]);
+
+        let (result, events) = pull_with_events(&url, "mistral").await;
+
+        assert!(!result.unwrap().already_installed);
+        let steps: Vec<(&str, u8)> = events.iter().map(|e| (e.status.as_str(), e.percent)).collect();
+        assert_eq!(
+            steps,
+            vec![("pulling manifest", 0), ("pulling abc", 50), ("pulling abc", 100), ("success", 100)]
+        );
+        assert_eq!(events[1].layer.as_deref(), Some("sha256:abc"));
+    }
+
+    #[tokio::test]
+    async fn test_pull_ollama_model_already_installed() {
+        let url = serve_responses(vec![json_response(
+            r#"{"models":[{"name":"mistral:latest","size":1}]}"#,
+        )]);
+
+        let (result, events) = pull_with_events(&url, "mistral").await;
+
+        assert!(result.unwrap().already_installed);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].percent, 100);
+    }
+
+    #[tokio::test]
+    async fn test_pull_ollama_model_failure() {
+        let url = serve_responses(vec![
+            json_response(r#"{"models":[]}"#),
+            streamed_response(&[
+                "{\"status\":\"pulling manifest\"}\n",
+                "{\"error\":\"pull model manifest: file does not exist\"}\n",
+            ]),
+        ]);
+
+        let (result, _) = pull_with_events(&url, "no-such-model").await;
+
+        assert!(result.unwrap_err().contains("file does not exist"));
+    }
+
+    fn ollama_config_for(base_url: &str, inference: &str) -> OllamaConfig {
+        OllamaConfig {
+            base_url: base_url.to_string(),
+            models: crate::commands::config::OllamaModelsConfig {
+                inference: Some(inference.to_string()),
+                vision: Some("llava".to_string()),
+            },
+            ..Default::default()
+        }
+    }
+
+    fn fallback_test_file(dir: &tempfile::TempDir) -> String {
+        let path = dir.path().join("scan001.txt");
+        std::fs::write(&path, "Quarterly budget report for the marketing team").unwrap();
+        path.to_string_lossy().to_string()
+    }
+
+    fn openai_primary_config(openai_url: &str, ollama_url: &str) -> OllamaConfig {
+        let mut config = ollama_config_for(ollama_url, "mistral");
+        config.provider = LlmProvider::Openai;
+        config.fallback_provider = Some(LlmProvider::Ollama);
+        config.openai.base_url = openai_url.to_string();
+        config.openai.api_key = "sk-test".to_string();
+        config
+    }
+
+    #[tokio::test]
+    async fn test_analyze_with_fallback_primary_fails_secondary_succeeds() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let file_path = fallback_test_file(&dir);
+        let openai_url = serve_responses(vec![
+            "HTTP/1.1 401 Unauthorized\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                .to_string(),
+        ]);
+        let suggestion = serde_json::json!({
+            "suggestedName": "marketing-budget-report",
+            "confidence": 0.9,
+            "reasoning": "Budget report",
+            "keywords": []
+        });
+        let body = serde_json::json!({ "response": suggestion.to_string() }).to_string();
+        let ollama_url = serve_responses(vec![json_response(&body)]);
+        let config = openai_primary_config(&openai_url, &ollama_url);
+
+        let result = analyze_with_fallback(&Client::new(), &file_path, &config, &[]).await;
+
+        assert_eq!(result.source, "ollama");
+        assert!(result.error.is_none());
+        assert_eq!(result.suggestion.unwrap().suggested_name, "marketing-budget-report");
+    }
+
+    #[tokio::test]
+    async fn test_analyze_with_fallback_reports_both_errors() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let file_path = fallback_test_file(&dir);
+        let openai_url = serve_responses(vec![
+            "HTTP/1.1 401 Unauthorized\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                .to_string(),
+        ]);
+        let ollama_url = serve_responses(vec![
+            "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                .to_string(),
+        ]);
+        let config = openai_primary_config(&openai_url, &ollama_url);
+
+        let result = analyze_with_fallback(&Client::new(), &file_path, &config, &[]).await;
+
+        assert!(result.suggestion.is_none());
+        let error = result.error.unwrap();
+        assert!(error.contains("Invalid API key"));
+        assert!(error.contains("fallback provider also failed: Ollama error: 404"));
+    }
+
+    #[tokio::test]
+    async fn test_analyze_with_fallback_validates_openai_url() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let file_path = fallback_test_file(&dir);
+        // Nothing listens on port 1: the Ollama primary can't connect
+        let mut config = ollama_config_for("http://127.0.0.1:1", "mistral");
+        config.fallback_provider = Some(LlmProvider::Openai);
+        config.openai.base_url = "http://api.example.com/v1".to_string();
+        config.openai.api_key = "sk-test".to_string();
+
+        let result = analyze_with_fallback(&Client::new(), &file_path, &config, &[]).await;
+
+        assert!(result.suggestion.is_none());
+        let error = result.error.unwrap();
+        assert!(error.starts_with("Request failed"));
+        assert!(error.contains("fallback provider not used: Security error"));
+    }
+
+    #[tokio::test]
+    async fn test_analyze_files_stops_between_files_when_cancelled() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let files: Vec<String> = (1..=6)
+            .map(|i| {
+                let path = dir.path().join(format!("scan00{}.txt", i));
+                std::fs::write(&path, format!("Quarterly budget report number {}", i)).unwrap();
+                path.to_string_lossy().to_string()
+            })
+            .collect();
+
+        // Model check first, then failing analysis requests; the first of
+        // those cancels the batch while the other files wait for a permit
+        let token = CancellationToken::new();
+        let cancel = token.clone();
+        let mut responses =
+            vec![json_response(r#"{"models":[{"name":"mistral:latest","size":1}]}"#)];
+        responses.extend(std::iter::repeat_n(
+            "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                .to_string(),
+            20,
+        ));
+        let url = serve_responses_with(responses, move |index| {
+            if index == 1 {
+                cancel.cancel();
+            }
+        });
+        let config = OllamaConfig { enabled: true, ..ollama_config_for(&url, "mistral") };
+
+        let result = analyze_files(|_| {}, token, files.clone(), config, None, None, None, None)
+            .await
+            .unwrap();
+
+        let cancelled = result
+            .results
+            .iter()
+            .filter(|r| r.skip_reason == Some(AnalysisSkipReason::Cancelled))
+            .count();
+        assert!(result.cancelled);
+        assert!(result.failed >= 1);
+        // At most the three files holding a permit were sent
+        assert!(cancelled >= files.len() - 3);
+        assert_eq!(result.failed + cancelled, files.len());
+        assert_eq!(result.skipped, cancelled);
+    }
+
+    #[test]
+    fn test_should_use_fallback_only_for_provider_errors() {
+        let failed = |failure: Option<ProviderFailure>| {
+            FileAnalysisResult::provider_failed("/docs/a.txt", "error", failure)
+        };
+
+        assert!(should_use_fallback(&failed(Some(ProviderFailure::Connection))));
+        assert!(should_use_fallback(&failed(Some(ProviderFailure::MissingApiKey))));
+        assert!(should_use_fallback(&failed(Some(ProviderFailure::RateLimited))));
+        assert!(!should_use_fallback(&failed(None)));
+        // The wording of an error no longer matters
+        let parse_error = FileAnalysisResult::failed("/docs/a.txt", "Request failed: bad JSON");
+        assert!(!should_use_fallback(&parse_error));
+    }
+
+    #[test]
+    fn test_provider_failure_from_status() {
+        use reqwest::StatusCode;
+
+        let failure = ProviderFailure::from_status;
+        assert_eq!(failure(StatusCode::UNAUTHORIZED), Some(ProviderFailure::Unauthorized));
+        assert_eq!(failure(StatusCode::TOO_MANY_REQUESTS), Some(ProviderFailure::RateLimited));
+        assert_eq!(failure(StatusCode::BAD_GATEWAY), Some(ProviderFailure::Unavailable));
+        assert_eq!(failure(StatusCode::NOT_FOUND), None);
+        assert!(ProviderFailure::RateLimited.is_transient());
+        assert!(!ProviderFailure::Unauthorized.is_transient());
+    }
+
+    #[tokio::test]
+    async fn test_verify_ollama_models_missing_model() {
+        let url = serve_responses(vec![json_response(
+            r#"{"models":[{"name":"mistral:latest","size":1},{"name":"llama3:8b","size":1}]}"#,
+        )]);
+
+        let config = ollama_config_for(&url, "mistrall");
+        let result = verify_ollama_models(&Client::new(), &config).await;
+
+        let error = result.unwrap_err();
+        assert!(error.contains("'mistrall'"));
+        assert!(error.contains("pull_ollama_model"));
+        assert!(error.contains("mistral:latest, llama3:8b"));
+    }
+
+    #[tokio::test]
+    async fn test_verify_ollama_models_cached_for_session() {
+        // The second tags response lacks the model: only the cache can make the second check pass
+        let url = serve_responses(vec![
+            json_response(r#"{"models":[{"name":"mistral:latest","size":1}]}"#),
+            json_response(r#"{"models":[]}"#),
+        ]);
+        let config = ollama_config_for(&url, "mistral");
+
+        assert!(verify_ollama_models(&Client::new(), &config).await.is_ok());
+        assert!(verify_ollama_models(&Client::new(), &config).await.is_ok());
+    }
+
+    // =========================================================================
+    // Sample Analysis Tests
+    // =========================================================================
+
+    fn create_mixed_batch() -> Vec<String> {
+        let mut paths = Vec::new();
+        paths.extend((0..80).map(|i| format!("/batch/photo{:02}.jpg", i)));
+        paths.extend((0..15).map(|i| format!("/batch/doc{:02}.pdf", i)));
+        paths.extend((0..5).map(|i| format!("/batch/song{:02}.mp3", i)));
+        paths
+    }
+
+    fn count_by_category(sample: &[String]) -> HashMap<FileCategory, usize> {
+        let mut counts = HashMap::new();
+        for path in sample {
+            *counts.entry(category_of(path)).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    #[test]
+    fn test_stratified_sample_proportions() {
+        let sample = stratified_sample(&create_mixed_batch(), 20);
+
+        assert_eq!(sample.len(), 20);
+        let counts = count_by_category(&sample);
+        assert_eq!(counts[&FileCategory::Image], 16);
+        assert_eq!(counts[&FileCategory::Document], 3);
+        assert_eq!(counts[&FileCategory::Audio], 1);
+
+        let unique: std::collections::HashSet<_> = sample.iter().collect();
+        assert_eq!(unique.len(), 20);
+    }
+
+    #[test]
+    fn test_stratified_sample_small_categories_represented() {
+        let sample = stratified_sample(&create_mixed_batch(), 10);
+
+        assert_eq!(sample.len(), 10);
+        let counts = count_by_category(&sample);
+        assert_eq!(counts[&FileCategory::Image], 7);
+        assert_eq!(counts[&FileCategory::Document], 2);
+        assert_eq!(counts[&FileCategory::Audio], 1);
+    }
+
+    #[test]
+    fn test_stratified_sample_larger_than_batch() {
+        let batch = create_mixed_batch();
+
+        assert_eq!(stratified_sample(&batch, 500).len(), batch.len());
+        assert!(stratified_sample(&batch, 0).is_empty());
+    }
+
+    #[test]
+    fn test_estimate_file_tokens() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let notes = dir.path().join("notes.txt");
+        std::fs::write(&notes, "a".repeat(4000)).unwrap();
+        let config = OllamaConfig::default();
+
+        let overhead = ESTIMATED_PROMPT_TOKENS + u64::from(config.max_tokens);
+        assert_eq!(estimate_file_tokens(&notes.to_string_lossy(), &config), 1000 + overhead);
+        // Vision disabled: images aren't sent
+        assert_eq!(estimate_file_tokens("/batch/photo.jpg", &config), 0);
+    }
+
+    #[test]
+    fn test_disabled_result_skip_reason() {
+        let result = disabled_result("/batch/notes.txt".to_string());
+
+        assert!(result.skipped);
+        assert_eq!(result.skip_reason, Some(AnalysisSkipReason::Disabled));
+    }
+
+    #[tokio::test]
+    async fn test_skip_reasons_without_network() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let write = |name: &str, content: &str| {
+            let path = dir.path().join(name);
+            std::fs::write(&path, content).unwrap();
+            path.to_string_lossy().to_string()
+        };
+        let descriptive = write("quarterly-sales-report-2024.txt", "Q1 sales");
+        let empty = write("IMG_1234.txt", "   ");
+        let unsupported = write("IMG_5678.bin", "data");
+        let excluded = write("notes.log", "log line");
+
+        let mut config = OllamaConfig::default();
+        config.file_types.excluded_extensions = vec![".LOG".to_string()];
+        let client = Client::new();
+        let reason = |result: FileAnalysisResult| result.skip_reason;
+
+        let result = analyze_single_file_with_cache(&client, &descriptive, &config, &[], false).await;
+        assert!(!result.skipped);
+        assert_eq!(reason(result), Some(AnalysisSkipReason::AlreadyDescriptive));
+
+        let result = analyze_single_file(&client, &empty, &config, &[]).await;
+        assert_eq!(reason(result), Some(AnalysisSkipReason::Empty));
+
+        let result = analyze_single_file(&client, &unsupported, &config, &[]).await;
+        assert_eq!(reason(result), Some(AnalysisSkipReason::Unsupported));
+
+        let result = analyze_single_file_with_cache(&client, &excluded, &config, &[], false).await;
+        assert!(result.skipped);
+        assert_eq!(result.source, "filtered");
+        assert_eq!(reason(result), Some(AnalysisSkipReason::Filtered));
+    }
+
+    #[tokio::test]
+    async fn test_organize_from_suggestions() {
+        let mut unsuggested = create_folder_result(3, "unused");
+        unsuggested.suggestion = None;
+        let results = vec![
+            create_folder_result(1, "Finance/Invoices"),
+            create_folder_result(2, "../Travel/"),
+            unsuggested,
+        ];
+
+        let preview = organize_from_suggestions(results, "/organized/".to_string())
+            .await
+            .unwrap();
+
+        assert_eq!(preview.proposals.len(), 2);
+        assert_eq!(preview.reorganization_mode, ReorganizationMode::Organize);
+        assert_eq!(preview.action_summary.move_count, 2);
+        let first = &preview.proposals[0];
+        assert_eq!(first.proposed_path, "/organized/Finance/Invoices/file1.pdf");
+        assert_eq!(first.proposed_name, "file1.pdf");
+        assert_eq!(first.destination_folder.as_deref(), Some("Finance/Invoices"));
+        // ".." is dropped so the file stays under the base directory
+        assert_eq!(preview.proposals[1].proposed_path, "/organized/Travel/file2.pdf");
+    }
+
+    fn create_merge_proposal(name: &str, proposed: &str) -> RenameProposal {
+        RenameProposal {
+            id: name.to_string(),
+            original_path: format!("/photos/{}", name),
+            original_name: name.to_string(),
+            proposed_name: proposed.to_string(),
+            proposed_path: format!("/photos/{}", proposed),
+            status: RenameStatus::Ready,
+            issues: vec![],
+            metadata_sources: None,
+            is_folder_move: false,
+            destination_folder: None,
+            action_type: FileActionType::Rename,
+            conflict: None,
+        }
+    }
+
+    fn create_name_result(path: &str, name: &str, confidence: f32) -> FileAnalysisResult {
+        let mut result = create_folder_result(1, "unused");
+        result.file_path = path.to_string();
+        let suggestion = result.suggestion.as_mut().unwrap();
+        suggestion.suggested_name = name.to_string();
+        suggestion.confidence = confidence;
+        suggestion.suggested_folder = None;
+        result
+    }
+
+    #[tokio::test]
+    async fn test_merge_suggestions_above_threshold() {
+        let preview = finalize_preview(
+            vec![
+                create_merge_proposal("IMG_001.JPG", "2024-01-01.JPG"),
+                create_merge_proposal("IMG_002.JPG", "2024-01-02.JPG"),
+            ],
+            "{date}".to_string(),
+            ReorganizationMode::RenameOnly,
+        );
+        let results = vec![
+            create_name_result("/photos/IMG_001.JPG", "beach-sunset", 0.9),
+            create_name_result("/photos/IMG_002.JPG", "blurry-shot", 0.4),
+        ];
+
+        let merged = merge_suggestions(preview, results, 0.7).await.unwrap();
+
+        let first = &merged.proposals[0];
+        assert_eq!(first.proposed_name, "beach-sunset.JPG");
+        assert_eq!(first.proposed_path, "/photos/beach-sunset.JPG");
+        assert_eq!(first.metadata_sources, Some(vec!["ai".to_string()]));
+        // Below the threshold: the template name stays
+        assert_eq!(merged.proposals[1].proposed_name, "2024-01-02.JPG");
+        assert_eq!(merged.template_used, "{date}");
+        assert_eq!(merged.summary.ready, 2);
+    }
+
+    #[tokio::test]
+    async fn test_merge_suggestions_rechecks_conflicts() {
+        let preview = finalize_preview(
+            vec![
+                create_merge_proposal("a.txt", "notes.txt"),
+                create_merge_proposal("b.txt", "notes.txt"),
+                create_merge_proposal("c.txt", "todo.txt"),
+            ],
+            String::new(),
+            ReorganizationMode::RenameOnly,
+        );
+        assert_eq!(preview.summary.conflicts, 2);
+        let mut kept = create_name_result("/photos/c.txt", "ignored", 0.95);
+        kept.suggestion.as_mut().unwrap().keep_original = true;
+        let results = vec![
+            create_name_result("/photos/a.txt", "meeting-notes", 0.8),
+            create_name_result("/photos/b.txt", "todo", 0.8),
+            kept,
+        ];
+
+        let merged = merge_suggestions(preview, results, 0.5).await.unwrap();
+
+        // a.txt no longer clashes, but b.txt now clashes with c.txt
+        assert_eq!(merged.proposals[0].status, RenameStatus::Ready);
+        assert!(merged.proposals[0].issues.is_empty());
+        assert_eq!(merged.proposals[1].proposed_name, "todo.txt");
+        assert_eq!(merged.proposals[1].status, RenameStatus::Conflict);
+        assert_eq!(merged.proposals[2].proposed_name, "todo.txt");
+        assert_eq!(merged.proposals[2].status, RenameStatus::Conflict);
+    }
+
+    #[tokio::test]
+    async fn test_merge_suggestions_keeps_blocking_issues() {
+        let mut missing = create_merge_proposal("a.txt", "{date}.txt");
+        missing.status = RenameStatus::MissingData;
+        missing.issues.push(crate::commands::rename::RenameIssue {
+            code: "MISSING_DATA".to_string(),
+            message: "No date".to_string(),
+            field: Some("date".to_string()),
+        });
+        let mut nested = create_merge_proposal("b.txt", "b.txt/inner.txt");
+        nested.status = RenameStatus::InvalidName;
+        nested.issues.push(crate::commands::rename::RenameIssue {
+            code: "NESTED_DESTINATION".to_string(),
+            message: "Destination is inside a file".to_string(),
+            field: None,
+        });
+        let unchanged = RenameProposal {
+            status: RenameStatus::NoChange,
+            action_type: FileActionType::NoChange,
+            ..create_merge_proposal("c.txt", "c.txt")
+        };
+        let preview = finalize_preview(
+            vec![missing, nested, unchanged],
+            String::new(),
+            ReorganizationMode::RenameOnly,
+        );
+        let results = vec![
+            create_name_result("/photos/a.txt", "meeting-notes", 0.9),
+            create_name_result("/photos/b.txt", "budget", 0.9),
+            create_name_result("/photos/c.txt", "todo", 0.9),
+        ];
+
+        let merged = merge_suggestions(preview, results, 0.5).await.unwrap();
+
+        assert_eq!(merged.proposals[0].status, RenameStatus::MissingData);
+        assert_eq!(merged.proposals[0].proposed_name, "{date}.txt");
+        assert_eq!(merged.proposals[0].issues[0].code, "MISSING_DATA");
+        assert_eq!(merged.proposals[1].status, RenameStatus::InvalidName);
+        assert_eq!(merged.proposals[1].issues[0].code, "NESTED_DESTINATION");
+        assert_eq!(merged.proposals[2].status, RenameStatus::Ready);
+        assert_eq!(merged.proposals[2].proposed_name, "todo.txt");
+    }
+
+    #[test]
+    fn test_sanitize_suggested_folder() {
+        assert_eq!(sanitize_suggested_folder("a\\b:c/./d").as_deref(), Some("a/b_c/d"));
+        assert_eq!(sanitize_suggested_folder("../.."), None);
+        assert_eq!(sanitize_suggested_folder("  "), None);
+    }
+
+    #[test]
+    fn test_folder_structure_cached_until_changed() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(dir.path().join("Projects/2024")).unwrap();
+        let base = dir.path().to_string_lossy().to_string();
+        let walks = std::sync::atomic::AtomicUsize::new(0);
+        let walk = |path: &str| {
+            walks.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            walk_folder_structure(path)
+        };
+
+        let first = cached_folder_structure(&base, walk);
+        let second = cached_folder_structure(&base, walk);
+
+        assert_eq!(first, vec!["Projects", "Projects/2024"]);
+        assert_eq!(second, first);
+        assert_eq!(walks.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        // A new second-level folder changes its parent's mtime; it is set
+        // explicitly since the clock may not tick between the two walks
+        std::fs::create_dir(dir.path().join("Projects/2025")).unwrap();
+        let projects = std::fs::File::open(dir.path().join("Projects")).unwrap();
+        let modified = projects.metadata().unwrap().modified().unwrap();
+        projects.set_modified(modified + Duration::from_secs(60)).unwrap();
+        let third = cached_folder_structure(&base, walk);
+
+        assert_eq!(third, vec!["Projects", "Projects/2024", "Projects/2025"]);
+        assert_eq!(walks.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_analysis_cache_preview_hit_miss_split() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let write = |name: &str, content: &str| {
+            let path = dir.path().join(name);
+            std::fs::write(&path, content).unwrap();
+            path.to_string_lossy().to_string()
+        };
+        let cached = write("cached.txt", "cache preview: already analyzed");
+        let fresh = write("fresh.txt", "cache preview: never analyzed");
+        let binary = write("blob.bin", "binary");
+        let config = OllamaConfig::default();
+
+        let key = analysis_cache_key(&cached, &config).unwrap();
+        let suggestion = AiSuggestion {
+            suggested_name: "already-analyzed".to_string(),
+            confidence: 0.9,
+            reasoning: "test".to_string(),
+            keywords: vec![],
+            keep_original: false,
+            suggested_folder: None,
+            folder_confidence: None,
+        };
+        cache_result(&key, &suggestion).await;
+
+        let preview = analysis_cache_preview(vec![cached, fresh, binary], config)
+            .await
+            .unwrap();
+
+        assert_eq!(preview.total, 3);
+        assert_eq!(preview.hits, 1);
+        assert_eq!(preview.misses, 1);
+        assert_eq!(preview.uncacheable, 1);
+    }
+
+    #[test]
+    fn test_consolidation_ties_pick_first_name() {
+        // "invoice" and "invoices" are similar and equally common
+        let mut results: Vec<_> = (0..3).map(|i| create_folder_result(i, "invoices")).collect();
+        results.extend((3..6).map(|i| create_folder_result(i, "invoice")));
+
+        consolidate_folder_suggestions(&mut results, &[], ConsolidationLimits::STANDARD);
+
+        assert!(results
+            .iter()
+            .all(|r| r.suggestion.as_ref().unwrap().suggested_folder.as_deref() == Some("invoice")));
+    }
+
+    #[test]
+    fn test_deterministic_consolidation_is_reproducible() {
+        let folders = ["invoices", "invoice", "receipts", "receipt", "travel", "travels"];
+        let batch = || (0..12).map(|i| create_folder_result(i, folders[i % 6]));
+        let existing = vec!["Travel".to_string(), "Receipts".to_string()];
+
+        let run = |mut results: Vec<FileAnalysisResult>, mut existing: Vec<String>| {
+            sort_for_reproducibility(&mut results, &mut existing);
+            let warnings =
+                consolidate_folder_suggestions(&mut results, &existing, ConsolidationLimits::STANDARD);
+            let folders: Vec<_> = results
+                .into_iter()
+                .map(|r| (r.file_path, r.suggestion.and_then(|s| s.suggested_folder)))
+                .collect();
+            let messages: Vec<_> = warnings.into_iter().map(|w| w.message).collect();
+            (folders, messages)
+        };
+
+        let first = run(batch().collect(), existing.clone());
+        let reversed_batch: Vec<_> = batch().rev().collect();
+        let reversed_existing: Vec<_> = existing.into_iter().rev().collect();
+        let second = run(reversed_batch, reversed_existing);
+
+        assert_eq!(first, second);
+    }
+
+    #[tokio::test]
+    async fn test_remap_cache_paths_moves_metadata_entry() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let old_path = dir.path().join("IMG_2041.jpg").to_string_lossy().to_string();
+        let new_path = dir.path().join("harbor-at-dusk.jpg").to_string_lossy().to_string();
+        std::fs::write(&old_path, b"remap cache test image").unwrap();
+
+        let old_key = compute_cache_key(&old_path, &CacheKeyStrategy::Metadata).unwrap();
+        let suggestion = AiSuggestion {
+            suggested_name: "harbor-at-dusk".to_string(),
+            confidence: 0.9,
+            reasoning: "test".to_string(),
+            keywords: vec![],
+            keep_original: false,
+            suggested_folder: None,
+            folder_confidence: None,
+        };
+        cache_result(&old_key, &suggestion).await;
+        std::fs::rename(&old_path, &new_path).unwrap();
+
+        let new_key = compute_cache_key(&new_path, &CacheKeyStrategy::Metadata).unwrap();
+        assert!(get_cached_result(&new_key).await.is_none());
+
+        let mappings = vec![CachePathMapping { old_path, new_path }];
+        assert_eq!(remap_cache_paths(mappings).await.unwrap(), 1);
+
+        let cached = get_cached_result(&new_key).await.unwrap();
+        assert_eq!(cached.suggested_name, "harbor-at-dusk");
+        assert!(get_cached_result(&old_key).await.is_none());
+    }
+
+    #[test]
+    fn test_is_metadata_key_of_requires_exact_path() {
+        assert!(is_metadata_key_of("/a/b.jpg:1f3a", "/a/b.jpg"));
+        assert!(!is_metadata_key_of("/a/b.jpg.bak:1f3a", "/a/b.jpg"));
+        assert!(!is_metadata_key_of("/a/b.jpg:", "/a/b.jpg"));
+        assert!(!is_metadata_key_of("/a/b.jpg:nothex", "/a/b.jpg"));
+    }
+
+    #[tokio::test]
+    async fn test_extra_text_extensions_become_analyzable() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let write = |name: &str, content: &str| {
+            let path = dir.path().join(name);
+            std::fs::write(&path, content).unwrap();
+            path.to_string_lossy().to_string()
+        };
+        // Blank content: a text file is skipped as empty without calling the AI
+        let prompt = write("IMG_1.prompt", "   ");
+        let binary = write("IMG_2.bin", "   ");
+        let client = Client::new();
+        let reason = |result: FileAnalysisResult| result.skip_reason;
+
+        let mut config = OllamaConfig::default();
+        assert!(!is_analyzable_text(&prompt, &config));
+        let result = analyze_single_file(&client, &prompt, &config, &[]).await;
+        assert_eq!(reason(result), Some(AnalysisSkipReason::Unsupported));
+
+        config.extra_text_extensions = vec!["prompt".to_string()];
+        assert!(is_analyzable_text(&prompt, &config));
+        assert!(is_analyzable_text("/notes/readme.md", &config));
+        let result = analyze_single_file(&client, &prompt, &config, &[]).await;
+        assert_eq!(reason(result), Some(AnalysisSkipReason::Empty));
+
+        assert!(!is_analyzable_text(&binary, &config));
+        let result = analyze_single_file(&client, &binary, &config, &[]).await;
+        assert_eq!(reason(result), Some(AnalysisSkipReason::Unsupported));
+    }
+
+    #[tokio::test]
+    async fn test_content_truncation_reported() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let large = dir.path().join("IMG_1.txt");
+        std::fs::write(&large, "quarterly report line\n".repeat(3000)).unwrap();
+        let small = dir.path().join("IMG_2.txt");
+        std::fs::write(&small, "short meeting notes").unwrap();
+        // Nothing listens on port 1: the request fails, the sampling is still reported
+        let config = OllamaConfig {
+            enabled: true,
+            base_url: "http://127.0.0.1:1".to_string(),
+            timeout: 1000,
+            ..Default::default()
+        };
+        let client = Client::new();
+
+        let result = analyze_single_file(&client, &large.to_string_lossy(), &config, &[]).await;
+        assert!(result.content_truncated);
+        let length = result.content_length.unwrap();
+        assert_eq!(length.original, 66000);
+        assert!(length.used < length.original / 4);
+
+        let result = analyze_single_file(&client, &small.to_string_lossy(), &config, &[]).await;
+        assert!(!result.content_truncated);
+        assert_eq!(result.content_length, Some(ContentLength { original: 19, used: 19 }));
+    }
+
+    /// Whether a permit future is ready on its first poll
+    async fn acquire_now(
+        batch: &Semaphore,
+        reserved: &Semaphore,
+        interactive: bool,
+    ) -> Option<bool> {
+        tokio::select! {
+            biased;
+            permit = acquire_analysis_permit(batch, reserved, interactive) => {
+                Some(permit.is_some())
+            }
+            _ = std::future::ready(()) => None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_interactive_permit_skips_saturated_batch_pool() {
+        let batch = Semaphore::new(2);
+        let reserved = Semaphore::new(1);
+        let _held = batch.acquire_many(2).await.unwrap();
+
+        // A batch request waits, an interactive one gets the reserved permit
+        assert_eq!(acquire_now(&batch, &reserved, false).await, None);
+        let interactive = acquire_analysis_permit(&batch, &reserved, true).await;
+        assert!(interactive.is_some());
+        assert_eq!(reserved.available_permits(), 0);
+
+        // A second interactive request only waits for the reserved permit
+        assert_eq!(acquire_now(&batch, &reserved, true).await, None);
+        drop(interactive);
+        assert_eq!(acquire_now(&batch, &reserved, true).await, Some(true));
+    }
+
+    #[tokio::test]
+    async fn test_batch_requests_never_use_reserved_permit() {
+        let batch = Semaphore::new(1);
+        let reserved = Semaphore::new(1);
+
+        let first = acquire_analysis_permit(&batch, &reserved, false).await;
+        assert!(first.is_some());
+        assert_eq!(acquire_now(&batch, &reserved, false).await, None);
+        assert_eq!(reserved.available_permits(), 1);
+    }
+
+    fn create_confidence_batch() -> BatchAnalysisResult {
+        let mut failed = create_folder_result(4, "unused");
+        failed.suggestion = None;
+        failed.error = Some("timeout".to_string());
+        let mut results: Vec<FileAnalysisResult> = [0.9, 0.6, 0.3]
+            .iter()
+            .enumerate()
+            .map(|(i, &confidence)| {
+                let mut result = create_folder_result(i + 1, "Docs");
+                result.suggestion.as_mut().unwrap().confidence = confidence;
+                result
+            })
+            .collect();
+        results.push(failed);
+
+        BatchAnalysisResult {
+            results,
+            total: 4,
+            analyzed: 3,
+            failed: 1,
+            skipped: 0,
+            llm_available: true,
+            warnings: vec![],
+            display_confidence_floor: 0.0,
+            cancelled: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_filter_results_by_confidence() {
+        let view = filter_results_by_confidence(create_confidence_batch(), 0.6).await.unwrap();
+        let paths: Vec<&str> = view.results.iter().map(|r| r.file_path.as_str()).collect();
+        assert_eq!(paths, vec!["/path/file1.pdf", "/path/file2.pdf"]);
+        assert_eq!(view.display_confidence_floor, 0.6);
+        // Counts still describe the whole batch
+        assert_eq!((view.total, view.analyzed, view.failed), (4, 3, 1));
+
+        let view = filter_results_by_confidence(create_confidence_batch(), 0.95).await.unwrap();
+        assert!(view.results.is_empty());
+
+        // A zero floor keeps every suggestion but not the failed file
+        let view = filter_results_by_confidence(create_confidence_batch(), 0.0).await.unwrap();
+        assert_eq!(view.results.len(), 3);
+
+        assert!(filter_results_by_confidence(create_confidence_batch(), 1.2).await.is_err());
+    }
 }
@@ -0,0 +1,525 @@
+// Folder merge commands for tidy-app GUI
+// Command names use snake_case per architecture requirements
+//
+// Moves every file of a source folder into a destination folder, keeping the
+// relative structure and resolving name collisions with a chosen strategy.
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::path::{Component, Path, PathBuf};
+use ts_rs::TS;
+use uuid::Uuid;
+use walkdir::WalkDir;
+
+use super::history::record_operation;
+use super::rename::{
+    BatchRenameResult, BatchRenameSummary, DirectoryCreationLog, FileRenameResult, RenameError,
+    RenameErrorKind, RenameOutcome,
+};
+use super::rename::{load_protected_paths, move_no_clobber, move_to_trash};
+use super::security::{find_protected_prefix, validate_scan_path};
+
+/// Maximum numeric suffix tried when resolving a collision
+const MAX_SUFFIX: usize = 10_000;
+
+// =============================================================================
+// Types
+// =============================================================================
+
+/// How to resolve a file that already exists in the destination
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "kebab-case")]
+pub enum ConflictStrategy {
+    /// Keep both: add " (1)", " (2)", ... to the incoming file (default)
+    #[default]
+    Suffix,
+    /// Leave the incoming file in the source folder
+    Skip,
+    /// Replace the existing destination file, moving it to the system trash
+    /// once the incoming file has arrived
+    OverwriteToTrash,
+}
+
+// =============================================================================
+// Merge
+// =============================================================================
+
+/// Find a free path by adding " (n)" before the extension
+fn suffixed_path(target: &Path) -> Option<PathBuf> {
+    let stem = target.file_stem()?.to_string_lossy().to_string();
+    let extension = target
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+
+    (1..=MAX_SUFFIX)
+        .map(|n| target.with_file_name(format!("{} ({}){}", stem, n, extension)))
+        .find(|candidate| !candidate.exists())
+}
+
+/// Resolve the destination folder, which may not exist yet
+fn resolve_destination(destination: &str) -> Result<PathBuf, RenameError> {
+    let path = Path::new(destination);
+    if destination.contains('\0') {
+        return Err(RenameError::ValidationFailed("Destination contains a null byte".to_string()));
+    }
+    if !path.is_absolute() || path.components().any(|c| matches!(c, Component::ParentDir)) {
+        return Err(RenameError::ValidationFailed(format!(
+            "Destination must be an absolute path without '..': {}",
+            destination
+        )));
+    }
+
+    if path.exists() {
+        return Ok(validate_scan_path(destination)?);
+    }
+
+    // Canonicalize the nearest existing ancestor so comparisons with the source hold
+    let existing = path
+        .ancestors()
+        .find(|p| p.exists())
+        .ok_or_else(|| RenameError::ValidationFailed(format!("Invalid destination: {}", destination)))?;
+    let missing = path.strip_prefix(existing).unwrap_or(path);
+    Ok(existing.canonicalize()?.join(missing))
+}
+
+/// Move one file into the destination, applying the conflict strategy
+fn merge_file(
+    file: &Path,
+    target: PathBuf,
+    strategy: ConflictStrategy,
+    directory_log: &DirectoryCreationLog,
+    trash_file: &impl Fn(&Path) -> std::io::Result<()>,
+) -> FileRenameResult {
+    let original_path = file.to_string_lossy().to_string();
+    let mut result = FileRenameResult {
+        proposal_id: original_path.clone(),
+        original_path: original_path.clone(),
+        original_name: file
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        new_path: None,
+        new_name: None,
+        outcome: RenameOutcome::Failed,
+        error: None,
+        error_kind: None,
+        symlink: None,
+    };
+
+    if let Some(parent) = target.parent() {
+        if let Err(e) = directory_log.ensure_dir(parent) {
+            result.error = Some(format!("Failed to create directory: {}", e));
+            result.error_kind = Some(RenameErrorKind::from_io_error(&e));
+            return result;
+        }
+    }
+
+    if !target.exists() {
+        return finish_move(result, file, target);
+    }
+
+    match strategy {
+        ConflictStrategy::Suffix => match suffixed_path(&target) {
+            Some(path) => finish_move(result, file, path),
+            None => {
+                result.error = Some("No free name found for the file".to_string());
+                result.error_kind = Some(RenameErrorKind::AlreadyExists);
+                result
+            }
+        },
+        ConflictStrategy::Skip => {
+            result.outcome = RenameOutcome::Skipped;
+            result.error = Some("A file with this name already exists in the destination".to_string());
+            result
+        }
+        ConflictStrategy::OverwriteToTrash => replace_file(result, file, target, trash_file),
+    }
+}
+
+/// Move `file` to `target` without replacing anything there and record the outcome
+fn finish_move(mut result: FileRenameResult, file: &Path, target: PathBuf) -> FileRenameResult {
+    match move_no_clobber(file, &target) {
+        Ok(_) => {
+            result.outcome = RenameOutcome::Success;
+            result.new_name = target.file_name().map(|n| n.to_string_lossy().to_string());
+            result.new_path = Some(target.to_string_lossy().to_string());
+        }
+        Err(e) => {
+            result.error = Some(e.to_string());
+            result.error_kind = Some(RenameErrorKind::from_io_error(&e));
+        }
+    }
+    result
+}
+
+/// Replace `target` with `file`, trashing the existing file only once `file` has arrived
+///
+/// The incoming file is first moved next to `target` under a hidden name. If
+/// that fails, or the existing file can't be trashed, nothing is replaced and
+/// the incoming file stays in (or goes back to) the source folder.
+fn replace_file(
+    mut result: FileRenameResult,
+    file: &Path,
+    target: PathBuf,
+    trash_file: &impl Fn(&Path) -> std::io::Result<()>,
+) -> FileRenameResult {
+    let name = target
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let incoming = target.with_file_name(format!(".{}.tidy-merge-{}", name, Uuid::new_v4()));
+
+    if let Err(e) = move_no_clobber(file, &incoming) {
+        result.error = Some(e.to_string());
+        result.error_kind = Some(RenameErrorKind::from_io_error(&e));
+        return result;
+    }
+
+    if let Err(e) = trash_file(&target) {
+        result.error = Some(format!("Failed to move existing file to trash: {}", e));
+        if let Err(e) = move_no_clobber(&incoming, file) {
+            result.error = Some(format!(
+                "{}; the incoming file could not be moved back and is at {} ({})",
+                result.error.unwrap_or_default(),
+                incoming.display(),
+                e
+            ));
+            result.new_path = Some(incoming.to_string_lossy().to_string());
+        }
+        return result;
+    }
+
+    // The existing file is in the trash now, so a failure here must say where both files are
+    if let Err(e) = move_no_clobber(&incoming, &target) {
+        result.error = Some(format!(
+            "Existing file {} was moved to the trash, but the incoming file could not take its \
+             place and is at {} ({})",
+            target.display(),
+            incoming.display(),
+            e
+        ));
+        result.error_kind = Some(RenameErrorKind::from_io_error(&e));
+        result.new_path = Some(incoming.to_string_lossy().to_string());
+        return result;
+    }
+
+    result.outcome = RenameOutcome::Success;
+    result.new_name = Some(name);
+    result.new_path = Some(target.to_string_lossy().to_string());
+    result
+}
+
+/// Move all files from `source` into `destination`, keeping subfolders
+///
+/// Both folders are compared in canonical form, so neither may fall under a
+/// protected path whichever way it was spelled. Files are never moved over an
+/// existing file, and are copied and verified when the folders are on
+/// different volumes. Existing files replaced under `overwrite-to-trash` are
+/// removed with `trash_file` only after the incoming file has been moved next
+/// to them, and are listed in `trashed_files` (a failed result says where the
+/// incoming file was left if it could not take the trashed file's place).
+fn merge_folders_internal(
+    source: &str,
+    destination: &str,
+    strategy: ConflictStrategy,
+    protected_paths: &[String],
+    trash_file: impl Fn(&Path) -> std::io::Result<()>,
+) -> Result<BatchRenameResult, RenameError> {
+    let started_at = Utc::now();
+    let source = validate_scan_path(source)?;
+    let destination = resolve_destination(destination)?;
+
+    for folder in [&source, &destination] {
+        let folder = folder.to_string_lossy();
+        if let Some(prefix) = find_protected_prefix(&folder, protected_paths) {
+            return Err(RenameError::ValidationFailed(format!(
+                "Protected path: {} is under {}",
+                folder, prefix
+            )));
+        }
+    }
+
+    if destination.starts_with(&source) {
+        return Err(RenameError::ValidationFailed(
+            "Destination can't be the source folder or inside it".to_string(),
+        ));
+    }
+
+    // Collect first so files moved into the destination are never walked again
+    let files: Vec<PathBuf> = WalkDir::new(&source)
+        .follow_links(false)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| entry.into_path())
+        .collect();
+
+    let directory_log = DirectoryCreationLog::default();
+    directory_log.ensure_dir(&destination)?;
+
+    // Replaced files are listed in the result so they can be found in the trash
+    let trashed = RefCell::new(Vec::new());
+    let trash_file = |path: &Path| {
+        trash_file(path)?;
+        trashed.borrow_mut().push(path.to_string_lossy().to_string());
+        Ok(())
+    };
+
+    let results: Vec<FileRenameResult> = files
+        .iter()
+        .map(|file| {
+            let relative = file.strip_prefix(&source).unwrap_or(file);
+            merge_file(
+                file,
+                destination.join(relative),
+                strategy,
+                &directory_log,
+                &trash_file,
+            )
+        })
+        .collect();
+
+    let count = |outcome: RenameOutcome| results.iter().filter(|r| r.outcome == outcome).count();
+    let summary = BatchRenameSummary {
+        total: results.len(),
+        succeeded: count(RenameOutcome::Success),
+        failed: count(RenameOutcome::Failed),
+        skipped: count(RenameOutcome::Skipped),
+    };
+
+    let completed_at = Utc::now();
+    Ok(BatchRenameResult {
+        success: summary.failed == 0,
+        results,
+        summary,
+        started_at,
+        completed_at,
+        duration_ms: (completed_at - started_at).num_milliseconds().max(0) as u64,
+        cancelled: false,
+        directories_created: directory_log.into_created(),
+        stopped_on_permission_error: false,
+        trashed_files: trashed.into_inner(),
+        history_entry_id: None,
+    })
+}
+
+// =============================================================================
+// Tauri Commands
+// =============================================================================
+
+/// Merge the files of one folder into another
+///
+/// Subfolders of the source are recreated in the destination, which is created
+/// if needed. Name collisions are resolved with `on_conflict`. The operation is
+/// recorded in history so it can be undone (files sent to the trash by
+/// `overwrite-to-trash` must be restored from the trash). Folders under a
+/// protected path are refused.
+///
+/// Command name: merge_folders (snake_case per architecture)
+#[tauri::command]
+pub async fn merge_folders(
+    source: String,
+    destination: String,
+    on_conflict: Option<ConflictStrategy>,
+) -> Result<BatchRenameResult, RenameError> {
+    let protected_paths = load_protected_paths().await?;
+    let result = merge_folders_internal(
+        &source,
+        &destination,
+        on_conflict.unwrap_or_default(),
+        &protected_paths,
+        move_to_trash,
+    )?;
+
+    // The files have already moved, so a history failure must not fail the merge
+    if let Err(e) = record_operation(result.clone(), None).await {
+        eprintln!("Warning: Failed to record folder merge in history: {}", e);
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    /// Source with a.txt, b.txt and sub/c.txt; destination with a colliding a.txt
+    fn create_folders() -> (TempDir, PathBuf, PathBuf) {
+        let dir = TempDir::new().unwrap();
+        let source = dir.path().join("source");
+        let destination = dir.path().join("destination");
+        fs::create_dir_all(source.join("sub")).unwrap();
+        fs::create_dir_all(&destination).unwrap();
+        fs::write(source.join("a.txt"), "incoming").unwrap();
+        fs::write(source.join("b.txt"), "b").unwrap();
+        fs::write(source.join("sub").join("c.txt"), "c").unwrap();
+        fs::write(destination.join("a.txt"), "existing").unwrap();
+        (dir, source, destination)
+    }
+
+    fn merge(source: &Path, destination: &Path, strategy: ConflictStrategy) -> BatchRenameResult {
+        merge_folders_internal(
+            &source.to_string_lossy(),
+            &destination.to_string_lossy(),
+            strategy,
+            &[],
+            |_| panic!("unexpected trash"),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_merge_folders_suffix() {
+        let (_dir, source, destination) = create_folders();
+
+        let result = merge(&source, &destination, ConflictStrategy::Suffix);
+
+        assert_eq!(result.summary.succeeded, 3);
+        assert_eq!(fs::read_to_string(destination.join("a.txt")).unwrap(), "existing");
+        assert_eq!(fs::read_to_string(destination.join("a (1).txt")).unwrap(), "incoming");
+        assert!(destination.join("sub").join("c.txt").exists());
+        assert!(!source.join("a.txt").exists());
+        let sub = destination.canonicalize().unwrap().join("sub");
+        assert_eq!(result.directories_created, vec![sub.to_string_lossy().to_string()]);
+    }
+
+    #[test]
+    fn test_merge_folders_skip() {
+        let (_dir, source, destination) = create_folders();
+
+        let result = merge(&source, &destination, ConflictStrategy::Skip);
+
+        assert_eq!(result.summary.succeeded, 2);
+        assert_eq!(result.summary.skipped, 1);
+        assert_eq!(fs::read_to_string(destination.join("a.txt")).unwrap(), "existing");
+        assert_eq!(fs::read_to_string(source.join("a.txt")).unwrap(), "incoming");
+    }
+
+    #[test]
+    fn test_merge_folders_overwrite_to_trash() {
+        let (_dir, source, destination) = create_folders();
+
+        let trashed = std::sync::Mutex::new(Vec::new());
+
+        let result = merge_folders_internal(
+            &source.to_string_lossy(),
+            &destination.to_string_lossy(),
+            ConflictStrategy::OverwriteToTrash,
+            &[],
+            |path| {
+                // The incoming file has already left the source when the old one goes
+                assert!(!source.join("a.txt").exists());
+                trashed.lock().unwrap().push(fs::read_to_string(path)?);
+                fs::remove_file(path)
+            },
+        )
+        .unwrap();
+
+        let incoming = result.results.iter().find(|r| r.original_name == "a.txt").unwrap();
+        assert_eq!(incoming.outcome, RenameOutcome::Success);
+        assert_eq!(result.summary.succeeded, 3);
+        assert_eq!(*trashed.lock().unwrap(), vec!["existing".to_string()]);
+        let replaced = destination.canonicalize().unwrap().join("a.txt");
+        assert_eq!(result.trashed_files, vec![replaced.to_string_lossy().to_string()]);
+        assert_eq!(fs::read_to_string(destination.join("a.txt")).unwrap(), "incoming");
+    }
+
+    #[test]
+    fn test_merge_folders_overwrite_keeps_existing_when_trash_fails() {
+        let (_dir, source, destination) = create_folders();
+
+        let result = merge_folders_internal(
+            &source.to_string_lossy(),
+            &destination.to_string_lossy(),
+            ConflictStrategy::OverwriteToTrash,
+            &[],
+            |_| Err(std::io::Error::other("trash unavailable")),
+        )
+        .unwrap();
+
+        let incoming = result.results.iter().find(|r| r.original_name == "a.txt").unwrap();
+        assert_eq!(incoming.outcome, RenameOutcome::Failed);
+        assert!(incoming.error.as_deref().unwrap().contains("trash"));
+        assert_eq!(fs::read_to_string(destination.join("a.txt")).unwrap(), "existing");
+        assert_eq!(fs::read_to_string(source.join("a.txt")).unwrap(), "incoming");
+        let mut names: Vec<String> = fs::read_dir(&destination)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["a.txt", "b.txt", "sub"]);
+    }
+
+    #[test]
+    fn test_merge_folders_creates_destination() {
+        let (dir, source, _) = create_folders();
+        let destination = dir.path().join("new").join("merged");
+
+        let result = merge(&source, &destination, ConflictStrategy::Suffix);
+
+        assert_eq!(result.summary.succeeded, 3);
+        assert!(destination.join("a.txt").exists());
+        assert_eq!(result.directories_created.len(), 3);
+    }
+
+    #[test]
+    fn test_merge_folders_rejects_destination_inside_source() {
+        let (_dir, source, _) = create_folders();
+
+        let result = merge_folders_internal(
+            &source.to_string_lossy(),
+            &source.join("sub").to_string_lossy(),
+            ConflictStrategy::Suffix,
+            &[],
+            move_to_trash,
+        );
+
+        assert!(matches!(result, Err(RenameError::ValidationFailed(_))));
+    }
+
+    #[test]
+    fn test_merge_folders_rejects_parent_dir_components() {
+        let (_dir, source, destination) = create_folders();
+        let sneaky = destination.join("..").join("source").join("sub");
+
+        for (from, to) in [(&sneaky, &destination), (&source, &sneaky)] {
+            let result = merge_folders_internal(
+                &from.to_string_lossy(),
+                &to.to_string_lossy(),
+                ConflictStrategy::Suffix,
+                &[],
+                move_to_trash,
+            );
+            assert!(result.is_err(), "{} -> {}", from.display(), to.display());
+        }
+        assert!(source.join("a.txt").exists());
+    }
+
+    #[test]
+    fn test_merge_folders_rejects_protected_paths() {
+        let (_dir, source, destination) = create_folders();
+        let protected =
+            |path: &Path| vec![path.canonicalize().unwrap().to_string_lossy().to_string()];
+
+        for protected_paths in [protected(&source), protected(&destination)] {
+            let result = merge_folders_internal(
+                &source.to_string_lossy(),
+                &destination.to_string_lossy(),
+                ConflictStrategy::Suffix,
+                &protected_paths,
+                |_| panic!("unexpected trash"),
+            );
+            assert!(matches!(
+                &result,
+                Err(RenameError::ValidationFailed(msg)) if msg.contains("Protected path")
+            ));
+        }
+        assert!(source.join("b.txt").exists());
+        assert!(!destination.join("b.txt").exists());
+    }
+}
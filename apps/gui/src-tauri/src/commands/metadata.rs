@@ -0,0 +1,636 @@
+// Per-file metadata extraction for tidy-app GUI
+//
+// Reads the key fields shown as badges in the file list: EXIF date and camera
+// for images, title/author/date from the PDF Info dictionary and ID3 tags for
+// audio. Parsers are minimal and only look at the parts of the file they need.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+
+use flate2::read::ZlibDecoder;
+
+use super::exif::read_exif_summary;
+use super::scanner::FileCategory;
+
+/// Maximum number of bytes read from the start of a PDF (1MB)
+const MAX_PDF_HEAD_BYTES: u64 = 1024 * 1024;
+
+/// Number of bytes read from the end of a PDF, where updated Info dictionaries live (64KB)
+const MAX_PDF_TAIL_BYTES: u64 = 64 * 1024;
+
+/// Maximum number of bytes of a PDF scanned for page text (16MB)
+const MAX_PDF_TEXT_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Maximum size of a decompressed PDF content stream (4MB, guards against
+/// compression bombs)
+const MAX_INFLATED_STREAM_BYTES: u64 = 4 * 1024 * 1024;
+
+/// Maximum ID3v2 tag size read (1MB, enough for text frames before embedded art)
+const MAX_ID3_TAG_BYTES: usize = 1024 * 1024;
+
+// =============================================================================
+// Public API
+// =============================================================================
+
+/// Extract key metadata fields for a file based on its type
+///
+/// Keys are camelCase ("captureDate", "cameraModel", "title", "artist", ...).
+/// Dates are formatted as YYYY-MM-DD. Returns None when nothing was found.
+pub fn extract_file_metadata(
+    path: &str,
+    extension: &str,
+    category: &FileCategory,
+) -> Option<HashMap<String, String>> {
+    let mut fields: HashMap<String, String> = HashMap::new();
+
+    match category {
+        FileCategory::Image => {
+            if let Some(exif) = read_exif_summary(path) {
+                if let Some(date) = exif.capture_date {
+                    fields.insert("captureDate".to_string(), date.format("%Y-%m-%d").to_string());
+                }
+                if let Some(make) = exif.camera_make {
+                    fields.insert("cameraMake".to_string(), make);
+                }
+                if let Some(model) = exif.camera_model {
+                    fields.insert("cameraModel".to_string(), model);
+                }
+            }
+        }
+        FileCategory::Document if extension.eq_ignore_ascii_case("pdf") => {
+            fields = read_pdf_info(path);
+        }
+        FileCategory::Audio => {
+            fields = read_id3_tags(path);
+        }
+        _ => {}
+    }
+
+    (!fields.is_empty()).then_some(fields)
+}
+
+// =============================================================================
+// PDF Info Dictionary
+// =============================================================================
+
+/// Read Title, Author and CreationDate from a PDF's Info dictionary
+///
+/// Only uncompressed Info dictionaries are found (the common case); values in
+/// compressed object streams are ignored.
+fn read_pdf_info(path: &str) -> HashMap<String, String> {
+    let mut fields = HashMap::new();
+    let Some(data) = read_pdf_bytes(path) else {
+        return fields;
+    };
+    if !data.starts_with(b"%PDF-") {
+        return fields;
+    }
+
+    for (key, name) in [("title", "/Title"), ("author", "/Author")] {
+        if let Some(value) = find_pdf_string(&data, name.as_bytes()) {
+            fields.insert(key.to_string(), value);
+        }
+    }
+    if let Some(date) = find_pdf_string(&data, b"/CreationDate").and_then(|d| parse_pdf_date(&d)) {
+        fields.insert("creationDate".to_string(), date);
+    }
+
+    fields
+}
+
+/// Extract the text drawn by a PDF's content streams
+///
+/// Collects the literal strings shown by text operators, one line per text
+/// object (BT ... ET). Streams are read as is or inflated (FlateDecode, used
+/// by most writers); other filters, and fonts, images and other non-content
+/// streams, are skipped. Scanned PDFs have no text to find. Returns None for
+/// files that aren't PDFs.
+pub fn extract_pdf_text(path: &str, max_chars: usize) -> Option<String> {
+    let mut data = Vec::new();
+    File::open(path).ok()?.take(MAX_PDF_TEXT_BYTES).read_to_end(&mut data).ok()?;
+    if !data.starts_with(b"%PDF-") {
+        return None;
+    }
+
+    let mut text = String::new();
+    let mut pos = 0;
+    while let Some(offset) = find_bytes(&data[pos..], b"stream") {
+        let keyword = pos + offset;
+        let start = keyword + b"stream".len();
+        let end = find_bytes(&data[start..], b"endstream").map_or(data.len(), |e| start + e);
+        pos = (end + b"endstream".len()).min(data.len());
+
+        // The stream dictionary sits between "N 0 obj" and the keyword
+        let object_start = data[..keyword]
+            .windows(3)
+            .rposition(|w| w == b"obj")
+            .unwrap_or(0);
+        let dictionary = &data[object_start..keyword];
+        if let Some(content) = decode_content_stream(dictionary, &data[start..end]) {
+            append_content_text(&content, &mut text);
+        }
+        if text.chars().count() >= max_chars {
+            break;
+        }
+    }
+
+    Some(text.trim().chars().take(max_chars).collect())
+}
+
+/// Bytes of a page content stream, inflated if needed
+///
+/// Returns None for streams that aren't page content (fonts, images, object
+/// streams, metadata...), use a filter other than a plain FlateDecode, or
+/// fail to inflate.
+fn decode_content_stream<'a>(dictionary: &[u8], stream: &'a [u8]) -> Option<Cow<'a, [u8]>> {
+    const NON_CONTENT_KEYS: [&[u8]; 5] =
+        [b"/Type", b"/Subtype", b"/Length1", b"/N ", b"/DecodeParms"];
+    if NON_CONTENT_KEYS.iter().any(|key| find_bytes(dictionary, key).is_some()) {
+        return None;
+    }
+    let Some(filter) = find_bytes(dictionary, b"/Filter") else {
+        return Some(Cow::Borrowed(stream));
+    };
+
+    // Only "/Filter /FlateDecode" or "/Filter [/FlateDecode]"
+    let rest = dictionary[filter + b"/Filter".len()..].trim_ascii_start();
+    let is_delimiter = |b: &u8| b.is_ascii_whitespace() || b"/<>[]".contains(b);
+    let names: Vec<&[u8]> = match rest.strip_prefix(b"[") {
+        Some(array) => array[..array.iter().position(|&b| b == b']')?]
+            .split(is_delimiter)
+            .filter(|name| !name.is_empty())
+            .collect(),
+        None => rest.strip_prefix(b"/")?.split(is_delimiter).take(1).collect(),
+    };
+    if names != [b"FlateDecode".as_slice()] && names != [b"Fl".as_slice()] {
+        return None;
+    }
+
+    // The data starts after the end of line following the keyword
+    let stream = stream.strip_prefix(b"\r").unwrap_or(stream);
+    let stream = stream.strip_prefix(b"\n").unwrap_or(stream);
+    let mut inflated = Vec::new();
+    ZlibDecoder::new(stream)
+        .take(MAX_INFLATED_STREAM_BYTES)
+        .read_to_end(&mut inflated)
+        .ok()?;
+    Some(Cow::Owned(inflated))
+}
+
+/// Append the strings shown in a content stream to `text`
+///
+/// Strings of a TJ array are joined as is; separate strings are separated by
+/// a space, and each text object ends a line.
+fn append_content_text(stream: &[u8], text: &mut String) {
+    let mut in_array = false;
+    // Whether the next string continues the previous one (same TJ array)
+    let mut joined = false;
+    let mut i = 0;
+    while i < stream.len() {
+        match stream[i] {
+            b'(' => {
+                let Some((value, len)) = parse_pdf_literal_at(&stream[i + 1..]) else {
+                    return;
+                };
+                if !joined && !text.is_empty() && !text.ends_with(char::is_whitespace) {
+                    text.push(' ');
+                }
+                text.push_str(&decode_pdf_text(&value));
+                joined = in_array;
+                i += len + 1;
+                continue;
+            }
+            b'[' => in_array = true,
+            b']' => {
+                in_array = false;
+                joined = false;
+            }
+            // "ET" operator closing a text object
+            b'E' if stream.get(i + 1) == Some(&b'T')
+                && (i == 0 || stream[i - 1].is_ascii_whitespace())
+                && stream.get(i + 2).is_none_or(|b| b.is_ascii_whitespace())
+                && !text.is_empty()
+                && !text.ends_with('\n') =>
+            {
+                text.push('\n');
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+}
+
+/// Read the head of a PDF plus its tail for larger files
+fn read_pdf_bytes(path: &str) -> Option<Vec<u8>> {
+    let mut file = File::open(path).ok()?;
+    let len = file.metadata().ok()?.len();
+
+    let mut data = Vec::new();
+    (&mut file).take(MAX_PDF_HEAD_BYTES).read_to_end(&mut data).ok()?;
+
+    if len > MAX_PDF_HEAD_BYTES {
+        let tail_start = len.saturating_sub(MAX_PDF_TAIL_BYTES).max(MAX_PDF_HEAD_BYTES);
+        file.seek(SeekFrom::Start(tail_start)).ok()?;
+        file.read_to_end(&mut data).ok()?;
+    }
+
+    Some(data)
+}
+
+/// Find the last value of a string entry (e.g. "/Title (...)") in PDF data
+///
+/// The last occurrence wins, matching incremental updates appended to the file.
+fn find_pdf_string(data: &[u8], name: &[u8]) -> Option<String> {
+    let mut found = None;
+    let mut pos = 0;
+
+    while let Some(offset) = find_bytes(&data[pos..], name) {
+        let start = pos + offset + name.len();
+        pos = start;
+
+        let rest = &data[start..];
+        let Some(value_start) = rest.iter().position(|b| !b.is_ascii_whitespace()) else {
+            break;
+        };
+        let value = match rest[value_start] {
+            b'(' => parse_pdf_literal(&rest[value_start + 1..]),
+            b'<' => parse_pdf_hex(&rest[value_start + 1..]),
+            _ => None,
+        };
+        if let Some(value) = value.map(|v| decode_pdf_text(&v)).filter(|v| !v.trim().is_empty()) {
+            found = Some(value.trim().to_string());
+        }
+    }
+
+    found
+}
+
+/// Parse a PDF literal string body (after the opening parenthesis)
+fn parse_pdf_literal(data: &[u8]) -> Option<Vec<u8>> {
+    parse_pdf_literal_at(data).map(|(value, _)| value)
+}
+
+/// Parse a PDF literal string body, also returning the bytes consumed
+/// (including the closing parenthesis)
+fn parse_pdf_literal_at(data: &[u8]) -> Option<(Vec<u8>, usize)> {
+    let mut value = Vec::new();
+    let mut depth = 0;
+    let mut bytes = data.iter().enumerate();
+
+    while let Some((index, &b)) = bytes.next() {
+        match b {
+            b'\\' => match bytes.next()?.1 {
+                b'n' => value.push(b'\n'),
+                b'r' => value.push(b'\r'),
+                b't' => value.push(b'\t'),
+                &other => value.push(other),
+            },
+            b'(' => {
+                depth += 1;
+                value.push(b);
+            }
+            b')' if depth == 0 => return Some((value, index + 1)),
+            b')' => {
+                depth -= 1;
+                value.push(b);
+            }
+            _ => value.push(b),
+        }
+    }
+
+    None
+}
+
+/// Parse a PDF hex string body (after the opening angle bracket)
+fn parse_pdf_hex(data: &[u8]) -> Option<Vec<u8>> {
+    let end = data.iter().position(|&b| b == b'>')?;
+    let digits: Vec<u8> = data[..end]
+        .iter()
+        .filter(|b| b.is_ascii_hexdigit())
+        .copied()
+        .collect();
+
+    digits
+        .chunks(2)
+        .map(|pair| {
+            let hex = std::str::from_utf8(pair).ok()?;
+            // A trailing odd digit is padded with 0 per the PDF spec
+            u8::from_str_radix(&format!("{:0<2}", hex), 16).ok()
+        })
+        .collect()
+}
+
+/// Decode PDF text: UTF-16BE with BOM, otherwise Latin-1 (close to PDFDocEncoding)
+fn decode_pdf_text(bytes: &[u8]) -> String {
+    match bytes.strip_prefix(&[0xFE, 0xFF]) {
+        Some(utf16) => {
+            let units: Vec<u16> = utf16
+                .chunks_exact(2)
+                .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+                .collect();
+            String::from_utf16_lossy(&units)
+        }
+        None => bytes.iter().map(|&b| b as char).collect(),
+    }
+}
+
+/// Format a PDF date ("D:20240601120000+02'00'") as YYYY-MM-DD
+fn parse_pdf_date(value: &str) -> Option<String> {
+    let digits = value.strip_prefix("D:").unwrap_or(value);
+    let date = chrono::NaiveDate::parse_from_str(digits.get(..8)?, "%Y%m%d").ok()?;
+    Some(date.format("%Y-%m-%d").to_string())
+}
+
+fn find_bytes(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+// =============================================================================
+// ID3 Tags
+// =============================================================================
+
+/// Read title, artist, album and year from ID3v2 (2.3/2.4) or ID3v1 tags
+fn read_id3_tags(path: &str) -> HashMap<String, String> {
+    let Ok(mut file) = File::open(path) else {
+        return HashMap::new();
+    };
+
+    let fields = read_id3v2(&mut file).unwrap_or_default();
+    if !fields.is_empty() {
+        return fields;
+    }
+    read_id3v1(&mut file).unwrap_or_default()
+}
+
+fn read_id3v2(file: &mut File) -> Option<HashMap<String, String>> {
+    let mut header = [0u8; 10];
+    file.read_exact(&mut header).ok()?;
+    if &header[..3] != b"ID3" {
+        return None;
+    }
+    let version = header[3];
+    if version != 3 && version != 4 {
+        return None;
+    }
+
+    let size = (syncsafe(&header[6..10]) as usize).min(MAX_ID3_TAG_BYTES);
+    let mut tag = vec![0u8; size];
+    file.read_exact(&mut tag).ok()?;
+
+    let mut fields = HashMap::new();
+    let mut pos = 0;
+    while pos + 10 <= tag.len() {
+        let id = &tag[pos..pos + 4];
+        if id[0] == 0 {
+            break; // Padding
+        }
+        let frame_size = if version == 4 {
+            syncsafe(&tag[pos + 4..pos + 8])
+        } else {
+            u32::from_be_bytes([tag[pos + 4], tag[pos + 5], tag[pos + 6], tag[pos + 7]])
+        } as usize;
+        let body = tag.get(pos + 10..pos + 10 + frame_size)?;
+        pos += 10 + frame_size;
+
+        let key = match id {
+            b"TIT2" => "title",
+            b"TPE1" => "artist",
+            b"TALB" => "album",
+            b"TYER" | b"TDRC" => "year",
+            _ => continue,
+        };
+        if let Some(mut text) = decode_id3_text(body) {
+            if key == "year" {
+                text = text.chars().take(4).collect();
+            }
+            fields.insert(key.to_string(), text);
+        }
+    }
+
+    Some(fields)
+}
+
+fn read_id3v1(file: &mut File) -> Option<HashMap<String, String>> {
+    file.seek(SeekFrom::End(-128)).ok()?;
+    let mut tag = [0u8; 128];
+    file.read_exact(&mut tag).ok()?;
+    if &tag[..3] != b"TAG" {
+        return None;
+    }
+
+    let text = |range: std::ops::Range<usize>| {
+        let value: String = tag[range]
+            .iter()
+            .take_while(|&&b| b != 0)
+            .map(|&b| b as char)
+            .collect();
+        let value = value.trim().to_string();
+        (!value.is_empty()).then_some(value)
+    };
+
+    let mut fields = HashMap::new();
+    for (key, range) in [("title", 3..33), ("artist", 33..63), ("album", 63..93), ("year", 93..97)] {
+        if let Some(value) = text(range) {
+            fields.insert(key.to_string(), value);
+        }
+    }
+    Some(fields)
+}
+
+/// Decode a 28-bit syncsafe integer (7 bits per byte)
+fn syncsafe(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0, |acc, &b| (acc << 7) | u32::from(b & 0x7F))
+}
+
+/// Decode an ID3v2 text frame body (encoding byte followed by text)
+fn decode_id3_text(body: &[u8]) -> Option<String> {
+    let (&encoding, text) = body.split_first()?;
+    let decoded = match encoding {
+        // ISO-8859-1
+        0 => text.iter().map(|&b| b as char).collect(),
+        // UTF-16 with BOM, UTF-16BE without BOM
+        1 | 2 => {
+            let (little_endian, text) = match text {
+                [0xFF, 0xFE, rest @ ..] => (true, rest),
+                [0xFE, 0xFF, rest @ ..] => (false, rest),
+                _ => (false, text),
+            };
+            let units: Vec<u16> = text
+                .chunks_exact(2)
+                .map(|pair| {
+                    if little_endian {
+                        u16::from_le_bytes([pair[0], pair[1]])
+                    } else {
+                        u16::from_be_bytes([pair[0], pair[1]])
+                    }
+                })
+                .collect();
+            String::from_utf16_lossy(&units)
+        }
+        // UTF-8
+        3 => String::from_utf8_lossy(text).to_string(),
+        _ => return None,
+    };
+
+    let value = decoded.trim_end_matches('\0').trim().to_string();
+    (!value.is_empty()).then_some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::exif::jpeg_with_exif;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn id3v2_frame(id: &[u8; 4], encoding: u8, text: &[u8]) -> Vec<u8> {
+        let mut frame = id.to_vec();
+        frame.extend_from_slice(&(text.len() as u32 + 1).to_be_bytes());
+        frame.extend_from_slice(&[0, 0, encoding]);
+        frame.extend_from_slice(text);
+        frame
+    }
+
+    #[test]
+    fn test_extract_image_metadata() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("photo.jpg");
+        let jpeg = jpeg_with_exif(
+            &[(0x010F, "FUJIFILM"), (0x0110, "X-T4")],
+            &[(0x9003, "2024:06:01 10:00:00")],
+        );
+        fs::write(&path, jpeg).unwrap();
+
+        let fields = extract_file_metadata(path.to_str().unwrap(), "jpg", &FileCategory::Image).unwrap();
+
+        assert_eq!(fields["captureDate"], "2024-06-01");
+        assert_eq!(fields["cameraMake"], "FUJIFILM");
+        assert_eq!(fields["cameraModel"], "X-T4");
+    }
+
+    #[test]
+    fn test_extract_pdf_metadata() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("report.pdf");
+        let pdf = b"%PDF-1.4\n1 0 obj\n<< /Title (Quarterly \\(Q2\\) Report) /Author <FEFF0041006E006E0061> /CreationDate (D:20240601120000+02'00') >>\nendobj\n%%EOF\n";
+        fs::write(&path, pdf).unwrap();
+
+        let fields = extract_file_metadata(path.to_str().unwrap(), "pdf", &FileCategory::Document).unwrap();
+
+        assert_eq!(fields["title"], "Quarterly (Q2) Report");
+        assert_eq!(fields["author"], "Anna");
+        assert_eq!(fields["creationDate"], "2024-06-01");
+    }
+
+    #[test]
+    fn test_extract_pdf_text_skips_streams_it_cannot_decode() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("invoice.pdf");
+        let pdf = b"%PDF-1.4\n4 0 obj\n<< /Length 60 >>\nstream\nBT /F1 12 Tf (Invoice) Tj [(ACME )-250(Corp)] TJ ET\nBT (Total: 42) Tj ET\nendstream\nendobj\n5 0 obj\n<< /Length 9 /Filter /FlateDecode >>\nstream\n(garbage) Tj\nendstream\nendobj\n%%EOF\n";
+        fs::write(&path, pdf).unwrap();
+
+        let text = extract_pdf_text(path.to_str().unwrap(), 1000).unwrap();
+
+        assert_eq!(text, "Invoice ACME Corp\nTotal: 42");
+        assert_eq!(extract_pdf_text(path.to_str().unwrap(), 7).unwrap(), "Invoice");
+
+        let not_pdf = dir.path().join("fake.pdf");
+        fs::write(&not_pdf, b"hello").unwrap();
+        assert!(extract_pdf_text(not_pdf.to_str().unwrap(), 1000).is_none());
+    }
+
+    /// Stream object with dictionary entries `dict` and zlib-compressed `content`
+    fn flate_stream_object(id: u32, dict: &str, content: &[u8]) -> Vec<u8> {
+        use std::io::Write;
+        let mut encoder =
+            flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(content).unwrap();
+        let compressed = encoder.finish().unwrap();
+        let mut object =
+            format!("{} 0 obj\n<< /Length {} {} >>\nstream\r\n", id, compressed.len(), dict)
+                .into_bytes();
+        object.extend(compressed);
+        object.extend(b"\nendstream\nendobj\n");
+        object
+    }
+
+    #[test]
+    fn test_extract_pdf_text_inflates_compressed_content_streams() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("report.pdf");
+        let mut pdf = b"%PDF-1.7\n".to_vec();
+        pdf.extend(flate_stream_object(
+            4,
+            "/Filter /FlateDecode",
+            b"BT /F1 12 Tf (Quarterly) Tj [(Re)10(port)] TJ ET",
+        ));
+        pdf.extend(flate_stream_object(5, "/Filter [/FlateDecode]", b"BT (Page 2) Tj ET"));
+        // Fonts and unsupported filter chains are not page content
+        pdf.extend(flate_stream_object(6, "/Filter /FlateDecode /Length1 20", b"(glyphs) Tj"));
+        pdf.extend(flate_stream_object(7, "/Filter [/ASCII85Decode /FlateDecode]", b"(x) Tj"));
+        pdf.extend(b"%%EOF\n");
+        fs::write(&path, pdf).unwrap();
+
+        let text = extract_pdf_text(path.to_str().unwrap(), 1000).unwrap();
+
+        assert_eq!(text, "Quarterly Report\nPage 2");
+    }
+
+    #[test]
+    fn test_extract_id3v2_metadata() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("song.mp3");
+        let mut frames = id3v2_frame(b"TIT2", 3, "Café del Mar".as_bytes());
+        frames.extend(id3v2_frame(b"TPE1", 1, &[0xFF, 0xFE, b'A', 0, b'B', 0]));
+        frames.extend(id3v2_frame(b"TDRC", 0, b"2019-05-01"));
+        let mut mp3 = b"ID3\x03\x00\x00".to_vec();
+        mp3.extend_from_slice(&[0, 0, 0, frames.len() as u8]);
+        mp3.extend(frames);
+        mp3.extend_from_slice(&[0xFF, 0xFB, 0x90, 0x00]);
+        fs::write(&path, mp3).unwrap();
+
+        let fields = extract_file_metadata(path.to_str().unwrap(), "mp3", &FileCategory::Audio).unwrap();
+
+        assert_eq!(fields["title"], "Café del Mar");
+        assert_eq!(fields["artist"], "AB");
+        assert_eq!(fields["year"], "2019");
+    }
+
+    #[test]
+    fn test_extract_id3v1_metadata() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("old.mp3");
+        let mut tag = vec![0u8; 128];
+        tag[..3].copy_from_slice(b"TAG");
+        tag[3..8].copy_from_slice(b"Intro");
+        tag[33..38].copy_from_slice(b"Band ");
+        tag[93..97].copy_from_slice(b"1999");
+        let mut mp3 = vec![0xFF, 0xFB, 0x90, 0x00];
+        mp3.extend(tag);
+        fs::write(&path, mp3).unwrap();
+
+        let fields = extract_file_metadata(path.to_str().unwrap(), "mp3", &FileCategory::Audio).unwrap();
+
+        assert_eq!(fields["title"], "Intro");
+        assert_eq!(fields["artist"], "Band");
+        assert_eq!(fields["year"], "1999");
+        assert!(!fields.contains_key("album"));
+    }
+
+    #[test]
+    fn test_extract_metadata_none() {
+        let dir = TempDir::new().unwrap();
+        let text = dir.path().join("notes.txt");
+        fs::write(&text, "plain text").unwrap();
+        let fake_pdf = dir.path().join("fake.pdf");
+        fs::write(&fake_pdf, "not a pdf /Title (Nope)").unwrap();
+
+        assert!(extract_file_metadata(text.to_str().unwrap(), "txt", &FileCategory::Document).is_none());
+        assert!(extract_file_metadata(fake_pdf.to_str().unwrap(), "pdf", &FileCategory::Document).is_none());
+        assert!(extract_file_metadata("/nonexistent/a.jpg", "jpg", &FileCategory::Image).is_none());
+    }
+}
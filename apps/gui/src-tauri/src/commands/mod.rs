@@ -5,28 +5,99 @@
 //!
 //! ## Command Categories
 //!
-//! - **Scanner** (`scan_folder`, `scan_folder_with_progress`, `cancel_scan`, `get_active_scans`)
+//! - **Scanner** (`scan_folder`, `scan_folder_with_progress`, `cancel_scan`, `get_active_scans`, `list_active_sessions`, `diff_scans`, `estimate_scan_time`)
 //!   - Scan directories for files with filtering and cancellation support
 //!   - Returns `FileInfo` objects with metadata and category information
+//!   - Reports skipped files with the exclusion rule that omitted them
+//!   - Optionally extracts key metadata (EXIF, PDF Info, ID3) for badges
+//!   - Diff two scans to find added, removed and modified files
+//!   - Estimate scan duration up front from a timed sample
+//!   - List active scan, duplicate and analysis sessions with their ages
 //!
-//! - **Rename** (`generate_preview`, `execute_rename`)
+//! - **Stats** (`analyze_directory`)
+//!   - Per-category counts and sizes, oldest/newest files and bad-name count
+//!
+//! - **Conventions** (`analyze_naming_conventions`, `folder_cleanliness_score`,
+//!   `preview_cleanliness_score`)
+//!   - Detect the case styles and date formats mixed in a folder
+//!   - Report the dominant convention and the files that don't follow it
+//!   - Score how tidy a folder is (0-100), before and after a preview
+//!
+//! - **Folders** (`suggest_folders_heuristic`)
+//!   - Suggest folders from file dates, categories, extensions and shared name
+//!     prefixes, deterministically and without AI
+//!
+//! - **Duplicates** (`find_duplicates`)
+//!   - Find files with identical content (size grouping, then parallel hashing)
+//!   - Cancellable through `cancel_scan`
+//!
+//! - **Rename** (`generate_preview`, `generate_preview_with_template`, `explain_preview`, `list_case_styles`, `preview_case_change`, `validate_date_format`, `validate_template`, `normalize_date_formats_preview`, `detect_rename_chains`, `find_destination_case_collisions`, `name_reuse_report`, `normalize_input_paths`, `minimize_renames`, `request_overwrite_confirmation`, `execute_rename`, `rename_one`, `execute_ready`, `cancel_rename`)
 //!   - Generate rename proposals using template patterns
-//!   - Execute batch renames with conflict detection
+//!   - Preview a saved template by id, honoring its file type filter
+//!   - Explain why each file would or wouldn't change
+//!   - List case styles with examples rendered by the engine
+//!   - Check a date format string and render it on a sample date
+//!   - Detect chained and circular renames (swaps) that need a specific order
+//!   - Explain destinations that differ only in case across source folders
+//!   - List names reused in different destination folders (not conflicts)
+//!   - Normalize input paths that mix `/` and `\` separators
+//!   - Drop proposals whose target already holds the file (idempotent re-runs)
+//!   - Execute batch renames with conflict detection and cancellation support
+//!   - Require a single-use confirmation token before overwriting existing files
+//!   - Rename a single file in place after an inline edit, with history
+//!
+//! - **Extensions** (`fix_extensions`, `recategorize_by_content`, `extension_histogram`)
+//!   - Propose extension-only renames (".JPEG" -> ".jpg")
+//!   - Correct wrong or missing extensions by sniffing file content
+//!   - Correct file categories from content for better organize destinations
+//!   - Count files and total sizes per extension
+//!
+//! - **Clean** (`clean_folder`)
+//!   - Propose cleaned names for a folder: no dates or counters, ASCII letters,
+//!     one case style, sanitized (no AI or templates)
+//!
+//! - **Risk** (`assess_organize_risk`, `check_destination_space`)
+//!   - Flag risky operations in a preview before executing it (system folders,
+//!     cloud-synced folders, cross-drive moves, many or deeply nested new folders)
+//!   - Find destination volumes without enough free space for cross-drive copies
 //!
-//! - **History** (`record_operation`, `load_history`, `undo_operation`, etc.)
+//! - **Readability** (`score_proposed_names`)
+//!   - Flag overly long, wordy or keyword-stuffed proposed names
+//!
+//! - **Merge** (`merge_folders`)
+//!   - Move all files of one folder into another, resolving name collisions
+//!     (suffix, skip or overwrite with the existing file sent to the trash)
+//!
+//! - **History** (`record_operation`, `load_history`, `undo_operation`, `history_stats`, etc.)
 //!   - Track rename operations for undo/restore functionality
+//!   - Undo several recent operations at once (`undo_recent`, `undo_since`)
 //!   - Persist history to disk in JSON format
+//!   - Report templates never applied (`find_unused_templates`)
+//!   - Mark files the app already renamed or moved (`annotate_with_history`)
 //!
-//! - **Config** (`get_config`, `save_config`, `reset_config`)
+//! - **Config** (`get_config`, `save_config`, `flush_config`, `reset_config`)
 //!   - Manage user preferences and templates
+//!   - Debounce saves into a single disk write; `flush_config` forces it
 //!   - Stored in OS-appropriate config directory
 //!
-//! - **Export** (`export_results`)
+//! - **Export** (`export_results`, `export_rename_script`, `export_rename_map`)
 //!   - Export scan results to JSON format
+//!   - Export a rename batch as a bash or PowerShell script (no execution)
+//!   - Write a JSON lines rename map and its inverse for external tools
 //!
 //! - **LLM** (`analyze_files_with_llm`, `check_ollama_health`, etc.)
 //!   - AI-powered file analysis with Ollama or OpenAI
 //!   - Caches results in memory to avoid redundant analysis
+//!   - Count cache hits and misses for a batch before analyzing (`analysis_cache_preview`)
+//!   - Caches the scanned folder structure until it changes (`clear_folder_structure_cache`)
+//!   - Re-keys cached results of renamed files (`remap_cache_paths`)
+//!   - Download missing Ollama models with progress events (`pull_ollama_model`)
+//!   - Analyze a stratified sample and estimate the full batch (`sample_analysis`)
+//!   - Re-run folder consolidation with adaptive depth (`reconsolidate_folder_suggestions`)
+//!   - Show how folder names are normalized before merging (`preview_folder_normalization`)
+//!   - Turn folder suggestions into an organize preview (`organize_from_suggestions`)
+//!   - Overlay confident name suggestions on an existing preview (`merge_suggestions`)
+//!   - Hide suggestions below a confidence floor (`filter_results_by_confidence`)
 //!
 //! - **Version** (`get_version`)
 //!   - Get application version information
@@ -37,25 +108,62 @@
 //! as structured `ErrorResponse` objects for consistent frontend handling.
 //! See [`error`] module for error types.
 
+mod clean;
+mod clock;
 mod config;
+mod conventions;
+mod duplicates;
 pub mod error;
+mod exclusions;
+mod exif;
+mod extensions;
 mod export;
+mod folders;
 mod history;
 mod llm;
+mod merge;
+mod metadata;
+mod readability;
 mod rename;
+mod risk;
 mod scanner;
 mod secrets;
 mod security;
+mod stats;
 mod version;
 
-pub use config::{get_config, reset_config, save_config};
-pub use export::export_results;
+pub use clean::clean_folder;
+pub use config::{
+    flush_config, flush_pending_config, get_config, reset_config, save_config,
+    DEFAULT_MAX_ACTIVE_SESSIONS,
+};
+pub use conventions::{
+    analyze_naming_conventions, folder_cleanliness_score, preview_cleanliness_score,
+};
+pub use duplicates::find_duplicates;
+pub use export::{export_rename_map, export_rename_script, export_results};
+pub use extensions::{extension_histogram, fix_extensions, recategorize_by_content};
+pub use folders::suggest_folders_heuristic;
 pub use history::{
-    can_undo_operation, clear_history, get_history_count, get_history_entry, load_history,
-    record_operation, undo_operation,
+    annotate_with_history, can_undo_operation, clear_history, find_unused_templates,
+    get_history_count, get_history_entry, history_stats, load_history, record_operation,
+    undo_operation, undo_recent, undo_since,
+};
+pub use llm::{analysis_cache_preview, analyze_files_with_llm, check_ollama_health, check_openai_health, clear_analysis_cache, clear_folder_structure_cache, filter_results_by_confidence, get_cache_stats, list_ollama_models, list_openai_models, merge_suggestions, organize_from_suggestions, preview_folder_normalization, pull_ollama_model, reconsolidate_folder_suggestions, remap_cache_paths, sample_analysis};
+pub use merge::merge_folders;
+pub use readability::score_proposed_names;
+pub use rename::{
+    cancel_rename, detect_rename_chains, execute_ready, execute_rename, explain_preview,
+    find_destination_case_collisions, generate_preview, generate_preview_with_template,
+    list_case_styles, minimize_renames, name_reuse_report, normalize_date_formats_preview,
+    normalize_input_paths, preview_case_change, rename_one, request_overwrite_confirmation,
+    validate_date_format, validate_template, RenameState,
+};
+pub use risk::{assess_organize_risk, check_destination_space};
+pub use scanner::{
+    cancel_scan, diff_scans, estimate_scan_time, get_active_scans, list_active_sessions,
+    scan_folder, scan_folder_with_progress, ScanState,
 };
-pub use llm::{analyze_files_with_llm, check_ollama_health, check_openai_health, clear_analysis_cache, get_cache_stats, list_ollama_models, list_openai_models};
-pub use rename::{execute_rename, generate_preview};
-pub use scanner::{cancel_scan, get_active_scans, scan_folder, scan_folder_with_progress, ScanState};
 pub use secrets::{delete_secret, retrieve_secret, store_secret};
+pub use stats::analyze_directory;
 pub use version::get_version;
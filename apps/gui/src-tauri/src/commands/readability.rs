@@ -0,0 +1,159 @@
+// Proposed name readability commands for tidy-app GUI
+// Command names use snake_case per architecture requirements
+//
+// Heuristic review of proposed names: flags overly long, wordy or
+// keyword-stuffed names (typically bad AI output) so they can be checked
+// before renaming.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+use ts_rs::TS;
+
+use super::rename::{RenameError, RenamePreview, RenameProposal};
+
+/// Names longer than this (without extension) are hard to read in file browsers
+const MAX_READABLE_LENGTH: usize = 60;
+
+/// Names with more words than this read like keyword lists
+const MAX_READABLE_WORDS: usize = 8;
+
+/// Words shorter than this (dates, numbers, articles) may repeat freely
+const MIN_REPEATED_WORD_LENGTH: usize = 3;
+
+const LENGTH_PENALTY: f32 = 0.3;
+const WORD_COUNT_PENALTY: f32 = 0.3;
+const REPEATED_WORD_PENALTY: f32 = 0.2;
+
+// =============================================================================
+// Types
+// =============================================================================
+
+/// Readability score of one proposed name
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct NameReadability {
+    pub proposal_id: String,
+    pub proposed_name: String,
+    /// 1.0 for a clean name, lower for each problem found (0.0 - 1.0)
+    pub score: f32,
+    /// Human-readable problems found, empty for a clean name
+    pub reasons: Vec<String>,
+}
+
+// =============================================================================
+// Scoring
+// =============================================================================
+
+/// Score a filename (extension ignored)
+fn score_name(name: &str) -> (f32, Vec<String>) {
+    let stem = Path::new(name)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| name.to_string());
+    let mut penalty = 0.0;
+    let mut reasons = Vec::new();
+
+    let length = stem.chars().count();
+    if length > MAX_READABLE_LENGTH {
+        penalty += LENGTH_PENALTY;
+        reasons.push(format!(
+            "Name is {} characters long (over {})",
+            length, MAX_READABLE_LENGTH
+        ));
+    }
+
+    let words: Vec<String> = stem
+        .split(['-', '_', ' ', '.'])
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect();
+    if words.len() > MAX_READABLE_WORDS {
+        penalty += WORD_COUNT_PENALTY;
+        reasons.push(format!(
+            "Name has {} words (over {})",
+            words.len(),
+            MAX_READABLE_WORDS
+        ));
+    }
+
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for word in words.iter().filter(|w| w.chars().count() >= MIN_REPEATED_WORD_LENGTH) {
+        *counts.entry(word.as_str()).or_insert(0) += 1;
+    }
+    // Keep the order of first appearance for stable messages
+    let mut repeated: Vec<&str> = Vec::new();
+    for word in &words {
+        if counts.get(word.as_str()).is_some_and(|&n| n > 1) && !repeated.contains(&word.as_str()) {
+            repeated.push(word);
+        }
+    }
+    if !repeated.is_empty() {
+        penalty += REPEATED_WORD_PENALTY * repeated.len() as f32;
+        reasons.push(format!("Repeated words: {}", repeated.join(", ")));
+    }
+
+    ((1.0 - penalty).max(0.0), reasons)
+}
+
+fn score_proposal(proposal: &RenameProposal) -> NameReadability {
+    let (score, reasons) = score_name(&proposal.proposed_name);
+    NameReadability {
+        proposal_id: proposal.id.clone(),
+        proposed_name: proposal.proposed_name.clone(),
+        score,
+        reasons,
+    }
+}
+
+// =============================================================================
+// Tauri Commands
+// =============================================================================
+
+/// Score how readable each proposed name in a preview is
+///
+/// Flags excessive length, too many words and repeated words, so the UI can
+/// highlight questionable (often AI-generated) names.
+///
+/// Command name: score_proposed_names (snake_case per architecture)
+#[tauri::command]
+pub async fn score_proposed_names(
+    preview: RenamePreview,
+) -> Result<Vec<NameReadability>, RenameError> {
+    Ok(preview.proposals.iter().map(score_proposal).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_score_name_clean() {
+        let (score, reasons) = score_name("2024-03-15-beach-sunset.jpg");
+
+        assert_eq!(score, 1.0);
+        assert!(reasons.is_empty());
+    }
+
+    #[test]
+    fn test_score_name_keyword_stuffed() {
+        let name =
+            "beach-sunset-beach-ocean-sunset-vacation-holiday-summer-trip-photo-waves-sand.jpg";
+
+        let (score, reasons) = score_name(name);
+
+        assert!(score < 0.5, "score {} should be low", score);
+        assert_eq!(reasons.len(), 3);
+        assert!(reasons[0].contains("characters long"));
+        assert!(reasons[1].contains("12 words"));
+        assert_eq!(reasons[2], "Repeated words: beach, sunset");
+    }
+
+    #[test]
+    fn test_score_name_short_words_may_repeat() {
+        let (score, _) = score_name("report-01-01.pdf");
+
+        assert_eq!(score, 1.0);
+    }
+}
@@ -3,20 +3,32 @@
 //
 // Story 6.4: Visual Rename Review (AC1, AC5)
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Datelike, NaiveDate, NaiveTime, Utc};
 use lazy_static::lazy_static;
 use regex_lite::Regex;
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::Emitter;
 use thiserror::Error;
 use ts_rs::TS;
 use uuid::Uuid;
 
+use super::clock::{Clock, SystemClock};
+use super::config::{get_config, Template, DEFAULT_MAX_DESTINATION_DEPTH};
+use super::duplicates::hash_file;
 use super::error::{ErrorCategory, ErrorResponse};
-use super::scanner::FileInfo;
-use super::security::{validate_rename_path, SecurityError};
+use super::exif::read_capture_date;
+use super::scanner::{CancellationToken, FileCategory, FileInfo, MetadataCapability};
+use super::history::{record_operation, HistoryJournal};
+use super::risk::deep_nesting_warning;
+use super::security::{
+    find_protected_prefix, has_mixed_separators, join_proposed_path, normalize_path_separators,
+    validate_rename_destination, validate_rename_path, validate_scan_path, SecurityError,
+};
 
 // =============================================================================
 // Error Types
@@ -35,6 +47,8 @@ pub enum RenameError {
     IoError(#[from] std::io::Error),
     #[error("Security violation: {0}")]
     SecurityViolation(String),
+    #[error("Integrity check failed: {0}")]
+    IntegrityCheckFailed(String),
 }
 
 impl From<SecurityError> for RenameError {
@@ -81,6 +95,13 @@ impl RenameError {
                 ErrorCategory::Security,
             )
             .non_recoverable(),
+
+            RenameError::IntegrityCheckFailed(msg) => ErrorResponse::new(
+                "INTEGRITY_CHECK_FAILED",
+                format!("Integrity check failed: {}", msg),
+                ErrorCategory::Validation,
+            )
+            .with_suggestion("The proposals changed since the preview was generated. Regenerate the preview and try again."),
         }
     }
 }
@@ -104,6 +125,19 @@ pub enum RenameStatus {
     InvalidName,
 }
 
+impl RenameStatus {
+    /// Serialized (kebab-case) name of the status
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RenameStatus::Ready => "ready",
+            RenameStatus::Conflict => "conflict",
+            RenameStatus::MissingData => "missing-data",
+            RenameStatus::NoChange => "no-change",
+            RenameStatus::InvalidName => "invalid-name",
+        }
+    }
+}
+
 /// Reorganization mode determines how files are handled during rename operations.
 ///
 /// - 'rename-only': Files stay in their current locations, only names change (safest)
@@ -139,12 +173,29 @@ pub struct OrganizeOptions {
     /// How many levels of parent folders to preserve when preserve_context is true.
     #[serde(default = "default_context_depth")]
     pub context_depth: i32,
+
+    /// Which date resolves {year}/{month}/{day} in the folder pattern.
+    #[serde(default)]
+    pub date_source: FolderDateSource,
 }
 
 fn default_context_depth() -> i32 {
     1
 }
 
+/// Date source used when resolving date placeholders in folder patterns.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "kebab-case")]
+pub enum FolderDateSource {
+    /// File modification time for every file (default)
+    #[default]
+    Modified,
+    /// EXIF capture date for images (falling back to modification time),
+    /// modification time for everything else
+    ExifForImages,
+}
+
 /// Action type for a file in the preview.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
 #[ts(export, export_to = "bindings/")]
@@ -279,6 +330,93 @@ pub struct RenamePreview {
     /// The reorganization mode used for this preview
     #[serde(default)]
     pub reorganization_mode: ReorganizationMode,
+    /// Hash of the proposal set, passed back to execute_rename for tamper/staleness detection
+    #[serde(default)]
+    pub integrity_hash: String,
+    /// Informational notes about the batch as a whole
+    #[serde(default)]
+    pub warnings: Vec<PreviewWarning>,
+}
+
+/// Informational note about a whole preview; nothing prevents applying it
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewWarning {
+    /// Warning code for programmatic handling (e.g., "DEEP_NESTING")
+    pub code: String,
+    /// Human-readable message
+    pub message: String,
+    /// Proposals the warning is about
+    pub proposal_ids: Vec<String>,
+}
+
+/// Phase of a two-phase organize
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "kebab-case")]
+pub enum StagingPhase {
+    /// Moving files into the staging folder
+    Staging,
+    /// Checking that every staged file arrived intact
+    Verifying,
+    /// Moving staged files to their final paths
+    Finalizing,
+    /// Moving staged files back after a failure
+    RollingBack,
+}
+
+/// Progress of a two-phase organize, emitted as "rename-staging-progress"
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct StagingProgress {
+    pub phase: StagingPhase,
+    /// Files done in this phase
+    pub processed: usize,
+    pub total: usize,
+}
+
+/// Confirmation required before `execute_rename` overwrites existing files
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct OverwriteConfirmation {
+    /// Single-use token to pass as `confirmation_token`
+    pub confirmation_token: String,
+    /// Existing files that will be moved to the trash and replaced
+    pub targets: Vec<String>,
+    /// When the token stops being accepted
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Explanation of the outcome of a single rename proposal
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct ProposalExplanation {
+    pub original_path: String,
+    pub original_name: String,
+    /// Filename produced by the template
+    pub rendered_name: String,
+    pub status: RenameStatus,
+    /// Human-readable reason for the status
+    pub explanation: String,
+}
+
+/// Proposals left to execute once already-satisfied ones are removed
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct MinimizedRenames {
+    /// Proposals that still need a filesystem operation
+    pub proposals: Vec<RenameProposal>,
+    /// Number of proposals dropped because the target already holds the file
+    pub already_correct: usize,
+    /// Integrity hash of the remaining proposals, set when the input carried
+    /// a valid preview hash (pass it to `execute_rename` instead of the old one)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub integrity_hash: Option<String>,
 }
 
 /// Outcome of a single file rename
@@ -306,6 +444,78 @@ pub struct FileRenameResult {
     pub outcome: RenameOutcome,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    /// Structured error category for targeted remediation in the UI
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_kind: Option<RenameErrorKind>,
+    /// How a symlinked source was handled (`symlink_rename_policy`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symlink: Option<SymlinkResolution>,
+}
+
+/// How `execute_rename` treats proposals whose file is a symbolic link
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "kebab-case")]
+pub enum SymlinkRenamePolicy {
+    /// Rename or move the link itself, leaving its target untouched
+    #[default]
+    RenameLink,
+    /// Resolve the link and rename or move the file it points to
+    RenameTarget,
+    /// Leave symlinked files alone
+    Skip,
+}
+
+/// Symlink found at the source of a rename, and how it was handled
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct SymlinkResolution {
+    /// Path of the link, as proposed
+    pub link_path: String,
+    /// Resolved path of the link's target (None for a broken link)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_path: Option<String>,
+    /// Policy applied to the link
+    pub policy: SymlinkRenamePolicy,
+}
+
+/// Category of a filesystem error during rename
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "kebab-case")]
+pub enum RenameErrorKind {
+    PermissionDenied,
+    NotFound,
+    AlreadyExists,
+    /// Source and destination are on different filesystems/volumes
+    CrossDevice,
+    /// Source or destination is under a protected path from the config
+    ProtectedPath,
+    Other,
+}
+
+impl RenameErrorKind {
+    /// Map an IO error to a rename error kind
+    pub fn from_io_error(err: &std::io::Error) -> Self {
+        // EXDEV (Unix) / ERROR_NOT_SAME_DEVICE (Windows)
+        #[cfg(unix)]
+        const CROSS_DEVICE_OS_ERROR: i32 = 18;
+        #[cfg(windows)]
+        const CROSS_DEVICE_OS_ERROR: i32 = 17;
+
+        #[cfg(any(unix, windows))]
+        if err.raw_os_error() == Some(CROSS_DEVICE_OS_ERROR) {
+            return RenameErrorKind::CrossDevice;
+        }
+
+        match err.kind() {
+            std::io::ErrorKind::PermissionDenied => RenameErrorKind::PermissionDenied,
+            std::io::ErrorKind::NotFound => RenameErrorKind::NotFound,
+            std::io::ErrorKind::AlreadyExists => RenameErrorKind::AlreadyExists,
+            _ => RenameErrorKind::Other,
+        }
+    }
 }
 
 /// Summary of batch rename results
@@ -330,6 +540,22 @@ pub struct BatchRenameResult {
     pub started_at: DateTime<Utc>,
     pub completed_at: DateTime<Utc>,
     pub duration_ms: u64,
+    /// Whether the batch was cancelled before all proposals were processed
+    #[serde(default)]
+    pub cancelled: bool,
+    /// Directories created by this batch (outermost first), each listed once
+    #[serde(default)]
+    pub directories_created: Vec<String>,
+    /// Whether the batch stopped at a permission error (`stop_on_permission_error`)
+    #[serde(default)]
+    pub stopped_on_permission_error: bool,
+    /// Existing files moved to the system trash to be replaced
+    /// (`overwrite_existing`); restoring them is done from the trash
+    #[serde(default)]
+    pub trashed_files: Vec<String>,
+    /// History entry already recorded for this batch (`record_incrementally`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub history_entry_id: Option<String>,
 }
 
 // =============================================================================
@@ -362,6 +588,89 @@ pub enum CaseStyle {
     PascalCase,
 }
 
+impl CaseStyle {
+    /// Every case style, in the order shown in settings
+    pub const ALL: [CaseStyle; 9] = [
+        CaseStyle::None,
+        CaseStyle::Lowercase,
+        CaseStyle::Uppercase,
+        CaseStyle::Capitalize,
+        CaseStyle::TitleCase,
+        CaseStyle::KebabCase,
+        CaseStyle::SnakeCase,
+        CaseStyle::CamelCase,
+        CaseStyle::PascalCase,
+    ];
+}
+
+/// A case style with a sample name rendered by `normalize_case`
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct CaseStyleExample {
+    pub style: CaseStyle,
+    /// The sample name in this style
+    pub example: String,
+}
+
+/// Language used for month names in dates
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "kebab-case")]
+pub enum DateLocale {
+    /// English (default)
+    #[default]
+    En,
+    /// French
+    Fr,
+    /// German
+    De,
+    /// Spanish
+    Es,
+}
+
+impl DateLocale {
+    /// Lowercase month names, January first
+    fn month_names(self) -> [&'static str; 12] {
+        match self {
+            DateLocale::En => [
+                "january", "february", "march", "april", "may", "june", "july", "august",
+                "september", "october", "november", "december",
+            ],
+            DateLocale::Fr => [
+                "janvier", "février", "mars", "avril", "mai", "juin", "juillet", "août",
+                "septembre", "octobre", "novembre", "décembre",
+            ],
+            DateLocale::De => [
+                "januar", "februar", "märz", "april", "mai", "juni", "juli", "august",
+                "september", "oktober", "november", "dezember",
+            ],
+            DateLocale::Es => [
+                "enero", "febrero", "marzo", "abril", "mayo", "junio", "julio", "agosto",
+                "septiembre", "octubre", "noviembre", "diciembre",
+            ],
+        }
+    }
+
+    /// Name of the month of a date in this locale
+    pub fn month_name(self, date: &DateTime<Utc>) -> &'static str {
+        self.month_names()[date.month0() as usize]
+    }
+}
+
+/// Result of checking a date format string
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct DateFormatPreview {
+    /// Whether every part of the format is a known token or a separator
+    pub valid: bool,
+    /// The format rendered on a sample date (2024-03-15 14:30:45)
+    pub example: String,
+    /// Letters (or "%") that would be copied verbatim into filenames
+    pub unknown_tokens: Vec<String>,
+}
+
 /// Options for generating a preview
 #[derive(Debug, Clone, Deserialize, Default, TS)]
 #[ts(export, export_to = "bindings/")]
@@ -393,6 +702,28 @@ pub struct GeneratePreviewOptions {
     /// Default: false (for backward compatibility)
     #[serde(default)]
     pub strip_existing_patterns: bool,
+    /// Append the original name to the proposed one so renames stay traceable
+    /// (e.g., "2024-06-01-beach__orig-IMG_1234.jpg"). Default: false
+    #[serde(default)]
+    pub preserve_original_suffix: bool,
+    /// Separator placed before the original name (default: "__orig-")
+    #[serde(default)]
+    pub original_suffix_separator: Option<String>,
+    /// Language of month names for `{month-name}` and the "MMMM" date token
+    /// (default: English). Numeric dates are the same in every locale.
+    #[serde(default)]
+    pub locale: DateLocale,
+    /// Text added before the name produced by the template (e.g., "2024-").
+    /// With an empty template it is added to the original name.
+    #[serde(default)]
+    pub prefix: Option<String>,
+    /// Text added after the name, before the extension (e.g., "-draft")
+    #[serde(default)]
+    pub suffix: Option<String>,
+    /// Give subtitle files (".srt", ".en.vtt", ...) the name and folder of the
+    /// video they belong to, keeping their language tag (default: false)
+    #[serde(default)]
+    pub attach_subtitles: bool,
 }
 
 /// Options for executing renames
@@ -403,6 +734,77 @@ pub struct ExecuteRenameOptions {
     /// IDs of proposals to rename (if empty, renames all ready)
     #[serde(default)]
     pub proposal_ids: Option<Vec<String>>,
+    /// Session ID for cancellation via `cancel_rename`
+    #[serde(default)]
+    pub session_id: Option<String>,
+    /// Integrity hash from the preview; when set, proposals must match it (opt-in)
+    #[serde(default)]
+    pub integrity_hash: Option<String>,
+    /// All or nothing: refuse to rename anything if a proposal has a conflict
+    /// or invalid name (default: false, ready proposals still run)
+    #[serde(default)]
+    pub fail_on_any_conflict: bool,
+    /// Copy then delete when source and destination are on different volumes
+    /// (default: false, such moves fail with `cross-device`)
+    #[serde(default)]
+    pub copy_across_devices: bool,
+    /// Compare hashes, not just sizes, of cross-device copies before deleting
+    /// the source (default: false)
+    #[serde(default)]
+    pub verify_copies: bool,
+    /// Stop the batch at the first permission-denied failure instead of
+    /// failing each remaining file against an inaccessible destination
+    /// (default: false)
+    #[serde(default)]
+    pub stop_on_permission_error: bool,
+    /// Move every file into a staging folder under the destination root, check
+    /// they all arrived, then move them into place. A failure while staging
+    /// moves the files back, leaving the original tree intact. Same volume
+    /// only (default: false)
+    #[serde(default)]
+    pub two_phase: bool,
+    /// Replace files that already exist at the proposed path (file-exists
+    /// conflicts), moving them to the system trash first. Requires a
+    /// `confirmation_token` (default: false)
+    #[serde(default)]
+    pub overwrite_existing: bool,
+    /// Token from `request_overwrite_confirmation` covering exactly the files
+    /// this batch would overwrite
+    #[serde(default)]
+    pub confirmation_token: Option<String>,
+    /// Record the batch to history while it runs, flushing the entry after
+    /// each successful rename so a crash still leaves it undoable. The entry
+    /// ID is returned in `history_entry_id`; don't call `record_operation`
+    /// for such a batch (default: false)
+    #[serde(default)]
+    pub record_incrementally: bool,
+    /// Whether symlinked files rename the link, rename the file it points to
+    /// or are skipped (default: rename the link). Two-phase batches refuse
+    /// symlinked files regardless
+    #[serde(default)]
+    pub symlink_rename_policy: SymlinkRenamePolicy,
+    /// Folder the proposals were scanned from. With `RenameTarget`, links
+    /// pointing outside it are refused (default: the folder of each link)
+    #[serde(default)]
+    pub scan_root: Option<String>,
+    /// Protected path prefixes, loaded from the config by `execute_rename`
+    #[serde(skip)]
+    #[ts(skip)]
+    pub protected_paths: Vec<String>,
+    /// Proposals whose existing target was confirmed for overwriting, set by
+    /// `execute_rename` once the confirmation token checks out
+    #[serde(skip)]
+    #[ts(skip)]
+    pub confirmed_overwrites: HashSet<String>,
+    /// Journal for `record_incrementally`, opened by `execute_rename`
+    #[serde(skip)]
+    #[ts(skip)]
+    pub history_journal: Option<HistoryJournal>,
+    /// Removes the existing target of a confirmed overwrite (default: the
+    /// system trash)
+    #[serde(skip)]
+    #[ts(skip)]
+    pub trash_file: Option<fn(&Path) -> std::io::Result<()>>,
 }
 
 // =============================================================================
@@ -418,6 +820,39 @@ const RESERVED_NAMES: &[&str] = &[
     "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
 ];
 
+/// Resolve a path that may not exist yet, for prefix comparisons
+///
+/// `.` and `..` are folded lexically, then the deepest existing ancestor is
+/// canonicalized so symlinked and differently spelled paths compare equal.
+fn resolve_for_comparison(path: &Path) -> PathBuf {
+    let mut lexical = PathBuf::new();
+    for component in path.components() {
+        match component {
+            std::path::Component::CurDir => {}
+            std::path::Component::ParentDir => {
+                lexical.pop();
+            }
+            other => lexical.push(other),
+        }
+    }
+
+    let Some(existing) = lexical.ancestors().find(|p| p.exists()) else {
+        return lexical;
+    };
+    match existing.canonicalize() {
+        Ok(canonical) => canonical.join(lexical.strip_prefix(existing).unwrap_or(&lexical)),
+        Err(_) => lexical,
+    }
+}
+
+/// Whether a proposed name is empty, only whitespace, or only the extension
+/// (".jpg" for a JPEG that had a name)
+fn is_blank_name(name: &str, extension: &str) -> bool {
+    let name = name.trim();
+    name.is_empty()
+        || (!extension.is_empty() && name.eq_ignore_ascii_case(&format!(".{}", extension)))
+}
+
 /// Check if a filename is valid
 fn is_valid_filename(name: &str) -> bool {
     if name.is_empty() || name.len() > 255 {
@@ -531,6 +966,29 @@ lazy_static! {
     /// Pre-compiled pattern for {date:FORMAT} template placeholders (SEC-P1-001, PERF-P2-001)
     /// Using a simple, non-backtracking pattern to prevent ReDoS attacks
     static ref COMPILED_DATE_FORMAT_PATTERN: Regex = Regex::new(r"\{date:([^}]{1,50})\}").unwrap();
+
+    /// Pre-compiled pattern for placeholders left unresolved after template application
+    static ref UNRESOLVED_PLACEHOLDER_PATTERN: Regex = Regex::new(r"\{([a-zA-Z_-]{1,50})(?::[^}]{0,50})?\}").unwrap();
+}
+
+/// Find placeholders the template could not resolve (e.g. `{camera}` with no data)
+///
+/// Doubled braces (`{{draft}}`) are literal text, and a `}` without an opening
+/// brace is just a character of the name.
+fn find_unresolved_placeholders(rendered: &str) -> Vec<String> {
+    let mut missing: Vec<String> = Vec::new();
+    for m in UNRESOLVED_PLACEHOLDER_PATTERN.find_iter(rendered) {
+        let escaped =
+            rendered[..m.start()].ends_with('{') || rendered[m.end()..].starts_with('}');
+        if escaped {
+            continue;
+        }
+        let placeholder = m.as_str().to_string();
+        if !missing.contains(&placeholder) {
+            missing.push(placeholder);
+        }
+    }
+    missing
 }
 
 /// Apply a pre-compiled regex pattern with boundary-aware replacement.
@@ -574,6 +1032,38 @@ fn apply_pattern_with_boundary_handling(input: &str, pattern: &str) -> String {
     .to_string()
 }
 
+/// Replace accented letters with their ASCII base ("Été" -> "Ete")
+pub fn strip_diacritics(name: &str) -> String {
+    let mut result = String::with_capacity(name.len());
+    for c in name.chars() {
+        match c {
+            'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' => result.push('a'),
+            'À' | 'Á' | 'Â' | 'Ã' | 'Ä' | 'Å' => result.push('A'),
+            'è' | 'é' | 'ê' | 'ë' => result.push('e'),
+            'È' | 'É' | 'Ê' | 'Ë' => result.push('E'),
+            'ì' | 'í' | 'î' | 'ï' => result.push('i'),
+            'Ì' | 'Í' | 'Î' | 'Ï' => result.push('I'),
+            'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' => result.push('o'),
+            'Ò' | 'Ó' | 'Ô' | 'Õ' | 'Ö' | 'Ø' => result.push('O'),
+            'ù' | 'ú' | 'û' | 'ü' => result.push('u'),
+            'Ù' | 'Ú' | 'Û' | 'Ü' => result.push('U'),
+            'ý' | 'ÿ' => result.push('y'),
+            'Ý' => result.push('Y'),
+            'ñ' => result.push('n'),
+            'Ñ' => result.push('N'),
+            'ç' => result.push('c'),
+            'Ç' => result.push('C'),
+            'ß' => result.push_str("ss"),
+            'æ' => result.push_str("ae"),
+            'Æ' => result.push_str("AE"),
+            'œ' => result.push_str("oe"),
+            'Œ' => result.push_str("OE"),
+            c => result.push(c),
+        }
+    }
+    result
+}
+
 /// Clean a filename by removing existing date, time, and counter patterns.
 /// This makes template application idempotent (applying the same template twice gives the same result).
 ///
@@ -585,7 +1075,7 @@ fn apply_pattern_with_boundary_handling(input: &str, pattern: &str) -> String {
 /// - Separators: `-`, `_`, `.`, ` `
 /// - Counters: _001, (1), etc.
 /// - Preserves leading dot for Unix hidden files
-fn clean_filename(name: &str) -> String {
+pub fn clean_filename(name: &str) -> String {
     if name.is_empty() {
         return name.to_string();
     }
@@ -696,7 +1186,7 @@ pub struct SanitizeResult {
 /// 3. Handle Windows reserved names
 /// 4. Fix trailing spaces and periods
 /// 5. Truncate if too long
-fn sanitize_filename(filename: &str, replacement: char) -> SanitizeResult {
+pub fn sanitize_filename(filename: &str, replacement: char) -> SanitizeResult {
     let mut changes: Vec<SanitizeChange> = Vec::new();
     let original = filename.to_string();
 
@@ -867,7 +1357,7 @@ fn capitalize_word(word: &str) -> String {
 }
 
 /// Apply case normalization to a filename (name part only, not extension)
-fn normalize_case(name: &str, style: &CaseStyle) -> String {
+pub fn normalize_case(name: &str, style: &CaseStyle) -> String {
     if matches!(style, CaseStyle::None) || name.is_empty() {
         return name.to_string();
     }
@@ -973,11 +1463,125 @@ fn truncate_filename(filename: &str, max_length: usize, changes: &mut Vec<Saniti
     result
 }
 
+/// Default separator between the new name and the preserved original name
+const DEFAULT_ORIGINAL_SUFFIX_SEPARATOR: &str = "__orig-";
+
+/// Append the original stem to a proposed filename, keeping the extension last
+///
+/// When the result would exceed the filename length limit, the new part is
+/// shortened so the original name (needed to trace back) stays intact.
+/// A name that already carries a suffix from an earlier run keeps its first
+/// original, so re-running doesn't stack "__orig-a__orig-a".
+fn append_original_suffix(proposed_name: &str, original_stem: &str, separator: &str) -> String {
+    let (name_part, ext_part) = split_filename(proposed_name);
+    let name_part = split_original_suffix(&name_part, separator).0;
+    let original_stem = match split_original_suffix(original_stem, separator) {
+        (_, Some(traced)) => traced,
+        (stem, None) => stem,
+    };
+    let suffix = format!("{}{}", separator, original_stem);
+
+    let available = MAX_FILENAME_LENGTH.saturating_sub(suffix.len() + ext_part.len());
+    let mut end = available.min(name_part.len());
+    while !name_part.is_char_boundary(end) {
+        end -= 1;
+    }
+
+    format!("{}{}{}", &name_part[..end], suffix, ext_part)
+}
+
+/// Split a stem at a preserved-original separator: ("beach", Some("IMG_1234"))
+fn split_original_suffix<'a>(stem: &'a str, separator: &str) -> (&'a str, Option<&'a str>) {
+    match stem.find(separator) {
+        Some(index) if !separator.is_empty() && index > 0 => {
+            (&stem[..index], Some(&stem[index + separator.len()..]))
+        }
+        _ => (stem, None),
+    }
+}
+
+/// Add a prefix and/or suffix around the stem of a filename
+///
+/// The result is sanitized again, so separators or invalid characters in the
+/// affixes can't produce an invalid name.
+fn apply_affixes(filename: &str, prefix: Option<&str>, suffix: Option<&str>) -> String {
+    if prefix.is_none() && suffix.is_none() {
+        return filename.to_string();
+    }
+
+    let (stem, ext) = split_filename(filename);
+    let affixed = format!("{}{}{}{}", prefix.unwrap_or(""), stem, suffix.unwrap_or(""), ext);
+    sanitize_filename(&affixed, '_').sanitized
+}
+
 /// Apply a template pattern to generate a new filename
-fn apply_template(file: &FileInfo, pattern: &str, date_format: &str, strip_existing_patterns: bool) -> (String, Vec<String>) {
+///
+/// Returns the filename, the metadata sources used and the placeholders of
+/// `pattern` that had no data for this file. Placeholders are looked up in
+/// the pattern before the filename is inserted, so braces in the original
+/// name are never taken for placeholders.
+fn apply_template(
+    file: &FileInfo,
+    pattern: &str,
+    date_format: &str,
+    strip_existing_patterns: bool,
+    locale: DateLocale,
+) -> (String, Vec<String>, Vec<String>) {
     let mut result = pattern.to_string();
     let mut sources: Vec<String> = Vec::new();
 
+    // Date placeholders are left unresolved when the modification time was
+    // synthesized by the scan, so the preview reports them as missing data
+    if file.has_valid_dates {
+        // Replace {date} with file modification date
+        if result.contains("{date}") {
+            let date_str = format_date(&file.modified_at, date_format, locale);
+            result = result.replace("{date}", &date_str);
+            sources.push("file-date".to_string());
+        }
+
+        // Replace {date:FORMAT} patterns using pre-compiled regex (SEC-P1-001, PERF-P2-001)
+        // The pattern limits format string length to 50 chars to prevent DoS
+        let mut new_result = result.clone();
+        for cap in COMPILED_DATE_FORMAT_PATTERN.captures_iter(&result) {
+            if let Some(format_match) = cap.get(1) {
+                let custom_format = format_match.as_str();
+                let date_str = format_date(&file.modified_at, custom_format, locale);
+                new_result = new_result.replace(&cap[0], &date_str);
+                if !sources.contains(&"file-date".to_string()) {
+                    sources.push("file-date".to_string());
+                }
+            }
+        }
+        result = new_result;
+
+        // Replace {year}, {month}, {day}
+        if result.contains("{year}") {
+            result = result.replace("{year}", &file.modified_at.format("%Y").to_string());
+            if !sources.contains(&"file-date".to_string()) {
+                sources.push("file-date".to_string());
+            }
+        }
+        if result.contains("{month}") {
+            result = result.replace("{month}", &file.modified_at.format("%m").to_string());
+        }
+        if result.contains("{day}") {
+            result = result.replace("{day}", &file.modified_at.format("%d").to_string());
+        }
+        if result.contains("{month-name}") {
+            result = result.replace("{month-name}", locale.month_name(&file.modified_at));
+            if !sources.contains(&"file-date".to_string()) {
+                sources.push("file-date".to_string());
+            }
+        }
+    }
+
+    // Only the filename placeholders are left to resolve
+    let unresolved: Vec<String> = find_unresolved_placeholders(&result)
+        .into_iter()
+        .filter(|p| !matches!(p.as_str(), "{name}" | "{original}" | "{ext}"))
+        .collect();
+
     // Get the name to use - either cleaned or original
     let name_to_use = if strip_existing_patterns {
         clean_filename(&file.name)
@@ -989,7 +1593,7 @@ fn apply_template(file: &FileInfo, pattern: &str, date_format: &str, strip_exist
     if result.contains("{name}") || result.contains("{original}") {
         result = result.replace("{name}", &name_to_use);
         result = result.replace("{original}", &name_to_use);
-        sources.push("filename".to_string());
+        sources.insert(0, "filename".to_string());
     }
 
     // Replace {ext} with extension
@@ -997,42 +1601,6 @@ fn apply_template(file: &FileInfo, pattern: &str, date_format: &str, strip_exist
         result = result.replace("{ext}", &file.extension);
     }
 
-    // Replace {date} with file modification date
-    if result.contains("{date}") {
-        let date_str = format_date(&file.modified_at, date_format);
-        result = result.replace("{date}", &date_str);
-        sources.push("file-date".to_string());
-    }
-
-    // Replace {date:FORMAT} patterns using pre-compiled regex (SEC-P1-001, PERF-P2-001)
-    // The pattern limits format string length to 50 chars to prevent DoS
-    let mut new_result = result.clone();
-    for cap in COMPILED_DATE_FORMAT_PATTERN.captures_iter(&result) {
-        if let Some(format_match) = cap.get(1) {
-            let custom_format = format_match.as_str();
-            let date_str = format_date(&file.modified_at, custom_format);
-            new_result = new_result.replace(&cap[0], &date_str);
-            if !sources.contains(&"file-date".to_string()) {
-                sources.push("file-date".to_string());
-            }
-        }
-    }
-    result = new_result;
-
-    // Replace {year}, {month}, {day}
-    if result.contains("{year}") {
-        result = result.replace("{year}", &file.modified_at.format("%Y").to_string());
-        if !sources.contains(&"file-date".to_string()) {
-            sources.push("file-date".to_string());
-        }
-    }
-    if result.contains("{month}") {
-        result = result.replace("{month}", &file.modified_at.format("%m").to_string());
-    }
-    if result.contains("{day}") {
-        result = result.replace("{day}", &file.modified_at.format("%d").to_string());
-    }
-
     // Add extension if not already present in pattern
     if !result.contains('.') && !file.extension.is_empty() {
         result = format!("{}.{}", result, file.extension);
@@ -1046,13 +1614,19 @@ fn apply_template(file: &FileInfo, pattern: &str, date_format: &str, strip_exist
     // Sanitize the filename to ensure cross-platform compatibility
     let sanitized = sanitize_filename(&result, '_');
 
-    (sanitized.sanitized, sources)
+    (sanitized.sanitized, sources, unresolved)
 }
 
+/// Stands in for the month name while the format goes through chrono
+const MONTH_NAME_MARKER: char = '\u{1}';
+
 /// Format a date according to a pattern
-fn format_date(date: &DateTime<Utc>, format: &str) -> String {
+///
+/// "MMMM" renders the month name in `locale`; other tokens are numeric.
+fn format_date(date: &DateTime<Utc>, format: &str, locale: DateLocale) -> String {
     // Convert common format tokens to chrono format
     let chrono_format = format
+        .replace("MMMM", &MONTH_NAME_MARKER.to_string())
         .replace("YYYY", "%Y")
         .replace("MM", "%m")
         .replace("DD", "%d")
@@ -1060,17 +1634,34 @@ fn format_date(date: &DateTime<Utc>, format: &str) -> String {
         .replace("mm", "%M")
         .replace("ss", "%S");
 
-    date.format(&chrono_format).to_string()
+    date.format(&chrono_format)
+        .to_string()
+        .replace(MONTH_NAME_MARKER, locale.month_name(date))
+}
+
+/// Resolve the date used for a file's destination folder
+fn resolve_folder_date(file: &FileInfo, date_source: FolderDateSource) -> DateTime<Utc> {
+    match (date_source, &file.category) {
+        (FolderDateSource::ExifForImages, super::scanner::FileCategory::Image) => {
+            read_capture_date(&file.path).unwrap_or(file.modified_at)
+        }
+        _ => file.modified_at,
+    }
 }
 
 /// Apply a folder pattern to generate a destination folder path
-fn apply_folder_pattern(file: &FileInfo, pattern: &str) -> String {
+pub fn apply_folder_pattern(
+    file: &FileInfo,
+    pattern: &str,
+    date_source: FolderDateSource,
+) -> String {
     let mut result = pattern.to_string();
 
     // Replace {year}, {month}, {day}
-    result = result.replace("{year}", &file.modified_at.format("%Y").to_string());
-    result = result.replace("{month}", &file.modified_at.format("%m").to_string());
-    result = result.replace("{day}", &file.modified_at.format("%d").to_string());
+    let date = resolve_folder_date(file, date_source);
+    result = result.replace("{year}", &date.format("%Y").to_string());
+    result = result.replace("{month}", &date.format("%m").to_string());
+    result = result.replace("{day}", &date.format("%d").to_string());
 
     // Replace {category} with file category
     let category_str = match file.category {
@@ -1101,12 +1692,103 @@ fn apply_folder_pattern(file: &FileInfo, pattern: &str) -> String {
     result
 }
 
+// =============================================================================
+// Subtitle Sidecars
+// =============================================================================
+
+/// Extensions of subtitle files that follow their video
+const SUBTITLE_EXTENSIONS: &[&str] = &["srt", "vtt", "ass", "ssa", "sub"];
+
+/// Whether `tag` looks like a subtitle language tag ("en", "pt-BR", "en.forced")
+fn is_language_tag(tag: &str) -> bool {
+    !tag.is_empty()
+        && tag.split('.').all(|segment| {
+            (2..=8).contains(&segment.len())
+                && segment.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+}
+
+/// Find the video a subtitle belongs to and the language tag between them
+///
+/// "movie.en.srt" belongs to "movie.mp4" in the same folder with tag "en";
+/// "movie.srt" has no tag. The longest matching video name wins.
+fn find_subtitle_video<'a>(
+    subtitle: &'a FileInfo,
+    files: &[FileInfo],
+) -> Option<(usize, &'a str)> {
+    let folder = Path::new(&subtitle.path).parent();
+    files
+        .iter()
+        .enumerate()
+        .filter(|(_, file)| file.category == FileCategory::Video)
+        .filter(|(_, file)| Path::new(&file.path).parent() == folder)
+        .filter_map(|(index, video)| {
+            let rest = subtitle.name.strip_prefix(video.name.as_str())?;
+            match rest.strip_prefix('.') {
+                Some(tag) if is_language_tag(tag) => Some((index, tag)),
+                None if rest.is_empty() => Some((index, "")),
+                _ => None,
+            }
+        })
+        .max_by_key(|(index, _)| files[*index].name.len())
+}
+
+/// Rename subtitle files after their video, keeping the language tag
+///
+/// `proposals` holds one proposal per file, in the same order. A subtitle
+/// whose video can't be renamed (missing data, invalid name) is left as is.
+fn attach_subtitle_proposals(files: &[FileInfo], proposals: &mut [RenameProposal]) {
+    for (index, subtitle) in files.iter().enumerate() {
+        let extension = subtitle.extension.to_lowercase();
+        if !SUBTITLE_EXTENSIONS.contains(&extension.as_str()) {
+            continue;
+        }
+        let Some((video_index, tag)) = find_subtitle_video(subtitle, files) else {
+            continue;
+        };
+        let video = &proposals[video_index];
+        if !matches!(video.status, RenameStatus::Ready | RenameStatus::NoChange) {
+            continue;
+        }
+
+        let (video_stem, _) = split_filename(&video.proposed_name);
+        let proposed_name = if tag.is_empty() {
+            format!("{}.{}", video_stem, subtitle.extension)
+        } else {
+            format!("{}.{}.{}", video_stem, tag, subtitle.extension)
+        };
+        let proposed_path =
+            join_proposed_path(Path::new(&video.proposed_path).parent(), &proposed_name);
+        let is_folder_move = video.is_folder_move;
+        let destination_folder = video.destination_folder.clone();
+
+        let proposal = &mut proposals[index];
+        let unchanged = proposed_name == subtitle.full_name && !is_folder_move;
+        (proposal.status, proposal.action_type) = if unchanged {
+            (RenameStatus::NoChange, FileActionType::NoChange)
+        } else if is_folder_move {
+            (RenameStatus::Ready, FileActionType::Move)
+        } else {
+            (RenameStatus::Ready, FileActionType::Rename)
+        };
+        proposal.proposed_name = proposed_name;
+        proposal.proposed_path = proposed_path;
+        proposal.is_folder_move = is_folder_move;
+        proposal.destination_folder = destination_folder;
+        // The template's own issues for the subtitle no longer apply
+        proposal.issues.clear();
+    }
+}
+
 // =============================================================================
 // Preview Generation
 // =============================================================================
 
 /// Generate a rename preview for files using a template
 ///
+/// Organize previews warn when files would land deeper than the configured
+/// `maxDestinationDepth` preference.
+///
 /// Command name: generate_preview (snake_case per architecture)
 #[tauri::command]
 pub async fn generate_preview(
@@ -1114,7 +1796,37 @@ pub async fn generate_preview(
     template_pattern: String,
     options: Option<GeneratePreviewOptions>,
 ) -> Result<RenamePreview, RenameError> {
-    let options = options.unwrap_or_default();
+    // The warning is informational, so an unreadable config falls back to the default
+    let max_destination_depth = get_config()
+        .await
+        .map(|config| config.preferences.max_destination_depth)
+        .unwrap_or(DEFAULT_MAX_DESTINATION_DEPTH);
+    build_preview(files, template_pattern, options, max_destination_depth, &SystemClock)
+}
+
+/// Build a rename preview, reading the current time from `clock`
+fn build_preview(
+    files: Vec<FileInfo>,
+    template_pattern: String,
+    options: Option<GeneratePreviewOptions>,
+    max_destination_depth: usize,
+    clock: &dyn Clock,
+) -> Result<RenamePreview, RenameError> {
+    // Consistent separators keep "a/b" and "a\b" from being different files
+    // to conflict detection
+    let files: Vec<FileInfo> = files
+        .into_iter()
+        .map(|mut file| {
+            file.path = normalize_path_separators(&file.path);
+            file
+        })
+        .collect();
+    let mut options = options.unwrap_or_default();
+    options.base_directory = options.base_directory.as_deref().map(normalize_path_separators);
+    if let Some(org_opts) = options.organize_options.as_mut() {
+        org_opts.destination_directory =
+            org_opts.destination_directory.as_deref().map(normalize_path_separators);
+    }
     let date_format = options.date_format.as_deref().unwrap_or("YYYY-MM-DD");
 
     // Determine reorganization mode and settings
@@ -1150,28 +1862,62 @@ pub async fn generate_preview(
         }
     };
 
+    // Legacy folder_pattern always uses modification time
+    let folder_date_source = options
+        .organize_options
+        .as_ref()
+        .map(|o| o.date_source)
+        .unwrap_or_default();
+
     // Pre-allocate with known capacity (PERF-008)
     let mut proposals: Vec<RenameProposal> = Vec::with_capacity(files.len());
-    let mut proposed_paths: HashMap<String, Vec<String>> = HashMap::with_capacity(files.len());
 
     // Get options
     let case_style = &options.case_style;
     let strip_existing_patterns = options.strip_existing_patterns;
 
+    // A prefix or suffix alone applies to the original name
+    let has_affixes = options.prefix.is_some() || options.suffix.is_some();
+    let name_pattern = if has_affixes && template_pattern.trim().is_empty() {
+        "{name}.{ext}"
+    } else {
+        template_pattern.as_str()
+    };
+
+    // Advisory for folder placeholders written into the filename template
+    let folder_in_name = find_folder_in_name_template(&template_pattern);
+
     // First pass: generate proposals
     for file in &files {
         let id = Uuid::new_v4().to_string();
-        let (raw_proposed_name, metadata_sources) = apply_template(file, &template_pattern, date_format, strip_existing_patterns);
+        let (templated_name, metadata_sources, missing_placeholders) = apply_template(
+            file,
+            name_pattern,
+            date_format,
+            strip_existing_patterns,
+            options.locale,
+        );
+        let raw_proposed_name =
+            apply_affixes(&templated_name, options.prefix.as_deref(), options.suffix.as_deref());
 
         // Apply case normalization
-        let proposed_name = normalize_filename(&raw_proposed_name, case_style);
+        let mut proposed_name = normalize_filename(&raw_proposed_name, case_style);
+
+        // The original name is kept verbatim (no case normalization)
+        if options.preserve_original_suffix && proposed_name != file.full_name {
+            let separator = options
+                .original_suffix_separator
+                .as_deref()
+                .unwrap_or(DEFAULT_ORIGINAL_SUFFIX_SEPARATOR);
+            proposed_name = append_original_suffix(&proposed_name, &file.name, separator);
+        }
 
         // Determine destination directory based on reorganization mode
         let (dest_dir, is_folder_move, destination_folder) = match reorg_mode {
             ReorganizationMode::Organize => {
                 if let Some(pattern) = folder_pattern {
                     // Apply folder pattern
-                    let folder_path = apply_folder_pattern(file, pattern);
+                    let folder_path = apply_folder_pattern(file, pattern, folder_date_source);
 
                     // Combine with base directory if provided
                     let full_dest = match base_directory {
@@ -1217,11 +1963,16 @@ pub async fn generate_preview(
             }
         };
 
-        let proposed_path = if dest_dir.is_empty() {
-            proposed_name.clone()
-        } else {
-            format!("{}/{}", dest_dir, proposed_name)
-        };
+        let proposed_path = join_proposed_path(Some(Path::new(&dest_dir)), &proposed_name);
+
+        // A misconfigured organize pattern can resolve back to the file's own
+        // path (e.g. an empty folder pattern): that isn't a move
+        let (is_folder_move, destination_folder) =
+            if Path::new(&proposed_path) == Path::new(&file.path) {
+                (false, None)
+            } else {
+                (is_folder_move, destination_folder)
+            };
 
         let mut issues: Vec<RenameIssue> = Vec::new();
         let mut status = RenameStatus::Ready;
@@ -1233,23 +1984,66 @@ pub async fn generate_preview(
             action_type = FileActionType::NoChange;
         }
 
-        // Check for invalid filename
-        if !is_valid_filename(&proposed_name) {
-            issues.push(RenameIssue {
-                code: "INVALID_NAME".to_string(),
-                message: "Proposed filename contains invalid characters".to_string(),
-                field: None,
+        // Check for placeholders with no data for this file
+        if !missing_placeholders.is_empty() {
+            for placeholder in &missing_placeholders {
+                issues.push(RenameIssue {
+                    code: "MISSING_DATA".to_string(),
+                    message: format!("{} not found", placeholder),
+                    field: Some(placeholder.clone()),
+                });
+            }
+            status = RenameStatus::MissingData;
+            action_type = FileActionType::Error;
+        }
+
+        // A template can render to nothing but the extension (e.g. only
+        // stripped characters)
+        if is_blank_name(&proposed_name, &file.extension) {
+            issues.push(RenameIssue {
+                code: "EMPTY_NAME".to_string(),
+                message: "Proposed filename is empty".to_string(),
+                field: None,
+            });
+            status = RenameStatus::InvalidName;
+            action_type = FileActionType::Error;
+        } else if !is_valid_filename(&proposed_name) {
+            issues.push(RenameIssue {
+                code: "INVALID_NAME".to_string(),
+                message: "Proposed filename contains invalid characters".to_string(),
+                field: None,
             });
             status = RenameStatus::InvalidName;
             action_type = FileActionType::Error;
         }
 
-        // Track for conflict detection
-        let path_key = proposed_path.to_lowercase();
-        proposed_paths
-            .entry(path_key)
-            .or_default()
-            .push(id.clone());
+        // A destination inside the file itself (e.g. a base directory set to
+        // the file's path) can never be created
+        if is_folder_move
+            && resolve_for_comparison(Path::new(&dest_dir))
+                .starts_with(resolve_for_comparison(Path::new(&file.path)))
+        {
+            issues.push(RenameIssue {
+                code: "NESTED_DESTINATION".to_string(),
+                message: "Destination folder is inside the file being moved".to_string(),
+                field: None,
+            });
+            status = RenameStatus::InvalidName;
+            action_type = FileActionType::Error;
+        }
+
+        // Advisory only: the proposal can still be applied as shown
+        if let Some(folder) = &folder_in_name {
+            issues.push(RenameIssue {
+                code: "FOLDER_IN_NAME_TEMPLATE".to_string(),
+                message: format!(
+                    "\"{}/\" in the name template becomes part of the filename; \
+                     use organize mode with a folder pattern to create folders",
+                    folder
+                ),
+                field: Some(folder.clone()),
+            });
+        }
 
         proposals.push(RenameProposal {
             id,
@@ -1271,29 +2065,168 @@ pub async fn generate_preview(
         });
     }
 
+    if options.attach_subtitles {
+        attach_subtitle_proposals(&files, &mut proposals);
+    }
+
+    let mut preview = finalize_preview_at(proposals, template_pattern, reorg_mode, clock);
+    let active: Vec<&RenameProposal> =
+        preview.proposals.iter().filter(|p| will_rename(p, None)).collect();
+    preview.warnings.extend(deep_nesting_warning(&active, max_destination_depth));
+    Ok(preview)
+}
+
+/// Placeholders that usually belong in a folder pattern rather than a filename
+const FOLDER_PLACEHOLDERS: &[&str] = &["{year}", "{month}", "{day}", "{date", "{category}"];
+
+/// Find a folder path written in a filename template (e.g. "{year}/{name}")
+///
+/// Returns the folder part when it uses a date or category placeholder: the
+/// user most likely meant a folder pattern, but the separator would only be
+/// sanitized into the name ("2024_photo.jpg"). Separators inside a placeholder
+/// (e.g. "{date:DD/MM/YYYY}") are ignored.
+fn find_folder_in_name_template(pattern: &str) -> Option<String> {
+    let mut depth = 0;
+    let mut last_separator = None;
+    for (i, c) in pattern.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth = (depth - 1).max(0),
+            '/' | '\\' if depth == 0 => last_separator = Some(i),
+            _ => {}
+        }
+    }
+
+    let folder_part = &pattern[..last_separator?];
+    FOLDER_PLACEHOLDERS
+        .iter()
+        .any(|placeholder| folder_part.contains(placeholder))
+        .then(|| folder_part.to_string())
+}
+
+/// Whether a file passes a template's `file_types` filter (no filter matches all)
+fn template_applies_to(template: &Template, file: &FileInfo) -> bool {
+    template.file_types.as_ref().is_none_or(|types| {
+        types
+            .iter()
+            .any(|t| t.trim_start_matches('.').eq_ignore_ascii_case(&file.extension))
+    })
+}
+
+/// Look up a template by id and preview it on the files it applies to
+async fn preview_with_template(
+    files: Vec<FileInfo>,
+    templates: &[Template],
+    template_id: &str,
+    options: Option<GeneratePreviewOptions>,
+) -> Result<RenamePreview, RenameError> {
+    let template = templates
+        .iter()
+        .find(|t| t.id == template_id)
+        .ok_or_else(|| RenameError::ValidationFailed(format!("Unknown template: {}", template_id)))?;
+
+    let files: Vec<FileInfo> = files
+        .into_iter()
+        .filter(|file| template_applies_to(template, file))
+        .collect();
+
+    generate_preview(files, template.pattern.clone(), options).await
+}
+
+/// Generate a rename preview using a saved template
+///
+/// Resolves `template_id` from the stored config. Files excluded by the
+/// template's `file_types` filter are left out of the preview.
+///
+/// Command name: generate_preview_with_template (snake_case per architecture)
+#[tauri::command]
+pub async fn generate_preview_with_template(
+    files: Vec<FileInfo>,
+    template_id: String,
+    options: Option<GeneratePreviewOptions>,
+) -> Result<RenamePreview, RenameError> {
+    let config = get_config()
+        .await
+        .map_err(|e| RenameError::PreviewFailed(format!("Failed to load config: {}", e)))?;
+
+    preview_with_template(files, &config.templates, &template_id, options).await
+}
+
+/// Key identifying a path in a batch: same separators, case-insensitive
+fn path_key(path: &str) -> String {
+    normalize_path_separators(path).to_lowercase()
+}
+
+/// Detect conflicts across proposals and assemble the preview
+///
+/// Shared by every command that produces standard proposals, so batch
+/// duplicates and existing-file conflicts are reported the same way.
+pub fn finalize_preview(
+    proposals: Vec<RenameProposal>,
+    template_used: String,
+    reorganization_mode: ReorganizationMode,
+) -> RenamePreview {
+    finalize_preview_at(proposals, template_used, reorganization_mode, &SystemClock)
+}
+
+/// [`finalize_preview`] with `generated_at` read from `clock`
+pub fn finalize_preview_at(
+    mut proposals: Vec<RenameProposal>,
+    template_used: String,
+    reorganization_mode: ReorganizationMode,
+    clock: &dyn Clock,
+) -> RenamePreview {
+    // Track proposed paths for conflict detection
+    let mut proposed_paths: HashMap<String, Vec<String>> = HashMap::with_capacity(proposals.len());
+    for proposal in &proposals {
+        proposed_paths
+            .entry(path_key(&proposal.proposed_path))
+            .or_default()
+            .push(proposal.id.clone());
+    }
+
     // Second pass: detect batch conflicts (duplicate names in same destination)
-    for (path_key, ids) in &proposed_paths {
+    for (key, ids) in &proposed_paths {
         if ids.len() > 1 {
             // Find the first file ID to reference in conflict details
             let first_id = ids.first().cloned();
 
             for (idx, id) in ids.iter().enumerate() {
+                let conflicting_file_id = if idx > 0 { first_id.clone() } else { ids.get(1).cloned() };
+                let case_message = conflicting_file_id
+                    .as_deref()
+                    .and_then(|other_id| case_collision_message(&proposals, id, other_id));
+
                 if let Some(proposal) = proposals.iter_mut().find(|p| p.id == *id) {
                     if proposal.status == RenameStatus::Ready {
                         proposal.status = RenameStatus::Conflict;
                         proposal.action_type = FileActionType::Conflict;
-                        proposal.issues.push(RenameIssue {
-                            code: "DUPLICATE_NAME".to_string(),
-                            message: format!("Another file would have the same name ({})", path_key),
-                            field: None,
-                        });
                         // Set conflict details
-                        proposal.conflict = Some(FileConflict {
-                            conflict_type: "duplicate-name".to_string(),
-                            message: "Another file in this batch would have the same name".to_string(),
-                            conflicting_file_id: if idx > 0 { first_id.clone() } else { ids.get(1).cloned() },
-                            existing_file_path: None,
-                        });
+                        if let Some(message) = case_message {
+                            proposal.issues.push(RenameIssue {
+                                code: "CASE_COLLISION".to_string(),
+                                message: message.clone(),
+                                field: None,
+                            });
+                            proposal.conflict = Some(FileConflict {
+                                conflict_type: "case-collision".to_string(),
+                                message,
+                                conflicting_file_id,
+                                existing_file_path: None,
+                            });
+                        } else {
+                            proposal.issues.push(RenameIssue {
+                                code: "DUPLICATE_NAME".to_string(),
+                                message: format!("Another file would have the same name ({})", key),
+                                field: None,
+                            });
+                            proposal.conflict = Some(FileConflict {
+                                conflict_type: "duplicate-name".to_string(),
+                                message: "Another file in this batch would have the same name".to_string(),
+                                conflicting_file_id,
+                                existing_file_path: None,
+                            });
+                        }
                     }
                 }
             }
@@ -1301,11 +2234,21 @@ pub async fn generate_preview(
     }
 
     // Third pass: check for filesystem conflicts (file already exists at target)
+    // Targets vacated by another rename in the batch are chains or swaps, not conflicts
+    // Compared like batch duplicates: case-insensitively, as chain planning does
+    let vacated_paths: HashSet<String> = proposals
+        .iter()
+        .filter(|p| will_rename(p, None))
+        .map(|p| path_key(&p.original_path))
+        .collect();
     for proposal in &mut proposals {
         if proposal.status == RenameStatus::Ready {
             // Check if target already exists (and isn't the source file)
             let target_path = Path::new(&proposal.proposed_path);
-            if target_path.exists() && proposal.proposed_path != proposal.original_path {
+            if target_path.exists()
+                && !is_same_file(&proposal.original_path, &proposal.proposed_path)
+                && !vacated_paths.contains(&path_key(&proposal.proposed_path))
+            {
                 proposal.status = RenameStatus::Conflict;
                 proposal.action_type = FileActionType::Conflict;
                 proposal.issues.push(RenameIssue {
@@ -1342,307 +2285,4212 @@ pub async fn generate_preview(
         error_count: proposals.iter().filter(|p| p.action_type == FileActionType::Error).count(),
     };
 
-    Ok(RenamePreview {
+    let integrity_hash = compute_proposals_hash(&proposals);
+
+    RenamePreview {
         proposals,
         summary,
-        generated_at: Utc::now(),
-        template_used: template_pattern,
+        generated_at: clock.now(),
+        template_used,
         action_summary,
-        reorganization_mode: reorg_mode,
-    })
+        reorganization_mode,
+        integrity_hash,
+        warnings: Vec::new(),
+    }
 }
 
-// =============================================================================
-// Rename Execution
-// =============================================================================
-
-/// Execute batch rename operation on selected proposals
+/// Whether two proposals target names differing only in case, from different folders
 ///
-/// Command name: execute_rename (snake_case per architecture)
-#[tauri::command]
-pub async fn execute_rename(
-    proposals: Vec<RenameProposal>,
-    options: Option<ExecuteRenameOptions>,
-) -> Result<BatchRenameResult, RenameError> {
-    let started_at = Utc::now();
-    let options = options.unwrap_or_default();
+/// Only case-insensitive file systems (the Windows and macOS defaults) make
+/// these collide, so they get a dedicated explanation.
+fn is_case_collision(a: &RenameProposal, b: &RenameProposal) -> bool {
+    let a_path = normalize_path_separators(&a.proposed_path);
+    let b_path = normalize_path_separators(&b.proposed_path);
+    a_path != b_path
+        && a_path.to_lowercase() == b_path.to_lowercase()
+        && Path::new(&a.original_path).parent() != Path::new(&b.original_path).parent()
+}
 
-    // Filter to only rename specified IDs (or all ready if none specified)
-    let selected_ids: Option<HashSet<String>> = options
-        .proposal_ids
-        .map(|ids| ids.into_iter().collect());
+/// Describe a case collision between two proposals of a batch, naming both sources
+fn case_collision_message(proposals: &[RenameProposal], id: &str, other_id: &str) -> Option<String> {
+    let proposal = proposals.iter().find(|p| p.id == id)?;
+    let other = proposals.iter().find(|p| p.id == other_id)?;
+    if !is_case_collision(proposal, other) {
+        return None;
+    }
 
-    // Pre-allocate with known capacity (PERF-008)
-    let mut results: Vec<FileRenameResult> = Vec::with_capacity(proposals.len());
+    let destination = Path::new(&proposal.proposed_path)
+        .parent()
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_default();
+    Some(format!(
+        "\"{}\" (from {}) and \"{}\" (from {}) differ only in case and would be the same file in {}",
+        proposal.proposed_name,
+        proposal.original_path,
+        other.proposed_name,
+        other.original_path,
+        destination
+    ))
+}
 
-    for proposal in &proposals {
-        // Check if this proposal should be processed
-        let should_process = match &selected_ids {
-            Some(ids) => ids.contains(&proposal.id),
-            None => true, // Process all if no IDs specified
-        };
+/// Group proposals whose destinations collide case-insensitively across source folders
+fn find_case_collisions(proposals: &[RenameProposal]) -> Vec<CaseCollision> {
+    let mut groups: Vec<Vec<&RenameProposal>> = Vec::new();
+    let mut group_by_path: HashMap<String, usize> = HashMap::new();
+    // Colliding proposals are already marked as conflicts in a finalized preview
+    let candidates = proposals
+        .iter()
+        .filter(|p| matches!(p.status, RenameStatus::Ready | RenameStatus::Conflict));
+    for proposal in candidates {
+        let key = path_key(&proposal.proposed_path);
+        let index = *group_by_path.entry(key).or_insert_with(|| {
+            groups.push(Vec::new());
+            groups.len() - 1
+        });
+        groups[index].push(proposal);
+    }
 
-        if !should_process {
-            results.push(FileRenameResult {
-                proposal_id: proposal.id.clone(),
-                original_path: proposal.original_path.clone(),
-                original_name: proposal.original_name.clone(),
-                new_path: None,
-                new_name: None,
-                outcome: RenameOutcome::Skipped,
-                error: Some("Not selected".to_string()),
-            });
-            continue;
-        }
+    groups
+        .into_iter()
+        .filter(|group| {
+            group
+                .iter()
+                .enumerate()
+                .any(|(i, a)| group[i + 1..].iter().any(|b| is_case_collision(a, b)))
+        })
+        .map(|group| CaseCollision {
+            destination_path: group[0].proposed_path.clone(),
+            proposal_ids: group.iter().map(|p| p.id.clone()).collect(),
+            original_paths: group.iter().map(|p| p.original_path.clone()).collect(),
+        })
+        .collect()
+}
 
-        // Skip non-ready proposals
-        if proposal.status != RenameStatus::Ready {
-            results.push(FileRenameResult {
-                proposal_id: proposal.id.clone(),
-                original_path: proposal.original_path.clone(),
-                original_name: proposal.original_name.clone(),
-                new_path: None,
-                new_name: None,
-                outcome: RenameOutcome::Skipped,
-                error: Some(format!("Status: {:?}", proposal.status)),
-            });
-            continue;
-        }
+/// Find files from different folders whose new names differ only in case
+///
+/// Each collision lists every file sharing the destination, so the UI can
+/// explain that they would overwrite each other on case-insensitive drives.
+///
+/// Command name: find_destination_case_collisions (snake_case per architecture)
+#[tauri::command]
+pub async fn find_destination_case_collisions(
+    preview: RenamePreview,
+) -> Result<Vec<CaseCollision>, RenameError> {
+    Ok(find_case_collisions(&preview.proposals))
+}
 
-        // Skip if no change needed (and not a folder move)
-        if proposal.original_name == proposal.proposed_name && !proposal.is_folder_move {
-            results.push(FileRenameResult {
-                proposal_id: proposal.id.clone(),
-                original_path: proposal.original_path.clone(),
-                original_name: proposal.original_name.clone(),
-                new_path: None,
-                new_name: None,
-                outcome: RenameOutcome::Skipped,
-                error: Some("No change needed".to_string()),
+/// Group non-conflicting proposals sharing a name across destination folders
+fn find_name_reuse(proposals: &[RenameProposal]) -> Vec<NameReuse> {
+    let mut groups: Vec<Vec<&RenameProposal>> = Vec::new();
+    let mut group_by_name: HashMap<String, usize> = HashMap::new();
+    for proposal in proposals
+        .iter()
+        .filter(|p| matches!(p.status, RenameStatus::Ready | RenameStatus::NoChange))
+    {
+        let index = *group_by_name
+            .entry(proposal.proposed_name.to_lowercase())
+            .or_insert_with(|| {
+                groups.push(Vec::new());
+                groups.len() - 1
             });
-            continue;
-        }
+        groups[index].push(proposal);
+    }
 
-        // Security: Validate proposed path doesn't escape the original file's directory tree
-        // For folder moves, the allowed_base will be the original file's directory
-        // For simple renames, same-directory operations are always allowed
-        if let Err(e) = validate_rename_path(
-            &proposal.original_path,
-            &proposal.proposed_path,
-            None, // Uses original's parent as base
-        ) {
-            results.push(FileRenameResult {
-                proposal_id: proposal.id.clone(),
-                original_path: proposal.original_path.clone(),
-                original_name: proposal.original_name.clone(),
-                new_path: None,
-                new_name: None,
-                outcome: RenameOutcome::Failed,
-                error: Some(format!("Security validation failed: {}", e)),
-            });
-            continue;
-        }
+    let folder = |p: &RenameProposal| {
+        Path::new(&p.proposed_path)
+            .parent()
+            .map(|dir| dir.to_string_lossy().to_string())
+            .unwrap_or_default()
+    };
+    let mut reuses: Vec<NameReuse> = groups
+        .into_iter()
+        .filter_map(|group| {
+            let folders: BTreeSet<String> = group.iter().map(|p| folder(p)).collect();
+            (folders.len() > 1).then(|| NameReuse {
+                proposed_name: group[0].proposed_name.clone(),
+                destination_folders: folders.into_iter().collect(),
+                proposal_ids: group.iter().map(|p| p.id.clone()).collect(),
+            })
+        })
+        .collect();
+    reuses.sort_by(|a, b| a.proposed_name.cmp(&b.proposed_name));
+    reuses
+}
 
-        // Create destination directory if it's a folder move
-        if proposal.is_folder_move {
-            if let Some(parent) = Path::new(&proposal.proposed_path).parent() {
-                if !parent.exists() {
-                    if let Err(e) = fs::create_dir_all(parent) {
-                        results.push(FileRenameResult {
-                            proposal_id: proposal.id.clone(),
-                            original_path: proposal.original_path.clone(),
-                            original_name: proposal.original_name.clone(),
-                            new_path: None,
-                            new_name: None,
-                            outcome: RenameOutcome::Failed,
-                            error: Some(format!("Failed to create directory: {}", e)),
-                        });
-                        continue;
-                    }
-                }
-            }
-        }
+/// List proposed names reused in different destination folders
+///
+/// These files don't conflict, since each lands at its own path; the report
+/// lets the UI explain why the same name shows up more than once.
+///
+/// Command name: name_reuse_report (snake_case per architecture)
+#[tauri::command]
+pub async fn name_reuse_report(preview: RenamePreview) -> Result<Vec<NameReuse>, RenameError> {
+    Ok(find_name_reuse(&preview.proposals))
+}
 
-        // Attempt the rename/move
-        match fs::rename(&proposal.original_path, &proposal.proposed_path) {
-            Ok(_) => {
-                results.push(FileRenameResult {
-                    proposal_id: proposal.id.clone(),
-                    original_path: proposal.original_path.clone(),
-                    original_name: proposal.original_name.clone(),
-                    new_path: Some(proposal.proposed_path.clone()),
-                    new_name: Some(proposal.proposed_name.clone()),
-                    outcome: RenameOutcome::Success,
-                    error: None,
-                });
-            }
-            Err(e) => {
-                results.push(FileRenameResult {
-                    proposal_id: proposal.id.clone(),
-                    original_path: proposal.original_path.clone(),
-                    original_name: proposal.original_name.clone(),
-                    new_path: None,
-                    new_name: None,
-                    outcome: RenameOutcome::Failed,
-                    error: Some(e.to_string()),
-                });
-            }
+/// Report which input paths mix `/` and `\` separators, with their normalized form
+///
+/// Previews normalize the paths they receive the same way; this lets the
+/// frontend check (and store) paths before sending them.
+///
+/// Command name: normalize_input_paths (snake_case per architecture)
+#[tauri::command]
+pub async fn normalize_input_paths(
+    paths: Vec<String>,
+) -> Result<Vec<PathNormalization>, RenameError> {
+    Ok(paths
+        .into_iter()
+        .map(|path| PathNormalization {
+            normalized: normalize_path_separators(&path),
+            mixed_separators: has_mixed_separators(&path),
+            original: path,
+        })
+        .collect())
+}
+
+/// Check whether two paths refer to the same file
+///
+/// Case-only renames (e.g. "photo.JPG" -> "photo.jpg") see their own source
+/// at the target path on case-insensitive filesystems.
+fn is_same_file(a: &str, b: &str) -> bool {
+    if a == b {
+        return true;
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        match (fs::metadata(a), fs::metadata(b)) {
+            (Ok(a), Ok(b)) => a.dev() == b.dev() && a.ino() == b.ino(),
+            _ => false,
         }
     }
 
-    let completed_at = Utc::now();
-    let duration_ms = (completed_at - started_at).num_milliseconds() as u64;
+    #[cfg(not(unix))]
+    {
+        a.eq_ignore_ascii_case(b)
+    }
+}
 
-    let summary = BatchRenameSummary {
-        total: results.len(),
-        succeeded: results.iter().filter(|r| r.outcome == RenameOutcome::Success).count(),
-        failed: results.iter().filter(|r| r.outcome == RenameOutcome::Failed).count(),
-        skipped: results.iter().filter(|r| r.outcome == RenameOutcome::Skipped).count(),
-    };
+/// Sample name used by `list_case_styles` when none is given
+const DEFAULT_CASE_STYLE_SAMPLE: &str = "My Example File";
+
+/// List every case style with a sample name rendered by the engine
+///
+/// Lets the settings UI show exactly what `normalize_case` produces instead
+/// of hardcoded examples. The sample defaults to "My Example File".
+///
+/// Command name: list_case_styles (snake_case per architecture)
+#[tauri::command]
+pub async fn list_case_styles(
+    sample: Option<String>,
+) -> Result<Vec<CaseStyleExample>, RenameError> {
+    let sample = sample
+        .filter(|s| !s.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_CASE_STYLE_SAMPLE.to_string());
+
+    Ok(CaseStyle::ALL
+        .into_iter()
+        .map(|style| CaseStyleExample {
+            example: normalize_case(&sample, &style),
+            style,
+        })
+        .collect())
+}
 
-    let success = summary.failed == 0;
+/// Preview renaming files with only a different case style applied
+///
+/// No template, date or cleanup logic runs: each name goes through
+/// `normalize_filename` alone, so the settings screen can show what flipping
+/// the case normalization preference would do to the last scan.
+///
+/// Command name: preview_case_change (snake_case per architecture)
+#[tauri::command]
+pub async fn preview_case_change(
+    files: Vec<FileInfo>,
+    new_style: CaseStyle,
+) -> Result<RenamePreview, RenameError> {
+    let proposals = files
+        .iter()
+        .map(|file| build_case_change_proposal(file, &new_style))
+        .collect();
 
-    Ok(BatchRenameResult {
-        success,
-        results,
-        summary,
-        started_at,
-        completed_at,
-        duration_ms,
-    })
+    Ok(finalize_preview(proposals, String::new(), ReorganizationMode::RenameOnly))
 }
 
-// =============================================================================
-// Tests
-// =============================================================================
+fn build_case_change_proposal(file: &FileInfo, style: &CaseStyle) -> RenameProposal {
+    let proposed_name = normalize_filename(&file.full_name, style);
+    let proposed_path = join_proposed_path(Path::new(&file.path).parent(), &proposed_name);
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::commands::scanner::{FileCategory, MetadataCapability};
-    use std::fs::File;
-    use std::io::Write;
-    use tempfile::TempDir;
+    let (status, action_type) = if proposed_name == file.full_name {
+        (RenameStatus::NoChange, FileActionType::NoChange)
+    } else {
+        (RenameStatus::Ready, FileActionType::Rename)
+    };
 
-    fn create_test_file_info(name: &str, ext: &str, path: &str) -> FileInfo {
-        FileInfo {
-            path: path.to_string(),
-            name: name.to_string(),
-            extension: ext.to_string(),
-            full_name: if ext.is_empty() {
-                name.to_string()
-            } else {
-                format!("{}.{}", name, ext)
-            },
-            size: 1024,
-            created_at: Utc::now(),
-            modified_at: Utc::now(),
-            relative_path: format!("{}.{}", name, ext),
-            category: FileCategory::Image,
-            metadata_supported: true,
-            metadata_capability: MetadataCapability::Full,
-        }
+    RenameProposal {
+        id: Uuid::new_v4().to_string(),
+        original_path: file.path.clone(),
+        original_name: file.full_name.clone(),
+        proposed_name,
+        proposed_path,
+        status,
+        issues: vec![],
+        metadata_sources: None,
+        is_folder_move: false,
+        destination_folder: None,
+        action_type,
+        conflict: None,
     }
+}
 
-    #[test]
-    fn test_is_valid_filename() {
-        assert!(is_valid_filename("test.jpg"));
-        assert!(is_valid_filename("my-photo_2024.png"));
-        assert!(!is_valid_filename("test/file.jpg")); // Contains /
-        assert!(!is_valid_filename("test:file.jpg")); // Contains :
-        assert!(!is_valid_filename("CON.txt")); // Reserved name
-        assert!(!is_valid_filename("")); // Empty
-        assert!(!is_valid_filename("test.")); // Trailing dot
-    }
+/// Tokens understood by `format_date`, longest first
+const DATE_FORMAT_TOKENS: &[&str] = &["YYYY", "MMMM", "MM", "DD", "HH", "mm", "ss"];
 
-    #[test]
-    fn test_apply_template_basic() {
-        let file = create_test_file_info("photo", "jpg", "/home/user/photo.jpg");
-        let (result, sources) = apply_template(&file, "{name}.{ext}", "YYYY-MM-DD", false);
-        assert_eq!(result, "photo.jpg");
-        assert!(sources.contains(&"filename".to_string()));
-    }
+/// Date rendered by `validate_date_format` (2024-03-15 14:30:45 UTC)
+const DATE_FORMAT_SAMPLE: &str = "2024-03-15T14:30:45Z";
 
-    #[test]
-    fn test_apply_template_with_date() {
-        let mut file = create_test_file_info("photo", "jpg", "/home/user/photo.jpg");
-        file.modified_at = DateTime::parse_from_rfc3339("2024-07-15T10:30:00Z")
+/// Find the parts of a date format that `format_date` wouldn't replace
+///
+/// Letters outside known tokens are copied verbatim, and "%" would be read
+/// by chrono as a directive, so both are reported.
+fn find_unknown_date_tokens(format: &str) -> Vec<String> {
+    let mut unknown: Vec<String> = Vec::new();
+    let mut current = String::new();
+    let mut rest = format;
+
+    while let Some(c) = rest.chars().next() {
+        if let Some(token) = DATE_FORMAT_TOKENS.iter().find(|t| rest.starts_with(*t)) {
+            rest = &rest[token.len()..];
+        } else {
+            rest = &rest[c.len_utf8()..];
+            if c.is_alphabetic() || c == '%' {
+                current.push(c);
+                continue;
+            }
+        }
+        if !current.is_empty() && !unknown.contains(&current) {
+            unknown.push(current.clone());
+        }
+        current.clear();
+    }
+    if !current.is_empty() && !unknown.contains(&current) {
+        unknown.push(current);
+    }
+
+    unknown
+}
+
+/// Check a date format and render it on a sample date
+///
+/// Lets the template editor warn about tokens that would end up verbatim in
+/// filenames (e.g. "QQ" in "YYYY-QQ") before the format is used.
+///
+/// Command name: validate_date_format (snake_case per architecture)
+#[tauri::command]
+pub async fn validate_date_format(format: String) -> Result<DateFormatPreview, RenameError> {
+    let unknown_tokens = find_unknown_date_tokens(&format);
+    let sample = DateTime::parse_from_rfc3339(DATE_FORMAT_SAMPLE)
+        .map_err(|e| RenameError::ValidationFailed(e.to_string()))?
+        .with_timezone(&Utc);
+
+    // A stray "%" can make chrono fail to render: only show safe examples
+    let example = if format.contains('%') {
+        String::new()
+    } else {
+        format_date(&sample, &format, DateLocale::En)
+    };
+
+    Ok(DateFormatPreview {
+        valid: unknown_tokens.is_empty() && !format.trim().is_empty(),
+        example,
+        unknown_tokens,
+    })
+}
+
+/// Result of checking a name template
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateValidation {
+    /// Whether applying the template to its own output keeps the same name
+    pub idempotent: bool,
+    /// Name produced for a sample file ("photo.jpg" modified on 2024-03-15)
+    pub example: String,
+    /// Advisories about the template (e.g. NON_IDEMPOTENT)
+    pub issues: Vec<RenameIssue>,
+}
+
+/// Sample file a template is checked against
+fn template_sample_file() -> FileInfo {
+    let modified_at = DateTime::parse_from_rfc3339(DATE_FORMAT_SAMPLE)
+        .map(|dt| dt.with_timezone(&Utc))
+        .unwrap_or_default();
+    FileInfo {
+        path: "/sample/photo.jpg".to_string(),
+        name: "photo".to_string(),
+        extension: "jpg".to_string(),
+        full_name: "photo.jpg".to_string(),
+        size: 0,
+        created_at: modified_at,
+        modified_at,
+        relative_path: "photo.jpg".to_string(),
+        category: FileCategory::Image,
+        metadata_supported: false,
+        metadata_capability: MetadataCapability::Basic,
+        has_valid_dates: true,
+        metadata: None,
+        stable_id: None,
+    }
+}
+
+/// Apply a template to a sample file, then to its own output
+///
+/// Returns both names; they differ when re-running the template keeps
+/// changing names (e.g. "{date}-{name}" adding another date each run).
+fn apply_template_twice(pattern: &str, strip_existing_patterns: bool) -> (String, String) {
+    let apply = |file: &FileInfo| {
+        apply_template(file, pattern, "YYYY-MM-DD", strip_existing_patterns, DateLocale::En).0
+    };
+
+    let mut file = template_sample_file();
+    let first = apply(&file);
+    let (stem, extension) = split_filename(&first);
+    file.name = stem.to_string();
+    file.extension = extension.trim_start_matches('.').to_string();
+    file.full_name = first.clone();
+    file.path = format!("/sample/{}", first);
+    let second = apply(&file);
+
+    (first, second)
+}
+
+/// Check a name template for problems before it is used
+///
+/// Applies the template to a sample file and again to the result; a name that
+/// keeps changing is flagged NON_IDEMPOTENT with a hint to enable
+/// `strip_existing_patterns`. Folder separators in the template are reported
+/// as FOLDER_IN_NAME_TEMPLATE.
+///
+/// Command name: validate_template (snake_case per architecture)
+#[tauri::command]
+pub async fn validate_template(
+    pattern: String,
+    strip_existing_patterns: Option<bool>,
+) -> Result<TemplateValidation, RenameError> {
+    let strip_existing_patterns = strip_existing_patterns.unwrap_or(false);
+    let (example, reapplied) = apply_template_twice(&pattern, strip_existing_patterns);
+    let idempotent = example == reapplied;
+
+    let mut issues = Vec::new();
+    if !idempotent {
+        let hint = if strip_existing_patterns {
+            "remove the parts that repeat".to_string()
+        } else {
+            "enable strip_existing_patterns to remove existing dates and counters first"
+                .to_string()
+        };
+        issues.push(RenameIssue {
+            code: "NON_IDEMPOTENT".to_string(),
+            message: format!(
+                "Applying the template again changes \"{}\" to \"{}\": {}",
+                example, reapplied, hint
+            ),
+            field: None,
+        });
+    }
+    if let Some(folder) = find_folder_in_name_template(&pattern) {
+        issues.push(RenameIssue {
+            code: "FOLDER_IN_NAME_TEMPLATE".to_string(),
+            message: format!("\"{}/\" in the name template becomes part of the filename", folder),
+            field: Some(folder),
+        });
+    }
+
+    Ok(TemplateValidation {
+        idempotent,
+        example,
+        issues,
+    })
+}
+
+/// How to read an ambiguous DD-MM-YYYY / MM-DD-YYYY date
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "kebab-case")]
+pub enum DateOrder {
+    /// 01-02-2024 is January 2nd (US, default)
+    #[default]
+    MonthFirst,
+    /// 01-02-2024 is February 1st (most of Europe)
+    DayFirst,
+}
+
+/// Read the date matched by one of the date patterns
+///
+/// "DD-MM-YYYY" and "MM-DD-YYYY" are told apart when one part is above 12;
+/// otherwise `order` decides.
+fn parse_filename_date(date: &str, order: DateOrder) -> Option<NaiveDate> {
+    let parts: Vec<&str> = date.split(['-', '_', '.']).collect();
+    let number = |part: &str| part.parse::<u32>().ok();
+
+    match parts.as_slice() {
+        [compact] if compact.len() == 8 => NaiveDate::from_ymd_opt(
+            compact[..4].parse().ok()?,
+            number(&compact[4..6])?,
+            number(&compact[6..])?,
+        ),
+        [year, month, day] if year.len() == 4 => {
+            NaiveDate::from_ymd_opt(year.parse().ok()?, number(month)?, number(day)?)
+        }
+        [first, second, year] => {
+            let (first, second, year) = (number(first)?, number(second)?, year.parse().ok()?);
+            let day_first = first > 12 || (second <= 12 && order == DateOrder::DayFirst);
+            if day_first {
+                NaiveDate::from_ymd_opt(year, second, first)
+            } else {
+                NaiveDate::from_ymd_opt(year, first, second)
+            }
+        }
+        _ => None,
+    }
+}
+
+/// First date in a filename stem, as its byte range and parsed value
+fn find_filename_date(stem: &str, order: DateOrder) -> Option<(std::ops::Range<usize>, NaiveDate)> {
+    COMPILED_DATE_SEPARATED_PATTERNS
+        .iter()
+        .chain(COMPILED_DATE_COMPACT_PATTERNS.iter())
+        .filter_map(|re| re.captures(stem)?.name("date"))
+        .min_by_key(|m| m.start())
+        .and_then(|m| Some((m.range(), parse_filename_date(m.as_str(), order)?)))
+}
+
+fn build_date_format_proposal(
+    file: &FileInfo,
+    target_format: &str,
+    order: DateOrder,
+) -> Option<RenameProposal> {
+    let (range, date) = find_filename_date(&file.name, order)?;
+    let midnight = date.and_time(NaiveTime::MIN).and_utc();
+    let formatted = format_date(&midnight, target_format, DateLocale::En);
+    let stem = format!("{}{}{}", &file.name[..range.start], formatted, &file.name[range.end..]);
+    let proposed_name = if file.extension.is_empty() {
+        stem
+    } else {
+        format!("{}.{}", stem, file.extension)
+    };
+    let proposed_path = join_proposed_path(Path::new(&file.path).parent(), &proposed_name);
+
+    let mut issues = Vec::new();
+    let (status, action_type) = if proposed_name == file.full_name {
+        (RenameStatus::NoChange, FileActionType::NoChange)
+    } else if !is_valid_filename(&proposed_name) {
+        issues.push(RenameIssue {
+            code: "INVALID_NAME".to_string(),
+            message: "Proposed filename contains invalid characters".to_string(),
+            field: None,
+        });
+        (RenameStatus::InvalidName, FileActionType::Error)
+    } else {
+        (RenameStatus::Ready, FileActionType::Rename)
+    };
+
+    Some(RenameProposal {
+        id: Uuid::new_v4().to_string(),
+        original_path: file.path.clone(),
+        original_name: file.full_name.clone(),
+        proposed_name,
+        proposed_path,
+        status,
+        issues,
+        metadata_sources: Some(vec!["filename".to_string()]),
+        is_folder_move: false,
+        destination_folder: None,
+        action_type,
+        conflict: None,
+    })
+}
+
+/// Preview rewriting the dates already in filenames to one format
+///
+/// Finds the first date in each name (YYYY-MM-DD, DD-MM-YYYY, MM-DD-YYYY or
+/// YYYYMMDD, with `-`, `_` or `.` separators) and rewrites it with
+/// `target_format`, leaving the rest of the name alone. Ambiguous day/month
+/// dates follow `date_order` (month first by default). Only files with a
+/// date are included.
+///
+/// Command name: normalize_date_formats_preview (snake_case per architecture)
+#[tauri::command]
+pub async fn normalize_date_formats_preview(
+    files: Vec<FileInfo>,
+    target_format: String,
+    date_order: Option<DateOrder>,
+) -> Result<RenamePreview, RenameError> {
+    let unknown_tokens = find_unknown_date_tokens(&target_format);
+    if target_format.trim().is_empty() || !unknown_tokens.is_empty() {
+        return Err(RenameError::ValidationFailed(format!(
+            "Invalid date format \"{}\" (unknown tokens: {})",
+            target_format,
+            unknown_tokens.join(", ")
+        )));
+    }
+
+    let order = date_order.unwrap_or_default();
+    let proposals = files
+        .iter()
+        .filter_map(|file| build_date_format_proposal(file, &target_format, order))
+        .collect();
+
+    Ok(finalize_preview(proposals, target_format, ReorganizationMode::RenameOnly))
+}
+
+/// Explain why each file in a preview would or wouldn't change
+///
+/// Runs the same logic as `generate_preview` and turns each proposal's
+/// status into a human-readable explanation.
+///
+/// Command name: explain_preview (snake_case per architecture)
+#[tauri::command]
+pub async fn explain_preview(
+    files: Vec<FileInfo>,
+    template_pattern: String,
+    options: Option<GeneratePreviewOptions>,
+) -> Result<Vec<ProposalExplanation>, RenameError> {
+    let preview = generate_preview(files, template_pattern, options).await?;
+
+    Ok(preview
+        .proposals
+        .iter()
+        .map(|proposal| ProposalExplanation {
+            original_path: proposal.original_path.clone(),
+            original_name: proposal.original_name.clone(),
+            rendered_name: proposal.proposed_name.clone(),
+            status: proposal.status.clone(),
+            explanation: explain_proposal(proposal),
+        })
+        .collect())
+}
+
+/// Build a human-readable explanation for a proposal's status
+fn explain_proposal(proposal: &RenameProposal) -> String {
+    let issue_messages = || {
+        proposal
+            .issues
+            .iter()
+            .map(|issue| issue.message.clone())
+            .collect::<Vec<_>>()
+            .join("; ")
+    };
+
+    match proposal.status {
+        RenameStatus::Ready if proposal.is_folder_move => format!(
+            "move: file will be moved to {}",
+            proposal.destination_folder.as_deref().unwrap_or(&proposal.proposed_path)
+        ),
+        RenameStatus::Ready => "rename: template output differs from current name".to_string(),
+        RenameStatus::NoChange => "no change: template output equals current name".to_string(),
+        RenameStatus::MissingData => format!("missing data: {}", issue_messages()),
+        RenameStatus::Conflict => format!("conflict: {}", issue_messages()),
+        RenameStatus::InvalidName => format!("invalid name: {}", issue_messages()),
+    }
+}
+
+// =============================================================================
+// Rename Minimization
+// =============================================================================
+
+/// Whether a proposal's target already holds the same content as its source
+///
+/// Case-only renames see their own source at the target and are not satisfied.
+fn is_already_correct(proposal: &RenameProposal) -> bool {
+    let (source, target) = (&proposal.original_path, &proposal.proposed_path);
+    if Path::new(source) == Path::new(target) || is_same_file(source, target) {
+        return false;
+    }
+
+    let same_size = match (fs::metadata(source), fs::metadata(target)) {
+        (Ok(a), Ok(b)) => a.is_file() && b.is_file() && a.len() == b.len(),
+        _ => false,
+    };
+
+    same_size
+        && matches!(
+            (hash_file(source, None), hash_file(target, None)),
+            (Some(a), Some(b)) if a == b
+        )
+}
+
+/// Drop proposals whose target already contains the file
+///
+/// Re-running an organize on files that were already copied or moved into
+/// place then skips them instead of reporting conflicts or creating duplicates.
+/// When `integrity_hash` is the preview's hash, it is verified and a new hash
+/// is issued for the smaller set, so integrity checks still apply.
+///
+/// Command name: minimize_renames (snake_case per architecture)
+#[tauri::command]
+pub async fn minimize_renames(
+    proposals: Vec<RenameProposal>,
+    integrity_hash: Option<String>,
+) -> Result<MinimizedRenames, RenameError> {
+    if let Some(expected) = &integrity_hash {
+        verify_proposals_hash(&proposals, expected)?;
+    }
+
+    let total = proposals.len();
+    let proposals: Vec<RenameProposal> = proposals
+        .into_iter()
+        .filter(|proposal| !is_already_correct(proposal))
+        .collect();
+
+    Ok(MinimizedRenames {
+        already_correct: total - proposals.len(),
+        integrity_hash: integrity_hash.map(|_| compute_proposals_hash(&proposals)),
+        proposals,
+    })
+}
+
+// =============================================================================
+// Proposal Integrity
+// =============================================================================
+
+/// SHA-256 block size, used for HMAC padding
+const HMAC_BLOCK_SIZE: usize = 64;
+
+lazy_static! {
+    /// Secret keying proposal hashes, generated once per app session
+    ///
+    /// Only previews generated by this process verify, so the frontend can't
+    /// forge a hash for proposals it edited.
+    static ref INTEGRITY_KEY: [u8; 32] = {
+        let mut key = [0u8; 32];
+        key[..16].copy_from_slice(Uuid::new_v4().as_bytes());
+        key[16..].copy_from_slice(Uuid::new_v4().as_bytes());
+        key
+    };
+}
+
+/// HMAC key padded to the block size and XORed with `pad`
+fn hmac_padded_key(pad: u8) -> [u8; HMAC_BLOCK_SIZE] {
+    let mut block = [pad; HMAC_BLOCK_SIZE];
+    for (b, k) in block.iter_mut().zip(INTEGRITY_KEY.iter()) {
+        *b ^= k;
+    }
+    block
+}
+
+/// Compute an HMAC-SHA256 over the fields of a proposal set that affect execution
+///
+/// The key is the session's [`INTEGRITY_KEY`]. Fields are separated with NUL
+/// bytes (invalid in paths) so values can't be shifted between fields to
+/// produce the same hash.
+pub fn compute_proposals_hash(proposals: &[RenameProposal]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(hmac_padded_key(0x36));
+    for proposal in proposals {
+        for field in [
+            proposal.id.as_str(),
+            proposal.original_path.as_str(),
+            proposal.original_name.as_str(),
+            proposal.proposed_path.as_str(),
+            proposal.proposed_name.as_str(),
+        ] {
+            hasher.update(field.as_bytes());
+            hasher.update([0u8]);
+        }
+        hasher.update(proposal.status.as_str().as_bytes());
+        hasher.update([u8::from(proposal.is_folder_move), b'\n']);
+    }
+    let inner = hasher.finalize();
+
+    let mut outer = Sha256::new();
+    outer.update(hmac_padded_key(0x5c));
+    outer.update(inner);
+    outer
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Verify proposals against an integrity hash from `generate_preview`
+fn verify_proposals_hash(proposals: &[RenameProposal], expected: &str) -> Result<(), RenameError> {
+    if compute_proposals_hash(proposals) == expected {
+        Ok(())
+    } else {
+        Err(RenameError::IntegrityCheckFailed(
+            "proposals do not match the previewed set".to_string(),
+        ))
+    }
+}
+
+// =============================================================================
+// Rename Chains
+// =============================================================================
+
+/// How renames in a chain depend on each other
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "kebab-case")]
+pub enum RenameChainKind {
+    /// Each rename targets the source of the next (a → b, b → c), so they must
+    /// run in reverse order
+    Chain,
+    /// The renames form a cycle (a → b, b → a) and need a temporary file
+    Cycle,
+}
+
+/// Files from different folders whose destinations differ only in case
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct CaseCollision {
+    /// Destination of the first file (others differ only in case)
+    pub destination_path: String,
+    pub proposal_ids: Vec<String>,
+    pub original_paths: Vec<String>,
+}
+
+/// An input path with its separators normalized
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct PathNormalization {
+    pub original: String,
+    pub normalized: String,
+    /// The original path used both `/` and `\` as separators
+    pub mixed_separators: bool,
+}
+
+/// A proposed name given to files in several destination folders
+///
+/// Not a conflict: the files end up at different paths.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct NameReuse {
+    /// Proposed name of the first file (others may differ in case)
+    pub proposed_name: String,
+    /// Distinct destination folders, sorted
+    pub destination_folders: Vec<String>,
+    pub proposal_ids: Vec<String>,
+}
+
+/// A group of renames whose targets are the sources of other renames in the batch
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct RenameChain {
+    pub kind: RenameChainKind,
+    /// Proposal IDs in safe execution order. For cycles the first proposal is
+    /// moved to a temporary file first and to its target last.
+    pub proposal_ids: Vec<String>,
+}
+
+/// Proposals (by index) that must be executed together, in order
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenameGroup {
+    /// None for a standalone rename
+    pub kind: Option<RenameChainKind>,
+    pub order: Vec<usize>,
+}
+
+/// Whether a proposal will actually move its file when executed
+pub fn will_rename(proposal: &RenameProposal, selected_ids: Option<&HashSet<String>>) -> bool {
+    selected_ids.is_none_or(|ids| ids.contains(&proposal.id))
+        && proposal.status == RenameStatus::Ready
+        && (proposal.original_name != proposal.proposed_name || proposal.is_folder_move)
+        && proposal.original_path != proposal.proposed_path
+}
+
+/// Group proposals into standalone renames, chains and cycles in execution order
+///
+/// A rename depends on another when its target is the other's source. Paths are
+/// compared case-insensitively, matching batch conflict detection. Groups are
+/// returned in the order of their first proposal in the batch.
+pub fn plan_rename_groups(
+    proposals: &[RenameProposal],
+    selected_ids: Option<&HashSet<String>>,
+) -> Vec<RenameGroup> {
+    let active: Vec<bool> = proposals.iter().map(|p| will_rename(p, selected_ids)).collect();
+
+    let mut source_index: HashMap<String, usize> = HashMap::new();
+    for (i, proposal) in proposals.iter().enumerate().filter(|(i, _)| active[*i]) {
+        source_index.entry(path_key(&proposal.original_path)).or_insert(i);
+    }
+
+    // next[i]: the rename that must vacate i's target first
+    // prev[j]: the rename waiting for j to vacate its source
+    let mut next: Vec<Option<usize>> = vec![None; proposals.len()];
+    let mut prev: Vec<Option<usize>> = vec![None; proposals.len()];
+    for (i, proposal) in proposals.iter().enumerate().filter(|(i, _)| active[*i]) {
+        if let Some(&j) = source_index.get(&path_key(&proposal.proposed_path)) {
+            if j != i && prev[j].is_none() {
+                next[i] = Some(j);
+                prev[j] = Some(i);
+            }
+        }
+    }
+
+    let mut visited = vec![false; proposals.len()];
+    let mut groups = Vec::new();
+
+    for start in 0..proposals.len() {
+        if visited[start] {
+            continue;
+        }
+
+        // Follow targets to the end of the chain, or back to the start for a cycle
+        let mut tail = start;
+        let mut is_cycle = false;
+        for _ in 0..proposals.len() {
+            match next[tail] {
+                Some(j) if j == start => {
+                    is_cycle = true;
+                    break;
+                }
+                Some(j) if !visited[j] => tail = j,
+                _ => break,
+            }
+        }
+
+        // Walk back from the tail: each rename runs once its target is vacated
+        let mut order = vec![if is_cycle { start } else { tail }];
+        let mut current = if is_cycle { prev[start] } else { prev[tail] };
+        while let Some(i) = current {
+            if i == start && is_cycle || visited[i] || order.contains(&i) {
+                break;
+            }
+            order.push(i);
+            current = prev[i];
+        }
+        if !order.contains(&start) {
+            order.push(start);
+        }
+
+        for &i in &order {
+            visited[i] = true;
+        }
+
+        let kind = match (is_cycle, order.len()) {
+            (true, _) => Some(RenameChainKind::Cycle),
+            (false, 1) => None,
+            (false, _) => Some(RenameChainKind::Chain),
+        };
+        groups.push(RenameGroup { kind, order });
+    }
+
+    groups
+}
+
+/// Find renames in a preview that must run in a specific order or through a temporary file
+///
+/// Command name: detect_rename_chains (snake_case per architecture)
+#[tauri::command]
+pub async fn detect_rename_chains(preview: RenamePreview) -> Result<Vec<RenameChain>, RenameError> {
+    Ok(find_rename_chains(&preview.proposals))
+}
+
+fn find_rename_chains(proposals: &[RenameProposal]) -> Vec<RenameChain> {
+    plan_rename_groups(proposals, None)
+        .into_iter()
+        .filter_map(|group| {
+            Some(RenameChain {
+                kind: group.kind?,
+                proposal_ids: group.order.iter().map(|&i| proposals[i].id.clone()).collect(),
+            })
+        })
+        .collect()
+}
+
+// =============================================================================
+// Rename Session Management
+// =============================================================================
+
+/// How long an overwrite confirmation token is accepted
+const CONFIRMATION_TTL_SECONDS: i64 = 300;
+
+/// An overwrite confirmation waiting to be used by `execute_rename`
+struct PendingConfirmation {
+    /// Sorted paths of the files that will be overwritten
+    targets: Vec<String>,
+    expires_at: DateTime<Utc>,
+}
+
+/// State for managing active rename sessions
+///
+/// The frontend supplies the session ID in `ExecuteRenameOptions` so it can
+/// cancel the batch with `cancel_rename` while it is running.
+pub struct RenameState {
+    /// Active rename sessions with their cancellation tokens
+    sessions: Mutex<HashMap<String, CancellationToken>>,
+    /// Issued overwrite confirmation tokens
+    confirmations: Mutex<HashMap<String, PendingConfirmation>>,
+}
+
+impl RenameState {
+    pub fn new() -> Self {
+        Self {
+            sessions: Mutex::new(HashMap::new()),
+            confirmations: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Issue a single-use token confirming that `targets` may be overwritten
+    pub fn issue_confirmation(
+        &self,
+        mut targets: Vec<String>,
+        clock: &dyn Clock,
+    ) -> OverwriteConfirmation {
+        targets.sort();
+        let now = clock.now();
+        let expires_at = now + chrono::Duration::seconds(CONFIRMATION_TTL_SECONDS);
+        let confirmation_token = Uuid::new_v4().to_string();
+
+        let mut confirmations = match self.confirmations.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => {
+                eprintln!("Warning: Confirmation mutex was poisoned, recovering");
+                poisoned.into_inner()
+            }
+        };
+        confirmations.retain(|_, pending| pending.expires_at > now);
+        confirmations.insert(
+            confirmation_token.clone(),
+            PendingConfirmation {
+                targets: targets.clone(),
+                expires_at,
+            },
+        );
+
+        OverwriteConfirmation {
+            confirmation_token,
+            targets,
+            expires_at,
+        }
+    }
+
+    /// Use up a confirmation token, checking it covers exactly `targets`
+    ///
+    /// The token is removed even when the check fails, so a refused batch
+    /// needs a fresh confirmation.
+    pub fn consume_confirmation(
+        &self,
+        token: &str,
+        mut targets: Vec<String>,
+        clock: &dyn Clock,
+    ) -> Result<(), RenameError> {
+        let pending = match self.confirmations.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => {
+                eprintln!("Warning: Confirmation mutex was poisoned during consume");
+                poisoned.into_inner()
+            }
+        }
+        .remove(token)
+        .ok_or_else(|| RenameError::ValidationFailed("Unknown confirmation token".to_string()))?;
+
+        if pending.expires_at <= clock.now() {
+            return Err(RenameError::ValidationFailed("Confirmation token has expired".to_string()));
+        }
+        targets.sort();
+        if pending.targets != targets {
+            return Err(RenameError::ValidationFailed(
+                "Confirmation token doesn't match the files this batch would overwrite".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Register a rename session and return its cancellation token
+    /// If the session already exists, its existing token is returned
+    pub fn register_session(&self, session_id: &str) -> CancellationToken {
+        let mut sessions = match self.sessions.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => {
+                eprintln!("Warning: Rename session mutex was poisoned, recovering");
+                poisoned.into_inner()
+            }
+        };
+
+        sessions
+            .entry(session_id.to_string())
+            .or_default()
+            .clone()
+    }
+
+    /// Cancel a rename session by ID
+    /// Returns false if the session doesn't exist
+    pub fn cancel_session(&self, session_id: &str) -> bool {
+        let sessions = match self.sessions.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => {
+                eprintln!("Warning: Rename session mutex was poisoned during cancel");
+                poisoned.into_inner()
+            }
+        };
+        if let Some(token) = sessions.get(session_id) {
+            token.cancel();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Remove a completed session
+    pub fn remove_session(&self, session_id: &str) {
+        let mut sessions = match self.sessions.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => {
+                eprintln!("Warning: Rename session mutex was poisoned during remove");
+                poisoned.into_inner()
+            }
+        };
+        sessions.remove(session_id);
+    }
+}
+
+impl Default for RenameState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// =============================================================================
+// Rename Execution
+// =============================================================================
+
+/// Execute batch rename operation on selected proposals
+///
+/// When `options.session_id` is set, the batch can be cancelled with
+/// `cancel_rename`. Cancellation stops before the next proposal and returns
+/// the results processed so far; completed renames are not rolled back.
+///
+/// With `options.two_phase`, files are staged first (see
+/// [`execute_two_phase`]) and "rename-staging-progress" events are emitted.
+///
+/// With `options.record_incrementally`, the history entry is written as each
+/// file is moved (including into the staging folder) and finalized at the end.
+///
+/// Command name: execute_rename (snake_case per architecture)
+#[tauri::command]
+pub async fn execute_rename(
+    window: tauri::Window,
+    rename_state: tauri::State<'_, RenameState>,
+    proposals: Vec<RenameProposal>,
+    options: Option<ExecuteRenameOptions>,
+) -> Result<BatchRenameResult, RenameError> {
+    let mut options = options.unwrap_or_default();
+    options.protected_paths = load_protected_paths().await?;
+    if options.overwrite_existing {
+        options.confirmed_overwrites =
+            confirm_overwrites(&rename_state, &proposals, &options, &SystemClock)?;
+    }
+    if options.record_incrementally {
+        // History is best effort: failing to open it must not block the renames
+        match HistoryJournal::open(None) {
+            Ok(journal) => options.history_journal = Some(journal),
+            Err(e) => eprintln!("Warning: Failed to start history entry: {}", e),
+        }
+    }
+    let journal = options.history_journal.clone();
+    let session_id = options.session_id.clone();
+    let cancel_token = session_id
+        .as_deref()
+        .map(|id| rename_state.register_session(id));
+
+    let result = if options.two_phase {
+        let progress = |progress: StagingProgress| {
+            let _ = window.emit("rename-staging-progress", progress);
+        };
+        execute_two_phase(proposals, options, cancel_token.as_ref(), &progress)
+    } else {
+        execute_rename_internal(proposals, options, cancel_token.as_ref())
+    };
+
+    if let Some(id) = session_id {
+        rename_state.remove_session(&id);
+    }
+
+    let mut result = result?;
+    if let Some(journal) = journal {
+        match journal.finish(&result) {
+            Ok(entry) => result.history_entry_id = Some(entry.id),
+            Err(e) => eprintln!("Warning: Failed to record rename in history: {}", e),
+        }
+    }
+    Ok(result)
+}
+
+/// Ask for confirmation before overwriting existing files
+///
+/// Lists the existing files that `execute_rename` with `overwrite_existing`
+/// would move to the trash (file-exists conflicts among the selected
+/// proposals) and returns a single-use token covering exactly those files.
+/// The token expires after five minutes.
+///
+/// Command name: request_overwrite_confirmation (snake_case per architecture)
+#[tauri::command]
+pub async fn request_overwrite_confirmation(
+    rename_state: tauri::State<'_, RenameState>,
+    proposals: Vec<RenameProposal>,
+    proposal_ids: Option<Vec<String>>,
+) -> Result<OverwriteConfirmation, RenameError> {
+    let selected_ids: Option<HashSet<String>> = proposal_ids.map(|ids| ids.into_iter().collect());
+    let targets: Vec<String> = overwrite_candidates(&proposals, selected_ids.as_ref())
+        .map(|p| p.proposed_path.clone())
+        .collect();
+    if targets.is_empty() {
+        return Err(RenameError::ValidationFailed(
+            "No existing files would be overwritten".to_string(),
+        ));
+    }
+    Ok(rename_state.issue_confirmation(targets, &SystemClock))
+}
+
+/// Selected proposals blocked only by a file already at their proposed path
+fn overwrite_candidates<'a>(
+    proposals: &'a [RenameProposal],
+    selected_ids: Option<&'a HashSet<String>>,
+) -> impl Iterator<Item = &'a RenameProposal> {
+    proposals.iter().filter(move |p| {
+        selected_ids.is_none_or(|ids| ids.contains(&p.id))
+            && p.status == RenameStatus::Conflict
+            && p.conflict.as_ref().is_some_and(|c| c.conflict_type == "file-exists")
+    })
+}
+
+/// Check the confirmation token of an overwriting batch
+///
+/// Returns the IDs of the proposals allowed to overwrite their target. A
+/// batch with nothing to overwrite needs no token.
+fn confirm_overwrites(
+    rename_state: &RenameState,
+    proposals: &[RenameProposal],
+    options: &ExecuteRenameOptions,
+    clock: &dyn Clock,
+) -> Result<HashSet<String>, RenameError> {
+    if options.two_phase {
+        return Err(RenameError::ValidationFailed(
+            "Overwriting existing files is not supported in two-phase mode".to_string(),
+        ));
+    }
+
+    let selected_ids: Option<HashSet<String>> = options
+        .proposal_ids
+        .clone()
+        .map(|ids| ids.into_iter().collect());
+    let candidates: Vec<&RenameProposal> =
+        overwrite_candidates(proposals, selected_ids.as_ref()).collect();
+    if candidates.is_empty() {
+        return Ok(HashSet::new());
+    }
+
+    let token = options.confirmation_token.as_deref().ok_or_else(|| {
+        RenameError::ValidationFailed(
+            "Overwriting existing files requires a confirmation token".to_string(),
+        )
+    })?;
+    let targets = candidates.iter().map(|p| p.proposed_path.clone()).collect();
+    rename_state.consume_confirmation(token, targets, clock)?;
+
+    Ok(candidates.iter().map(|p| p.id.clone()).collect())
+}
+
+/// Execute only the ready proposals of a preview
+///
+/// Every other proposal is reported as skipped with its status as the reason,
+/// so the frontend doesn't have to filter. Results follow the preview order.
+///
+/// The preview must still match its `integrity_hash`. With a `session_id`,
+/// the batch can be cancelled with `cancel_rename`.
+///
+/// Command name: execute_ready (snake_case per architecture)
+#[tauri::command]
+pub async fn execute_ready(
+    rename_state: tauri::State<'_, RenameState>,
+    preview: RenamePreview,
+    session_id: Option<String>,
+) -> Result<BatchRenameResult, RenameError> {
+    let options = ExecuteRenameOptions {
+        protected_paths: load_protected_paths().await?,
+        ..Default::default()
+    };
+    let cancel_token = session_id.as_deref().map(|id| rename_state.register_session(id));
+
+    let result = execute_ready_internal(preview, options, cancel_token.as_ref());
+
+    if let Some(id) = session_id {
+        rename_state.remove_session(&id);
+    }
+    result
+}
+
+/// Rename a single file in place, e.g. after an inline edit
+///
+/// The new name must be a valid filename; the file stays in its folder and an
+/// existing file with that name is never replaced (the result fails with
+/// `already-exists`). A successful rename is recorded in history as a
+/// one-file operation.
+///
+/// Command name: rename_one (snake_case per architecture)
+#[tauri::command]
+pub async fn rename_one(
+    original_path: String,
+    new_name: String,
+) -> Result<FileRenameResult, RenameError> {
+    let protected_paths = load_protected_paths().await?;
+    let started_at = Utc::now();
+    let result = rename_one_internal(&original_path, &new_name, &protected_paths)?;
+
+    if result.outcome == RenameOutcome::Success {
+        let batch = finish_batch(vec![result.clone()], started_at, false, Default::default());
+        // The file has already been renamed, so a history failure must not fail it
+        if let Err(e) = record_operation(batch, None).await {
+            eprintln!("Warning: Failed to record rename in history: {}", e);
+        }
+    }
+    Ok(result)
+}
+
+fn rename_one_internal(
+    original_path: &str,
+    new_name: &str,
+    protected_paths: &[String],
+) -> Result<FileRenameResult, RenameError> {
+    if is_blank_name(new_name, "") || !is_valid_filename(new_name) {
+        return Err(RenameError::ValidationFailed(format!("Invalid filename: {:?}", new_name)));
+    }
+
+    let source = Path::new(original_path);
+    let directory = source
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .ok_or_else(|| RenameError::ValidationFailed(format!("Invalid path: {}", original_path)))?;
+    validate_scan_path(&directory.to_string_lossy())?;
+    if !fs::symlink_metadata(source).is_ok_and(|m| m.is_file()) {
+        return Err(RenameError::ValidationFailed(format!("Not a file: {}", original_path)));
+    }
+
+    let original_name = source.file_name().unwrap_or_default().to_string_lossy().to_string();
+    let proposed_path = join_proposed_path(Some(directory), new_name);
+    let proposal = RenameProposal {
+        id: original_path.to_string(),
+        original_path: original_path.to_string(),
+        original_name,
+        proposed_name: new_name.to_string(),
+        proposed_path: proposed_path.clone(),
+        status: RenameStatus::Ready,
+        issues: vec![],
+        metadata_sources: None,
+        is_folder_move: false,
+        destination_folder: None,
+        action_type: FileActionType::Rename,
+        conflict: None,
+    };
+
+    let mut results = Vec::with_capacity(1);
+    let single = std::slice::from_ref(&proposal);
+    if refuse_protected_group(single, &[0], protected_paths, &mut results) {
+        return Ok(results.remove(0));
+    }
+
+    // A case-only rename targets the file itself on case-insensitive drives
+    if Path::new(&proposed_path).exists() && !is_same_file(original_path, &proposed_path) {
+        return Ok(FileRenameResult {
+            proposal_id: proposal.id.clone(),
+            original_path: proposal.original_path.clone(),
+            original_name: proposal.original_name.clone(),
+            new_path: None,
+            new_name: None,
+            outcome: RenameOutcome::Failed,
+            error: Some(format!("A file named {} already exists", new_name)),
+            error_kind: Some(RenameErrorKind::AlreadyExists),
+            symlink: None,
+        });
+    }
+
+    Ok(execute_proposal(&proposal, None, &DirectoryCreationLog::default(), None))
+}
+
+/// Protected path prefixes from the config
+pub(super) async fn load_protected_paths() -> Result<Vec<String>, RenameError> {
+    Ok(get_config()
+        .await
+        .map_err(|e| RenameError::ValidationFailed(format!("Failed to load config: {}", e)))?
+        .protected_paths)
+}
+
+fn execute_ready_internal(
+    preview: RenamePreview,
+    options: ExecuteRenameOptions,
+    cancel_token: Option<&CancellationToken>,
+) -> Result<BatchRenameResult, RenameError> {
+    verify_proposals_hash(&preview.proposals, &preview.integrity_hash)?;
+
+    let order: HashMap<String, usize> = preview
+        .proposals
+        .iter()
+        .enumerate()
+        .map(|(index, p)| (p.id.clone(), index))
+        .collect();
+    let (ready, not_ready): (Vec<_>, Vec<_>) = preview
+        .proposals
+        .into_iter()
+        .partition(|p| p.status == RenameStatus::Ready);
+
+    let mut result = execute_rename_internal(ready, options, cancel_token)?;
+    result.results.extend(not_ready.iter().map(|p| FileRenameResult {
+        proposal_id: p.id.clone(),
+        original_path: p.original_path.clone(),
+        original_name: p.original_name.clone(),
+        new_path: None,
+        new_name: None,
+        outcome: RenameOutcome::Skipped,
+        error: Some(format!("Status: {}", p.status.as_str())),
+        error_kind: None,
+        symlink: None,
+    }));
+    result.results.sort_by_key(|r| order.get(&r.proposal_id).copied());
+    result.summary.total += not_ready.len();
+    result.summary.skipped += not_ready.len();
+    Ok(result)
+}
+
+/// Cancel an in-progress rename batch
+///
+/// Command name: cancel_rename (snake_case per architecture)
+#[tauri::command]
+pub async fn cancel_rename(
+    rename_state: tauri::State<'_, RenameState>,
+    session_id: String,
+) -> Result<bool, String> {
+    Ok(rename_state.cancel_session(&session_id))
+}
+
+/// Fail if any selected proposal has a conflict or an invalid name
+fn check_no_blocking_conflicts(
+    proposals: &[RenameProposal],
+    selected_ids: Option<&HashSet<String>>,
+) -> Result<(), RenameError> {
+    let blocking: Vec<&str> = proposals
+        .iter()
+        .filter(|p| selected_ids.is_none_or(|ids| ids.contains(&p.id)))
+        .filter(|p| matches!(p.status, RenameStatus::Conflict | RenameStatus::InvalidName))
+        .map(|p| p.original_name.as_str())
+        .collect();
+
+    if blocking.is_empty() {
+        return Ok(());
+    }
+
+    Err(RenameError::ValidationFailed(format!(
+        "{} proposal(s) have conflicts or invalid names ({}); nothing was renamed",
+        blocking.len(),
+        blocking.join(", ")
+    )))
+}
+
+/// Internal rename execution with optional cancellation support
+///
+/// Proposals are executed in dependency order: when a target is the source of
+/// another rename in the batch, that rename runs first, and cycles are broken
+/// with a temporary file. Results are reported in execution order.
+fn execute_rename_internal(
+    mut proposals: Vec<RenameProposal>,
+    options: ExecuteRenameOptions,
+    cancel_token: Option<&CancellationToken>,
+) -> Result<BatchRenameResult, RenameError> {
+    let started_at = Utc::now();
+    let selected_ids = check_batch_preconditions(&proposals, &options)?;
+    let (mut symlinks, symlink_refusals) = resolve_symlinks(
+        &mut proposals,
+        selected_ids.as_ref(),
+        options.symlink_rename_policy,
+        options.scan_root.as_deref(),
+    );
+
+    // Confirmed overwrites run like ready proposals once their target is trashed
+    for proposal in &mut proposals {
+        if options.confirmed_overwrites.contains(&proposal.id) {
+            proposal.status = RenameStatus::Ready;
+        }
+    }
+
+    // Pre-allocate with known capacity (PERF-008)
+    let mut results: Vec<FileRenameResult> = Vec::with_capacity(proposals.len());
+    let mut trashed: Vec<String> = Vec::new();
+    let trash_file = options.trash_file.unwrap_or(move_to_trash);
+    let mut cancelled = false;
+    let mut stopped_on_permission_error = false;
+    let directory_log = DirectoryCreationLog::default();
+    let fallback = options.copy_across_devices.then_some(if options.verify_copies {
+        CopyVerification::Hash
+    } else {
+        CopyVerification::Size
+    });
+
+    for group in plan_rename_groups(&proposals, selected_ids.as_ref()) {
+        // Check for cancellation between files (chains and cycles run to completion)
+        if let Some(token) = cancel_token {
+            if token.is_cancelled() {
+                cancelled = true;
+                break;
+            }
+        }
+
+        if refuse_symlink_group(&proposals, &group.order, &symlink_refusals, &mut results) {
+            continue;
+        }
+        if refuse_protected_group(&proposals, &group.order, &options.protected_paths, &mut results) {
+            continue;
+        }
+        let confirmed = &options.confirmed_overwrites;
+        let trashing = TrashLog { trash_file, trashed: &mut trashed };
+        if !trash_overwritten_targets(&proposals, &group.order, confirmed, trashing, &mut results) {
+            continue;
+        }
+
+        let group_start = results.len();
+        let journal = options.history_journal.as_ref();
+        let record = |results: &[FileRenameResult]| {
+            record_progress(journal, results, &directory_log, &trashed);
+        };
+        if group.kind == Some(RenameChainKind::Cycle) {
+            execute_rename_cycle(
+                &proposals,
+                &group.order,
+                &directory_log,
+                fallback,
+                &mut results,
+                &record,
+            );
+        } else {
+            execute_rename_group(
+                &proposals,
+                &group.order,
+                selected_ids.as_ref(),
+                &directory_log,
+                fallback,
+                &mut results,
+                &record,
+            );
+        }
+
+        // A protected destination fails every remaining file the same way
+        if options.stop_on_permission_error
+            && results[group_start..]
+                .iter()
+                .any(|r| r.error_kind == Some(RenameErrorKind::PermissionDenied))
+        {
+            stopped_on_permission_error = true;
+            break;
+        }
+    }
+
+    for result in &mut results {
+        result.symlink = symlinks.remove(&result.proposal_id);
+    }
+    let mut result = finish_batch(results, started_at, cancelled, directory_log);
+    result.stopped_on_permission_error = stopped_on_permission_error;
+    result.trashed_files = trashed;
+    Ok(result)
+}
+
+/// Why a symlinked proposal can't run: outcome, error and error kind
+type SymlinkRefusal = (RenameOutcome, String, Option<RenameErrorKind>);
+
+/// Apply `symlink_rename_policy` to the proposals whose file is a symlink
+///
+/// With `RenameTarget`, a link to a regular file is replaced by its target:
+/// the target is renamed in its own folder, or moved for a folder move, and
+/// the link is left as is. Targets outside `scan_root` (default: the link's
+/// folder) are refused, as are rewritten paths that already exist, since
+/// conflict detection only saw the link. Returns the resolution of each
+/// symlinked proposal and the refusals, both by ID.
+fn resolve_symlinks(
+    proposals: &mut [RenameProposal],
+    selected_ids: Option<&HashSet<String>>,
+    policy: SymlinkRenamePolicy,
+    scan_root: Option<&str>,
+) -> (HashMap<String, SymlinkResolution>, HashMap<String, SymlinkRefusal>) {
+    let mut resolutions = HashMap::new();
+    let mut refusals = HashMap::new();
+    for proposal in proposals.iter_mut() {
+        if !will_rename(proposal, selected_ids) || !Path::new(&proposal.original_path).is_symlink()
+        {
+            continue;
+        }
+
+        let target = fs::canonicalize(&proposal.original_path).ok();
+        let resolution = SymlinkResolution {
+            link_path: proposal.original_path.clone(),
+            target_path: target.as_ref().map(|t| t.to_string_lossy().to_string()),
+            policy,
+        };
+        let refusal = match (policy, target.filter(|t| t.is_file())) {
+            (SymlinkRenamePolicy::RenameLink, _) => None,
+            (SymlinkRenamePolicy::Skip, _) => {
+                Some((RenameOutcome::Skipped, "Symlink skipped".to_string(), None))
+            }
+            (SymlinkRenamePolicy::RenameTarget, None) => Some((
+                RenameOutcome::Failed,
+                "Symlink target is missing or not a file".to_string(),
+                Some(RenameErrorKind::NotFound),
+            )),
+            (SymlinkRenamePolicy::RenameTarget, Some(target)) => {
+                retarget_symlink_proposal(proposal, &target, scan_root).err()
+            }
+        };
+        if let Some(refusal) = refusal {
+            refusals.insert(proposal.id.clone(), refusal);
+        }
+        resolutions.insert(proposal.id.clone(), resolution);
+    }
+    (resolutions, refusals)
+}
+
+/// Point a symlinked proposal at the link's target, if the target may be renamed
+fn retarget_symlink_proposal(
+    proposal: &mut RenameProposal,
+    target: &Path,
+    scan_root: Option<&str>,
+) -> Result<(), SymlinkRefusal> {
+    let link_dir = Path::new(&proposal.original_path).parent().unwrap_or(Path::new(""));
+    let root = fs::canonicalize(scan_root.map(Path::new).unwrap_or(link_dir));
+    if !root.is_ok_and(|root| target.starts_with(root)) {
+        return Err((
+            RenameOutcome::Failed,
+            format!("Symlink target {} is outside the scanned folder", target.display()),
+            Some(RenameErrorKind::Other),
+        ));
+    }
+
+    let proposed_path = if proposal.is_folder_move {
+        PathBuf::from(&proposal.proposed_path)
+    } else {
+        target.parent().unwrap_or(Path::new("")).join(&proposal.proposed_name)
+    };
+    // Renaming the target onto itself (same name, or a case change) is fine
+    let taken = proposed_path.exists()
+        && fs::canonicalize(&proposed_path).is_ok_and(|existing| existing != target);
+    if taken {
+        return Err((
+            RenameOutcome::Failed,
+            format!("{} already exists", proposed_path.display()),
+            Some(RenameErrorKind::AlreadyExists),
+        ));
+    }
+
+    proposal.proposed_path = proposed_path.to_string_lossy().to_string();
+    proposal.original_name = target
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    proposal.original_path = target.to_string_lossy().to_string();
+    Ok(())
+}
+
+/// Refuse a group when the symlink policy rules out one of its proposals
+///
+/// Refused proposals are reported with their refusal, and the rest of a chain
+/// or cycle as blocked. Returns whether the group was refused.
+fn refuse_symlink_group(
+    proposals: &[RenameProposal],
+    order: &[usize],
+    refusals: &HashMap<String, SymlinkRefusal>,
+    results: &mut Vec<FileRenameResult>,
+) -> bool {
+    let refusal = |proposal: &RenameProposal| refusals.get(&proposal.id).cloned();
+    let Some(&blocking) = order.iter().find(|&&i| refusal(&proposals[i]).is_some()) else {
+        return false;
+    };
+
+    for &index in order {
+        let proposal = &proposals[index];
+        let result = match refusal(proposal) {
+            Some((outcome, error, error_kind)) => FileRenameResult {
+                proposal_id: proposal.id.clone(),
+                original_path: proposal.original_path.clone(),
+                original_name: proposal.original_name.clone(),
+                new_path: None,
+                new_name: None,
+                outcome,
+                error: Some(error),
+                error_kind,
+                symlink: None,
+            },
+            None => blocked_result(proposal, &proposals[blocking].original_path),
+        };
+        results.push(result);
+    }
+    true
+}
+
+/// Flush the results to the history journal, if any, right after a file moved
+///
+/// Called before the next move starts, so a crash loses no completed move.
+fn record_progress(
+    journal: Option<&HistoryJournal>,
+    results: &[FileRenameResult],
+    directory_log: &DirectoryCreationLog,
+    trashed: &[String],
+) {
+    let Some(journal) = journal else {
+        return;
+    };
+    if let Err(e) = journal.record(results, directory_log.created(), trashed.to_vec()) {
+        eprintln!("Warning: Failed to record rename progress in history: {}", e);
+    }
+}
+
+/// Check the integrity hash and all-or-nothing mode before touching disk
+///
+/// Returns the selected proposal IDs (`None` when all ready proposals run).
+fn check_batch_preconditions(
+    proposals: &[RenameProposal],
+    options: &ExecuteRenameOptions,
+) -> Result<Option<HashSet<String>>, RenameError> {
+    // Opt-in tamper/staleness detection
+    if let Some(ref expected) = options.integrity_hash {
+        verify_proposals_hash(proposals, expected)?;
+    }
+
+    // Filter to only rename specified IDs (or all ready if none specified)
+    let selected_ids: Option<HashSet<String>> = options
+        .proposal_ids
+        .clone()
+        .map(|ids| ids.into_iter().collect());
+
+    // All-or-nothing mode: check every selected proposal before touching disk
+    if options.fail_on_any_conflict {
+        check_no_blocking_conflicts(proposals, selected_ids.as_ref())?;
+    }
+
+    Ok(selected_ids)
+}
+
+/// Summarize the results of a batch
+fn finish_batch(
+    results: Vec<FileRenameResult>,
+    started_at: DateTime<Utc>,
+    cancelled: bool,
+    directory_log: DirectoryCreationLog,
+) -> BatchRenameResult {
+    let completed_at = Utc::now();
+    let duration_ms = (completed_at - started_at).num_milliseconds() as u64;
+
+    let summary = BatchRenameSummary {
+        total: results.len(),
+        succeeded: results.iter().filter(|r| r.outcome == RenameOutcome::Success).count(),
+        failed: results.iter().filter(|r| r.outcome == RenameOutcome::Failed).count(),
+        skipped: results.iter().filter(|r| r.outcome == RenameOutcome::Skipped).count(),
+    };
+
+    BatchRenameResult {
+        success: summary.failed == 0,
+        results,
+        summary,
+        started_at,
+        completed_at,
+        duration_ms,
+        cancelled,
+        directories_created: directory_log.into_created(),
+        stopped_on_permission_error: false,
+        trashed_files: Vec::new(),
+        history_entry_id: None,
+    }
+}
+
+/// Refuse a group when one of its proposals touches a protected path
+///
+/// Protected proposals fail with the matching prefix; the rest of a chain or
+/// cycle depends on them and is reported as blocked. Returns whether the
+/// group was refused.
+fn refuse_protected_group(
+    proposals: &[RenameProposal],
+    order: &[usize],
+    protected_paths: &[String],
+    results: &mut Vec<FileRenameResult>,
+) -> bool {
+    // The protected path (source first) and the prefix it falls under
+    let protected_prefix = |proposal: &RenameProposal| {
+        [&proposal.original_path, &proposal.proposed_path]
+            .into_iter()
+            .find_map(|path| Some((path.clone(), find_protected_prefix(path, protected_paths)?)))
+    };
+    let Some(&blocking) = order.iter().find(|&&i| protected_prefix(&proposals[i]).is_some()) else {
+        return false;
+    };
+
+    for &index in order {
+        let proposal = &proposals[index];
+        let result = match protected_prefix(proposal) {
+            Some((path, prefix)) => FileRenameResult {
+                proposal_id: proposal.id.clone(),
+                original_path: proposal.original_path.clone(),
+                original_name: proposal.original_name.clone(),
+                new_path: None,
+                new_name: None,
+                outcome: RenameOutcome::Failed,
+                error: Some(format!("Protected path: {} is under {}", path, prefix)),
+                error_kind: Some(RenameErrorKind::ProtectedPath),
+                symlink: None,
+            },
+            None => blocked_result(proposal, &proposals[blocking].original_path),
+        };
+        results.push(result);
+    }
+    true
+}
+
+/// Move a file to the system trash
+pub(super) fn move_to_trash(path: &Path) -> std::io::Result<()> {
+    trash::delete(path).map_err(std::io::Error::other)
+}
+
+/// How overwritten targets are removed, and the ones removed so far
+struct TrashLog<'a> {
+    trash_file: fn(&Path) -> std::io::Result<()>,
+    trashed: &'a mut Vec<String>,
+}
+
+/// Move the existing targets of confirmed overwrites in a group to the trash
+///
+/// Trashed targets are added to the log. When a target can't be trashed, its
+/// proposal fails and the rest of the group is reported as blocked. Returns
+/// whether the group can run.
+fn trash_overwritten_targets(
+    proposals: &[RenameProposal],
+    order: &[usize],
+    confirmed: &HashSet<String>,
+    log: TrashLog,
+    results: &mut Vec<FileRenameResult>,
+) -> bool {
+    let mut failure = None;
+    for &index in order.iter().filter(|&&i| confirmed.contains(&proposals[i].id)) {
+        let target = &proposals[index].proposed_path;
+        match (log.trash_file)(Path::new(target)) {
+            Ok(()) => log.trashed.push(target.clone()),
+            // Gone since the preview: there is nothing left to overwrite
+            Err(_) if !Path::new(target).exists() => {}
+            Err(e) => {
+                failure = Some((index, e));
+                break;
+            }
+        }
+    }
+    let Some((failed, error)) = failure else {
+        return true;
+    };
+
+    for &index in order {
+        let proposal = &proposals[index];
+        let result = if index == failed {
+            FileRenameResult {
+                proposal_id: proposal.id.clone(),
+                original_path: proposal.original_path.clone(),
+                original_name: proposal.original_name.clone(),
+                new_path: None,
+                new_name: None,
+                outcome: RenameOutcome::Failed,
+                error: Some(format!("Failed to move existing file to trash: {}", error)),
+                error_kind: Some(RenameErrorKind::AlreadyExists),
+                symlink: None,
+            }
+        } else {
+            blocked_result(proposal, &proposals[failed].original_path)
+        };
+        results.push(result);
+    }
+    false
+}
+
+/// Execute a standalone rename or a chain in order
+///
+/// A chain stops at the first failure: later renames target a path that is
+/// still occupied.
+fn execute_rename_group(
+    proposals: &[RenameProposal],
+    order: &[usize],
+    selected_ids: Option<&HashSet<String>>,
+    directory_log: &DirectoryCreationLog,
+    fallback: Option<CopyVerification>,
+    results: &mut Vec<FileRenameResult>,
+    record: &dyn Fn(&[FileRenameResult]),
+) {
+    let mut blocked_by: Option<&str> = None;
+    for &index in order {
+        let proposal = &proposals[index];
+        let result = match blocked_by {
+            Some(path) => blocked_result(proposal, path),
+            None => execute_proposal(proposal, selected_ids, directory_log, fallback),
+        };
+        let moved = result.outcome == RenameOutcome::Success;
+        if !moved {
+            blocked_by = blocked_by.or(Some(&proposal.original_path));
+        }
+        results.push(result);
+        if moved {
+            record(results);
+        }
+    }
+}
+
+/// Execute a single proposal, skipping it if it isn't selected, ready or changed
+fn execute_proposal(
+    proposal: &RenameProposal,
+    selected_ids: Option<&HashSet<String>>,
+    directory_log: &DirectoryCreationLog,
+    fallback: Option<CopyVerification>,
+) -> FileRenameResult {
+    // Check if this proposal should be processed
+    let should_process = match selected_ids {
+        Some(ids) => ids.contains(&proposal.id),
+        None => true, // Process all if no IDs specified
+    };
+
+    if !should_process {
+        return FileRenameResult {
+            proposal_id: proposal.id.clone(),
+            original_path: proposal.original_path.clone(),
+            original_name: proposal.original_name.clone(),
+            new_path: None,
+            new_name: None,
+            outcome: RenameOutcome::Skipped,
+            error: Some("Not selected".to_string()),
+            error_kind: None,
+            symlink: None,
+        };
+    }
+
+    // Skip non-ready proposals
+    if proposal.status != RenameStatus::Ready {
+        return FileRenameResult {
+            proposal_id: proposal.id.clone(),
+            original_path: proposal.original_path.clone(),
+            original_name: proposal.original_name.clone(),
+            new_path: None,
+            new_name: None,
+            outcome: RenameOutcome::Skipped,
+            error: Some(format!("Status: {:?}", proposal.status)),
+            error_kind: None,
+            symlink: None,
+        };
+    }
+
+    // Never rename to an empty name, even if the proposal claims to be ready
+    if let Some(result) = blank_name_result(proposal) {
+        return result;
+    }
+
+    // Skip if no change needed (and not a folder move)
+    if proposal.original_name == proposal.proposed_name && !proposal.is_folder_move {
+        return FileRenameResult {
+            proposal_id: proposal.id.clone(),
+            original_path: proposal.original_path.clone(),
+            original_name: proposal.original_name.clone(),
+            new_path: None,
+            new_name: None,
+            outcome: RenameOutcome::Skipped,
+            error: Some("No change needed".to_string()),
+            error_kind: None,
+            symlink: None,
+        };
+    }
+
+    // Security: Validate proposed path doesn't escape the original file's directory tree
+    // For folder moves, the allowed_base will be the original file's directory
+    // For simple renames, same-directory operations are always allowed
+    // Renaming a link never follows it; `symlink_rename_policy` decides which
+    // links get this far
+    let is_link = Path::new(&proposal.original_path).is_symlink();
+    let validation = if is_link {
+        validate_rename_destination(&proposal.original_path, &proposal.proposed_path, None)
+    } else {
+        validate_rename_path(
+            &proposal.original_path,
+            &proposal.proposed_path,
+            None, // Uses original's parent as base
+        )
+    };
+    if let Err(e) = validation {
+        return FileRenameResult {
+            proposal_id: proposal.id.clone(),
+            original_path: proposal.original_path.clone(),
+            original_name: proposal.original_name.clone(),
+            new_path: None,
+            new_name: None,
+            outcome: RenameOutcome::Failed,
+            error: Some(format!("Security validation failed: {}", e)),
+            error_kind: None,
+            symlink: None,
+        };
+    }
+
+    // Create destination directory if it's a folder move
+    if proposal.is_folder_move {
+        if let Some(parent) = Path::new(&proposal.proposed_path).parent() {
+            if let Err(e) = directory_log.ensure_dir(parent) {
+                return FileRenameResult {
+                    proposal_id: proposal.id.clone(),
+                    original_path: proposal.original_path.clone(),
+                    original_name: proposal.original_name.clone(),
+                    new_path: None,
+                    new_name: None,
+                    outcome: RenameOutcome::Failed,
+                    error: Some(format!("Failed to create directory: {}", e)),
+                    error_kind: Some(RenameErrorKind::from_io_error(&e)),
+                    symlink: None,
+                };
+            }
+        }
+    }
+
+    // Attempt the rename/move (a copy would follow a link, so links never fall back)
+    match move_file(
+        Path::new(&proposal.original_path),
+        Path::new(&proposal.proposed_path),
+        fallback.filter(|_| !is_link),
+    ) {
+        Ok(_) => FileRenameResult {
+            proposal_id: proposal.id.clone(),
+            original_path: proposal.original_path.clone(),
+            original_name: proposal.original_name.clone(),
+            new_path: Some(proposal.proposed_path.clone()),
+            new_name: Some(proposal.proposed_name.clone()),
+            outcome: RenameOutcome::Success,
+            error: None,
+            error_kind: None,
+            symlink: None,
+        },
+        Err(e) => FileRenameResult {
+            proposal_id: proposal.id.clone(),
+            original_path: proposal.original_path.clone(),
+            original_name: proposal.original_name.clone(),
+            new_path: None,
+            new_name: None,
+            outcome: RenameOutcome::Failed,
+            error: Some(e.to_string()),
+            error_kind: Some(RenameErrorKind::from_io_error(&e)),
+            symlink: None,
+        },
+    }
+}
+
+// =============================================================================
+// Cross-Device Moves
+// =============================================================================
+
+/// How a cross-device copy is checked before the source is deleted
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CopyVerification {
+    /// Compare file sizes
+    Size,
+    /// Compare sizes and SHA-256 hashes
+    Hash,
+}
+
+/// Rename a file, falling back to copy + delete across volumes when enabled
+fn move_file(from: &Path, to: &Path, fallback: Option<CopyVerification>) -> std::io::Result<()> {
+    match (fs::rename(from, to), fallback) {
+        (Err(e), Some(verification))
+            if RenameErrorKind::from_io_error(&e) == RenameErrorKind::CrossDevice =>
+        {
+            move_by_copy(from, to, verification, std::io::copy)
+        }
+        (result, _) => result,
+    }
+}
+
+/// Copy `from` to `to`, verify the copy, then delete `from`
+///
+/// `to` is created exclusively, so a file that appeared there since the
+/// preview is never overwritten or removed. A copy that fails or doesn't
+/// verify is removed and the source is kept.
+fn move_by_copy(
+    from: &Path,
+    to: &Path,
+    verification: CopyVerification,
+    copy: impl Fn(&mut fs::File, &mut fs::File) -> std::io::Result<u64>,
+) -> std::io::Result<()> {
+    let mut source = fs::File::open(from)?;
+    let mut target = fs::OpenOptions::new().write(true).create_new(true).open(to)?;
+
+    // From here on `to` is ours, so it is removed on any failure
+    let copied = copy(&mut source, &mut target)
+        .and_then(|_| target.set_permissions(source.metadata()?.permissions()))
+        .and_then(|_| target.sync_all());
+    drop(target);
+    if let Err(e) = copied {
+        let _ = fs::remove_file(to);
+        return Err(e);
+    }
+
+    let verify_failed = |message: String| {
+        let _ = fs::remove_file(to);
+        Err(std::io::Error::other(format!(
+            "Cross-device copy verification failed ({}); source kept",
+            message
+        )))
+    };
+
+    let expected = fs::metadata(from)?.len();
+    let copied = fs::metadata(to).map(|m| m.len()).unwrap_or(0);
+    if copied != expected {
+        return verify_failed(format!("copied {} of {} bytes", copied, expected));
+    }
+
+    if verification == CopyVerification::Hash {
+        let source_hash = hash_file(&from.to_string_lossy(), None);
+        if source_hash.is_none() || source_hash != hash_file(&to.to_string_lossy(), None) {
+            return verify_failed("content hash mismatch".to_string());
+        }
+    }
+
+    fs::remove_file(from)
+}
+
+/// Directories created during a batch, recorded exactly once for undo
+///
+/// The existence check, creation and recording happen under one lock, so
+/// concurrent moves into the same new folder can't both claim to create it.
+#[derive(Debug, Default)]
+pub struct DirectoryCreationLog {
+    created: Mutex<Vec<String>>,
+}
+
+impl DirectoryCreationLog {
+    /// Create `dir` (and missing ancestors) if needed, recording each new directory
+    pub fn ensure_dir(&self, dir: &Path) -> std::io::Result<()> {
+        let mut created = match self.created.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => {
+                eprintln!("Warning: Directory creation mutex was poisoned, recovering");
+                poisoned.into_inner()
+            }
+        };
+
+        if dir.exists() {
+            return Ok(());
+        }
+
+        let missing: Vec<String> = dir
+            .ancestors()
+            .take_while(|p| !p.as_os_str().is_empty() && !p.exists())
+            .map(|p| p.to_string_lossy().to_string())
+            .collect();
+
+        fs::create_dir_all(dir)?;
+        created.extend(missing.into_iter().rev());
+        Ok(())
+    }
+
+    /// Copy of the directories created so far, outermost first
+    pub fn created(&self) -> Vec<String> {
+        match self.created.lock() {
+            Ok(guard) => guard.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+
+    /// Directories created so far, outermost first
+    pub fn into_created(self) -> Vec<String> {
+        self.created
+            .into_inner()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Result for a rename that can't run because an earlier rename in its chain failed
+fn blocked_result(proposal: &RenameProposal, blocking_path: &str) -> FileRenameResult {
+    FileRenameResult {
+        proposal_id: proposal.id.clone(),
+        original_path: proposal.original_path.clone(),
+        original_name: proposal.original_name.clone(),
+        new_path: None,
+        new_name: None,
+        outcome: RenameOutcome::Failed,
+        error: Some(format!("Blocked: {} was not renamed out of the way", blocking_path)),
+        error_kind: Some(RenameErrorKind::AlreadyExists),
+        symlink: None,
+    }
+}
+
+/// Failed result for a proposal whose proposed name is blank
+fn blank_name_result(proposal: &RenameProposal) -> Option<FileRenameResult> {
+    let extension = Path::new(&proposal.original_path)
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_string())
+        .unwrap_or_default();
+    is_blank_name(&proposal.proposed_name, &extension).then(|| FileRenameResult {
+        proposal_id: proposal.id.clone(),
+        original_path: proposal.original_path.clone(),
+        original_name: proposal.original_name.clone(),
+        new_path: None,
+        new_name: None,
+        outcome: RenameOutcome::Failed,
+        error: Some("Proposed name is empty".to_string()),
+        error_kind: None,
+        symlink: None,
+    })
+}
+
+/// Temporary path used to break a rename cycle, next to the original file
+pub fn cycle_temp_path(original_path: &str) -> String {
+    let temp_name = format!(".tidy-rename-{}.tmp", Uuid::new_v4());
+    match Path::new(original_path).parent() {
+        Some(parent) => parent.join(temp_name).to_string_lossy().to_string(),
+        None => temp_name,
+    }
+}
+
+/// Execute a rename cycle: move the first file aside, run the rest of the
+/// cycle, then move the first file to its target
+///
+/// If any rename in the cycle fails, the first file is moved back to its
+/// original path when that path is still free. While the first file is at its
+/// temporary path, `record` sees that move as its result.
+fn execute_rename_cycle(
+    proposals: &[RenameProposal],
+    order: &[usize],
+    directory_log: &DirectoryCreationLog,
+    fallback: Option<CopyVerification>,
+    results: &mut Vec<FileRenameResult>,
+    record: &dyn Fn(&[FileRenameResult]),
+) {
+    let first = &proposals[order[0]];
+    let temp_path = cycle_temp_path(&first.original_path);
+
+    // Stage 1: move the first file out of the way
+    let staging = RenameProposal {
+        proposed_path: temp_path.clone(),
+        proposed_name: Path::new(&temp_path)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        ..first.clone()
+    };
+    let staged = execute_proposal(&staging, None, directory_log, fallback);
+    if staged.outcome != RenameOutcome::Success {
+        results.push(FileRenameResult {
+            new_path: None,
+            new_name: None,
+            ..staged
+        });
+        for &index in &order[1..] {
+            results.push(blocked_result(&proposals[index], &first.original_path));
+        }
+        return;
+    }
+    let staged_at = results.len();
+    results.push(staged);
+    record(results);
+
+    // Stage 2: the rest of the cycle, each target vacated by the previous rename
+    let mut blocked_by: Option<&str> = None;
+    for &index in &order[1..] {
+        let proposal = &proposals[index];
+        let result = match blocked_by {
+            Some(path) => blocked_result(proposal, path),
+            None => execute_proposal(proposal, None, directory_log, fallback),
+        };
+        let moved = result.outcome == RenameOutcome::Success;
+        if !moved {
+            blocked_by = blocked_by.or(Some(&proposal.original_path));
+        }
+        results.push(result);
+        if moved {
+            record(results);
+        }
+    }
+
+    // Stage 3: move the first file from the temporary path to its target
+    results.remove(staged_at);
+    if let Some(path) = blocked_by {
+        let restored = !Path::new(&first.original_path).exists()
+            && fs::rename(&temp_path, &first.original_path).is_ok();
+        let mut result = blocked_result(first, path);
+        if !restored {
+            result.error = Some(format!(
+                "{}; file left at temporary path {}",
+                result.error.unwrap_or_default(),
+                temp_path
+            ));
+        }
+        results.push(result);
+        record(results);
+        return;
+    }
+
+    let finishing = RenameProposal {
+        original_path: temp_path,
+        ..first.clone()
+    };
+    results.push(FileRenameResult {
+        original_path: first.original_path.clone(),
+        ..execute_proposal(&finishing, None, directory_log, fallback)
+    });
+    record(results);
+}
+
+// =============================================================================
+// Two-Phase Organize
+// =============================================================================
+
+/// Closest existing directory containing every destination folder
+fn staging_root(proposals: &[&RenameProposal]) -> Option<PathBuf> {
+    let mut common: Option<PathBuf> = None;
+    for parent in proposals.iter().filter_map(|p| Path::new(&p.proposed_path).parent()) {
+        common = Some(match common {
+            None => parent.to_path_buf(),
+            Some(common) => common
+                .components()
+                .zip(parent.components())
+                .take_while(|(a, b)| a == b)
+                .map(|(a, _)| a)
+                .collect(),
+        });
+    }
+    common?.ancestors().find(|dir| dir.is_dir()).map(Path::to_path_buf)
+}
+
+/// Failed result for a proposal of a two-phase organize
+fn staged_failure(
+    proposal: &RenameProposal,
+    error: String,
+    error_kind: Option<RenameErrorKind>,
+) -> FileRenameResult {
+    FileRenameResult {
+        proposal_id: proposal.id.clone(),
+        original_path: proposal.original_path.clone(),
+        original_name: proposal.original_name.clone(),
+        new_path: None,
+        new_name: None,
+        outcome: RenameOutcome::Failed,
+        error: Some(error),
+        error_kind,
+        symlink: None,
+    }
+}
+
+/// Result listing a file as moved to its staging path, for the history journal
+fn staged_result(proposal: &RenameProposal, staged_path: &Path) -> FileRenameResult {
+    FileRenameResult {
+        proposal_id: proposal.id.clone(),
+        original_path: proposal.original_path.clone(),
+        original_name: proposal.original_name.clone(),
+        new_path: Some(staged_path.to_string_lossy().to_string()),
+        new_name: staged_path.file_name().map(|n| n.to_string_lossy().to_string()),
+        outcome: RenameOutcome::Success,
+        error: None,
+        error_kind: None,
+        symlink: None,
+    }
+}
+
+/// Move a staged file back to a path only if nothing took that path
+///
+/// Linking fails when `to` exists, so a file that moved there in the
+/// meantime is never replaced. If the staged copy can't be removed, the link
+/// is undone and the file stays staged. Volumes without hard links (FAT32,
+/// exFAT, many network shares) fall back to a rename once `to` is checked to
+/// be free.
+fn restore_staged(from: &Path, to: &Path) -> std::io::Result<()> {
+    restore_staged_with(from, to, |from, to| fs::hard_link(from, to))
+}
+
+/// [`restore_staged`] with the hard link operation injected
+fn restore_staged_with(
+    from: &Path,
+    to: &Path,
+    hard_link: impl Fn(&Path, &Path) -> std::io::Result<()>,
+) -> std::io::Result<()> {
+    match hard_link(from, to) {
+        Ok(()) => fs::remove_file(from).inspect_err(|_| {
+            let _ = fs::remove_file(to);
+        }),
+        Err(e)
+            if matches!(
+                e.kind(),
+                std::io::ErrorKind::Unsupported | std::io::ErrorKind::PermissionDenied
+            ) =>
+        {
+            if fs::symlink_metadata(to).is_ok() {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::AlreadyExists,
+                    format!("{} already exists", to.display()),
+                ));
+            }
+            fs::rename(from, to)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Move a file without ever replacing what is at `to`, copying across volumes
+///
+/// Within a volume this is [`restore_staged`]. Across volumes the file is
+/// copied to a newly created `to`, its hash checked, then the source deleted.
+pub(super) fn move_no_clobber(from: &Path, to: &Path) -> std::io::Result<()> {
+    match restore_staged(from, to) {
+        Err(e) if RenameErrorKind::from_io_error(&e) == RenameErrorKind::CrossDevice => {
+            move_by_copy(from, to, CopyVerification::Hash, std::io::copy)
+        }
+        result => result,
+    }
+}
+
+/// Execute a batch in two phases through a staging folder
+///
+/// 1. Every file is moved into `.tidy-staging-<uuid>` under the common root
+///    of the destinations, then checked to have arrived with its size.
+/// 2. Staged files are moved to their final paths.
+///
+/// A failure or cancellation during phase 1 moves the staged files back, so
+/// the original tree is untouched. In phase 2 a file that can't be placed is
+/// moved back to its original path if that path is still free (another file
+/// of the batch may have taken it), otherwise it is left in the staging
+/// folder and its path reported; the others still complete. Chains and
+/// cycles need no ordering since every source is vacated first. Cross-device
+/// moves fail while staging.
+///
+/// With a history journal, every move into, out of or back from the staging
+/// folder is recorded before the next one starts. Files still staged are
+/// listed as moved to their staging path, so a crash leaves them undoable.
+fn execute_two_phase(
+    proposals: Vec<RenameProposal>,
+    options: ExecuteRenameOptions,
+    cancel_token: Option<&CancellationToken>,
+    progress: &dyn Fn(StagingProgress),
+) -> Result<BatchRenameResult, RenameError> {
+    let started_at = Utc::now();
+    let selected_ids = check_batch_preconditions(&proposals, &options)?;
+    let directory_log = DirectoryCreationLog::default();
+    let mut results: Vec<FileRenameResult> = Vec::with_capacity(proposals.len());
+
+    // Skipped, protected and unsafe proposals are reported up front
+    let mut runnable: Vec<&RenameProposal> = Vec::new();
+    for (index, proposal) in proposals.iter().enumerate() {
+        if !will_rename(proposal, selected_ids.as_ref()) {
+            results.push(execute_proposal(proposal, selected_ids.as_ref(), &directory_log, None));
+            continue;
+        }
+        if refuse_protected_group(&proposals, &[index], &options.protected_paths, &mut results) {
+            continue;
+        }
+        if let Some(result) = blank_name_result(proposal) {
+            results.push(result);
+            continue;
+        }
+        match validate_rename_path(&proposal.original_path, &proposal.proposed_path, None) {
+            Ok(_) => runnable.push(proposal),
+            Err(e) => results.push(staged_failure(
+                proposal,
+                format!("Security validation failed: {}", e),
+                None,
+            )),
+        }
+    }
+
+    if runnable.is_empty() {
+        return Ok(finish_batch(results, started_at, false, directory_log));
+    }
+
+    let staging_dir = staging_root(&runnable)
+        .ok_or_else(|| RenameError::RenameFailed("No existing destination root".to_string()))?
+        .join(format!(".tidy-staging-{}", Uuid::new_v4()));
+    fs::create_dir(&staging_dir)?;
+
+    let total = runnable.len();
+    let staged_path = |index: usize| staging_dir.join(index.to_string());
+    let record = |results: &[FileRenameResult], still_staged: std::ops::Range<usize>| {
+        let journal = options.history_journal.as_ref();
+        if journal.is_some() {
+            let mut journaled = results.to_vec();
+            journaled.extend(still_staged.map(|i| staged_result(runnable[i], &staged_path(i))));
+            record_progress(journal, &journaled, &directory_log, &[]);
+        }
+    };
+
+    // Phase 1: stage every file and check it arrived
+    let mut staged: Vec<u64> = Vec::with_capacity(total);
+    let mut failure: Option<FileRenameResult> = None;
+    let mut cancelled = false;
+    for (index, proposal) in runnable.iter().enumerate() {
+        if cancel_token.is_some_and(|token| token.is_cancelled()) {
+            cancelled = true;
+            break;
+        }
+
+        let moved = fs::metadata(&proposal.original_path)
+            .and_then(|m| fs::rename(&proposal.original_path, staged_path(index)).map(|_| m.len()));
+        match moved {
+            Ok(size) => {
+                staged.push(size);
+                record(&results, 0..staged.len());
+            }
+            Err(e) => {
+                failure = Some(staged_failure(
+                    proposal,
+                    format!("Staging failed: {}", e),
+                    Some(RenameErrorKind::from_io_error(&e)),
+                ));
+                break;
+            }
+        }
+        progress(StagingProgress { phase: StagingPhase::Staging, processed: index + 1, total });
+    }
+
+    if failure.is_none() && !cancelled {
+        progress(StagingProgress { phase: StagingPhase::Verifying, processed: 0, total });
+        if let Some(index) = (0..staged.len()).find(|&i| {
+            fs::metadata(staged_path(i)).map(|m| m.len()).ok() != Some(staged[i])
+        }) {
+            failure = Some(staged_failure(
+                runnable[index],
+                "Staged file is missing or changed size".to_string(),
+                None,
+            ));
+        }
+    }
+
+    if failure.is_some() || cancelled {
+        let reason = match &failure {
+            Some(failed) => format!("staging failed for {}", failed.original_path),
+            None => "cancelled while staging".to_string(),
+        };
+        for (index, proposal) in runnable.iter().enumerate().take(staged.len()) {
+            let mut error = format!("Rolled back: {}", reason);
+            let original_path = Path::new(&proposal.original_path);
+            if let Err(e) = restore_staged(&staged_path(index), original_path) {
+                error = format!(
+                    "{}; could not move back ({}), file left at {}",
+                    error,
+                    e,
+                    staged_path(index).display()
+                );
+            }
+            progress(StagingProgress {
+                phase: StagingPhase::RollingBack,
+                processed: index + 1,
+                total: staged.len(),
+            });
+            if failure.as_ref().is_none_or(|f| f.proposal_id != proposal.id) {
+                results.push(staged_failure(proposal, error, None));
+            }
+            record(&results, index + 1..staged.len());
+        }
+        results.extend(failure);
+        let _ = fs::remove_dir(&staging_dir);
+        return Ok(finish_batch(results, started_at, cancelled, directory_log));
+    }
+
+    // Phase 2: move staged files into their final paths
+    for (index, proposal) in runnable.iter().enumerate() {
+        let destination = Path::new(&proposal.proposed_path);
+        let placed = match destination.parent().map(|dir| directory_log.ensure_dir(dir)) {
+            Some(Err(e)) => Err(e),
+            _ if destination.exists() => Err(std::io::Error::new(
+                std::io::ErrorKind::AlreadyExists,
+                format!("{} already exists", proposal.proposed_path),
+            )),
+            _ => fs::rename(staged_path(index), destination),
+        };
+
+        results.push(match placed {
+            Ok(()) => FileRenameResult {
+                proposal_id: proposal.id.clone(),
+                original_path: proposal.original_path.clone(),
+                original_name: proposal.original_name.clone(),
+                new_path: Some(proposal.proposed_path.clone()),
+                new_name: Some(proposal.proposed_name.clone()),
+                outcome: RenameOutcome::Success,
+                error: None,
+                error_kind: None,
+                symlink: None,
+            },
+            Err(e) => {
+                let mut error = e.to_string();
+                let original_path = Path::new(&proposal.original_path);
+                if let Err(restore) = restore_staged(&staged_path(index), original_path) {
+                    error = format!(
+                        "{}; could not move back ({}), file left at {}",
+                        error,
+                        restore,
+                        staged_path(index).display()
+                    );
+                }
+                staged_failure(proposal, error, Some(RenameErrorKind::from_io_error(&e)))
+            }
+        });
+        record(&results, index + 1..total);
+        progress(StagingProgress { phase: StagingPhase::Finalizing, processed: index + 1, total });
+    }
+
+    let _ = fs::remove_dir(&staging_dir);
+    Ok(finish_batch(results, started_at, false, directory_log))
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::scanner::{FileCategory, MetadataCapability};
+    use std::fs::File;
+    use std::io::{Read, Write};
+    use tempfile::TempDir;
+
+    fn create_test_file_info(name: &str, ext: &str, path: &str) -> FileInfo {
+        FileInfo {
+            path: path.to_string(),
+            name: name.to_string(),
+            extension: ext.to_string(),
+            full_name: if ext.is_empty() {
+                name.to_string()
+            } else {
+                format!("{}.{}", name, ext)
+            },
+            size: 1024,
+            created_at: Utc::now(),
+            modified_at: Utc::now(),
+            relative_path: format!("{}.{}", name, ext),
+            category: FileCategory::Image,
+            metadata_supported: true,
+            metadata_capability: MetadataCapability::Full,
+            has_valid_dates: true,
+            metadata: None,
+            stable_id: None,
+        }
+    }
+
+    #[test]
+    fn test_is_valid_filename() {
+        assert!(is_valid_filename("test.jpg"));
+        assert!(is_valid_filename("my-photo_2024.png"));
+        assert!(!is_valid_filename("test/file.jpg")); // Contains /
+        assert!(!is_valid_filename("test:file.jpg")); // Contains :
+        assert!(!is_valid_filename("CON.txt")); // Reserved name
+        assert!(!is_valid_filename("")); // Empty
+        assert!(!is_valid_filename("test.")); // Trailing dot
+    }
+
+    #[test]
+    fn test_apply_template_basic() {
+        let file = create_test_file_info("photo", "jpg", "/home/user/photo.jpg");
+        let (result, sources, _) = apply_template(&file, "{name}.{ext}", "YYYY-MM-DD", false, DateLocale::En);
+        assert_eq!(result, "photo.jpg");
+        assert!(sources.contains(&"filename".to_string()));
+    }
+
+    #[test]
+    fn test_apply_template_with_date() {
+        let mut file = create_test_file_info("photo", "jpg", "/home/user/photo.jpg");
+        file.modified_at = DateTime::parse_from_rfc3339("2024-07-15T10:30:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        let (result, sources, _) = apply_template(&file, "{date}_{name}.{ext}", "YYYY-MM-DD", false, DateLocale::En);
+        assert_eq!(result, "2024-07-15_photo.jpg");
+        assert!(sources.contains(&"file-date".to_string()));
+    }
+
+    #[test]
+    fn test_apply_template_without_valid_dates() {
+        let mut file = create_test_file_info("photo", "jpg", "/test/photo.jpg");
+        file.has_valid_dates = false;
+
+        let (result, sources, _) = apply_template(&file, "{date}_{name}", "YYYY-MM-DD", false, DateLocale::En);
+
+        assert_eq!(result, "{date}_photo.jpg");
+        assert!(!sources.contains(&"file-date".to_string()));
+    }
+
+    #[test]
+    fn test_format_date_month_name_locales() {
+        let date = DateTime::parse_from_rfc3339("2024-06-15T10:30:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        assert_eq!(format_date(&date, "DD MMMM YYYY", DateLocale::En), "15 june 2024");
+        assert_eq!(format_date(&date, "DD MMMM YYYY", DateLocale::Fr), "15 juin 2024");
+        // Numeric formats don't depend on the locale
+        assert_eq!(format_date(&date, "YYYY-MM-DD", DateLocale::Fr), "2024-06-15");
+    }
+
+    #[test]
+    fn test_apply_template_month_name_placeholder() {
+        let mut file = create_test_file_info("photo", "jpg", "/test/photo.jpg");
+        file.modified_at = DateTime::parse_from_rfc3339("2024-08-02T10:30:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        let (english, sources, _) =
+            apply_template(&file, "{month-name}_{name}", "YYYY-MM-DD", false, DateLocale::En);
+        let (french, _, _) =
+            apply_template(&file, "{month-name}_{name}", "YYYY-MM-DD", false, DateLocale::Fr);
+
+        assert_eq!(english, "august_photo.jpg");
+        assert_eq!(french, "août_photo.jpg");
+        assert!(sources.contains(&"file-date".to_string()));
+
+        // Without a real date the placeholder is reported as missing
+        file.has_valid_dates = false;
+        let (_, _, unresolved) =
+            apply_template(&file, "{month-name}_{name}", "YYYY-MM-DD", false, DateLocale::Fr);
+        assert_eq!(unresolved, vec!["{month-name}"]);
+    }
+
+    #[test]
+    fn test_unresolved_placeholders_skip_literal_braces() {
+        assert!(find_unresolved_placeholders("{{draft}}-photo.jpg").is_empty());
+        assert!(find_unresolved_placeholders("report}.pdf").is_empty());
+        assert!(find_unresolved_placeholders("notes {v2}}.txt").is_empty());
+        assert_eq!(find_unresolved_placeholders("{{draft}}-{camera}.jpg"), vec!["{camera}"]);
+    }
+
+    #[tokio::test]
+    async fn test_generate_preview_literal_braces_are_ready() {
+        let files = vec![create_test_file_info("photo", "jpg", "/test/photo.jpg")];
+
+        let preview = generate_preview(files, "{{draft}}-{name}.{ext}".to_string(), None)
+            .await
+            .unwrap();
+
+        assert_eq!(preview.proposals[0].status, RenameStatus::Ready);
+        assert!(preview.proposals[0].issues.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_generate_preview_braces_in_original_name_are_not_placeholders() {
+        let files = vec![create_test_file_info("notes {draft}", "txt", "/test/notes {draft}.txt")];
+
+        let preview = generate_preview(files, "{date}-{name}".to_string(), None).await.unwrap();
+
+        let proposal = &preview.proposals[0];
+        assert_eq!(proposal.status, RenameStatus::Ready);
+        assert!(proposal.issues.is_empty());
+        assert!(proposal.proposed_name.contains("draft"));
+    }
+
+    #[tokio::test]
+    async fn test_generate_preview_synthesized_date_is_missing_data() {
+        let mut file = create_test_file_info("photo", "jpg", "/test/photo.jpg");
+        file.has_valid_dates = false;
+
+        let preview = generate_preview(vec![file], "{date}-{year}_{name}".to_string(), None)
+            .await
+            .unwrap();
+
+        let proposal = &preview.proposals[0];
+        assert_eq!(proposal.status, RenameStatus::MissingData);
+        let fields: Vec<_> = proposal.issues.iter().filter_map(|i| i.field.as_deref()).collect();
+        assert_eq!(fields, vec!["{date}", "{year}"]);
+        let today = Utc::now().format("%Y-%m-%d").to_string();
+        assert!(!proposal.proposed_name.contains(&today));
+    }
+
+    #[test]
+    fn test_apply_template_custom_date_format() {
+        let mut file = create_test_file_info("photo", "jpg", "/home/user/photo.jpg");
+        file.modified_at = DateTime::parse_from_rfc3339("2024-07-15T10:30:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        let (result, _, _) = apply_template(&file, "{date:YYYYMMDD}_{name}.{ext}", "YYYY-MM-DD", false, DateLocale::En);
+        assert_eq!(result, "20240715_photo.jpg");
+    }
+
+    #[tokio::test]
+    async fn test_generate_preview_basic() {
+        let files = vec![
+            create_test_file_info("photo1", "jpg", "/tmp/photo1.jpg"),
+            create_test_file_info("photo2", "jpg", "/tmp/photo2.jpg"),
+        ];
+
+        let result = generate_preview(files, "{name}_renamed.{ext}".to_string(), None)
+            .await
+            .unwrap();
+
+        assert_eq!(result.proposals.len(), 2);
+        assert_eq!(result.summary.total, 2);
+        assert_eq!(result.proposals[0].proposed_name, "photo1_renamed.jpg");
+        assert_eq!(result.proposals[1].proposed_name, "photo2_renamed.jpg");
+    }
+
+    fn create_test_template(id: &str, pattern: &str, file_types: Option<Vec<&str>>) -> Template {
+        Template {
+            id: id.to_string(),
+            name: id.to_string(),
+            pattern: pattern.to_string(),
+            file_types: file_types.map(|types| types.iter().map(|t| t.to_string()).collect()),
+            is_default: false,
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+            updated_at: "2024-01-01T00:00:00Z".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_preview_with_template_applies_file_types() {
+        let templates = vec![
+            create_test_template("photos", "photo-{name}.{ext}", Some(vec!["JPG", ".png"])),
+            create_test_template("all", "{name}-all.{ext}", None),
+        ];
+        let files = vec![
+            create_test_file_info("beach", "jpg", "/tmp/beach.jpg"),
+            create_test_file_info("notes", "txt", "/tmp/notes.txt"),
+        ];
+
+        let preview = preview_with_template(files.clone(), &templates, "photos", None)
+            .await
+            .unwrap();
+        assert_eq!(preview.proposals.len(), 1);
+        assert_eq!(preview.proposals[0].proposed_name, "photo-beach.jpg");
+        assert_eq!(preview.template_used, "photo-{name}.{ext}");
+
+        let preview = preview_with_template(files, &templates, "all", None).await.unwrap();
+        assert_eq!(preview.proposals.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_preview_with_template_unknown_id() {
+        let templates = vec![create_test_template("photos", "photo-{name}.{ext}", None)];
+        let files = vec![create_test_file_info("beach", "jpg", "/tmp/beach.jpg")];
+
+        let result = preview_with_template(files, &templates, "missing", None).await;
+
+        assert!(
+            matches!(result, Err(RenameError::ValidationFailed(ref msg)) if msg.contains("missing"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_generate_preview_detects_no_change() {
+        let files = vec![create_test_file_info("photo", "jpg", "/tmp/photo.jpg")];
+
+        let result = generate_preview(files, "{name}.{ext}".to_string(), None)
+            .await
+            .unwrap();
+
+        assert_eq!(result.proposals[0].status, RenameStatus::NoChange);
+        assert_eq!(result.summary.no_change, 1);
+    }
+
+    #[tokio::test]
+    async fn test_generate_preview_detects_conflicts() {
+        let files = vec![
+            create_test_file_info("photo1", "jpg", "/tmp/photo1.jpg"),
+            create_test_file_info("photo2", "jpg", "/tmp/photo2.jpg"),
+        ];
+
+        // Template that produces same output for different files
+        let result = generate_preview(files, "output.{ext}".to_string(), None)
+            .await
+            .unwrap();
+
+        assert_eq!(result.summary.conflicts, 2);
+    }
+
+    #[tokio::test]
+    async fn test_generate_preview_case_collision_across_folders() {
+        let files = vec![
+            create_test_file_info("Photo", "jpg", "/camera/Photo.jpg"),
+            create_test_file_info("photo", "jpg", "/phone/photo.jpg"),
+        ];
+        let options = GeneratePreviewOptions {
+            reorganization_mode: ReorganizationMode::Organize,
+            organize_options: Some(OrganizeOptions {
+                destination_directory: Some("/sorted".to_string()),
+                folder_pattern: "Photos".to_string(),
+                preserve_context: false,
+                context_depth: 1,
+                date_source: FolderDateSource::default(),
+            }),
+            ..Default::default()
+        };
+
+        let preview = generate_preview(files, "{name}.{ext}".to_string(), Some(options))
+            .await
+            .unwrap();
+
+        assert_eq!(preview.summary.conflicts, 2);
+        let conflict = preview.proposals[0].conflict.as_ref().unwrap();
+        assert_eq!(conflict.conflict_type, "case-collision");
+        assert!(conflict.message.contains("/camera/Photo.jpg"));
+        assert!(conflict.message.contains("/phone/photo.jpg"));
+        assert_eq!(preview.proposals[1].issues[0].code, "CASE_COLLISION");
+
+        let collisions = find_destination_case_collisions(preview).await.unwrap();
+        assert_eq!(collisions.len(), 1);
+        assert_eq!(collisions[0].destination_path, "/sorted/Photos/Photo.jpg");
+        assert_eq!(collisions[0].original_paths, vec!["/camera/Photo.jpg", "/phone/photo.jpg"]);
+    }
+
+    #[tokio::test]
+    async fn test_generate_preview_normalizes_mixed_separators() {
+        let files = vec![
+            create_test_file_info("IMG_1", "jpg", "C:\\Photos\\IMG_1.jpg"),
+            create_test_file_info("IMG_2", "jpg", "C:/Photos\\IMG_2.jpg"),
+        ];
+        let options = GeneratePreviewOptions {
+            reorganization_mode: ReorganizationMode::Organize,
+            organize_options: Some(OrganizeOptions {
+                destination_directory: Some("C:\\Sorted/".to_string()),
+                folder_pattern: "Trip".to_string(),
+                preserve_context: false,
+                context_depth: 1,
+                date_source: FolderDateSource::default(),
+            }),
+            ..Default::default()
+        };
+
+        let preview = generate_preview(files, "vacation.{ext}".to_string(), Some(options))
+            .await
+            .unwrap();
+
+        assert_eq!(preview.proposals[0].original_path, "C:/Photos/IMG_1.jpg");
+        assert!(preview
+            .proposals
+            .iter()
+            .all(|p| p.proposed_path == "C:/Sorted/Trip/vacation.jpg"));
+        assert_eq!(preview.summary.conflicts, 2);
+        let conflict = preview.proposals[0].conflict.as_ref().unwrap();
+        assert_eq!(conflict.conflict_type, "duplicate-name");
+    }
+
+    #[tokio::test]
+    async fn test_finalize_preview_detects_conflicts_across_separators() {
+        let proposal = |id: &str, proposed_path: &str| RenameProposal {
+            id: id.to_string(),
+            original_path: format!("C:/Photos/{}.jpg", id),
+            original_name: format!("{}.jpg", id),
+            proposed_name: "vacation.jpg".to_string(),
+            proposed_path: proposed_path.to_string(),
+            status: RenameStatus::Ready,
+            issues: vec![],
+            metadata_sources: None,
+            is_folder_move: false,
+            destination_folder: None,
+            action_type: FileActionType::Rename,
+            conflict: None,
+        };
+        let proposals = vec![
+            proposal("a", "C:/Photos/vacation.jpg"),
+            proposal("b", "C:\\Photos\\vacation.jpg"),
+        ];
+
+        let preview = finalize_preview(proposals, String::new(), ReorganizationMode::RenameOnly);
+
+        assert_eq!(preview.summary.conflicts, 2);
+        let conflict = preview.proposals[1].conflict.as_ref().unwrap();
+        assert_eq!(conflict.conflict_type, "duplicate-name");
+    }
+
+    #[tokio::test]
+    async fn test_normalize_input_paths_reports_mixed_separators() {
+        let paths = vec!["C:\\Photos/IMG_1.jpg".to_string(), "/home/me/a.jpg".to_string()];
+        let normalized = normalize_input_paths(paths).await.unwrap();
+        assert!(normalized[0].mixed_separators);
+        assert_eq!(normalized[0].normalized, "C:/Photos/IMG_1.jpg");
+        assert!(!normalized[1].mixed_separators);
+        assert_eq!(normalized[1].normalized, "/home/me/a.jpg");
+    }
+
+    #[tokio::test]
+    async fn test_exact_duplicate_is_not_case_collision() {
+        let files = vec![
+            create_test_file_info("photo1", "jpg", "/camera/photo1.jpg"),
+            create_test_file_info("photo2", "jpg", "/camera/photo2.jpg"),
+        ];
+
+        let preview = generate_preview(files, "output.{ext}".to_string(), None)
+            .await
+            .unwrap();
+
+        let conflict = preview.proposals[0].conflict.as_ref().unwrap();
+        assert_eq!(conflict.conflict_type, "duplicate-name");
+        assert!(find_destination_case_collisions(preview).await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_build_preview_with_pinned_clock() {
+        let pinned = DateTime::parse_from_rfc3339("2024-03-01T12:00:00Z")
             .unwrap()
             .with_timezone(&Utc);
+        let clock = crate::commands::clock::FixedClock(pinned);
+        let files = vec![create_test_file_info("photo", "jpg", "/tmp/photo.jpg")];
+
+        let template = "{name}-x.{ext}".to_string();
+        let first = build_preview(files.clone(), template.clone(), None, 3, &clock).unwrap();
+        let second = build_preview(files, template, None, 3, &clock).unwrap();
+
+        assert_eq!(first.generated_at, pinned);
+        assert_eq!(second.generated_at, first.generated_at);
+        assert_eq!(first.proposals[0].proposed_name, "photo-x.jpg");
+    }
+
+    async fn preview_with_affixes(
+        files: Vec<FileInfo>,
+        template: &str,
+        prefix: Option<&str>,
+        suffix: Option<&str>,
+    ) -> RenamePreview {
+        let options = GeneratePreviewOptions {
+            prefix: prefix.map(str::to_string),
+            suffix: suffix.map(str::to_string),
+            ..Default::default()
+        };
+        generate_preview(files, template.to_string(), Some(options)).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_generate_preview_prefix_only() {
+        let files = vec![create_test_file_info("report", "pdf", "/tmp/report.pdf")];
+
+        let preview = preview_with_affixes(files, "", Some("2024-"), None).await;
+
+        assert_eq!(preview.proposals[0].proposed_name, "2024-report.pdf");
+        assert_eq!(preview.proposals[0].status, RenameStatus::Ready);
+    }
+
+    #[tokio::test]
+    async fn test_generate_preview_suffix_only() {
+        let files = vec![create_test_file_info("report", "pdf", "/tmp/report.pdf")];
+
+        let preview = preview_with_affixes(files, "", None, Some("-draft")).await;
+
+        assert_eq!(preview.proposals[0].proposed_name, "report-draft.pdf");
+    }
+
+    #[tokio::test]
+    async fn test_generate_preview_prefix_and_suffix_around_template() {
+        let files = vec![
+            create_test_file_info("a", "pdf", "/tmp/a.pdf"),
+            create_test_file_info("b", "pdf", "/tmp/b.pdf"),
+        ];
+
+        let preview = preview_with_affixes(files, "notes.{ext}", Some("x/"), Some("-v2")).await;
+
+        // The separator in the prefix is sanitized, and both files still collide
+        assert_eq!(preview.proposals[0].proposed_name, "x_notes-v2.pdf");
+        assert_eq!(preview.summary.conflicts, 2);
+    }
+
+    #[tokio::test]
+    async fn test_validate_date_format() {
+        let valid = validate_date_format("YYYY-MM-DD".to_string()).await.unwrap();
+        assert!(valid.valid);
+        assert_eq!(valid.example, "2024-03-15");
+        assert!(valid.unknown_tokens.is_empty());
+
+        let invalid = validate_date_format("YYYY-QQ".to_string()).await.unwrap();
+        assert!(!invalid.valid);
+        assert_eq!(invalid.example, "2024-QQ");
+        assert_eq!(invalid.unknown_tokens, vec!["QQ"]);
+    }
+
+    #[test]
+    fn test_find_unknown_date_tokens() {
+        assert!(find_unknown_date_tokens("DD MMMM YYYY HH:mm:ss").is_empty());
+        assert_eq!(find_unknown_date_tokens("YYYYxMM%d"), vec!["x", "%d"]);
+    }
+
+    #[tokio::test]
+    async fn test_generate_preview_detects_missing_data() {
+        let files = vec![create_test_file_info("photo", "jpg", "/tmp/photo.jpg")];
+
+        let result = generate_preview(files, "{camera}_{name}.{ext}".to_string(), None)
+            .await
+            .unwrap();
+
+        assert_eq!(result.proposals[0].status, RenameStatus::MissingData);
+        assert_eq!(result.proposals[0].issues[0].code, "MISSING_DATA");
+        assert_eq!(result.summary.missing_data, 1);
+    }
+
+    #[tokio::test]
+    async fn test_explain_preview_no_change() {
+        let files = vec![create_test_file_info("photo", "jpg", "/tmp/photo.jpg")];
+
+        let explanations = explain_preview(files, "{name}.{ext}".to_string(), None)
+            .await
+            .unwrap();
+
+        assert_eq!(explanations.len(), 1);
+        assert_eq!(explanations[0].status, RenameStatus::NoChange);
+        assert_eq!(explanations[0].rendered_name, "photo.jpg");
+        assert_eq!(explanations[0].explanation, "no change: template output equals current name");
+    }
+
+    #[tokio::test]
+    async fn test_explain_preview_missing_data() {
+        let files = vec![create_test_file_info("photo", "jpg", "/tmp/photo.jpg")];
+
+        let explanations = explain_preview(files, "{camera}_{name}.{ext}".to_string(), None)
+            .await
+            .unwrap();
+
+        assert_eq!(explanations[0].status, RenameStatus::MissingData);
+        assert_eq!(explanations[0].explanation, "missing data: {camera} not found");
+    }
+
+    #[tokio::test]
+    async fn test_execute_rename_success() {
+        let dir = TempDir::new().unwrap();
+        let file_path = dir.path().join("test.jpg");
+        let mut file = File::create(&file_path).unwrap();
+        file.write_all(b"test content").unwrap();
+
+        let proposal = RenameProposal {
+            id: "test-id".to_string(),
+            original_path: file_path.to_string_lossy().to_string(),
+            original_name: "test.jpg".to_string(),
+            proposed_name: "renamed.jpg".to_string(),
+            proposed_path: dir.path().join("renamed.jpg").to_string_lossy().to_string(),
+            status: RenameStatus::Ready,
+            issues: vec![],
+            metadata_sources: None,
+            is_folder_move: false,
+            destination_folder: None,
+            action_type: FileActionType::Rename,
+            conflict: None,
+        };
+
+        let result = execute_rename_internal(vec![proposal], ExecuteRenameOptions::default(), None).unwrap();
+
+        assert!(result.success);
+        assert_eq!(result.summary.succeeded, 1);
+        assert!(dir.path().join("renamed.jpg").exists());
+        assert!(!file_path.exists());
+    }
+
+    #[tokio::test]
+    async fn test_execute_rename_skips_non_ready() {
+        let proposal = RenameProposal {
+            id: "test-id".to_string(),
+            original_path: "/tmp/test.jpg".to_string(),
+            original_name: "test.jpg".to_string(),
+            proposed_name: "renamed.jpg".to_string(),
+            proposed_path: "/tmp/renamed.jpg".to_string(),
+            status: RenameStatus::Conflict,
+            issues: vec![],
+            metadata_sources: None,
+            is_folder_move: false,
+            destination_folder: None,
+            action_type: FileActionType::Conflict,
+            conflict: None,
+        };
+
+        let result = execute_rename_internal(vec![proposal], ExecuteRenameOptions::default(), None).unwrap();
+
+        assert!(result.success);
+        assert_eq!(result.summary.skipped, 1);
+        assert_eq!(result.summary.succeeded, 0);
+    }
+
+    #[tokio::test]
+    async fn test_execute_rename_with_selection() {
+        let dir = TempDir::new().unwrap();
+
+        // Create two files
+        let file1_path = dir.path().join("test1.jpg");
+        let file2_path = dir.path().join("test2.jpg");
+        File::create(&file1_path).unwrap().write_all(b"1").unwrap();
+        File::create(&file2_path).unwrap().write_all(b"2").unwrap();
+
+        let proposals = vec![
+            RenameProposal {
+                id: "id-1".to_string(),
+                original_path: file1_path.to_string_lossy().to_string(),
+                original_name: "test1.jpg".to_string(),
+                proposed_name: "renamed1.jpg".to_string(),
+                proposed_path: dir.path().join("renamed1.jpg").to_string_lossy().to_string(),
+                status: RenameStatus::Ready,
+                issues: vec![],
+                metadata_sources: None,
+                is_folder_move: false,
+                destination_folder: None,
+                action_type: FileActionType::Rename,
+                conflict: None,
+            },
+            RenameProposal {
+                id: "id-2".to_string(),
+                original_path: file2_path.to_string_lossy().to_string(),
+                original_name: "test2.jpg".to_string(),
+                proposed_name: "renamed2.jpg".to_string(),
+                proposed_path: dir.path().join("renamed2.jpg").to_string_lossy().to_string(),
+                status: RenameStatus::Ready,
+                issues: vec![],
+                metadata_sources: None,
+                is_folder_move: false,
+                destination_folder: None,
+                action_type: FileActionType::Rename,
+                conflict: None,
+            },
+        ];
+
+        // Only rename the first file
+        let options = ExecuteRenameOptions {
+            proposal_ids: Some(vec!["id-1".to_string()]),
+            ..Default::default()
+        };
+
+        let result = execute_rename_internal(proposals, options, None).unwrap();
+
+        assert!(result.success);
+        assert_eq!(result.summary.succeeded, 1);
+        assert_eq!(result.summary.skipped, 1);
+        assert!(dir.path().join("renamed1.jpg").exists());
+        assert!(file2_path.exists()); // Second file should not be renamed
+    }
+
+    #[test]
+    fn test_rename_error_kind_from_io_error() {
+        use std::io::{Error, ErrorKind};
+
+        let cases = [
+            (ErrorKind::PermissionDenied, RenameErrorKind::PermissionDenied),
+            (ErrorKind::NotFound, RenameErrorKind::NotFound),
+            (ErrorKind::AlreadyExists, RenameErrorKind::AlreadyExists),
+            (ErrorKind::InvalidInput, RenameErrorKind::Other),
+        ];
+        for (io_kind, expected) in cases {
+            let err = Error::new(io_kind, "synthetic");
+            assert_eq!(RenameErrorKind::from_io_error(&err), expected);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_rename_error_kind_cross_device() {
+        // EXDEV
+        let err = std::io::Error::from_raw_os_error(18);
+        assert_eq!(RenameErrorKind::from_io_error(&err), RenameErrorKind::CrossDevice);
+    }
+
+    /// Source file with 1 KB of content and a destination path next to it
+    fn create_move_pair(dir: &TempDir) -> (std::path::PathBuf, std::path::PathBuf) {
+        let from = dir.path().join("source.bin");
+        let to = dir.path().join("copy.bin");
+        fs::write(&from, vec![7u8; 1024]).unwrap();
+        (from, to)
+    }
+
+    #[test]
+    fn test_move_by_copy_short_copy_keeps_source() {
+        let dir = TempDir::new().unwrap();
+        let (from, to) = create_move_pair(&dir);
+        // Simulate a copy that stops halfway (e.g. the volume filled up)
+        let short_copy = |from: &mut fs::File, to: &mut fs::File| {
+            let mut content = Vec::new();
+            from.read_to_end(&mut content)?;
+            to.write_all(&content[..content.len() / 2])?;
+            Ok(content.len() as u64)
+        };
+
+        let err = move_by_copy(&from, &to, CopyVerification::Size, short_copy).unwrap_err();
+
+        assert!(err.to_string().contains("copied 512 of 1024 bytes"));
+        assert!(err.to_string().contains("source kept"));
+        assert_eq!(fs::read(&from).unwrap().len(), 1024);
+        assert!(!to.exists());
+    }
+
+    #[test]
+    fn test_move_by_copy_hash_verification() {
+        let corrupt_copy = |from: &mut fs::File, to: &mut fs::File| {
+            let mut content = Vec::new();
+            from.read_to_end(&mut content)?;
+            content[0] ^= 0xff;
+            to.write_all(&content)?;
+            Ok(content.len() as u64)
+        };
+
+        // Sizes match, so only hash verification catches the corruption
+        let dir = TempDir::new().unwrap();
+        let (from, to) = create_move_pair(&dir);
+        assert!(move_by_copy(&from, &to, CopyVerification::Hash, corrupt_copy).is_err());
+        assert!(from.exists());
+        assert!(!to.exists());
+
+        assert!(move_by_copy(&from, &to, CopyVerification::Hash, std::io::copy).is_ok());
+        assert!(!from.exists());
+        assert_eq!(fs::read(&to).unwrap(), vec![7u8; 1024]);
+    }
+
+    #[test]
+    fn test_move_by_copy_never_touches_existing_target() {
+        let dir = TempDir::new().unwrap();
+        let (from, to) = create_move_pair(&dir);
+        // Created between the preview and the copy
+        fs::write(&to, "someone else's file").unwrap();
+
+        let err = move_by_copy(&from, &to, CopyVerification::Size, std::io::copy).unwrap_err();
+
+        assert_eq!(err.kind(), std::io::ErrorKind::AlreadyExists);
+        assert_eq!(fs::read_to_string(&to).unwrap(), "someone else's file");
+        assert_eq!(fs::read(&from).unwrap().len(), 1024);
+    }
+
+    #[tokio::test]
+    async fn test_execute_rename_missing_source_reports_not_found() {
+        let dir = TempDir::new().unwrap();
+
+        let proposal = RenameProposal {
+            id: "test-id".to_string(),
+            original_path: dir.path().join("missing.jpg").to_string_lossy().to_string(),
+            original_name: "missing.jpg".to_string(),
+            proposed_name: "renamed.jpg".to_string(),
+            proposed_path: dir.path().join("renamed.jpg").to_string_lossy().to_string(),
+            status: RenameStatus::Ready,
+            issues: vec![],
+            metadata_sources: None,
+            is_folder_move: false,
+            destination_folder: None,
+            action_type: FileActionType::Rename,
+            conflict: None,
+        };
+
+        let result = execute_rename_internal(vec![proposal], ExecuteRenameOptions::default(), None).unwrap();
+
+        assert_eq!(result.summary.failed, 1);
+        assert_eq!(result.results[0].error_kind, Some(RenameErrorKind::NotFound));
+        assert!(result.results[0].error.is_some());
+    }
+
+    async fn create_preview_with_one_conflict(dir: &TempDir) -> RenamePreview {
+        let files: Vec<FileInfo> = ["a", "b", "c"]
+            .iter()
+            .map(|name| {
+                let path = dir.path().join(format!("{}.jpg", name));
+                File::create(&path).unwrap().write_all(name.as_bytes()).unwrap();
+                create_test_file_info(name, "jpg", &path.to_string_lossy())
+            })
+            .collect();
+        // "c" is renamed onto a file that already exists
+        File::create(dir.path().join("taken.jpg")).unwrap();
+
+        let mut preview = generate_preview(files, "renamed-{name}.{ext}".to_string(), None)
+            .await
+            .unwrap();
+        preview.proposals[2].proposed_name = "taken.jpg".to_string();
+        preview.proposals[2].proposed_path =
+            dir.path().join("taken.jpg").to_string_lossy().to_string();
+        preview.proposals[2].status = RenameStatus::Conflict;
+        preview
+    }
+
+    #[tokio::test]
+    async fn test_execute_rename_conflict_skipped_by_default() {
+        let dir = TempDir::new().unwrap();
+        let preview = create_preview_with_one_conflict(&dir).await;
+
+        let result =
+            execute_rename_internal(preview.proposals, ExecuteRenameOptions::default(), None)
+                .unwrap();
+
+        assert_eq!(result.summary.succeeded, 2);
+        assert_eq!(result.summary.skipped, 1);
+        assert!(dir.path().join("renamed-a.jpg").exists());
+        assert!(dir.path().join("c.jpg").exists());
+    }
+
+    #[tokio::test]
+    async fn test_execute_rename_fail_on_any_conflict() {
+        let dir = TempDir::new().unwrap();
+        let preview = create_preview_with_one_conflict(&dir).await;
+
+        let options = ExecuteRenameOptions {
+            fail_on_any_conflict: true,
+            ..Default::default()
+        };
+        let result = execute_rename_internal(preview.proposals.clone(), options, None);
+
+        assert!(
+            matches!(result, Err(RenameError::ValidationFailed(ref msg)) if msg.contains("c.jpg"))
+        );
+        assert!(dir.path().join("a.jpg").exists());
+        assert!(!dir.path().join("renamed-a.jpg").exists());
+
+        // Deselecting the conflicting proposal lets the rest run
+        let options = ExecuteRenameOptions {
+            fail_on_any_conflict: true,
+            proposal_ids: Some(vec![preview.proposals[0].id.clone()]),
+            ..Default::default()
+        };
+        let result = execute_rename_internal(preview.proposals, options, None).unwrap();
+        assert_eq!(result.summary.succeeded, 1);
+    }
+
+    #[tokio::test]
+    async fn test_execute_rename_integrity_hash_untouched_passes() {
+        let dir = TempDir::new().unwrap();
+        let file_path = dir.path().join("photo.jpg");
+        File::create(&file_path).unwrap().write_all(b"test").unwrap();
+
+        let file_info = create_test_file_info("photo", "jpg", &file_path.to_string_lossy());
+        let preview = generate_preview(vec![file_info], "renamed.{ext}".to_string(), None)
+            .await
+            .unwrap();
+
+        let options = ExecuteRenameOptions {
+            integrity_hash: Some(preview.integrity_hash.clone()),
+            ..Default::default()
+        };
+        let result = execute_rename_internal(preview.proposals, options, None).unwrap();
+
+        assert_eq!(result.summary.succeeded, 1);
+        assert!(dir.path().join("renamed.jpg").exists());
+    }
+
+    #[tokio::test]
+    async fn test_integrity_hash_is_keyed() {
+        let file_info = create_test_file_info("photo", "jpg", "/test/photo.jpg");
+        let preview = generate_preview(vec![file_info], "renamed.{ext}".to_string(), None)
+            .await
+            .unwrap();
+
+        // Anyone can compute a plain SHA-256 of the fields; it must not verify
+        let mut hasher = Sha256::new();
+        for proposal in &preview.proposals {
+            for field in [
+                proposal.id.as_str(),
+                proposal.original_path.as_str(),
+                proposal.original_name.as_str(),
+                proposal.proposed_path.as_str(),
+                proposal.proposed_name.as_str(),
+            ] {
+                hasher.update(field.as_bytes());
+                hasher.update([0u8]);
+            }
+            hasher.update(proposal.status.as_str().as_bytes());
+            hasher.update([u8::from(proposal.is_folder_move), b'\n']);
+        }
+        let unkeyed: String = hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect();
+
+        assert_ne!(unkeyed, preview.integrity_hash);
+        assert!(verify_proposals_hash(&preview.proposals, &unkeyed).is_err());
+        assert!(verify_proposals_hash(&preview.proposals, &preview.integrity_hash).is_ok());
+    }
+
+    #[test]
+    fn test_rename_status_as_str_matches_serde() {
+        for status in [
+            RenameStatus::Ready,
+            RenameStatus::Conflict,
+            RenameStatus::MissingData,
+            RenameStatus::NoChange,
+            RenameStatus::InvalidName,
+        ] {
+            let serialized = serde_json::to_string(&status).unwrap();
+            assert_eq!(serialized, format!("\"{}\"", status.as_str()));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execute_rename_integrity_hash_modified_fails() {
+        let dir = TempDir::new().unwrap();
+        let file_path = dir.path().join("photo.jpg");
+        File::create(&file_path).unwrap().write_all(b"test").unwrap();
+
+        let file_info = create_test_file_info("photo", "jpg", &file_path.to_string_lossy());
+        let preview = generate_preview(vec![file_info], "renamed.{ext}".to_string(), None)
+            .await
+            .unwrap();
+
+        // Tamper with the proposal after the preview was generated
+        let mut proposals = preview.proposals;
+        proposals[0].proposed_name = "forged.jpg".to_string();
+        proposals[0].proposed_path = dir.path().join("forged.jpg").to_string_lossy().to_string();
+
+        let options = ExecuteRenameOptions {
+            integrity_hash: Some(preview.integrity_hash),
+            ..Default::default()
+        };
+        let result = execute_rename_internal(proposals, options, None);
+
+        assert!(matches!(result, Err(RenameError::IntegrityCheckFailed(_))));
+        assert!(file_path.exists());
+        assert!(!dir.path().join("forged.jpg").exists());
+    }
+
+    #[tokio::test]
+    async fn test_execute_rename_cancelled_returns_partial_results() {
+        let dir = TempDir::new().unwrap();
+        let file_path = dir.path().join("test.jpg");
+        File::create(&file_path).unwrap().write_all(b"test").unwrap();
+
+        let proposal = RenameProposal {
+            id: "test-id".to_string(),
+            original_path: file_path.to_string_lossy().to_string(),
+            original_name: "test.jpg".to_string(),
+            proposed_name: "renamed.jpg".to_string(),
+            proposed_path: dir.path().join("renamed.jpg").to_string_lossy().to_string(),
+            status: RenameStatus::Ready,
+            issues: vec![],
+            metadata_sources: None,
+            is_folder_move: false,
+            destination_folder: None,
+            action_type: FileActionType::Rename,
+            conflict: None,
+        };
+
+        let state = RenameState::new();
+        let token = state.register_session("session-1");
+        assert!(state.cancel_session("session-1"));
+
+        let result = execute_rename_internal(vec![proposal], ExecuteRenameOptions::default(), Some(&token)).unwrap();
+
+        assert!(result.cancelled);
+        assert!(result.results.is_empty());
+        assert_eq!(result.summary.total, 0);
+        assert!(file_path.exists()); // Nothing renamed after cancellation
+        assert!(!dir.path().join("renamed.jpg").exists());
+    }
+
+    #[test]
+    fn test_rename_state_sessions() {
+        let state = RenameState::new();
+
+        // Unknown sessions can't be cancelled
+        assert!(!state.cancel_session("missing"));
+
+        // Registering twice returns the same token
+        let token = state.register_session("session-1");
+        let same = state.register_session("session-1");
+        assert!(state.cancel_session("session-1"));
+        assert!(token.is_cancelled());
+        assert!(same.is_cancelled());
+
+        state.remove_session("session-1");
+        assert!(!state.cancel_session("session-1"));
+    }
+
+    // =============================================================================
+    // Sanitization Tests
+    // =============================================================================
+
+    #[test]
+    fn test_sanitize_filename_no_change() {
+        let result = sanitize_filename("valid_filename.jpg", '_');
+        assert_eq!(result.sanitized, "valid_filename.jpg");
+        assert!(!result.was_modified);
+        assert!(result.changes.is_empty());
+    }
+
+    #[test]
+    fn test_sanitize_filename_replaces_invalid_chars() {
+        let result = sanitize_filename("photo:2024.jpg", '_');
+        assert_eq!(result.sanitized, "photo_2024.jpg");
+        assert!(result.was_modified);
+        assert_eq!(result.changes.len(), 1);
+        assert_eq!(result.changes[0].change_type, "char_replacement");
+    }
+
+    #[test]
+    fn test_sanitize_filename_collapses_multiple_replacements() {
+        let result = sanitize_filename("test::file.jpg", '_');
+        assert_eq!(result.sanitized, "test_file.jpg");
+        assert!(result.was_modified);
+    }
+
+    #[test]
+    fn test_sanitize_filename_handles_reserved_names() {
+        let result = sanitize_filename("CON.txt", '_');
+        assert_eq!(result.sanitized, "CON_file.txt");
+        assert!(result.was_modified);
+        assert!(result.changes.iter().any(|c| c.change_type == "reserved_name"));
+    }
+
+    #[test]
+    fn test_sanitize_filename_fixes_trailing_spaces() {
+        let result = sanitize_filename("test .jpg", '_');
+        assert_eq!(result.sanitized, "test.jpg");
+        assert!(result.was_modified);
+    }
+
+    #[test]
+    fn test_sanitize_filename_fixes_trailing_dots() {
+        let result = sanitize_filename("test..jpg", '_');
+        assert_eq!(result.sanitized, "test.jpg");
+        assert!(result.was_modified);
+    }
+
+    #[test]
+    fn test_split_filename() {
+        assert_eq!(split_filename("file.txt"), ("file".to_string(), ".txt".to_string()));
+        assert_eq!(split_filename("file.tar.gz"), ("file.tar".to_string(), ".gz".to_string()));
+        assert_eq!(split_filename(".gitignore"), (".gitignore".to_string(), String::new()));
+        assert_eq!(split_filename("noextension"), ("noextension".to_string(), String::new()));
+        assert_eq!(split_filename(""), (String::new(), String::new()));
+    }
+
+    #[test]
+    fn test_apply_template_sanitizes_output() {
+        // Create a file with invalid characters in the name
+        let file = create_test_file_info("photo:test", "jpg", "/home/user/photo:test.jpg");
+        let (result, _, _) = apply_template(&file, "{name}.{ext}", "YYYY-MM-DD", false, DateLocale::En);
+        // The sanitization should replace : with _
+        assert_eq!(result, "photo_test.jpg");
+    }
+
+    // =============================================================================
+    // Case Normalization Tests
+    // =============================================================================
+
+    #[test]
+    fn test_split_into_words_simple() {
+        let words = split_into_words("hello world");
+        assert_eq!(words, vec!["hello", "world"]);
+    }
+
+    #[test]
+    fn test_split_into_words_with_separators() {
+        let words = split_into_words("hello-world_test");
+        assert_eq!(words, vec!["hello", "world", "test"]);
+    }
+
+    #[test]
+    fn test_split_into_words_camel_case() {
+        let words = split_into_words("helloWorldTest");
+        assert_eq!(words, vec!["hello", "World", "Test"]);
+    }
+
+    #[test]
+    fn test_split_into_words_pascal_case() {
+        let words = split_into_words("HelloWorldTest");
+        assert_eq!(words, vec!["Hello", "World", "Test"]);
+    }
+
+    #[test]
+    fn test_capitalize_word() {
+        assert_eq!(capitalize_word("hello"), "Hello");
+        assert_eq!(capitalize_word("HELLO"), "Hello");
+        assert_eq!(capitalize_word(""), "");
+    }
+
+    #[test]
+    fn test_normalize_case_none() {
+        assert_eq!(normalize_case("Hello World", &CaseStyle::None), "Hello World");
+    }
+
+    #[test]
+    fn test_normalize_case_lowercase() {
+        assert_eq!(normalize_case("Hello World", &CaseStyle::Lowercase), "hello world");
+    }
+
+    #[test]
+    fn test_normalize_case_uppercase() {
+        assert_eq!(normalize_case("Hello World", &CaseStyle::Uppercase), "HELLO WORLD");
+    }
+
+    #[test]
+    fn test_normalize_case_capitalize() {
+        assert_eq!(normalize_case("hello world", &CaseStyle::Capitalize), "Hello world");
+        assert_eq!(normalize_case("HELLO WORLD", &CaseStyle::Capitalize), "Hello world");
+    }
+
+    #[test]
+    fn test_normalize_case_title_case() {
+        assert_eq!(normalize_case("hello world", &CaseStyle::TitleCase), "Hello World");
+    }
+
+    #[test]
+    fn test_normalize_case_kebab_case() {
+        assert_eq!(normalize_case("Hello World", &CaseStyle::KebabCase), "hello-world");
+        assert_eq!(normalize_case("helloWorld", &CaseStyle::KebabCase), "hello-world");
+    }
+
+    #[test]
+    fn test_normalize_case_snake_case() {
+        assert_eq!(normalize_case("Hello World", &CaseStyle::SnakeCase), "hello_world");
+        assert_eq!(normalize_case("helloWorld", &CaseStyle::SnakeCase), "hello_world");
+    }
+
+    #[test]
+    fn test_normalize_case_camel_case() {
+        assert_eq!(normalize_case("hello world", &CaseStyle::CamelCase), "helloWorld");
+        assert_eq!(normalize_case("Hello World", &CaseStyle::CamelCase), "helloWorld");
+    }
+
+    #[test]
+    fn test_normalize_case_pascal_case() {
+        assert_eq!(normalize_case("hello world", &CaseStyle::PascalCase), "HelloWorld");
+    }
+
+    #[test]
+    fn test_normalize_filename_preserves_extension() {
+        assert_eq!(normalize_filename("Hello World.JPG", &CaseStyle::KebabCase), "hello-world.jpg");
+        assert_eq!(normalize_filename("My Document.PDF", &CaseStyle::SnakeCase), "my_document.pdf");
+    }
+
+    #[test]
+    fn test_normalize_filename_handles_hidden_files() {
+        assert_eq!(normalize_filename(".Hidden File.txt", &CaseStyle::KebabCase), ".hidden-file.txt");
+    }
+
+    #[test]
+    fn test_normalize_filename_none_style() {
+        assert_eq!(normalize_filename("Hello World.JPG", &CaseStyle::None), "Hello World.JPG");
+    }
+
+    #[tokio::test]
+    async fn test_generate_preview_with_case_normalization() {
+        let files = vec![create_test_file_info("My Photo", "JPG", "/tmp/My Photo.JPG")];
+
+        let options = GeneratePreviewOptions {
+            case_style: CaseStyle::KebabCase,
+            ..Default::default()
+        };
+
+        let result = generate_preview(files, "{name}.{ext}".to_string(), Some(options))
+            .await
+            .unwrap();
+
+        assert_eq!(result.proposals[0].proposed_name, "my-photo.jpg");
+    }
+
+    // =============================================================================
+    // Pattern Stripping Tests
+    // =============================================================================
+
+    #[test]
+    fn test_strip_diacritics() {
+        assert_eq!(strip_diacritics("Été à la plage"), "Ete a la plage");
+        assert_eq!(strip_diacritics("Straße Œuvre"), "Strasse OEuvre");
+        assert_eq!(strip_diacritics("plain"), "plain");
+    }
+
+    #[test]
+    fn test_clean_filename_no_patterns() {
+        assert_eq!(clean_filename("photo"), "photo");
+        assert_eq!(clean_filename("my-vacation-pic"), "my-vacation-pic");
+        assert_eq!(clean_filename("document"), "document");
+    }
+
+    #[test]
+    fn test_clean_filename_iso_date_prefix() {
+        // ISO format YYYY-MM-DD at start
+        assert_eq!(clean_filename("2024-01-15_photo"), "photo");
+        assert_eq!(clean_filename("2024-01-15-photo"), "photo");
+        assert_eq!(clean_filename("2024_01_15_photo"), "photo");
+        assert_eq!(clean_filename("2024.01.15_photo"), "photo");
+    }
+
+    #[test]
+    fn test_clean_filename_european_date_prefix() {
+        // European format DD-MM-YYYY at start
+        assert_eq!(clean_filename("15-01-2024_photo"), "photo");
+        assert_eq!(clean_filename("15_01_2024_photo"), "photo");
+        assert_eq!(clean_filename("15.01.2024_photo"), "photo");
+    }
+
+    #[test]
+    fn test_clean_filename_compact_date_prefix() {
+        // Compact YYYYMMDD at start
+        assert_eq!(clean_filename("20240115_photo"), "photo");
+        assert_eq!(clean_filename("20240115-photo"), "photo");
+        assert_eq!(clean_filename("20240115.photo"), "photo");
+    }
+
+    #[test]
+    fn test_clean_filename_date_suffix() {
+        // Dates at end
+        assert_eq!(clean_filename("photo_2024-01-15"), "photo");
+        assert_eq!(clean_filename("photo-2024-01-15"), "photo");
+        assert_eq!(clean_filename("photo_20240115"), "photo");
+        assert_eq!(clean_filename("photo.2024.01.15"), "photo");
+    }
+
+    #[test]
+    fn test_clean_filename_date_in_middle() {
+        // NEW: Dates in the middle of filename
+        assert_eq!(clean_filename("photo_2024-01-15_vacation"), "photo_vacation");
+        assert_eq!(clean_filename("IMG_20240115_edited"), "IMG_edited");
+        assert_eq!(clean_filename("trip_15-01-2024_memories"), "trip_memories");
+        assert_eq!(clean_filename("scan_2024.01.15_document"), "scan_document");
+    }
+
+    #[test]
+    fn test_clean_filename_multiple_dates() {
+        // Multiple dates should all be removed
+        assert_eq!(clean_filename("2024-01-15_trip_2024-01-20"), "trip");
+        assert_eq!(clean_filename("photo_20240115_20240120"), "photo");
+    }
+
+    #[test]
+    fn test_clean_filename_datetime_camera_format() {
+        // Camera format: YYYYMMDD_HHMMSS
+        assert_eq!(clean_filename("IMG_20240115_103045"), "IMG");
+        assert_eq!(clean_filename("20240115_103045_photo"), "photo");
+        assert_eq!(clean_filename("photo_20240115_103045_edited"), "photo_edited");
+        // Separated date + time
+        assert_eq!(clean_filename("2024-01-15_103045_photo"), "photo");
+    }
+
+    #[test]
+    fn test_clean_filename_counter_suffix() {
+        // Counter patterns at end
+        assert_eq!(clean_filename("photo_001"), "photo");
+        assert_eq!(clean_filename("photo-02"), "photo");
+        assert_eq!(clean_filename("photo(3)"), "photo");
+        assert_eq!(clean_filename("photo (1)"), "photo");  // Windows style
+        assert_eq!(clean_filename("document_0001"), "document");
+    }
+
+    #[test]
+    fn test_clean_filename_date_and_counter() {
+        // Combined date + counter
+        assert_eq!(clean_filename("2024-01-15_photo_001"), "photo");
+        assert_eq!(clean_filename("photo_2024-01-15_001"), "photo");
+        assert_eq!(clean_filename("IMG_20240115_103045_001"), "IMG");
+    }
+
+    #[test]
+    fn test_clean_filename_preserves_non_date_numbers() {
+        // Numbers that aren't dates should be preserved
+        assert_eq!(clean_filename("photo123"), "photo123");
+        assert_eq!(clean_filename("vacation2024"), "vacation2024");
+        assert_eq!(clean_filename("room101"), "room101");
+        assert_eq!(clean_filename("v2_final"), "v2_final");
+        // Year alone (4 digits) should NOT be treated as date
+        assert_eq!(clean_filename("report_2024_annual"), "report_2024_annual");
+    }
+
+    #[test]
+    fn test_clean_filename_invalid_dates_preserved() {
+        // Invalid month (13) or day (32) should not match
+        assert_eq!(clean_filename("2024-13-01_photo"), "2024-13-01_photo");
+        assert_eq!(clean_filename("2024-01-32_photo"), "2024-01-32_photo");
+        // Invalid year (not 19xx or 20xx)
+        assert_eq!(clean_filename("1899-01-15_photo"), "1899-01-15_photo");
+        assert_eq!(clean_filename("2100-01-15_photo"), "2100-01-15_photo");
+    }
+
+    #[test]
+    fn test_clean_filename_empty_result_returns_original() {
+        // If cleaning would result in empty string, return original
+        assert_eq!(clean_filename("2024-01-15"), "2024-01-15");
+        assert_eq!(clean_filename("20240115"), "20240115");
+        assert_eq!(clean_filename("001"), "001");
+        assert_eq!(clean_filename("20240115_103045"), "20240115_103045");
+    }
+
+    #[test]
+    fn test_clean_filename_hidden_files() {
+        // Hidden files (Unix style) should preserve the leading dot
+        assert_eq!(clean_filename(".hidden_2024-01-15"), ".hidden");
+        assert_eq!(clean_filename(".config_20240115"), ".config");
+    }
+
+    #[test]
+    fn test_clean_filename_mixed_separators() {
+        // Mixed separators in date should still work
+        assert_eq!(clean_filename("2024-01_15_photo"), "photo");
+        assert_eq!(clean_filename("photo_2024.01-15"), "photo");
+    }
+
+    #[test]
+    fn test_clean_filename_separator_cleanup() {
+        // Multiple consecutive separators should be collapsed
+        assert_eq!(clean_filename("photo__vacation"), "photo_vacation");
+        assert_eq!(clean_filename("photo---trip"), "photo_trip");
+    }
+
+    #[tokio::test]
+    async fn test_generate_preview_with_strip_existing_patterns() {
+        // Simulate a file that was already renamed with a date prefix
+        let files = vec![create_test_file_info("2024-01-15_photo", "jpg", "/tmp/2024-01-15_photo.jpg")];
+
+        // Without stripping - would create duplicate date
+        let options_no_strip = GeneratePreviewOptions {
+            strip_existing_patterns: false,
+            ..Default::default()
+        };
 
-        let (result, sources) = apply_template(&file, "{date}_{name}.{ext}", "YYYY-MM-DD", false);
-        assert_eq!(result, "2024-07-15_photo.jpg");
-        assert!(sources.contains(&"file-date".to_string()));
+        let result = generate_preview(files.clone(), "{date}_{name}.{ext}".to_string(), Some(options_no_strip))
+            .await
+            .unwrap();
+
+        // The date appears twice because {name} includes the existing date
+        assert!(result.proposals[0].proposed_name.contains("2024"));
+        assert!(result.proposals[0].proposed_name.matches("2024").count() >= 1);
+
+        // With stripping - clean result
+        let options_strip = GeneratePreviewOptions {
+            strip_existing_patterns: true,
+            ..Default::default()
+        };
+
+        let result = generate_preview(files, "{date}_{name}.{ext}".to_string(), Some(options_strip))
+            .await
+            .unwrap();
+
+        // The date should only appear once
+        let date_count = result.proposals[0].proposed_name.matches('-').count();
+        // ISO date has 2 dashes (YYYY-MM-DD), plus 1 underscore separator = clean format
+        assert!(date_count <= 3, "Expected clean date format, got: {}", result.proposals[0].proposed_name);
     }
 
-    #[test]
-    fn test_apply_template_custom_date_format() {
-        let mut file = create_test_file_info("photo", "jpg", "/home/user/photo.jpg");
-        file.modified_at = DateTime::parse_from_rfc3339("2024-07-15T10:30:00Z")
-            .unwrap()
-            .with_timezone(&Utc);
+    #[tokio::test]
+    async fn test_strip_existing_patterns_idempotent() {
+        // Apply template to a clean file
+        let files = vec![create_test_file_info("vacation", "jpg", "/tmp/vacation.jpg")];
 
-        let (result, _) = apply_template(&file, "{date:YYYYMMDD}_{name}.{ext}", "YYYY-MM-DD", false);
-        assert_eq!(result, "20240715_photo.jpg");
+        let options = GeneratePreviewOptions {
+            strip_existing_patterns: true,
+            ..Default::default()
+        };
+
+        let result1 = generate_preview(files, "{date}_{name}.{ext}".to_string(), Some(options.clone()))
+            .await
+            .unwrap();
+
+        let first_name = &result1.proposals[0].proposed_name;
+
+        // Now simulate applying to the already-renamed file
+        let renamed_files = vec![create_test_file_info(
+            first_name.strip_suffix(".jpg").unwrap_or(first_name),
+            "jpg",
+            &format!("/tmp/{}", first_name)
+        )];
+
+        let result2 = generate_preview(renamed_files, "{date}_{name}.{ext}".to_string(), Some(options))
+            .await
+            .unwrap();
+
+        // With strip_existing_patterns=true, applying the same template twice
+        // should give similar results (idempotent)
+        assert!(
+            result2.proposals[0].proposed_name.ends_with("_vacation.jpg"),
+            "Expected idempotent result ending with _vacation.jpg, got: {}",
+            result2.proposals[0].proposed_name
+        );
     }
 
     #[tokio::test]
-    async fn test_generate_preview_basic() {
-        let files = vec![
-            create_test_file_info("photo1", "jpg", "/tmp/photo1.jpg"),
-            create_test_file_info("photo2", "jpg", "/tmp/photo2.jpg"),
-        ];
+    async fn test_list_case_styles_renders_sample() {
+        let styles = list_case_styles(None).await.unwrap();
+        let example = |style: CaseStyle| {
+            styles.iter().find(|s| s.style == style).map(|s| s.example.clone()).unwrap()
+        };
 
-        let result = generate_preview(files, "{name}_renamed.{ext}".to_string(), None)
+        assert_eq!(styles.len(), CaseStyle::ALL.len());
+        assert_eq!(example(CaseStyle::KebabCase), "my-example-file");
+        assert_eq!(example(CaseStyle::None), "My Example File");
+        assert_eq!(example(CaseStyle::CamelCase), "myExampleFile");
+
+        let custom = list_case_styles(Some("beach sunset".to_string())).await.unwrap();
+        assert!(custom.iter().any(|s| s.example == "beach_sunset"));
+    }
+
+    // =========================================================================
+    // Folder In Name Template Tests
+    // =========================================================================
+
+    #[test]
+    fn test_find_folder_in_name_template() {
+        assert_eq!(find_folder_in_name_template("{year}/{name}.{ext}").as_deref(), Some("{year}"));
+        assert_eq!(
+            find_folder_in_name_template("{category}\\{year}-{month}/{name}").as_deref(),
+            Some("{category}\\{year}-{month}")
+        );
+        assert_eq!(find_folder_in_name_template("{date:DD/MM/YYYY}_{name}"), None);
+        assert_eq!(find_folder_in_name_template("photos/{name}"), None);
+        assert_eq!(find_folder_in_name_template("{date}_{name}"), None);
+    }
+
+    #[tokio::test]
+    async fn test_generate_preview_folder_in_name_template_advisory() {
+        let files = vec![create_test_file_info("photo", "jpg", "/tmp/photo.jpg")];
+
+        let result = generate_preview(files, "{year}/{name}.{ext}".to_string(), None)
             .await
             .unwrap();
 
-        assert_eq!(result.proposals.len(), 2);
-        assert_eq!(result.summary.total, 2);
-        assert_eq!(result.proposals[0].proposed_name, "photo1_renamed.jpg");
-        assert_eq!(result.proposals[1].proposed_name, "photo2_renamed.jpg");
+        let proposal = &result.proposals[0];
+        assert!(!proposal.proposed_name.contains('/'));
+        assert_eq!(proposal.status, RenameStatus::Ready);
+        let issue = proposal
+            .issues
+            .iter()
+            .find(|i| i.code == "FOLDER_IN_NAME_TEMPLATE")
+            .expect("advisory issue");
+        assert_eq!(issue.field.as_deref(), Some("{year}"));
+        assert!(issue.message.contains("organize mode"));
     }
 
     #[tokio::test]
-    async fn test_generate_preview_detects_no_change() {
+    async fn test_generate_preview_plain_template_has_no_folder_advisory() {
         let files = vec![create_test_file_info("photo", "jpg", "/tmp/photo.jpg")];
 
-        let result = generate_preview(files, "{name}.{ext}".to_string(), None)
+        let result = generate_preview(files, "{year}-{name}.{ext}".to_string(), None)
             .await
             .unwrap();
 
-        assert_eq!(result.proposals[0].status, RenameStatus::NoChange);
-        assert_eq!(result.summary.no_change, 1);
+        assert!(result.proposals[0].issues.is_empty());
     }
 
+    // =========================================================================
+    // Original Name Suffix Tests
+    // =========================================================================
+
     #[tokio::test]
-    async fn test_generate_preview_detects_conflicts() {
-        let files = vec![
-            create_test_file_info("photo1", "jpg", "/tmp/photo1.jpg"),
-            create_test_file_info("photo2", "jpg", "/tmp/photo2.jpg"),
-        ];
+    async fn test_preserve_original_suffix_appended() {
+        let files = vec![create_test_file_info("IMG_1234", "JPG", "/tmp/IMG_1234.JPG")];
+        let options = GeneratePreviewOptions {
+            preserve_original_suffix: true,
+            ..Default::default()
+        };
 
-        // Template that produces same output for different files
-        let result = generate_preview(files, "output.{ext}".to_string(), None)
+        let result = generate_preview(files.clone(), "beach.{ext}".to_string(), Some(options))
             .await
             .unwrap();
+        assert_eq!(result.proposals[0].proposed_name, "beach__orig-IMG_1234.JPG");
 
-        assert_eq!(result.summary.conflicts, 2);
+        let options = GeneratePreviewOptions {
+            preserve_original_suffix: true,
+            original_suffix_separator: Some("--was-".to_string()),
+            case_style: CaseStyle::KebabCase,
+            ..Default::default()
+        };
+        let result = generate_preview(files, "Beach Day.{ext}".to_string(), Some(options))
+            .await
+            .unwrap();
+        assert_eq!(result.proposals[0].proposed_name, "beach-day--was-IMG_1234.jpg");
     }
 
     #[tokio::test]
-    async fn test_execute_rename_success() {
+    async fn test_preserve_original_suffix_skipped_without_change() {
+        let files = vec![create_test_file_info("photo", "jpg", "/tmp/photo.jpg")];
+        let options = GeneratePreviewOptions {
+            preserve_original_suffix: true,
+            ..Default::default()
+        };
+
+        let result = generate_preview(files, "{name}.{ext}".to_string(), Some(options))
+            .await
+            .unwrap();
+
+        assert_eq!(result.proposals[0].proposed_name, "photo.jpg");
+        assert_eq!(result.proposals[0].status, RenameStatus::NoChange);
+    }
+
+    #[test]
+    fn test_append_original_suffix_truncates_new_part() {
+        let original = "o".repeat(100);
+        let proposed = format!("{}.jpg", "n".repeat(200));
+
+        let result = append_original_suffix(&proposed, &original, "__orig-");
+
+        assert_eq!(result.len(), MAX_FILENAME_LENGTH);
+        assert!(result.ends_with(&format!("__orig-{}.jpg", original)));
+        assert!(result.starts_with("nnn"));
+        assert!(is_valid_filename(&result));
+    }
+
+    #[test]
+    fn test_append_original_suffix_does_not_stack() {
+        // A file renamed with the suffix before, re-run with "{name}"
+        let result = append_original_suffix(
+            "beach__orig-IMG_1234.jpg",
+            "beach__orig-IMG_1234",
+            "__orig-",
+        );
+        assert_eq!(result, "beach__orig-IMG_1234.jpg");
+
+        let result = append_original_suffix("sunset.jpg", "beach--was-IMG_1234", "--was-");
+        assert_eq!(result, "sunset--was-IMG_1234.jpg");
+    }
+
+    // =========================================================================
+    // Folder Date Source Tests
+    // =========================================================================
+
+    fn organize_by_date_options(date_source: FolderDateSource) -> GeneratePreviewOptions {
+        GeneratePreviewOptions {
+            reorganization_mode: ReorganizationMode::Organize,
+            organize_options: Some(OrganizeOptions {
+                destination_directory: Some("/sorted".to_string()),
+                folder_pattern: "{year}/{month}".to_string(),
+                preserve_context: false,
+                context_depth: 1,
+                date_source,
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn organize_options(destination: Option<&str>, pattern: &str) -> GeneratePreviewOptions {
+        GeneratePreviewOptions {
+            reorganization_mode: ReorganizationMode::Organize,
+            organize_options: Some(OrganizeOptions {
+                destination_directory: destination.map(|d| d.to_string()),
+                folder_pattern: pattern.to_string(),
+                preserve_context: false,
+                context_depth: 1,
+                date_source: FolderDateSource::default(),
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_organize_self_move_is_no_change() {
+        let files = vec![create_test_file_info("photo", "jpg", "/photos/photo.jpg")];
+
+        for options in [organize_options(None, ""), organize_options(Some("/photos/"), ".")] {
+            let result = generate_preview(files.clone(), "{name}.{ext}".to_string(), Some(options))
+                .await
+                .unwrap();
+
+            let proposal = &result.proposals[0];
+            assert_eq!(proposal.status, RenameStatus::NoChange);
+            assert!(!proposal.is_folder_move);
+            assert!(proposal.destination_folder.is_none());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_organize_destination_inside_file_is_error() {
+        let files = vec![create_test_file_info("photo", "jpg", "/photos/photo.jpg")];
+
+        let result = generate_preview(
+            files,
+            "{name}.{ext}".to_string(),
+            Some(organize_options(Some("/photos/photo.jpg"), "{year}")),
+        )
+        .await
+        .unwrap();
+
+        let proposal = &result.proposals[0];
+        assert_eq!(proposal.status, RenameStatus::InvalidName);
+        assert_eq!(proposal.action_type, FileActionType::Error);
+        assert_eq!(proposal.issues[0].code, "NESTED_DESTINATION");
+    }
+
+    #[tokio::test]
+    async fn test_organize_destination_inside_file_via_dot_dot_is_error() {
+        let files = vec![create_test_file_info("photo", "jpg", "/photos/photo.jpg")];
+
+        let result = generate_preview(
+            files,
+            "{name}.{ext}".to_string(),
+            Some(organize_options(Some("/photos/other/../photo.jpg/deep"), "{year}/nested")),
+        )
+        .await
+        .unwrap();
+
+        let proposal = &result.proposals[0];
+        assert_eq!(proposal.status, RenameStatus::InvalidName);
+        assert!(proposal.issues.iter().any(|i| i.code == "NESTED_DESTINATION"));
+    }
+
+    #[test]
+    fn test_resolve_for_comparison_canonicalizes_existing_ancestor() {
         let dir = TempDir::new().unwrap();
-        let file_path = dir.path().join("test.jpg");
-        let mut file = File::create(&file_path).unwrap();
-        file.write_all(b"test content").unwrap();
+        fs::create_dir(dir.path().join("real")).unwrap();
+        let spelled = dir.path().join("real/./sub/../missing/file.txt");
 
-        let proposal = RenameProposal {
-            id: "test-id".to_string(),
-            original_path: file_path.to_string_lossy().to_string(),
-            original_name: "test.jpg".to_string(),
-            proposed_name: "renamed.jpg".to_string(),
-            proposed_path: dir.path().join("renamed.jpg").to_string_lossy().to_string(),
+        let resolved = resolve_for_comparison(&spelled);
+
+        let expected = dir.path().canonicalize().unwrap().join("real/missing/file.txt");
+        assert_eq!(resolved, expected);
+    }
+
+    /// A photo with EXIF from July 2019 and a document, both modified in March 2024
+    fn create_photo_and_document(dir: &TempDir) -> Vec<FileInfo> {
+        let modified_at = DateTime::parse_from_rfc3339("2024-03-10T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        let photo_path = dir.path().join("photo.jpg");
+        fs::write(&photo_path, crate::commands::exif::jpeg_with_capture_date("2019:07:04 10:30:00")).unwrap();
+        let mut photo = create_test_file_info("photo", "jpg", photo_path.to_str().unwrap());
+        photo.modified_at = modified_at;
+
+        let doc_path = dir.path().join("report.pdf");
+        fs::write(&doc_path, b"%PDF-1.4").unwrap();
+        let mut document = create_test_file_info("report", "pdf", doc_path.to_str().unwrap());
+        document.category = FileCategory::Document;
+        document.modified_at = modified_at;
+
+        vec![photo, document]
+    }
+
+    #[tokio::test]
+    async fn test_organize_exif_for_images_date_source() {
+        let dir = TempDir::new().unwrap();
+        let files = create_photo_and_document(&dir);
+
+        let result = generate_preview(
+            files,
+            "{name}.{ext}".to_string(),
+            Some(organize_by_date_options(FolderDateSource::ExifForImages)),
+        )
+        .await
+        .unwrap();
+
+        let photo = result.proposals.iter().find(|p| p.original_name == "photo.jpg").unwrap();
+        let document = result.proposals.iter().find(|p| p.original_name == "report.pdf").unwrap();
+
+        // Photo uses its EXIF capture date, document its modification time
+        assert_eq!(photo.destination_folder.as_deref(), Some("2019/07"));
+        assert_eq!(photo.proposed_path, "/sorted/2019/07/photo.jpg");
+        assert_eq!(document.destination_folder.as_deref(), Some("2024/03"));
+        assert_eq!(document.proposed_path, "/sorted/2024/03/report.pdf");
+    }
+
+    #[tokio::test]
+    async fn test_organize_default_date_source_uses_modified_time() {
+        let dir = TempDir::new().unwrap();
+        let files = create_photo_and_document(&dir);
+
+        let result = generate_preview(
+            files,
+            "{name}.{ext}".to_string(),
+            Some(organize_by_date_options(FolderDateSource::default())),
+        )
+        .await
+        .unwrap();
+
+        assert!(result
+            .proposals
+            .iter()
+            .all(|p| p.destination_folder.as_deref() == Some("2024/03")));
+    }
+
+    #[test]
+    fn test_resolve_folder_date_falls_back_without_exif() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("scan.png");
+        fs::write(&path, b"not really a png").unwrap();
+        let file = create_test_file_info("scan", "png", path.to_str().unwrap());
+
+        let date = resolve_folder_date(&file, FolderDateSource::ExifForImages);
+
+        assert_eq!(date, file.modified_at);
+    }
+
+    // =========================================================================
+    // Rename Chain Tests
+    // =========================================================================
+
+    /// Create a file named `from` containing its own name, with a ready proposal to `to`
+    fn create_chain_proposal(dir: &TempDir, from: &str, to: &str) -> RenameProposal {
+        let original_path = dir.path().join(from);
+        fs::write(&original_path, from).unwrap();
+
+        RenameProposal {
+            id: format!("{}->{}", from, to),
+            original_path: original_path.to_string_lossy().to_string(),
+            original_name: from.to_string(),
+            proposed_name: to.to_string(),
+            proposed_path: dir.path().join(to).to_string_lossy().to_string(),
             status: RenameStatus::Ready,
             issues: vec![],
             metadata_sources: None,
@@ -1650,487 +6498,1259 @@ mod tests {
             destination_folder: None,
             action_type: FileActionType::Rename,
             conflict: None,
-        };
+        }
+    }
+
+    #[tokio::test]
+    async fn test_minimize_renames_drops_already_organized_file() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir(dir.path().join("2024")).unwrap();
+        // a.jpg was organized before and its copy is already in place
+        let organized = create_chain_proposal(&dir, "a.jpg", "2024/a.jpg");
+        fs::write(dir.path().join("2024/a.jpg"), "a.jpg").unwrap();
+        // b.jpg's target holds different content
+        let different = create_chain_proposal(&dir, "b.jpg", "2024/b.jpg");
+        fs::write(dir.path().join("2024/b.jpg"), "other").unwrap();
+        let pending = create_chain_proposal(&dir, "c.jpg", "2024/c.jpg");
+
+        let result = minimize_renames(vec![organized, different, pending], None).await.unwrap();
+
+        assert_eq!(result.already_correct, 1);
+        assert!(result.integrity_hash.is_none());
+        let ids: Vec<&str> = result.proposals.iter().map(|p| p.id.as_str()).collect();
+        assert_eq!(ids, vec!["b.jpg->2024/b.jpg", "c.jpg->2024/c.jpg"]);
+    }
+
+    #[tokio::test]
+    async fn test_minimize_renames_reissues_integrity_hash() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir(dir.path().join("2024")).unwrap();
+        let organized = create_chain_proposal(&dir, "a.jpg", "2024/a.jpg");
+        fs::write(dir.path().join("2024/a.jpg"), "a.jpg").unwrap();
+        let pending = create_chain_proposal(&dir, "b.jpg", "2024/b.jpg");
+        let proposals = vec![organized, pending];
+        let preview_hash = compute_proposals_hash(&proposals);
+
+        let result = minimize_renames(proposals.clone(), Some(preview_hash)).await.unwrap();
+
+        let hash = result.integrity_hash.unwrap();
+        assert!(verify_proposals_hash(&result.proposals, &hash).is_ok());
+        assert!(verify_proposals_hash(&proposals, &hash).is_err());
+
+        let forged = minimize_renames(proposals, Some("forged".to_string())).await;
+        assert!(matches!(forged, Err(RenameError::IntegrityCheckFailed(_))));
+    }
+
+    #[tokio::test]
+    async fn test_minimize_renames_keeps_case_only_rename() {
+        let dir = TempDir::new().unwrap();
+        let proposal = create_chain_proposal(&dir, "photo.JPG", "photo.jpg");
+
+        let result = minimize_renames(vec![proposal], None).await.unwrap();
+
+        assert_eq!(result.already_correct, 0);
+        assert_eq!(result.proposals.len(), 1);
+    }
+
+    fn read_file(dir: &TempDir, name: &str) -> String {
+        fs::read_to_string(dir.path().join(name)).unwrap()
+    }
+
+    #[test]
+    fn test_find_rename_chains_swap_is_cycle() {
+        let dir = TempDir::new().unwrap();
+        let proposals = vec![
+            create_chain_proposal(&dir, "a.jpg", "b.jpg"),
+            create_chain_proposal(&dir, "b.jpg", "a.jpg"),
+            create_chain_proposal(&dir, "c.jpg", "d.jpg"),
+        ];
+
+        let chains = find_rename_chains(&proposals);
+
+        assert_eq!(chains.len(), 1);
+        assert_eq!(chains[0].kind, RenameChainKind::Cycle);
+        assert_eq!(chains[0].proposal_ids, vec!["a.jpg->b.jpg", "b.jpg->a.jpg"]);
+    }
+
+    #[test]
+    fn test_chain_target_differing_in_case_is_not_file_exists() {
+        let dir = TempDir::new().unwrap();
+        // "B.jpg" and "b.jpg" are one file on case-insensitive drives
+        let vacating = create_chain_proposal(&dir, "B.jpg", "c.jpg");
+        fs::write(dir.path().join("b.jpg"), "b").unwrap();
+        let following = create_chain_proposal(&dir, "a.jpg", "b.jpg");
+
+        let preview = finalize_preview(
+            vec![vacating, following],
+            String::new(),
+            ReorganizationMode::RenameOnly,
+        );
+
+        assert!(preview.proposals.iter().all(|p| p.status == RenameStatus::Ready));
+        let chains = find_rename_chains(&preview.proposals);
+        assert_eq!(chains.len(), 1);
+        assert_eq!(chains[0].kind, RenameChainKind::Chain);
+    }
+
+    #[test]
+    fn test_find_rename_chains_three_file_chain_order() {
+        let dir = TempDir::new().unwrap();
+        let proposals = vec![
+            create_chain_proposal(&dir, "a.jpg", "b.jpg"),
+            create_chain_proposal(&dir, "b.jpg", "c.jpg"),
+            create_chain_proposal(&dir, "c.jpg", "d.jpg"),
+        ];
+
+        let chains = find_rename_chains(&proposals);
+
+        // The end of the chain must be renamed first
+        assert_eq!(chains.len(), 1);
+        assert_eq!(chains[0].kind, RenameChainKind::Chain);
+        assert_eq!(
+            chains[0].proposal_ids,
+            vec!["c.jpg->d.jpg", "b.jpg->c.jpg", "a.jpg->b.jpg"]
+        );
+    }
+
+    #[test]
+    fn test_execute_rename_swap_uses_temp_file() {
+        let dir = TempDir::new().unwrap();
+        let proposals = vec![
+            create_chain_proposal(&dir, "a.jpg", "b.jpg"),
+            create_chain_proposal(&dir, "b.jpg", "a.jpg"),
+        ];
+
+        let result = execute_rename_internal(proposals, ExecuteRenameOptions::default(), None).unwrap();
+
+        assert!(result.success);
+        assert_eq!(result.summary.succeeded, 2);
+        assert_eq!(read_file(&dir, "a.jpg"), "b.jpg");
+        assert_eq!(read_file(&dir, "b.jpg"), "a.jpg");
+        assert!(result.results.iter().all(|r| !r.original_path.contains(".tidy-rename-")));
+        // No temporary file left behind
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn test_execute_rename_three_file_chain() {
+        let dir = TempDir::new().unwrap();
+        // Listed in the order that would clobber files if executed naively
+        let proposals = vec![
+            create_chain_proposal(&dir, "a.jpg", "b.jpg"),
+            create_chain_proposal(&dir, "b.jpg", "c.jpg"),
+            create_chain_proposal(&dir, "c.jpg", "d.jpg"),
+        ];
+
+        let result = execute_rename_internal(proposals, ExecuteRenameOptions::default(), None).unwrap();
+
+        assert!(result.success);
+        assert_eq!(result.summary.succeeded, 3);
+        assert!(!dir.path().join("a.jpg").exists());
+        assert_eq!(read_file(&dir, "b.jpg"), "a.jpg");
+        assert_eq!(read_file(&dir, "c.jpg"), "b.jpg");
+        assert_eq!(read_file(&dir, "d.jpg"), "c.jpg");
+    }
+
+    #[test]
+    fn test_execute_rename_chain_blocked_after_failure() {
+        let dir = TempDir::new().unwrap();
+        let mut proposals = vec![
+            create_chain_proposal(&dir, "a.jpg", "b.jpg"),
+            create_chain_proposal(&dir, "b.jpg", "c.jpg"),
+        ];
+        // The end of the chain fails, so a.jpg must not overwrite b.jpg
+        proposals[1].proposed_path = dir.path().join("missing/c.jpg").to_string_lossy().to_string();
+
+        let result = execute_rename_internal(proposals, ExecuteRenameOptions::default(), None).unwrap();
+
+        assert!(!result.success);
+        assert_eq!(result.summary.failed, 2);
+        assert_eq!(read_file(&dir, "a.jpg"), "a.jpg");
+        assert_eq!(read_file(&dir, "b.jpg"), "b.jpg");
+    }
+
+    // =========================================================================
+    // Empty Input Tests
+    // =========================================================================
+
+    #[tokio::test]
+    async fn test_generate_preview_empty_file_list() {
+        let preview = generate_preview(vec![], "{name}.{ext}".to_string(), None)
+            .await
+            .unwrap();
+
+        assert!(preview.proposals.is_empty());
+        assert_eq!(preview.summary.total, 0);
+        assert_eq!(preview.summary.ready, 0);
+        assert_eq!(preview.action_summary.rename_count, 0);
+        assert_eq!(preview.integrity_hash, compute_proposals_hash(&[]));
+    }
 
-        let result = execute_rename(vec![proposal], None).await.unwrap();
+    #[test]
+    fn test_execute_rename_empty_proposal_list() {
+        let result = execute_rename_internal(vec![], ExecuteRenameOptions::default(), None).unwrap();
 
         assert!(result.success);
-        assert_eq!(result.summary.succeeded, 1);
-        assert!(dir.path().join("renamed.jpg").exists());
-        assert!(!file_path.exists());
+        assert!(!result.cancelled);
+        assert!(result.results.is_empty());
+        assert_eq!(result.summary.total, 0);
+        assert_eq!(result.summary.failed, 0);
     }
 
-    #[tokio::test]
-    async fn test_execute_rename_skips_non_ready() {
-        let proposal = RenameProposal {
-            id: "test-id".to_string(),
-            original_path: "/tmp/test.jpg".to_string(),
-            original_name: "test.jpg".to_string(),
-            proposed_name: "renamed.jpg".to_string(),
-            proposed_path: "/tmp/renamed.jpg".to_string(),
-            status: RenameStatus::Conflict,
+    // =========================================================================
+    // Directory Creation Tests
+    // =========================================================================
+
+    fn create_move_proposal(dir: &TempDir, name: &str, folder: &str) -> RenameProposal {
+        let original_path = dir.path().join(name);
+        fs::write(&original_path, name).unwrap();
+
+        RenameProposal {
+            id: name.to_string(),
+            original_path: original_path.to_string_lossy().to_string(),
+            original_name: name.to_string(),
+            proposed_name: name.to_string(),
+            proposed_path: dir.path().join(folder).join(name).to_string_lossy().to_string(),
+            status: RenameStatus::Ready,
             issues: vec![],
             metadata_sources: None,
-            is_folder_move: false,
-            destination_folder: None,
-            action_type: FileActionType::Conflict,
+            is_folder_move: true,
+            destination_folder: Some(folder.to_string()),
+            action_type: FileActionType::Move,
             conflict: None,
-        };
-
-        let result = execute_rename(vec![proposal], None).await.unwrap();
-
-        assert!(result.success);
-        assert_eq!(result.summary.skipped, 1);
-        assert_eq!(result.summary.succeeded, 0);
+        }
     }
 
-    #[tokio::test]
-    async fn test_execute_rename_with_selection() {
+    #[test]
+    fn test_execute_rename_records_new_directory_once() {
         let dir = TempDir::new().unwrap();
+        let proposals = vec![
+            create_move_proposal(&dir, "a.jpg", "2024/07"),
+            create_move_proposal(&dir, "b.jpg", "2024/07"),
+            create_move_proposal(&dir, "c.jpg", "2024/07"),
+        ];
 
-        // Create two files
-        let file1_path = dir.path().join("test1.jpg");
-        let file2_path = dir.path().join("test2.jpg");
-        File::create(&file1_path).unwrap().write_all(b"1").unwrap();
-        File::create(&file2_path).unwrap().write_all(b"2").unwrap();
+        let result =
+            execute_rename_internal(proposals, ExecuteRenameOptions::default(), None).unwrap();
+
+        assert_eq!(result.summary.succeeded, 3);
+        let year = dir.path().join("2024").to_string_lossy().to_string();
+        let month = dir.path().join("2024/07").to_string_lossy().to_string();
+        assert_eq!(result.directories_created, vec![year, month]);
+    }
+
+    /// Create a read-only folder in `dir`, or None when permissions aren't
+    /// enforced (e.g. tests running as root)
+    #[cfg(unix)]
+    fn create_locked_folder(dir: &TempDir, folder: &str) -> Option<std::path::PathBuf> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let locked = dir.path().join(folder);
+        fs::create_dir(&locked).unwrap();
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o555)).unwrap();
+        if fs::write(locked.join("probe"), b"").is_ok() {
+            return None;
+        }
+        Some(locked)
+    }
 
+    #[test]
+    fn test_execute_rename_refuses_protected_paths() {
+        let dir = TempDir::new().unwrap();
+        let archive = dir.path().join("archive");
+        fs::create_dir(&archive).unwrap();
+        let protected_file = archive.join("old.jpg");
+        fs::write(&protected_file, b"old").unwrap();
+
+        let mut from_archive = create_move_proposal(&dir, "unused.jpg", "sorted");
+        from_archive.id = "from-archive".to_string();
+        from_archive.original_path = protected_file.to_string_lossy().to_string();
+        from_archive.original_name = "old.jpg".to_string();
         let proposals = vec![
-            RenameProposal {
-                id: "id-1".to_string(),
-                original_path: file1_path.to_string_lossy().to_string(),
-                original_name: "test1.jpg".to_string(),
-                proposed_name: "renamed1.jpg".to_string(),
-                proposed_path: dir.path().join("renamed1.jpg").to_string_lossy().to_string(),
-                status: RenameStatus::Ready,
-                issues: vec![],
-                metadata_sources: None,
-                is_folder_move: false,
-                destination_folder: None,
-                action_type: FileActionType::Rename,
-                conflict: None,
-            },
-            RenameProposal {
-                id: "id-2".to_string(),
-                original_path: file2_path.to_string_lossy().to_string(),
-                original_name: "test2.jpg".to_string(),
-                proposed_name: "renamed2.jpg".to_string(),
-                proposed_path: dir.path().join("renamed2.jpg").to_string_lossy().to_string(),
-                status: RenameStatus::Ready,
-                issues: vec![],
-                metadata_sources: None,
-                is_folder_move: false,
-                destination_folder: None,
-                action_type: FileActionType::Rename,
-                conflict: None,
-            },
+            from_archive,
+            create_move_proposal(&dir, "into.jpg", "archive"),
+            create_move_proposal(&dir, "free.jpg", "sorted"),
         ];
-
-        // Only rename the first file
         let options = ExecuteRenameOptions {
-            proposal_ids: Some(vec!["id-1".to_string()]),
+            protected_paths: vec![archive.to_string_lossy().to_string()],
+            ..Default::default()
         };
 
-        let result = execute_rename(proposals, Some(options)).await.unwrap();
+        let result = execute_rename_internal(proposals, options, None).unwrap();
 
-        assert!(result.success);
+        assert_eq!(result.summary.failed, 2);
         assert_eq!(result.summary.succeeded, 1);
-        assert_eq!(result.summary.skipped, 1);
-        assert!(dir.path().join("renamed1.jpg").exists());
-        assert!(file2_path.exists()); // Second file should not be renamed
+        let refused: Vec<_> = result
+            .results
+            .iter()
+            .filter(|r| r.error_kind == Some(RenameErrorKind::ProtectedPath))
+            .map(|r| r.proposal_id.as_str())
+            .collect();
+        assert_eq!(refused, vec!["from-archive", "into.jpg"]);
+        assert!(protected_file.exists());
+        assert!(dir.path().join("into.jpg").exists());
+        assert!(dir.path().join("sorted/free.jpg").exists());
     }
 
-    // =============================================================================
-    // Sanitization Tests
-    // =============================================================================
-
+    #[cfg(unix)]
     #[test]
-    fn test_sanitize_filename_no_change() {
-        let result = sanitize_filename("valid_filename.jpg", '_');
-        assert_eq!(result.sanitized, "valid_filename.jpg");
-        assert!(!result.was_modified);
-        assert!(result.changes.is_empty());
+    fn test_execute_rename_classifies_permission_denied() {
+        let dir = TempDir::new().unwrap();
+        let Some(_locked) = create_locked_folder(&dir, "locked") else {
+            return;
+        };
+        let proposals = vec![
+            create_move_proposal(&dir, "a.jpg", "locked"),
+            create_move_proposal(&dir, "b.jpg", "locked"),
+        ];
+
+        let result =
+            execute_rename_internal(proposals, ExecuteRenameOptions::default(), None).unwrap();
+
+        assert_eq!(result.summary.failed, 2);
+        assert!(!result.stopped_on_permission_error);
+        assert!(result
+            .results
+            .iter()
+            .all(|r| r.error_kind == Some(RenameErrorKind::PermissionDenied)));
+        assert!(dir.path().join("a.jpg").exists());
     }
 
+    #[cfg(unix)]
     #[test]
-    fn test_sanitize_filename_replaces_invalid_chars() {
-        let result = sanitize_filename("photo:2024.jpg", '_');
-        assert_eq!(result.sanitized, "photo_2024.jpg");
-        assert!(result.was_modified);
-        assert_eq!(result.changes.len(), 1);
-        assert_eq!(result.changes[0].change_type, "char_replacement");
+    fn test_execute_rename_stops_on_permission_error() {
+        let dir = TempDir::new().unwrap();
+        let Some(_locked) = create_locked_folder(&dir, "locked") else {
+            return;
+        };
+        let proposals = vec![
+            create_move_proposal(&dir, "a.jpg", "locked"),
+            create_move_proposal(&dir, "b.jpg", "locked"),
+            create_move_proposal(&dir, "c.jpg", "locked"),
+        ];
+        let options = ExecuteRenameOptions {
+            stop_on_permission_error: true,
+            ..Default::default()
+        };
+
+        let result = execute_rename_internal(proposals, options, None).unwrap();
+
+        assert!(result.stopped_on_permission_error);
+        assert!(!result.success);
+        assert_eq!(result.results.len(), 1);
+        assert_eq!(result.results[0].error_kind, Some(RenameErrorKind::PermissionDenied));
+        assert!(dir.path().join("c.jpg").exists());
     }
 
     #[test]
-    fn test_sanitize_filename_collapses_multiple_replacements() {
-        let result = sanitize_filename("test::file.jpg", '_');
-        assert_eq!(result.sanitized, "test_file.jpg");
-        assert!(result.was_modified);
+    fn test_directory_creation_log_concurrent() {
+        let dir = TempDir::new().unwrap();
+        let target = dir.path().join("shared/new-folder");
+        let log = DirectoryCreationLog::default();
+
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| log.ensure_dir(&target).unwrap());
+            }
+        });
+
+        let created = log.into_created();
+        assert_eq!(created.len(), 2);
+        assert_eq!(created[1], target.to_string_lossy());
+        assert!(target.is_dir());
     }
 
     #[test]
-    fn test_sanitize_filename_handles_reserved_names() {
-        let result = sanitize_filename("CON.txt", '_');
-        assert_eq!(result.sanitized, "CON_file.txt");
-        assert!(result.was_modified);
-        assert!(result.changes.iter().any(|c| c.change_type == "reserved_name"));
+    fn test_directory_creation_log_ignores_existing() {
+        let dir = TempDir::new().unwrap();
+        let log = DirectoryCreationLog::default();
+
+        log.ensure_dir(dir.path()).unwrap();
+
+        assert!(log.into_created().is_empty());
     }
 
-    #[test]
-    fn test_sanitize_filename_fixes_trailing_spaces() {
-        let result = sanitize_filename("test .jpg", '_');
-        assert_eq!(result.sanitized, "test.jpg");
-        assert!(result.was_modified);
+    // =========================================================================
+    // Two-Phase Organize Tests
+    // =========================================================================
+
+    fn has_staging_dir(dir: &TempDir) -> bool {
+        fs::read_dir(dir.path())
+            .unwrap()
+            .any(|e| e.unwrap().file_name().to_string_lossy().starts_with(".tidy-staging-"))
     }
 
     #[test]
-    fn test_sanitize_filename_fixes_trailing_dots() {
-        let result = sanitize_filename("test..jpg", '_');
-        assert_eq!(result.sanitized, "test.jpg");
-        assert!(result.was_modified);
+    fn test_two_phase_moves_files_through_staging() {
+        let dir = TempDir::new().unwrap();
+        let proposals = vec![
+            create_move_proposal(&dir, "a.jpg", "2024/07"),
+            create_move_proposal(&dir, "b.jpg", "2024/08"),
+            // Swap: both sources are vacated before either target is written
+            create_chain_proposal(&dir, "x.txt", "y.txt"),
+            create_chain_proposal(&dir, "y.txt", "x.txt"),
+        ];
+        let phases = Mutex::new(Vec::new());
+        let progress = |p: StagingProgress| phases.lock().unwrap().push(p.phase);
+
+        let result =
+            execute_two_phase(proposals, ExecuteRenameOptions::default(), None, &progress).unwrap();
+
+        assert!(result.success);
+        assert_eq!(result.summary.succeeded, 4);
+        assert_eq!(fs::read_to_string(dir.path().join("2024/07/a.jpg")).unwrap(), "a.jpg");
+        assert_eq!(fs::read_to_string(dir.path().join("2024/08/b.jpg")).unwrap(), "b.jpg");
+        assert_eq!(fs::read_to_string(dir.path().join("x.txt")).unwrap(), "y.txt");
+        assert_eq!(fs::read_to_string(dir.path().join("y.txt")).unwrap(), "x.txt");
+        assert!(!has_staging_dir(&dir));
+
+        let phases = phases.into_inner().unwrap();
+        assert_eq!(phases.first(), Some(&StagingPhase::Staging));
+        assert!(phases.contains(&StagingPhase::Verifying));
+        assert_eq!(phases.last(), Some(&StagingPhase::Finalizing));
     }
 
     #[test]
-    fn test_split_filename() {
-        assert_eq!(split_filename("file.txt"), ("file".to_string(), ".txt".to_string()));
-        assert_eq!(split_filename("file.tar.gz"), ("file.tar".to_string(), ".gz".to_string()));
-        assert_eq!(split_filename(".gitignore"), (".gitignore".to_string(), String::new()));
-        assert_eq!(split_filename("noextension"), ("noextension".to_string(), String::new()));
-        assert_eq!(split_filename(""), (String::new(), String::new()));
+    fn test_two_phase_rolls_back_when_staging_fails() {
+        let dir = TempDir::new().unwrap();
+        let missing = create_move_proposal(&dir, "c.jpg", "2024");
+        fs::remove_file(&missing.original_path).unwrap();
+        let proposals = vec![
+            create_move_proposal(&dir, "a.jpg", "2024"),
+            create_move_proposal(&dir, "b.jpg", "2024"),
+            missing,
+        ];
+
+        let result =
+            execute_two_phase(proposals, ExecuteRenameOptions::default(), None, &|_| {}).unwrap();
+
+        assert!(!result.success);
+        assert_eq!(result.summary.failed, 3);
+        assert_eq!(result.summary.succeeded, 0);
+        let failed = result.results.iter().find(|r| r.proposal_id == "c.jpg").unwrap();
+        assert_eq!(failed.error_kind, Some(RenameErrorKind::NotFound));
+        assert!(result
+            .results
+            .iter()
+            .filter(|r| r.proposal_id != "c.jpg")
+            .all(|r| r.error.as_deref().unwrap().starts_with("Rolled back")));
+        assert_eq!(fs::read_to_string(dir.path().join("a.jpg")).unwrap(), "a.jpg");
+        assert_eq!(fs::read_to_string(dir.path().join("b.jpg")).unwrap(), "b.jpg");
+        assert!(!dir.path().join("2024").exists());
+        assert!(!has_staging_dir(&dir));
     }
 
     #[test]
-    fn test_apply_template_sanitizes_output() {
-        // Create a file with invalid characters in the name
-        let file = create_test_file_info("photo:test", "jpg", "/home/user/photo:test.jpg");
-        let (result, _) = apply_template(&file, "{name}.{ext}", "YYYY-MM-DD", false);
-        // The sanitization should replace : with _
-        assert_eq!(result, "photo_test.jpg");
-    }
+    fn test_two_phase_never_restores_over_a_placed_file() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("c.txt"), "existing").unwrap();
+        // b.txt can't be placed, and a.txt has already taken its original path
+        let proposals = vec![
+            create_chain_proposal(&dir, "a.txt", "b.txt"),
+            create_chain_proposal(&dir, "b.txt", "c.txt"),
+        ];
 
-    // =============================================================================
-    // Case Normalization Tests
-    // =============================================================================
+        let result =
+            execute_two_phase(proposals, ExecuteRenameOptions::default(), None, &|_| {}).unwrap();
 
-    #[test]
-    fn test_split_into_words_simple() {
-        let words = split_into_words("hello world");
-        assert_eq!(words, vec!["hello", "world"]);
+        assert_eq!(result.summary.succeeded, 1);
+        assert_eq!(fs::read_to_string(dir.path().join("b.txt")).unwrap(), "a.txt");
+        assert_eq!(fs::read_to_string(dir.path().join("c.txt")).unwrap(), "existing");
+
+        let failed = result.results.iter().find(|r| r.proposal_id == "b.txt->c.txt").unwrap();
+        assert_eq!(failed.outcome, RenameOutcome::Failed);
+        let error = failed.error.as_deref().unwrap();
+        let left_at = error.split("file left at ").nth(1).unwrap();
+        assert_eq!(fs::read_to_string(left_at).unwrap(), "b.txt");
+        assert!(has_staging_dir(&dir));
     }
 
     #[test]
-    fn test_split_into_words_with_separators() {
-        let words = split_into_words("hello-world_test");
-        assert_eq!(words, vec!["hello", "world", "test"]);
+    fn test_restore_staged_falls_back_to_rename_without_hard_links() {
+        let dir = TempDir::new().unwrap();
+        let staged = dir.path().join("staged");
+        let original = dir.path().join("original.txt");
+        let unsupported = |_: &Path, _: &Path| Err(std::io::ErrorKind::Unsupported.into());
+
+        // A path taken in the meantime is still never replaced
+        fs::write(&staged, "staged").unwrap();
+        fs::write(&original, "taken").unwrap();
+        let err = restore_staged_with(&staged, &original, unsupported).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::AlreadyExists);
+        assert_eq!(fs::read_to_string(&original).unwrap(), "taken");
+        assert!(staged.exists());
+
+        fs::remove_file(&original).unwrap();
+        restore_staged_with(&staged, &original, unsupported).unwrap();
+        assert_eq!(fs::read_to_string(&original).unwrap(), "staged");
+        assert!(!staged.exists());
     }
 
     #[test]
-    fn test_split_into_words_camel_case() {
-        let words = split_into_words("helloWorldTest");
-        assert_eq!(words, vec!["hello", "World", "Test"]);
+    fn test_move_no_clobber_never_replaces() {
+        let dir = TempDir::new().unwrap();
+        let from = dir.path().join("from.txt");
+        let to = dir.path().join("to.txt");
+        fs::write(&from, "from").unwrap();
+        fs::write(&to, "to").unwrap();
+
+        let err = move_no_clobber(&from, &to).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::AlreadyExists);
+        assert_eq!(fs::read_to_string(&to).unwrap(), "to");
+
+        fs::remove_file(&to).unwrap();
+        move_no_clobber(&from, &to).unwrap();
+        assert_eq!(fs::read_to_string(&to).unwrap(), "from");
+        assert!(!from.exists());
     }
 
     #[test]
-    fn test_split_into_words_pascal_case() {
-        let words = split_into_words("HelloWorldTest");
-        assert_eq!(words, vec!["Hello", "World", "Test"]);
+    fn test_execute_ready_runs_only_ready_proposals() {
+        let dir = TempDir::new().unwrap();
+        let ready = create_chain_proposal(&dir, "a.txt", "renamed-a.txt");
+        let mut conflict = create_chain_proposal(&dir, "b.txt", "renamed-b.txt");
+        conflict.status = RenameStatus::Conflict;
+        let mut unchanged = create_chain_proposal(&dir, "c.txt", "c.txt");
+        unchanged.status = RenameStatus::NoChange;
+        let preview = ready_preview(vec![conflict, ready, unchanged]);
+
+        let result =
+            execute_ready_internal(preview, ExecuteRenameOptions::default(), None).unwrap();
+
+        assert!(result.success);
+        assert_eq!(result.summary.total, 3);
+        assert_eq!(result.summary.succeeded, 1);
+        assert_eq!(result.summary.skipped, 2);
+        let ids: Vec<_> = result.results.iter().map(|r| r.proposal_id.as_str()).collect();
+        assert_eq!(ids, vec!["b.txt->renamed-b.txt", "a.txt->renamed-a.txt", "c.txt->c.txt"]);
+        assert_eq!(result.results[0].error.as_deref(), Some("Status: conflict"));
+        assert_eq!(result.results[2].error.as_deref(), Some("Status: no-change"));
+        assert!(dir.path().join("renamed-a.txt").exists());
+        assert!(dir.path().join("b.txt").exists());
+        assert!(!dir.path().join("renamed-b.txt").exists());
     }
 
-    #[test]
-    fn test_capitalize_word() {
-        assert_eq!(capitalize_word("hello"), "Hello");
-        assert_eq!(capitalize_word("HELLO"), "Hello");
-        assert_eq!(capitalize_word(""), "");
+    /// A preview of `proposals` with a valid integrity hash
+    fn ready_preview(proposals: Vec<RenameProposal>) -> RenamePreview {
+        let preview = finalize_preview(vec![], String::new(), ReorganizationMode::RenameOnly);
+        RenamePreview {
+            integrity_hash: compute_proposals_hash(&proposals),
+            proposals,
+            ..preview
+        }
     }
 
     #[test]
-    fn test_normalize_case_none() {
-        assert_eq!(normalize_case("Hello World", &CaseStyle::None), "Hello World");
+    fn test_execute_ready_rejects_modified_preview() {
+        let dir = TempDir::new().unwrap();
+        let mut preview = ready_preview(vec![create_chain_proposal(&dir, "a.txt", "b.txt")]);
+        preview.proposals[0].proposed_path = dir.path().join("c.txt").to_string_lossy().to_string();
+
+        let result = execute_ready_internal(preview, ExecuteRenameOptions::default(), None);
+
+        assert!(matches!(result, Err(RenameError::IntegrityCheckFailed(_))));
+        assert!(dir.path().join("a.txt").exists());
     }
 
     #[test]
-    fn test_normalize_case_lowercase() {
-        assert_eq!(normalize_case("Hello World", &CaseStyle::Lowercase), "hello world");
+    fn test_execute_ready_stops_when_cancelled() {
+        let dir = TempDir::new().unwrap();
+        let preview = ready_preview(vec![
+            create_chain_proposal(&dir, "a.txt", "renamed-a.txt"),
+            create_chain_proposal(&dir, "b.txt", "renamed-b.txt"),
+        ]);
+        let state = RenameState::new();
+        let token = state.register_session("ready");
+        assert!(state.cancel_session("ready"));
+
+        let result =
+            execute_ready_internal(preview, ExecuteRenameOptions::default(), Some(&token))
+                .unwrap();
+
+        assert!(result.cancelled);
+        assert_eq!(result.summary.succeeded, 0);
+        assert!(dir.path().join("a.txt").exists());
+        assert!(dir.path().join("b.txt").exists());
     }
 
-    #[test]
-    fn test_normalize_case_uppercase() {
-        assert_eq!(normalize_case("Hello World", &CaseStyle::Uppercase), "HELLO WORLD");
+    // =========================================================================
+    // Empty Name Tests
+    // =========================================================================
+
+    #[tokio::test]
+    async fn test_generate_preview_flags_empty_name() {
+        let files = vec![create_test_file_info("photo", "jpg", "/tmp/photo.jpg")];
+
+        let preview = generate_preview(files, "   ".to_string(), None).await.unwrap();
+
+        let proposal = &preview.proposals[0];
+        assert_eq!(proposal.status, RenameStatus::InvalidName);
+        assert_eq!(proposal.issues[0].code, "EMPTY_NAME");
+        assert_eq!(preview.summary.ready, 0);
     }
 
     #[test]
-    fn test_normalize_case_capitalize() {
-        assert_eq!(normalize_case("hello world", &CaseStyle::Capitalize), "Hello world");
-        assert_eq!(normalize_case("HELLO WORLD", &CaseStyle::Capitalize), "Hello world");
+    fn test_execute_rename_fails_empty_proposed_name() {
+        let dir = TempDir::new().unwrap();
+        let mut blank = create_chain_proposal(&dir, "a.txt", "unused.txt");
+        blank.proposed_name = "  ".to_string();
+        blank.proposed_path = dir.path().join("  ").to_string_lossy().to_string();
+        let mut empty = create_chain_proposal(&dir, "b.txt", "unused.txt");
+        empty.proposed_name = String::new();
+        empty.proposed_path = format!("{}/", dir.path().to_string_lossy());
+        let mut extension_only = create_chain_proposal(&dir, "c.txt", ".txt");
+        extension_only.id = "extension-only".to_string();
+
+        for two_phase in [false, true] {
+            let proposals = vec![blank.clone(), empty.clone(), extension_only.clone()];
+            let options = ExecuteRenameOptions { two_phase, ..Default::default() };
+            let result = if two_phase {
+                execute_two_phase(proposals, options, None, &|_| {}).unwrap()
+            } else {
+                execute_rename_internal(proposals, options, None).unwrap()
+            };
+
+            assert_eq!(result.summary.failed, 3);
+            assert!(result
+                .results
+                .iter()
+                .all(|r| r.error.as_deref() == Some("Proposed name is empty")));
+            assert!(dir.path().join("a.txt").exists());
+            assert!(dir.path().join("b.txt").exists());
+            assert!(dir.path().join("c.txt").exists());
+            assert!(!dir.path().join("  ").exists());
+            assert!(!dir.path().join(".txt").exists());
+        }
     }
 
-    #[test]
-    fn test_normalize_case_title_case() {
-        assert_eq!(normalize_case("hello world", &CaseStyle::TitleCase), "Hello World");
+    #[tokio::test]
+    async fn test_preview_case_change_snake_vs_kebab() {
+        let files = vec![
+            create_test_file_info("My Holiday Photo", "JPG", "/tmp/My Holiday Photo.JPG"),
+            create_test_file_info("notes", "txt", "/tmp/notes.txt"),
+        ];
+
+        let snake = preview_case_change(files.clone(), CaseStyle::SnakeCase).await.unwrap();
+        let kebab = preview_case_change(files, CaseStyle::KebabCase).await.unwrap();
+
+        assert_eq!(snake.proposals[0].proposed_name, "my_holiday_photo.jpg");
+        assert_eq!(snake.proposals[0].proposed_path, "/tmp/my_holiday_photo.jpg");
+        assert_eq!(kebab.proposals[0].proposed_name, "my-holiday-photo.jpg");
+        assert_eq!(snake.proposals[1].status, RenameStatus::NoChange);
+        assert_eq!(kebab.proposals[1].status, RenameStatus::NoChange);
+        assert_eq!(snake.summary.ready, 1);
+        assert_eq!(kebab.summary.ready, 1);
     }
 
-    #[test]
-    fn test_normalize_case_kebab_case() {
-        assert_eq!(normalize_case("Hello World", &CaseStyle::KebabCase), "hello-world");
-        assert_eq!(normalize_case("helloWorld", &CaseStyle::KebabCase), "hello-world");
+    // =========================================================================
+    // Date Format Normalization Tests
+    // =========================================================================
+
+    async fn normalized_names(names: &[&str], order: Option<DateOrder>) -> Vec<String> {
+        let files = names
+            .iter()
+            .map(|name| {
+                let (stem, ext) = name.rsplit_once('.').unwrap();
+                create_test_file_info(stem, ext, &format!("/tmp/{}", name))
+            })
+            .collect();
+        let preview = normalize_date_formats_preview(files, "YYYY-MM-DD".to_string(), order)
+            .await
+            .unwrap();
+        preview.proposals.into_iter().map(|p| p.proposed_name).collect()
     }
 
-    #[test]
-    fn test_normalize_case_snake_case() {
-        assert_eq!(normalize_case("Hello World", &CaseStyle::SnakeCase), "hello_world");
-        assert_eq!(normalize_case("helloWorld", &CaseStyle::SnakeCase), "hello_world");
+    #[tokio::test]
+    async fn test_normalize_date_formats_to_iso() {
+        let names = normalized_names(
+            &[
+                "2024_01_02 minutes.txt",
+                "invoice 20240102.pdf",
+                "scan-02.01.2024.jpg",
+                "report 25-12-2024.doc",
+                "notes.txt",
+            ],
+            Some(DateOrder::DayFirst),
+        )
+        .await;
+
+        assert_eq!(
+            names,
+            vec![
+                "2024-01-02 minutes.txt",
+                "invoice 2024-01-02.pdf",
+                "scan-2024-01-02.jpg",
+                "report 2024-12-25.doc",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_normalize_date_formats_ambiguous_order() {
+        let month_first = normalized_names(&["photo_01-02-2024.jpg"], None).await;
+        let day_first =
+            normalized_names(&["photo_01-02-2024.jpg"], Some(DateOrder::DayFirst)).await;
+
+        assert_eq!(month_first, vec!["photo_2024-01-02.jpg"]);
+        assert_eq!(day_first, vec!["photo_2024-02-01.jpg"]);
+        // 12-25 can only be December 25th
+        assert_eq!(
+            normalized_names(&["12-25-2024.jpg"], Some(DateOrder::DayFirst)).await,
+            vec!["2024-12-25.jpg"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_normalize_date_formats_already_normalized_and_invalid_format() {
+        let files =
+            vec![create_test_file_info("2024-01-02 notes", "txt", "/tmp/2024-01-02 notes.txt")];
+        let preview = normalize_date_formats_preview(files.clone(), "YYYY-MM-DD".to_string(), None)
+            .await
+            .unwrap();
+        assert_eq!(preview.proposals[0].status, RenameStatus::NoChange);
+
+        let result = normalize_date_formats_preview(files, "YYYY-QQ".to_string(), None).await;
+        assert!(matches!(result, Err(RenameError::ValidationFailed(_))));
+    }
+
+    // =========================================================================
+    // Template Validation Tests
+    // =========================================================================
+
+    #[tokio::test]
+    async fn test_validate_template_flags_non_idempotent_date_prefix() {
+        let validation = validate_template("{date}-{name}.{ext}".to_string(), None).await.unwrap();
+
+        assert!(!validation.idempotent);
+        assert_eq!(validation.example, "2024-03-15-photo.jpg");
+        assert_eq!(validation.issues[0].code, "NON_IDEMPOTENT");
+        assert!(validation.issues[0].message.contains("strip_existing_patterns"));
     }
 
-    #[test]
-    fn test_normalize_case_camel_case() {
-        assert_eq!(normalize_case("hello world", &CaseStyle::CamelCase), "helloWorld");
-        assert_eq!(normalize_case("Hello World", &CaseStyle::CamelCase), "helloWorld");
+    #[tokio::test]
+    async fn test_validate_template_with_stripping_is_idempotent() {
+        let validation = validate_template("{date}-{name}.{ext}".to_string(), Some(true))
+            .await
+            .unwrap();
+
+        assert!(validation.idempotent);
+        assert!(validation.issues.is_empty());
+        // Templates that don't grow the name are idempotent without stripping
+        let plain = validate_template("{name}.{ext}".to_string(), None).await.unwrap();
+        assert!(plain.idempotent);
     }
 
-    #[test]
-    fn test_normalize_case_pascal_case() {
-        assert_eq!(normalize_case("hello world", &CaseStyle::PascalCase), "HelloWorld");
+    /// A batch renaming a.txt onto an existing b.txt (a file-exists conflict)
+    fn create_overwrite_batch(dir: &TempDir) -> Vec<RenameProposal> {
+        let proposal = create_chain_proposal(dir, "a.txt", "b.txt");
+        fs::write(dir.path().join("a.txt"), "new").unwrap();
+        fs::write(dir.path().join("b.txt"), "old").unwrap();
+        finalize_preview(vec![proposal], String::new(), ReorganizationMode::RenameOnly).proposals
     }
 
     #[test]
-    fn test_normalize_filename_preserves_extension() {
-        assert_eq!(normalize_filename("Hello World.JPG", &CaseStyle::KebabCase), "hello-world.jpg");
-        assert_eq!(normalize_filename("My Document.PDF", &CaseStyle::SnakeCase), "my_document.pdf");
+    fn test_overwrite_refused_without_valid_token() {
+        let dir = TempDir::new().unwrap();
+        let proposals = create_overwrite_batch(&dir);
+        let state = RenameState::new();
+        let clock = crate::commands::clock::FixedClock(Utc::now());
+        let mut options = ExecuteRenameOptions { overwrite_existing: true, ..Default::default() };
+
+        // No token, an unknown token, then tokens for other files or expired
+        assert!(confirm_overwrites(&state, &proposals, &options, &clock).is_err());
+        options.confirmation_token = Some("forged".to_string());
+        assert!(confirm_overwrites(&state, &proposals, &options, &clock).is_err());
+
+        let other = state.issue_confirmation(vec!["/elsewhere/b.txt".to_string()], &clock);
+        options.confirmation_token = Some(other.confirmation_token);
+        assert!(confirm_overwrites(&state, &proposals, &options, &clock).is_err());
+
+        let target = proposals[0].proposed_path.clone();
+        let expired = state.issue_confirmation(vec![target], &clock);
+        options.confirmation_token = Some(expired.confirmation_token);
+        let ttl = chrono::Duration::seconds(CONFIRMATION_TTL_SECONDS);
+        let later = crate::commands::clock::FixedClock(clock.0 + ttl);
+        assert!(confirm_overwrites(&state, &proposals, &options, &later).is_err());
+
+        // Without confirmed overwrites the conflict is skipped, not clobbered
+        let result = execute_rename_internal(proposals, options, None).unwrap();
+        assert_eq!(result.results[0].outcome, RenameOutcome::Skipped);
+        assert_eq!(fs::read_to_string(dir.path().join("b.txt")).unwrap(), "old");
     }
 
     #[test]
-    fn test_normalize_filename_handles_hidden_files() {
-        assert_eq!(normalize_filename(".Hidden File.txt", &CaseStyle::KebabCase), ".hidden-file.txt");
+    fn test_overwrite_proceeds_with_matching_token() {
+        let dir = TempDir::new().unwrap();
+        let proposals = create_overwrite_batch(&dir);
+        let state = RenameState::new();
+        let clock = crate::commands::clock::FixedClock(Utc::now());
+
+        let target = proposals[0].proposed_path.clone();
+        let confirmation = state.issue_confirmation(vec![target.clone()], &clock);
+        assert_eq!(confirmation.targets, vec![target.clone()]);
+        let mut options = ExecuteRenameOptions {
+            overwrite_existing: true,
+            confirmation_token: Some(confirmation.confirmation_token.clone()),
+            ..Default::default()
+        };
+        options.confirmed_overwrites =
+            confirm_overwrites(&state, &proposals, &options, &clock).unwrap();
+        assert!(options.confirmed_overwrites.contains(&proposals[0].id));
+        // Tokens are single-use
+        assert!(confirm_overwrites(&state, &proposals, &options, &clock).is_err());
+
+        options.trash_file = Some(|path| fs::remove_file(path));
+        let result = execute_rename_internal(proposals, options, None).unwrap();
+
+        assert_eq!(result.results[0].outcome, RenameOutcome::Success);
+        assert!(!dir.path().join("a.txt").exists());
+        assert_eq!(fs::read_to_string(dir.path().join("b.txt")).unwrap(), "new");
+        assert_eq!(result.trashed_files, vec![target.clone()]);
+        let entry = crate::commands::history::create_entry_from_result(&result);
+        assert_eq!(entry.trashed_files, Some(vec![target]));
     }
 
     #[test]
-    fn test_normalize_filename_none_style() {
-        assert_eq!(normalize_filename("Hello World.JPG", &CaseStyle::None), "Hello World.JPG");
-    }
+    fn test_overwrite_keeps_existing_when_trash_fails() {
+        let dir = TempDir::new().unwrap();
+        let proposals = create_overwrite_batch(&dir);
+        let options = ExecuteRenameOptions {
+            confirmed_overwrites: HashSet::from([proposals[0].id.clone()]),
+            trash_file: Some(|_| Err(std::io::Error::other("trash unavailable"))),
+            ..Default::default()
+        };
 
-    #[tokio::test]
-    async fn test_generate_preview_with_case_normalization() {
-        let files = vec![create_test_file_info("My Photo", "JPG", "/tmp/My Photo.JPG")];
+        let result = execute_rename_internal(proposals, options, None).unwrap();
 
-        let options = GeneratePreviewOptions {
-            case_style: CaseStyle::KebabCase,
-            ..Default::default()
+        assert_eq!(result.results[0].outcome, RenameOutcome::Failed);
+        assert!(result.results[0].error.as_deref().unwrap().contains("trash"));
+        assert_eq!(fs::read_to_string(dir.path().join("b.txt")).unwrap(), "old");
+        assert!(result.trashed_files.is_empty());
+    }
+
+    #[test]
+    fn test_preview_warns_about_deep_destinations() {
+        let files = vec![
+            create_test_file_info("a", "jpg", "/inbox/a.jpg"),
+            create_test_file_info("b", "jpg", "/inbox/b.jpg"),
+        ];
+        let preview_with = |pattern: &str, max_depth: usize| {
+            let options = organize_options(Some("/sorted"), pattern);
+            let template = "{name}.{ext}".to_string();
+            build_preview(files.clone(), template, Some(options), max_depth, &SystemClock).unwrap()
         };
 
-        let result = generate_preview(files, "{name}.{ext}".to_string(), Some(options))
-            .await
-            .unwrap();
+        let preview = preview_with("{year}/{month}/{day}/Photos", 3);
+        assert_eq!(preview.warnings.len(), 1);
+        assert_eq!(preview.warnings[0].code, "DEEP_NESTING");
+        assert_eq!(preview.warnings[0].proposal_ids.len(), 2);
+        let deepest = preview.proposals[0].destination_folder.clone().unwrap();
+        assert!(preview.warnings[0].message.contains(&format!("deepest: \"{}\"", deepest)));
 
-        assert_eq!(result.proposals[0].proposed_name, "my-photo.jpg");
+        // A higher threshold accepts the same tree, and shallow trees never warn
+        assert!(preview_with("{year}/{month}/{day}/Photos", 4).warnings.is_empty());
+        assert!(preview_with("{year}/{month}", 3).warnings.is_empty());
     }
 
-    // =============================================================================
-    // Pattern Stripping Tests
-    // =============================================================================
+    fn create_movie_files() -> Vec<FileInfo> {
+        let mut movie = create_test_file_info("movie", "mp4", "/videos/movie.mp4");
+        movie.category = FileCategory::Video;
+        let mut subtitle = create_test_file_info("movie.en", "srt", "/videos/movie.en.srt");
+        subtitle.category = FileCategory::Other;
+        let mut unrelated = create_test_file_info("other.fr", "srt", "/videos/other.fr.srt");
+        unrelated.category = FileCategory::Other;
+        vec![movie, subtitle, unrelated]
+    }
 
-    #[test]
-    fn test_clean_filename_no_patterns() {
-        assert_eq!(clean_filename("photo"), "photo");
-        assert_eq!(clean_filename("my-vacation-pic"), "my-vacation-pic");
-        assert_eq!(clean_filename("document"), "document");
+    #[tokio::test]
+    async fn test_subtitles_follow_video_name() {
+        let options = GeneratePreviewOptions { attach_subtitles: true, ..Default::default() };
+        let template = "holiday.{ext}".to_string();
+        let files = create_movie_files();
+        let preview = generate_preview(files, template, Some(options)).await.unwrap();
+
+        assert_eq!(preview.proposals[0].proposed_name, "holiday.mp4");
+        assert_eq!(preview.proposals[1].proposed_name, "holiday.en.srt");
+        assert_eq!(preview.proposals[1].proposed_path, "/videos/holiday.en.srt");
+        assert_eq!(preview.proposals[1].status, RenameStatus::Ready);
+        // Without a matching video the template applies as usual
+        assert_eq!(preview.proposals[2].proposed_name, "holiday.srt");
     }
 
-    #[test]
-    fn test_clean_filename_iso_date_prefix() {
-        // ISO format YYYY-MM-DD at start
-        assert_eq!(clean_filename("2024-01-15_photo"), "photo");
-        assert_eq!(clean_filename("2024-01-15-photo"), "photo");
-        assert_eq!(clean_filename("2024_01_15_photo"), "photo");
-        assert_eq!(clean_filename("2024.01.15_photo"), "photo");
+    #[tokio::test]
+    async fn test_subtitles_move_with_video() {
+        let mut options = organize_options(Some("/sorted"), "Films");
+        options.attach_subtitles = true;
+        let template = "{name}.{ext}".to_string();
+        let files = create_movie_files();
+        let preview = generate_preview(files, template, Some(options)).await.unwrap();
+
+        let subtitle = &preview.proposals[1];
+        assert_eq!(subtitle.proposed_path, "/sorted/Films/movie.en.srt");
+        assert_eq!(subtitle.destination_folder.as_deref(), Some("Films"));
+        assert_eq!(subtitle.action_type, FileActionType::Move);
     }
 
     #[test]
-    fn test_clean_filename_european_date_prefix() {
-        // European format DD-MM-YYYY at start
-        assert_eq!(clean_filename("15-01-2024_photo"), "photo");
-        assert_eq!(clean_filename("15_01_2024_photo"), "photo");
-        assert_eq!(clean_filename("15.01.2024_photo"), "photo");
+    fn test_find_subtitle_video() {
+        let mut files = create_movie_files();
+        let mut part = create_test_file_info("movie.part2", "mkv", "/videos/movie.part2.mkv");
+        part.category = FileCategory::Video;
+        files.push(part);
+        let subtitle =
+            |name: &str| create_test_file_info(name, "srt", &format!("/videos/{}.srt", name));
+
+        assert_eq!(find_subtitle_video(&subtitle("movie"), &files), Some((0, "")));
+        let tagged = subtitle("movie.pt-BR.forced");
+        assert_eq!(find_subtitle_video(&tagged, &files), Some((0, "pt-BR.forced")));
+        assert_eq!(find_subtitle_video(&subtitle("movie.part2.en"), &files), Some((3, "en")));
+        assert_eq!(find_subtitle_video(&subtitle("movie extras"), &files), None);
+        let elsewhere = create_test_file_info("movie", "srt", "/other/movie.srt");
+        assert_eq!(find_subtitle_video(&elsewhere, &files), None);
     }
 
     #[test]
-    fn test_clean_filename_compact_date_prefix() {
-        // Compact YYYYMMDD at start
-        assert_eq!(clean_filename("20240115_photo"), "photo");
-        assert_eq!(clean_filename("20240115-photo"), "photo");
-        assert_eq!(clean_filename("20240115.photo"), "photo");
+    fn test_rename_one_renames_in_place() {
+        let dir = TempDir::new().unwrap();
+        let original = dir.path().join("IMG_0001.jpg");
+        fs::write(&original, "photo").unwrap();
+
+        let original_path = original.to_string_lossy().to_string();
+        let result = rename_one_internal(&original_path, "beach.jpg", &[]).unwrap();
+
+        assert_eq!(result.outcome, RenameOutcome::Success);
+        assert_eq!(result.new_name.as_deref(), Some("beach.jpg"));
+        assert!(!original.exists());
+        assert_eq!(fs::read_to_string(dir.path().join("beach.jpg")).unwrap(), "photo");
     }
 
     #[test]
-    fn test_clean_filename_date_suffix() {
-        // Dates at end
-        assert_eq!(clean_filename("photo_2024-01-15"), "photo");
-        assert_eq!(clean_filename("photo-2024-01-15"), "photo");
-        assert_eq!(clean_filename("photo_20240115"), "photo");
-        assert_eq!(clean_filename("photo.2024.01.15"), "photo");
+    fn test_rename_one_rejects_invalid_names() {
+        let dir = TempDir::new().unwrap();
+        let original = dir.path().join("notes.txt");
+        fs::write(&original, "notes").unwrap();
+        let original_path = original.to_string_lossy().to_string();
+
+        for name in ["", "   ", "a/b.txt", "../notes.txt", "bad:name.txt", "CON"] {
+            let result = rename_one_internal(&original_path, name, &[]);
+            assert!(matches!(result, Err(RenameError::ValidationFailed(_))), "{:?}", name);
+        }
+        let missing = dir.path().join("missing.txt").to_string_lossy().to_string();
+        assert!(rename_one_internal(&missing, "found.txt", &[]).is_err());
+        assert!(original.exists());
     }
 
     #[test]
-    fn test_clean_filename_date_in_middle() {
-        // NEW: Dates in the middle of filename
-        assert_eq!(clean_filename("photo_2024-01-15_vacation"), "photo_vacation");
-        assert_eq!(clean_filename("IMG_20240115_edited"), "IMG_edited");
-        assert_eq!(clean_filename("trip_15-01-2024_memories"), "trip_memories");
-        assert_eq!(clean_filename("scan_2024.01.15_document"), "scan_document");
+    fn test_rename_one_refuses_existing_target() {
+        let dir = TempDir::new().unwrap();
+        let original = dir.path().join("draft.txt");
+        fs::write(&original, "draft").unwrap();
+        fs::write(dir.path().join("final.txt"), "final").unwrap();
+        let original_path = original.to_string_lossy().to_string();
+
+        let result = rename_one_internal(&original_path, "final.txt", &[]).unwrap();
+
+        assert_eq!(result.outcome, RenameOutcome::Failed);
+        assert_eq!(result.error_kind, Some(RenameErrorKind::AlreadyExists));
+        assert_eq!(fs::read_to_string(dir.path().join("final.txt")).unwrap(), "final");
+
+        // Protected folders are refused before touching the file
+        let protected = vec![dir.path().to_string_lossy().to_string()];
+        let result = rename_one_internal(&original_path, "other.txt", &protected).unwrap();
+        assert_eq!(result.error_kind, Some(RenameErrorKind::ProtectedPath));
+        assert!(original.exists());
     }
 
     #[test]
-    fn test_clean_filename_multiple_dates() {
-        // Multiple dates should all be removed
-        assert_eq!(clean_filename("2024-01-15_trip_2024-01-20"), "trip");
-        assert_eq!(clean_filename("photo_20240115_20240120"), "photo");
+    fn test_execute_rename_records_progress_to_history_journal() {
+        let temp_dir = TempDir::new().unwrap();
+        let history_path = temp_dir.path().join("history.json");
+        let journal = HistoryJournal::at(history_path.clone(), None);
+        let first = create_chain_proposal(&temp_dir, "a.txt", "x.txt");
+        let second = create_chain_proposal(&temp_dir, "b.txt", "y.txt");
+
+        let options = ExecuteRenameOptions {
+            history_journal: Some(journal.clone()),
+            ..Default::default()
+        };
+        let result = execute_rename_internal(vec![first, second], options, None).unwrap();
+        assert_eq!(result.summary.succeeded, 2);
+
+        // Never finished: the journal alone already holds both renames
+        let contents = fs::read_to_string(&history_path).unwrap();
+        let store: super::super::history::HistoryStore = serde_json::from_str(&contents).unwrap();
+        assert_eq!(store.entries.len(), 1);
+        assert_eq!(store.entries[0].id, journal.entry_id());
+        assert_eq!(store.entries[0].summary.succeeded, 2);
+        assert!(store.entries[0].files.iter().all(|f| f.success && f.new_path.is_some()));
     }
 
-    #[test]
-    fn test_clean_filename_datetime_camera_format() {
-        // Camera format: YYYYMMDD_HHMMSS
-        assert_eq!(clean_filename("IMG_20240115_103045"), "IMG");
-        assert_eq!(clean_filename("20240115_103045_photo"), "photo");
-        assert_eq!(clean_filename("photo_20240115_103045_edited"), "photo_edited");
-        // Separated date + time
-        assert_eq!(clean_filename("2024-01-15_103045_photo"), "photo");
+    /// New paths of each snapshot passed to a group's `record` callback
+    fn recorded_moves(
+        run: impl FnOnce(&mut Vec<FileRenameResult>, &dyn Fn(&[FileRenameResult])),
+    ) -> Vec<Vec<String>> {
+        let snapshots = Mutex::new(Vec::new());
+        let record = |results: &[FileRenameResult]| {
+            let paths = results.iter().filter_map(|r| r.new_path.clone()).collect();
+            snapshots.lock().unwrap().push(paths);
+        };
+        run(&mut Vec::new(), &record);
+        snapshots.into_inner().unwrap()
     }
 
     #[test]
-    fn test_clean_filename_counter_suffix() {
-        // Counter patterns at end
-        assert_eq!(clean_filename("photo_001"), "photo");
-        assert_eq!(clean_filename("photo-02"), "photo");
-        assert_eq!(clean_filename("photo(3)"), "photo");
-        assert_eq!(clean_filename("photo (1)"), "photo");  // Windows style
-        assert_eq!(clean_filename("document_0001"), "document");
+    fn test_rename_groups_record_each_move_before_the_next() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = |name: &str| temp_dir.path().join(name).to_string_lossy().to_string();
+        let proposals = vec![
+            create_chain_proposal(&temp_dir, "a.txt", "b.txt"),
+            create_chain_proposal(&temp_dir, "b.txt", "c.txt"),
+        ];
+        let log = DirectoryCreationLog::default();
+        let groups = plan_rename_groups(&proposals, None);
+
+        let snapshots = recorded_moves(|results, record| {
+            execute_rename_group(&proposals, &groups[0].order, None, &log, None, results, record)
+        });
+
+        assert_eq!(snapshots, vec![vec![path("c.txt")], vec![path("c.txt"), path("b.txt")]]);
+
+        // A cycle records the first file at its temporary path until it is placed
+        let proposals = vec![
+            create_chain_proposal(&temp_dir, "x.txt", "y.txt"),
+            create_chain_proposal(&temp_dir, "y.txt", "x.txt"),
+        ];
+        let groups = plan_rename_groups(&proposals, None);
+
+        let snapshots = recorded_moves(|results, record| {
+            execute_rename_cycle(&proposals, &groups[0].order, &log, None, results, record)
+        });
+
+        assert_eq!(snapshots.len(), 3);
+        assert!(snapshots[0][0].contains(".tidy-rename-"));
+        assert_eq!(snapshots[1][1], path("x.txt"));
+        assert_eq!(snapshots[2], vec![path("x.txt"), path("y.txt")]);
     }
 
     #[test]
-    fn test_clean_filename_date_and_counter() {
-        // Combined date + counter
-        assert_eq!(clean_filename("2024-01-15_photo_001"), "photo");
-        assert_eq!(clean_filename("photo_2024-01-15_001"), "photo");
-        assert_eq!(clean_filename("IMG_20240115_103045_001"), "IMG");
+    fn test_two_phase_records_staged_files_in_history_journal() {
+        let temp_dir = TempDir::new().unwrap();
+        let history_path = temp_dir.path().join("history.json");
+        let proposals = vec![
+            create_move_proposal(&temp_dir, "a.jpg", "2024/07"),
+            create_move_proposal(&temp_dir, "b.jpg", "2024/08"),
+        ];
+        let options = ExecuteRenameOptions {
+            history_journal: Some(HistoryJournal::at(history_path.clone(), None)),
+            ..Default::default()
+        };
+        let read_entry = || {
+            let contents = fs::read_to_string(&history_path).unwrap();
+            let store: super::super::history::HistoryStore =
+                serde_json::from_str(&contents).unwrap();
+            store.entries.into_iter().next().unwrap()
+        };
+        // Progress is reported after each move, so the entry must already list it
+        let checked = Mutex::new(0);
+        let progress = |p: StagingProgress| {
+            let moved: Vec<String> =
+                read_entry().files.into_iter().filter_map(|f| f.new_path).collect();
+            match p.phase {
+                StagingPhase::Staging => {
+                    assert_eq!(moved.len(), p.processed);
+                    assert!(moved.iter().all(|path| path.contains(".tidy-staging-")));
+                }
+                StagingPhase::Finalizing => {
+                    let placed = moved.iter().filter(|path| !path.contains(".tidy-staging-"));
+                    assert_eq!((moved.len(), placed.count()), (2, p.processed));
+                }
+                _ => return,
+            }
+            *checked.lock().unwrap() += 1;
+        };
+
+        let result = execute_two_phase(proposals, options, None, &progress).unwrap();
+
+        assert_eq!(result.summary.succeeded, 2);
+        assert_eq!(checked.into_inner().unwrap(), 4);
     }
 
-    #[test]
-    fn test_clean_filename_preserves_non_date_numbers() {
-        // Numbers that aren't dates should be preserved
-        assert_eq!(clean_filename("photo123"), "photo123");
-        assert_eq!(clean_filename("vacation2024"), "vacation2024");
-        assert_eq!(clean_filename("room101"), "room101");
-        assert_eq!(clean_filename("v2_final"), "v2_final");
-        // Year alone (4 digits) should NOT be treated as date
-        assert_eq!(clean_filename("report_2024_annual"), "report_2024_annual");
+    /// A link "link.txt" to "real/target.txt" and a proposal renaming the link
+    #[cfg(unix)]
+    fn create_symlink_proposal(dir: &TempDir) -> RenameProposal {
+        fs::create_dir(dir.path().join("real")).unwrap();
+        fs::write(dir.path().join("real/target.txt"), "content").unwrap();
+        let proposal = create_chain_proposal(dir, "link.txt", "renamed.txt");
+        fs::remove_file(dir.path().join("link.txt")).unwrap();
+        std::os::unix::fs::symlink(
+            dir.path().join("real/target.txt"),
+            dir.path().join("link.txt"),
+        )
+        .unwrap();
+        proposal
     }
 
-    #[test]
-    fn test_clean_filename_invalid_dates_preserved() {
-        // Invalid month (13) or day (32) should not match
-        assert_eq!(clean_filename("2024-13-01_photo"), "2024-13-01_photo");
-        assert_eq!(clean_filename("2024-01-32_photo"), "2024-01-32_photo");
-        // Invalid year (not 19xx or 20xx)
-        assert_eq!(clean_filename("1899-01-15_photo"), "1899-01-15_photo");
-        assert_eq!(clean_filename("2100-01-15_photo"), "2100-01-15_photo");
+    #[cfg(unix)]
+    fn execute_with_symlink_policy(
+        proposal: RenameProposal,
+        policy: SymlinkRenamePolicy,
+    ) -> FileRenameResult {
+        let options = ExecuteRenameOptions {
+            symlink_rename_policy: policy,
+            ..Default::default()
+        };
+        let result = execute_rename_internal(vec![proposal], options, None).unwrap();
+        result.results.into_iter().next().unwrap()
     }
 
+    #[cfg(unix)]
     #[test]
-    fn test_clean_filename_empty_result_returns_original() {
-        // If cleaning would result in empty string, return original
-        assert_eq!(clean_filename("2024-01-15"), "2024-01-15");
-        assert_eq!(clean_filename("20240115"), "20240115");
-        assert_eq!(clean_filename("001"), "001");
-        assert_eq!(clean_filename("20240115_103045"), "20240115_103045");
+    fn test_symlink_policy_rename_link() {
+        let dir = TempDir::new().unwrap();
+        let proposal = create_symlink_proposal(&dir);
+
+        let result = execute_with_symlink_policy(proposal, SymlinkRenamePolicy::RenameLink);
+
+        assert_eq!(result.outcome, RenameOutcome::Success);
+        assert!(dir.path().join("renamed.txt").is_symlink());
+        assert!(dir.path().join("real/target.txt").is_file());
+        let symlink = result.symlink.unwrap();
+        assert_eq!(symlink.policy, SymlinkRenamePolicy::RenameLink);
+        assert!(symlink.target_path.unwrap().ends_with("real/target.txt"));
     }
 
+    #[cfg(unix)]
     #[test]
-    fn test_clean_filename_hidden_files() {
-        // Hidden files (Unix style) should preserve the leading dot
-        assert_eq!(clean_filename(".hidden_2024-01-15"), ".hidden");
-        assert_eq!(clean_filename(".config_20240115"), ".config");
+    fn test_symlink_policy_rename_target() {
+        let dir = TempDir::new().unwrap();
+        let proposal = create_symlink_proposal(&dir);
+
+        let result = execute_with_symlink_policy(proposal, SymlinkRenamePolicy::RenameTarget);
+
+        assert_eq!(result.outcome, RenameOutcome::Success);
+        // The target is renamed in its own folder; the link is untouched
+        assert!(dir.path().join("real/renamed.txt").is_file());
+        assert!(!dir.path().join("real/target.txt").exists());
+        assert!(dir.path().join("link.txt").is_symlink());
+        assert!(!dir.path().join("renamed.txt").exists());
+        assert!(result.original_path.ends_with("real/target.txt"));
+        assert_eq!(result.symlink.unwrap().policy, SymlinkRenamePolicy::RenameTarget);
     }
 
+    #[cfg(unix)]
     #[test]
-    fn test_clean_filename_mixed_separators() {
-        // Mixed separators in date should still work
-        assert_eq!(clean_filename("2024-01_15_photo"), "photo");
-        assert_eq!(clean_filename("photo_2024.01-15"), "photo");
+    fn test_symlink_policy_rename_target_fails_for_broken_link() {
+        let dir = TempDir::new().unwrap();
+        let proposal = create_symlink_proposal(&dir);
+        fs::remove_file(dir.path().join("real/target.txt")).unwrap();
+
+        let result = execute_with_symlink_policy(proposal, SymlinkRenamePolicy::RenameTarget);
+
+        assert_eq!(result.outcome, RenameOutcome::Failed);
+        assert_eq!(result.error_kind, Some(RenameErrorKind::NotFound));
+        assert!(result.symlink.unwrap().target_path.is_none());
+        assert!(dir.path().join("link.txt").is_symlink());
     }
 
+    #[cfg(unix)]
     #[test]
-    fn test_clean_filename_separator_cleanup() {
-        // Multiple consecutive separators should be collapsed
-        assert_eq!(clean_filename("photo__vacation"), "photo_vacation");
-        assert_eq!(clean_filename("photo---trip"), "photo_trip");
-    }
+    fn test_symlink_policy_rename_target_refuses_existing_path() {
+        let dir = TempDir::new().unwrap();
+        let proposal = create_symlink_proposal(&dir);
+        fs::write(dir.path().join("real/renamed.txt"), "existing").unwrap();
 
-    #[tokio::test]
-    async fn test_generate_preview_with_strip_existing_patterns() {
-        // Simulate a file that was already renamed with a date prefix
-        let files = vec![create_test_file_info("2024-01-15_photo", "jpg", "/tmp/2024-01-15_photo.jpg")];
+        let result = execute_with_symlink_policy(proposal, SymlinkRenamePolicy::RenameTarget);
 
-        // Without stripping - would create duplicate date
-        let options_no_strip = GeneratePreviewOptions {
-            strip_existing_patterns: false,
-            ..Default::default()
-        };
+        assert_eq!(result.outcome, RenameOutcome::Failed);
+        assert_eq!(result.error_kind, Some(RenameErrorKind::AlreadyExists));
+        assert_eq!(fs::read_to_string(dir.path().join("real/renamed.txt")).unwrap(), "existing");
+        assert_eq!(fs::read_to_string(dir.path().join("real/target.txt")).unwrap(), "content");
+    }
 
-        let result = generate_preview(files.clone(), "{date}_{name}.{ext}".to_string(), Some(options_no_strip))
-            .await
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_policy_rename_target_refuses_targets_outside_scan_root() {
+        let dir = TempDir::new().unwrap();
+        let outside = TempDir::new().unwrap();
+        fs::write(outside.path().join("target.txt"), "content").unwrap();
+        let proposal = create_chain_proposal(&dir, "link.txt", "renamed.txt");
+        fs::remove_file(dir.path().join("link.txt")).unwrap();
+        std::os::unix::fs::symlink(outside.path().join("target.txt"), dir.path().join("link.txt"))
             .unwrap();
 
-        // The date appears twice because {name} includes the existing date
-        assert!(result.proposals[0].proposed_name.contains("2024"));
-        assert!(result.proposals[0].proposed_name.matches("2024").count() >= 1);
+        let result =
+            execute_with_symlink_policy(proposal.clone(), SymlinkRenamePolicy::RenameTarget);
 
-        // With stripping - clean result
-        let options_strip = GeneratePreviewOptions {
-            strip_existing_patterns: true,
+        assert_eq!(result.outcome, RenameOutcome::Failed);
+        assert!(result.error.unwrap().contains("outside the scanned folder"));
+        assert!(outside.path().join("target.txt").is_file());
+
+        // An explicit scan root covering the target lets it through
+        let options = ExecuteRenameOptions {
+            symlink_rename_policy: SymlinkRenamePolicy::RenameTarget,
+            scan_root: Some(outside.path().to_string_lossy().to_string()),
             ..Default::default()
         };
+        let result = execute_rename_internal(vec![proposal], options, None).unwrap();
+        assert_eq!(result.summary.succeeded, 1);
+        assert!(outside.path().join("renamed.txt").is_file());
+    }
 
-        let result = generate_preview(files, "{date}_{name}.{ext}".to_string(), Some(options_strip))
-            .await
-            .unwrap();
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_policy_skip() {
+        let dir = TempDir::new().unwrap();
+        let proposal = create_symlink_proposal(&dir);
 
-        // The date should only appear once
-        let date_count = result.proposals[0].proposed_name.matches('-').count();
-        // ISO date has 2 dashes (YYYY-MM-DD), plus 1 underscore separator = clean format
-        assert!(date_count <= 3, "Expected clean date format, got: {}", result.proposals[0].proposed_name);
+        let result = execute_with_symlink_policy(proposal, SymlinkRenamePolicy::Skip);
+
+        assert_eq!(result.outcome, RenameOutcome::Skipped);
+        assert!(dir.path().join("link.txt").is_symlink());
+        assert!(dir.path().join("real/target.txt").is_file());
+        assert!(!dir.path().join("renamed.txt").exists());
+        assert_eq!(result.symlink.unwrap().policy, SymlinkRenamePolicy::Skip);
     }
 
     #[tokio::test]
-    async fn test_strip_existing_patterns_idempotent() {
-        // Apply template to a clean file
-        let files = vec![create_test_file_info("vacation", "jpg", "/tmp/vacation.jpg")];
-
-        let options = GeneratePreviewOptions {
-            strip_existing_patterns: true,
-            ..Default::default()
-        };
+    async fn test_name_reuse_report_lists_same_name_in_different_folders() {
+        let mut first = create_test_file_info("IMG_001", "jpg", "/camera/IMG_001.jpg");
+        first.modified_at = DateTime::parse_from_rfc3339("2023-06-01T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let mut second = create_test_file_info("IMG_002", "jpg", "/phone/IMG_002.jpg");
+        second.modified_at = DateTime::parse_from_rfc3339("2024-06-01T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let options = organize_options(Some("/sorted"), "{year}");
 
-        let result1 = generate_preview(files, "{date}_{name}.{ext}".to_string(), Some(options.clone()))
+        let template = "photo.{ext}".to_string();
+        let preview = generate_preview(vec![first, second], template, Some(options))
             .await
             .unwrap();
 
-        let first_name = &result1.proposals[0].proposed_name;
+        // Same name, different destinations: not a conflict
+        assert_eq!(preview.summary.conflicts, 0);
+        assert!(preview.proposals.iter().all(|p| p.proposed_name == "photo.jpg"));
 
-        // Now simulate applying to the already-renamed file
-        let renamed_files = vec![create_test_file_info(
-            first_name.strip_suffix(".jpg").unwrap_or(first_name),
-            "jpg",
-            &format!("/tmp/{}", first_name)
-        )];
+        let reuses = name_reuse_report(preview).await.unwrap();
+        assert_eq!(reuses.len(), 1);
+        assert_eq!(reuses[0].proposed_name, "photo.jpg");
+        assert_eq!(reuses[0].destination_folders, vec!["/sorted/2023", "/sorted/2024"]);
+        assert_eq!(reuses[0].proposal_ids.len(), 2);
+    }
 
-        let result2 = generate_preview(renamed_files, "{date}_{name}.{ext}".to_string(), Some(options))
+    #[tokio::test]
+    async fn test_name_reuse_report_ignores_conflicts() {
+        let files = vec![
+            create_test_file_info("photo1", "jpg", "/camera/photo1.jpg"),
+            create_test_file_info("photo2", "jpg", "/camera/photo2.jpg"),
+        ];
+
+        let preview = generate_preview(files, "output.{ext}".to_string(), None)
             .await
             .unwrap();
 
-        // With strip_existing_patterns=true, applying the same template twice
-        // should give similar results (idempotent)
-        assert!(
-            result2.proposals[0].proposed_name.ends_with("_vacation.jpg"),
-            "Expected idempotent result ending with _vacation.jpg, got: {}",
-            result2.proposals[0].proposed_name
-        );
+        assert_eq!(preview.summary.conflicts, 2);
+        assert!(name_reuse_report(preview).await.unwrap().is_empty());
     }
 }
@@ -0,0 +1,691 @@
+// Organize risk assessment commands for tidy-app GUI
+// Command names use snake_case per architecture requirements
+//
+// Safety review before a large organize: flags operations that touch system
+// folders, pull files out of cloud-synced folders, cross drives or create
+// many new folders or deeply nested ones. Detection is heuristic and based on
+// paths only.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use ts_rs::TS;
+
+use super::config::DEFAULT_MAX_DESTINATION_DEPTH;
+use super::rename::{will_rename, PreviewWarning, RenameError, RenamePreview, RenameProposal};
+
+/// Default number of cross-drive moves above which a flag is raised
+const DEFAULT_MAX_CROSS_DRIVE_MOVES: usize = 25;
+
+/// Default number of new folders above which a flag is raised
+const DEFAULT_MAX_NEW_FOLDERS: usize = 50;
+
+/// Path prefixes of operating system and application directories (lowercase, '/' separators)
+///
+/// "/opt/" holds installed software on Linux and Homebrew on Apple silicon,
+/// so anything under it is flagged. User folders under "/var/" are exempted
+/// by `USER_PATH_EXCEPTIONS`.
+const SYSTEM_PATH_PREFIXES: &[&str] = &[
+    "/system/",
+    "/usr/",
+    "/bin/",
+    "/sbin/",
+    "/etc/",
+    "/var/",
+    "/opt/",
+    "/library/",
+    "/applications/",
+    "c:/windows/",
+    "c:/program files/",
+    "c:/program files (x86)/",
+    "c:/programdata/",
+];
+
+/// User-writable folders under a system prefix (lowercase, '/' separators)
+///
+/// macOS per-user temp folders live in "/var/folders", shared temp space in
+/// "/var/tmp", and home folders on Fedora Atomic desktops in "/var/home".
+const USER_PATH_EXCEPTIONS: &[&str] = &["/var/folders/", "/var/tmp/", "/var/home/"];
+
+/// Folder names used by cloud sync clients (lowercase)
+const CLOUD_SYNC_FOLDERS: &[&str] = &[
+    "dropbox",
+    "onedrive",
+    "icloud drive",
+    "mobile documents",
+    "google drive",
+    "googledrive",
+];
+
+// =============================================================================
+// Types
+// =============================================================================
+
+/// How serious a risk flag is
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, PartialOrd, Ord, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "kebab-case")]
+pub enum RiskSeverity {
+    Info,
+    Warning,
+    Critical,
+}
+
+/// Kind of potentially destructive operation
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "kebab-case")]
+pub enum RiskKind {
+    /// Files inside operating system or application directories would change
+    SystemDirectory,
+    /// Files would be moved out of a cloud-synced folder
+    CloudSync,
+    /// Many files would be moved to a different drive
+    CrossDrive,
+    /// Many new folders would be created
+    LargeFolderCreation,
+    /// Files would be placed in deeply nested destination folders
+    DeepNesting,
+}
+
+/// A single risk found in a preview
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct RiskFlag {
+    pub kind: RiskKind,
+    pub severity: RiskSeverity,
+    /// Human-readable description
+    pub message: String,
+    /// Proposals involved in this risk
+    pub proposal_ids: Vec<String>,
+}
+
+/// Thresholds for risk assessment
+#[derive(Debug, Clone, Deserialize, Default, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct OrganizeRiskOptions {
+    /// Flag when more than this many files move across drives (default: 25)
+    #[serde(default)]
+    pub max_cross_drive_moves: Option<usize>,
+    /// Flag when more than this many new folders would be created (default: 50)
+    #[serde(default)]
+    pub max_new_folders: Option<usize>,
+    /// Flag destination folders nested deeper than this many levels (default: 3)
+    #[serde(default)]
+    pub max_destination_depth: Option<usize>,
+}
+
+/// A destination volume without enough free space for the files copied to it
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct VolumeSpaceShortfall {
+    /// Existing destination folder on the volume (free space is queried there)
+    pub volume_path: String,
+    /// Bytes that would be copied to the volume
+    pub required_bytes: u64,
+    pub available_bytes: u64,
+    /// Bytes missing (`required_bytes - available_bytes`)
+    pub shortfall_bytes: u64,
+    /// Proposals copying files to the volume
+    pub proposal_ids: Vec<String>,
+}
+
+/// Bytes a batch would copy to one destination volume
+#[derive(Debug, Clone)]
+struct VolumeUsage {
+    key: String,
+    path: String,
+    required_bytes: u64,
+    proposal_ids: Vec<String>,
+}
+
+// =============================================================================
+// Path Heuristics
+// =============================================================================
+
+/// Normalize a path for prefix matching ('/' separators, lowercase)
+fn normalize_path(path: &str) -> String {
+    path.replace('\\', "/").to_lowercase()
+}
+
+/// Whether a path is inside an operating system or application directory
+fn is_system_path(path: &str) -> bool {
+    let normalized = normalize_path(path);
+    if USER_PATH_EXCEPTIONS.iter().any(|prefix| normalized.starts_with(prefix)) {
+        return false;
+    }
+    SYSTEM_PATH_PREFIXES
+        .iter()
+        .any(|prefix| normalized.starts_with(prefix))
+        || normalized.contains("/appdata/")
+}
+
+/// Root of the cloud-synced folder containing a path, if any
+///
+/// Matches folders such as "Dropbox", "OneDrive - Contoso", "iCloud Drive"
+/// and macOS "Library/CloudStorage/<provider>".
+fn cloud_sync_root(path: &str) -> Option<String> {
+    let normalized = normalize_path(path);
+    let components: Vec<&str> = normalized.split('/').collect();
+
+    components.iter().enumerate().find_map(|(i, component)| {
+        let end = if *component == "cloudstorage" {
+            i + 2
+        } else if CLOUD_SYNC_FOLDERS
+            .iter()
+            .any(|name| component == name || component.starts_with(&format!("{} ", name)))
+        {
+            i + 1
+        } else {
+            return None;
+        };
+        Some(components[..end.min(components.len())].join("/"))
+    })
+}
+
+/// Identify the drive or volume a path lives on
+///
+/// Windows paths use their drive letter or UNC share; on Unix, removable and
+/// network volumes are recognized by their mount location.
+fn volume_root(path: &str) -> String {
+    let normalized = normalize_path(path);
+    let bytes = normalized.as_bytes();
+
+    if bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' {
+        return normalized[..2].to_string();
+    }
+
+    let parts: Vec<&str> = normalized.split('/').filter(|p| !p.is_empty()).collect();
+    let depth = if normalized.starts_with("//") {
+        // UNC share: //server/share
+        2
+    } else {
+        match parts.as_slice() {
+            ["volumes", ..] | ["mnt", ..] => 2,
+            ["media", ..] => 3,
+            ["run", "media", ..] => 4,
+            _ => 0,
+        }
+    };
+
+    format!("/{}", parts[..depth.min(parts.len())].join("/"))
+}
+
+// =============================================================================
+// Assessment
+// =============================================================================
+
+fn proposal_ids(proposals: &[&RenameProposal]) -> Vec<String> {
+    proposals.iter().map(|p| p.id.clone()).collect()
+}
+
+/// Number of levels in a proposal's destination folder ("2024/01/15" -> 3)
+fn destination_depth(proposal: &RenameProposal) -> usize {
+    proposal
+        .destination_folder
+        .as_deref()
+        .map_or(0, |folder| folder.split(['/', '\\']).filter(|p| !p.is_empty()).count())
+}
+
+/// Warn about proposals placed more than `max_depth` folder levels deep
+///
+/// Shared by preview generation and risk assessment; the message names the
+/// first of the deepest destination folders as an example.
+pub(super) fn deep_nesting_warning(
+    proposals: &[&RenameProposal],
+    max_depth: usize,
+) -> Option<PreviewWarning> {
+    let deep: Vec<&RenameProposal> = proposals
+        .iter()
+        .copied()
+        .filter(|p| destination_depth(p) > max_depth)
+        .collect();
+    let deepest = deep.iter().min_by_key(|p| std::cmp::Reverse(destination_depth(p)))?;
+    Some(PreviewWarning {
+        code: "DEEP_NESTING".to_string(),
+        message: format!(
+            "{} file(s) would be placed more than {} folder levels deep (deepest: \"{}\")",
+            deep.len(),
+            max_depth,
+            deepest.destination_folder.as_deref().unwrap_or_default()
+        ),
+        proposal_ids: proposal_ids(&deep),
+    })
+}
+
+/// Assess the proposals of a preview and return risk flags, most severe first
+fn assess_proposals(proposals: &[RenameProposal], options: &OrganizeRiskOptions) -> Vec<RiskFlag> {
+    let max_cross_drive = options.max_cross_drive_moves.unwrap_or(DEFAULT_MAX_CROSS_DRIVE_MOVES);
+    let max_new_folders = options.max_new_folders.unwrap_or(DEFAULT_MAX_NEW_FOLDERS);
+    let max_depth = options.max_destination_depth.unwrap_or(DEFAULT_MAX_DESTINATION_DEPTH);
+
+    let active: Vec<&RenameProposal> = proposals.iter().filter(|p| will_rename(p, None)).collect();
+    let mut flags = Vec::new();
+
+    let system: Vec<&RenameProposal> = active
+        .iter()
+        .copied()
+        .filter(|p| is_system_path(&p.original_path) || is_system_path(&p.proposed_path))
+        .collect();
+    if !system.is_empty() {
+        flags.push(RiskFlag {
+            kind: RiskKind::SystemDirectory,
+            severity: RiskSeverity::Critical,
+            message: format!(
+                "{} file(s) in system or application directories would be changed",
+                system.len()
+            ),
+            proposal_ids: proposal_ids(&system),
+        });
+    }
+
+    let out_of_cloud: Vec<&RenameProposal> = active
+        .iter()
+        .copied()
+        .filter(|p| match cloud_sync_root(&p.original_path) {
+            Some(root) => cloud_sync_root(&p.proposed_path).as_ref() != Some(&root),
+            None => false,
+        })
+        .collect();
+    if !out_of_cloud.is_empty() {
+        flags.push(RiskFlag {
+            kind: RiskKind::CloudSync,
+            severity: RiskSeverity::Warning,
+            message: format!(
+                "{} file(s) would be moved out of a cloud-synced folder and stop syncing",
+                out_of_cloud.len()
+            ),
+            proposal_ids: proposal_ids(&out_of_cloud),
+        });
+    }
+
+    let cross_drive: Vec<&RenameProposal> = active
+        .iter()
+        .copied()
+        .filter(|p| volume_root(&p.original_path) != volume_root(&p.proposed_path))
+        .collect();
+    if cross_drive.len() > max_cross_drive {
+        flags.push(RiskFlag {
+            kind: RiskKind::CrossDrive,
+            severity: RiskSeverity::Warning,
+            message: format!(
+                "{} file(s) would be moved to a different drive (limit: {})",
+                cross_drive.len(),
+                max_cross_drive
+            ),
+            proposal_ids: proposal_ids(&cross_drive),
+        });
+    }
+
+    let mut new_folders: BTreeSet<&Path> = BTreeSet::new();
+    let mut creating: Vec<&RenameProposal> = Vec::new();
+    for proposal in active.iter().copied().filter(|p| p.is_folder_move) {
+        if let Some(parent) = Path::new(&proposal.proposed_path).parent() {
+            if !parent.exists() {
+                // Missing ancestors are created too; the walk stops at the
+                // first folder that exists or was already counted
+                let missing: Vec<&Path> = parent
+                    .ancestors()
+                    .take_while(|dir| !new_folders.contains(dir) && !dir.exists())
+                    .collect();
+                new_folders.extend(missing);
+                creating.push(proposal);
+            }
+        }
+    }
+    if new_folders.len() > max_new_folders {
+        flags.push(RiskFlag {
+            kind: RiskKind::LargeFolderCreation,
+            severity: RiskSeverity::Warning,
+            message: format!(
+                "{} new folders would be created (limit: {})",
+                new_folders.len(),
+                max_new_folders
+            ),
+            proposal_ids: proposal_ids(&creating),
+        });
+    }
+
+    if let Some(warning) = deep_nesting_warning(&active, max_depth) {
+        flags.push(RiskFlag {
+            kind: RiskKind::DeepNesting,
+            severity: RiskSeverity::Info,
+            message: warning.message,
+            proposal_ids: warning.proposal_ids,
+        });
+    }
+
+    flags.sort_by_key(|flag| std::cmp::Reverse(flag.severity));
+    flags
+}
+
+// =============================================================================
+// Destination Space
+// =============================================================================
+
+/// Closest ancestor of a path (or the path itself) that exists on disk
+fn existing_ancestor(path: &Path) -> Option<PathBuf> {
+    path.ancestors().find(|p| p.exists()).map(Path::to_path_buf)
+}
+
+/// Identify the volume holding an existing path
+///
+/// Uses the device id on Unix and the drive letter or share elsewhere.
+fn volume_key(path: &Path) -> String {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        if let Ok(metadata) = fs::metadata(path) {
+            return metadata.dev().to_string();
+        }
+    }
+
+    volume_root(&path.to_string_lossy())
+}
+
+/// Sum the bytes each destination volume would receive
+///
+/// Moves within a volume are plain renames and need no space; only moves to
+/// another volume (copied, then the source deleted) are counted.
+fn plan_volume_usage(proposals: &[RenameProposal]) -> Vec<VolumeUsage> {
+    let mut usages: Vec<VolumeUsage> = Vec::new();
+
+    for proposal in proposals.iter().filter(|p| will_rename(p, None)) {
+        let Ok(metadata) = fs::metadata(&proposal.original_path) else {
+            continue;
+        };
+        let Some(destination) = Path::new(&proposal.proposed_path)
+            .parent()
+            .and_then(existing_ancestor)
+        else {
+            continue;
+        };
+
+        let key = volume_key(&destination);
+        if key == volume_key(Path::new(&proposal.original_path)) {
+            continue;
+        }
+
+        let index = match usages.iter().position(|u| u.key == key) {
+            Some(index) => index,
+            None => {
+                usages.push(VolumeUsage {
+                    key,
+                    path: destination.to_string_lossy().to_string(),
+                    required_bytes: 0,
+                    proposal_ids: Vec::new(),
+                });
+                usages.len() - 1
+            }
+        };
+        usages[index].required_bytes += metadata.len();
+        usages[index].proposal_ids.push(proposal.id.clone());
+    }
+
+    usages
+}
+
+/// Compare each volume's required bytes with its free space
+///
+/// Volumes whose free space can't be read are not reported.
+fn find_shortfalls(
+    usages: Vec<VolumeUsage>,
+    available_space: impl Fn(&str) -> Option<u64>,
+) -> Vec<VolumeSpaceShortfall> {
+    usages
+        .into_iter()
+        .filter_map(|usage| {
+            let available_bytes = available_space(&usage.path)?;
+            (usage.required_bytes > available_bytes).then(|| VolumeSpaceShortfall {
+                shortfall_bytes: usage.required_bytes - available_bytes,
+                volume_path: usage.path,
+                required_bytes: usage.required_bytes,
+                available_bytes,
+                proposal_ids: usage.proposal_ids,
+            })
+        })
+        .collect()
+}
+
+// =============================================================================
+// Tauri Commands
+// =============================================================================
+
+/// Flag potentially destructive operations in a preview before executing it
+///
+/// Only proposals that would actually run are assessed. Returns an empty list
+/// when nothing risky was found.
+///
+/// Command name: assess_organize_risk (snake_case per architecture)
+#[tauri::command]
+pub async fn assess_organize_risk(
+    preview: RenamePreview,
+    options: Option<OrganizeRiskOptions>,
+) -> Result<Vec<RiskFlag>, RenameError> {
+    let options = options.unwrap_or_default();
+    Ok(assess_proposals(&preview.proposals, &options))
+}
+
+/// Find destination volumes without enough free space for a preview
+///
+/// Files moved to another volume are copied before the source is deleted,
+/// so the destination needs room for all of them. Returns an empty list when
+/// every volume has enough space.
+///
+/// Command name: check_destination_space (snake_case per architecture)
+#[tauri::command]
+pub async fn check_destination_space(
+    preview: RenamePreview,
+) -> Result<Vec<VolumeSpaceShortfall>, RenameError> {
+    let usages = plan_volume_usage(&preview.proposals);
+    Ok(find_shortfalls(usages, |path| fs2::available_space(path).ok()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::rename::{FileActionType, RenameStatus};
+
+    fn move_proposal(id: &str, original_path: &str, proposed_path: &str) -> RenameProposal {
+        let name = |path: &str| path.rsplit('/').next().unwrap().to_string();
+        RenameProposal {
+            id: id.to_string(),
+            original_path: original_path.to_string(),
+            original_name: name(original_path),
+            proposed_name: name(proposed_path),
+            proposed_path: proposed_path.to_string(),
+            status: RenameStatus::Ready,
+            issues: vec![],
+            metadata_sources: None,
+            is_folder_move: true,
+            destination_folder: None,
+            action_type: FileActionType::Move,
+            conflict: None,
+        }
+    }
+
+    fn kinds(flags: &[RiskFlag]) -> Vec<RiskKind> {
+        flags.iter().map(|f| f.kind).collect()
+    }
+
+    #[test]
+    fn test_cross_drive_flag() {
+        let proposals: Vec<RenameProposal> = (0..3)
+            .map(|i| {
+                move_proposal(
+                    &i.to_string(),
+                    &format!("/Users/me/Pictures/{}.jpg", i),
+                    &format!("/Volumes/Backup/Pictures/{}.jpg", i),
+                )
+            })
+            .collect();
+        let options = OrganizeRiskOptions {
+            max_cross_drive_moves: Some(2),
+            max_new_folders: Some(usize::MAX),
+            max_destination_depth: None,
+        };
+
+        let flags = assess_proposals(&proposals, &options);
+
+        assert_eq!(kinds(&flags), vec![RiskKind::CrossDrive]);
+        assert_eq!(flags[0].proposal_ids.len(), 3);
+
+        // Below the limit nothing is flagged
+        let relaxed = OrganizeRiskOptions {
+            max_cross_drive_moves: Some(3),
+            max_new_folders: Some(usize::MAX),
+            max_destination_depth: None,
+        };
+        assert!(assess_proposals(&proposals, &relaxed).is_empty());
+    }
+
+    #[test]
+    fn test_large_folder_creation_flag() {
+        let proposals: Vec<RenameProposal> = (0..4)
+            .map(|i| {
+                move_proposal(
+                    &i.to_string(),
+                    &format!("/nonexistent-tidy/photos/{}.jpg", i),
+                    &format!("/nonexistent-tidy/photos/2024-0{}/{}.jpg", i + 1, i),
+                )
+            })
+            .collect();
+        let options = OrganizeRiskOptions {
+            max_cross_drive_moves: None,
+            max_new_folders: Some(3),
+            max_destination_depth: None,
+        };
+
+        let flags = assess_proposals(&proposals, &options);
+
+        // Four month folders plus the missing "photos" and "nonexistent-tidy" above them
+        assert_eq!(kinds(&flags), vec![RiskKind::LargeFolderCreation]);
+        assert!(flags[0].message.starts_with("6 new folders"));
+    }
+
+    fn organized_proposals(folders: &[&str]) -> Vec<RenameProposal> {
+        folders
+            .iter()
+            .enumerate()
+            .map(|(i, folder)| RenameProposal {
+                destination_folder: Some(folder.to_string()),
+                ..move_proposal(
+                    &i.to_string(),
+                    &format!("/home/me/inbox/{}.jpg", i),
+                    &format!("/home/me/inbox/{}/{}.jpg", folder, i),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_deep_nesting_flag() {
+        // Resolved from "{year}/{month}/{day}/{category}"
+        let proposals = organized_proposals(&["2024/01/15/image", "2024/01/16/image", "2024/02"]);
+
+        let flags = assess_proposals(&proposals, &OrganizeRiskOptions::default());
+
+        assert_eq!(kinds(&flags), vec![RiskKind::DeepNesting]);
+        assert_eq!(flags[0].severity, RiskSeverity::Info);
+        assert_eq!(flags[0].proposal_ids, vec!["0".to_string(), "1".to_string()]);
+        assert!(flags[0].message.contains("deepest: \"2024/01/15/image\""));
+
+        // A higher threshold accepts the same tree
+        let options = OrganizeRiskOptions {
+            max_destination_depth: Some(4),
+            ..Default::default()
+        };
+        assert!(assess_proposals(&proposals, &options).is_empty());
+    }
+
+    #[test]
+    fn test_shallow_destinations_not_flagged() {
+        // Resolved from "{year}/{month}"
+        let proposals = organized_proposals(&["2024/01", "2024/02", "2023/12"]);
+
+        assert!(assess_proposals(&proposals, &OrganizeRiskOptions::default()).is_empty());
+    }
+
+    #[test]
+    fn test_system_and_cloud_flags_sorted_by_severity() {
+        let proposals = vec![
+            move_proposal("a", "/Users/me/Dropbox/scan.pdf", "/Users/me/Documents/scan.pdf"),
+            move_proposal("b", "C:\\Windows\\notes.txt", "C:\\Windows\\Old\\notes.txt"),
+            move_proposal("c", "/Users/me/Dropbox/a.pdf", "/Users/me/Dropbox/2024/a.pdf"),
+        ];
+
+        let flags = assess_proposals(&proposals, &OrganizeRiskOptions::default());
+
+        assert_eq!(kinds(&flags), vec![RiskKind::SystemDirectory, RiskKind::CloudSync]);
+        assert_eq!(flags[0].severity, RiskSeverity::Critical);
+        assert_eq!(flags[1].proposal_ids, vec!["a".to_string()]);
+    }
+
+    #[test]
+    fn test_path_heuristics() {
+        assert_eq!(volume_root("D:\\Photos\\a.jpg"), "d:");
+        assert_eq!(volume_root("/media/me/usb/a.jpg"), "/media/me/usb");
+        assert_eq!(volume_root("/home/me/a.jpg"), "/");
+        assert_eq!(
+            cloud_sync_root("C:\\Users\\me\\OneDrive - Contoso\\a.docx").as_deref(),
+            Some("c:/users/me/onedrive - contoso")
+        );
+        assert_eq!(
+            cloud_sync_root("/Users/me/Library/CloudStorage/GoogleDrive-me/a.pdf").as_deref(),
+            Some("/users/me/library/cloudstorage/googledrive-me")
+        );
+        assert!(cloud_sync_root("/Users/me/Documents/a.pdf").is_none());
+        assert!(is_system_path("/var/log/syslog"));
+        assert!(is_system_path("/opt/homebrew/bin/brew"));
+        assert!(!is_system_path("/var/folders/xy/T/tmp.abc/a.jpg"));
+        assert!(!is_system_path("/var/home/me/Pictures/a.jpg"));
+    }
+
+    fn usage(key: &str, required_bytes: u64, ids: &[&str]) -> VolumeUsage {
+        VolumeUsage {
+            key: key.to_string(),
+            path: format!("/mnt/{}", key),
+            required_bytes,
+            proposal_ids: ids.iter().map(|id| id.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_find_shortfalls_with_mocked_volumes() {
+        let usages = vec![
+            usage("backup", 5_000, &["a", "b"]),
+            usage("usb", 1_000, &["c"]),
+            usage("unknown", 9_000, &["d"]),
+        ];
+        let available = |path: &str| match path {
+            "/mnt/backup" => Some(3_000),
+            "/mnt/usb" => Some(1_000),
+            _ => None,
+        };
+
+        let shortfalls = find_shortfalls(usages, available);
+
+        assert_eq!(shortfalls.len(), 1);
+        assert_eq!(shortfalls[0].volume_path, "/mnt/backup");
+        assert_eq!(shortfalls[0].shortfall_bytes, 2_000);
+        assert_eq!(shortfalls[0].proposal_ids, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_same_volume_moves_need_no_space() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let source = dir.path().join("a.jpg");
+        fs::write(&source, vec![0u8; 2048]).unwrap();
+        let proposal = move_proposal(
+            "a",
+            &source.to_string_lossy(),
+            &dir.path().join("new/folder/a.jpg").to_string_lossy(),
+        );
+
+        assert!(plan_volume_usage(&[proposal]).is_empty());
+    }
+}
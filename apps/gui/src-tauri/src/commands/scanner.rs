@@ -1,5 +1,6 @@
 use chrono::{DateTime, Utc};
 use serde::Serialize;
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
@@ -10,7 +11,10 @@ use ts_rs::TS;
 use uuid::Uuid;
 use walkdir::WalkDir;
 
+use super::config::DEFAULT_MAX_ACTIVE_SESSIONS;
 use super::error::{ErrorCategory, ErrorResponse};
+use super::exclusions::ExclusionRules;
+use super::metadata::extract_file_metadata;
 use super::security::{validate_scan_path, SecurityError};
 
 /// Error types for scan operations
@@ -26,6 +30,10 @@ pub enum ScanError {
     SecurityViolation(String),
     #[error("Internal error: {0}")]
     InternalError(String),
+    #[error("Too many active scans (limit: {0})")]
+    TooManyScans(usize),
+    #[error("A session with this ID is already running: {0}")]
+    SessionExists(String),
 }
 
 impl From<SecurityError> for ScanError {
@@ -72,6 +80,20 @@ impl ScanError {
                 ErrorCategory::Internal,
             )
             .with_suggestion("This is a bug. Please report it."),
+
+            ScanError::TooManyScans(limit) => ErrorResponse::new(
+                "TOO_MANY_SCANS",
+                format!("Too many active scans (limit: {})", limit),
+                ErrorCategory::Internal,
+            )
+            .with_suggestion("Wait for a running scan to finish or cancel it, then try again."),
+
+            ScanError::SessionExists(id) => ErrorResponse::new(
+                "SESSION_EXISTS",
+                format!("A session with this ID is already running: {}", id),
+                ErrorCategory::Validation,
+            )
+            .with_suggestion("Use a new session ID for each operation."),
         }
     }
 }
@@ -132,6 +154,22 @@ pub struct FileInfo {
     pub metadata_supported: bool,
     /// Level of metadata capability
     pub metadata_capability: MetadataCapability,
+    /// Whether `modified_at` came from the filesystem (false when the scan time was substituted)
+    #[serde(default = "default_has_valid_dates")]
+    pub has_valid_dates: bool,
+    /// Key metadata fields (EXIF date/camera, PDF title/date, ID3 tags),
+    /// only populated when the scan was run with `extract_metadata`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+    /// Filesystem id (device and inode, or volume and file index on Windows)
+    /// that stays the same across renames and re-scans, only populated when
+    /// the scan was run with `compute_stable_ids`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stable_id: Option<String>,
+}
+
+fn default_has_valid_dates() -> bool {
+    true
 }
 
 /// Options for folder scanning
@@ -145,10 +183,36 @@ pub struct ScanOptions {
     /// Filter by file extensions (without dot, e.g., ["jpg", "png"])
     #[serde(default)]
     pub extensions: Option<Vec<String>>,
+    /// Skip hidden files and files inside hidden folders (default: false)
+    #[serde(default)]
+    pub skip_hidden: bool,
+    /// Glob patterns to exclude, matched against the path relative to the scan root
+    #[serde(default)]
+    pub exclude_patterns: Option<Vec<String>>,
+    /// Apply .gitignore rules from the scan root (default: false)
+    #[serde(default)]
+    pub respect_gitignore: bool,
+    /// Apply .tidyignore rules from the scan root (default: false)
+    #[serde(default)]
+    pub respect_tidyignore: bool,
+    /// Report progress every N files; 0 disables progress events
+    /// (default: adaptive interval based on the number of files discovered)
+    #[serde(default)]
+    pub progress_interval: Option<usize>,
+    /// Read key metadata fields for each file into `FileInfo.metadata` (default: false)
+    #[serde(default)]
+    pub extract_metadata: bool,
+    /// Record each file's filesystem id in `FileInfo.stable_id` (default: false)
+    #[serde(default)]
+    pub compute_stable_ids: bool,
+    /// Also return the directories found, for a tree view (default: false).
+    /// Extension filters don't apply to directories; exclusion rules do.
+    #[serde(default)]
+    pub include_directories: bool,
 }
 
 /// Reason why a file was skipped during scan
-#[derive(Debug, Clone, Serialize, TS)]
+#[derive(Debug, Clone, Serialize, serde::Deserialize, TS)]
 #[ts(export, export_to = "bindings/")]
 #[serde(rename_all = "camelCase")]
 #[allow(dead_code)]
@@ -157,14 +221,38 @@ pub enum SkipReason {
     MetadataError,
     /// File was filtered out by extension
     FilteredByExtension,
+    /// Hidden file or inside a hidden folder
+    Hidden,
+    /// Excluded by a .gitignore pattern
+    Gitignore,
+    /// Excluded by a .tidyignore pattern
+    Tidyignore,
+    /// Excluded by a glob pattern from scan options
+    Glob,
     /// Permission denied
     PermissionDenied,
     /// Other error
     Other,
 }
 
+impl SkipReason {
+    /// Serialized (camelCase) name of the reason
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SkipReason::MetadataError => "metadataError",
+            SkipReason::FilteredByExtension => "filteredByExtension",
+            SkipReason::Hidden => "hidden",
+            SkipReason::Gitignore => "gitignore",
+            SkipReason::Tidyignore => "tidyignore",
+            SkipReason::Glob => "glob",
+            SkipReason::PermissionDenied => "permissionDenied",
+            SkipReason::Other => "other",
+        }
+    }
+}
+
 /// Information about a file that was skipped during scan
-#[derive(Debug, Clone, Serialize, TS)]
+#[derive(Debug, Clone, Serialize, serde::Deserialize, TS)]
 #[ts(export, export_to = "bindings/")]
 #[serde(rename_all = "camelCase")]
 pub struct SkippedFile {
@@ -175,10 +263,29 @@ pub struct SkippedFile {
     /// Optional error message
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    /// Pattern that excluded the file (for ignore-file and glob rules)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rule: Option<String>,
+}
+
+/// A directory found during a scan (see `ScanOptions.include_directories`)
+#[derive(Debug, Clone, Serialize, serde::Deserialize, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct DirectoryInfo {
+    pub path: String,
+    pub name: String,
+    /// Path relative to the scan root
+    pub relative_path: String,
+    /// Nesting level below the scan root (1 for direct children)
+    pub depth: usize,
+    /// Earliest modification date of the scanned files inside the directory,
+    /// or the directory's own mtime when it holds none
+    pub modified_at: DateTime<Utc>,
 }
 
 /// Result of a folder scan
-#[derive(Debug, Serialize, TS)]
+#[derive(Debug, Serialize, serde::Deserialize, TS)]
 #[ts(export, export_to = "bindings/")]
 #[serde(rename_all = "camelCase")]
 pub struct ScanResult {
@@ -200,6 +307,40 @@ pub struct ScanResult {
     /// Whether the scan was cancelled
     #[serde(default)]
     pub cancelled: bool,
+    /// Directories found, when `include_directories` is set
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub directories: Vec<DirectoryInfo>,
+}
+
+/// Files that changed between two scans of the same folder
+#[derive(Debug, Serialize, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct ScanDiff {
+    /// Files only in the current scan
+    pub added: Vec<FileInfo>,
+    /// Files only in the previous scan
+    pub removed: Vec<FileInfo>,
+    /// Files at the same path with a different size or modification time
+    /// (as found by the current scan)
+    pub modified: Vec<FileInfo>,
+}
+
+/// Up-front estimate of how long a scan would take
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct ScanTimeEstimate {
+    /// Estimated duration of a full scan in seconds
+    pub estimated_seconds: f64,
+    /// Number of files found by the quick count
+    pub file_count: usize,
+    /// Number of files fully processed in the timed sample
+    pub sampled_files: usize,
+    /// Duration of the timed sample in milliseconds
+    pub sample_duration_ms: u64,
+    /// Whether the estimate was cancelled (the counts are then partial)
+    pub cancelled: bool,
 }
 
 // =============================================================================
@@ -246,6 +387,27 @@ pub enum ScanPhase {
     Cancelled,
 }
 
+/// Decide whether to report progress after `count` items
+///
+/// `interval` of `Some(n)` reports every n items and `Some(0)` never reports.
+/// `None` uses an adaptive interval that reduces IPC overhead for large
+/// directories while keeping small scans responsive.
+pub fn should_report_progress(count: usize, interval: Option<usize>) -> bool {
+    let report_interval = match interval {
+        Some(0) => return false,
+        Some(n) => n,
+        None if count == 1 => return true,
+        None => match count {
+            0..=100 => 1,        // Report every file for small scans
+            101..=1000 => 10,    // Report every 10 files
+            1001..=10000 => 100, // Report every 100 files
+            _ => 500,            // Report every 500 files for very large scans
+        },
+    };
+
+    count % report_interval == 0
+}
+
 // =============================================================================
 // Cancellation Support
 // =============================================================================
@@ -278,10 +440,39 @@ impl Default for CancellationToken {
     }
 }
 
+/// Kind of long-running operation tracked by a session
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "kebab-case")]
+pub enum SessionKind {
+    /// Folder scan (`scan_folder_with_progress`, `estimate_scan_time`)
+    Scan,
+    /// Duplicate search (`find_duplicates`)
+    Duplicates,
+    /// AI analysis (`analyze_files_with_llm`)
+    Analysis,
+}
+
+/// An active session, for diagnostics
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveSession {
+    pub session_id: String,
+    pub kind: SessionKind,
+    /// Folder (or file) being processed
+    pub path: String,
+    /// Time since the session started, in milliseconds
+    #[ts(type = "number")]
+    pub age_ms: u64,
+}
+
 /// A scan session with its cancellation token and creation time
 struct ScanSession {
     token: CancellationToken,
     created_at: Instant,
+    kind: SessionKind,
+    path: String,
 }
 
 /// Maximum session lifetime before automatic cleanup (1 hour)
@@ -291,21 +482,48 @@ const SESSION_TTL_SECS: u64 = 3600;
 pub struct ScanState {
     /// Active scan sessions with their cancellation tokens and timestamps
     sessions: Mutex<HashMap<String, ScanSession>>,
+    /// Sessions beyond this many are refused with `TooManyScans`
+    max_sessions: usize,
 }
 
 impl ScanState {
     pub fn new() -> Self {
+        Self::with_max_sessions(DEFAULT_MAX_ACTIVE_SESSIONS)
+    }
+
+    /// Create a state allowing at most `max_sessions` active sessions
+    pub fn with_max_sessions(max_sessions: usize) -> Self {
         Self {
             sessions: Mutex::new(HashMap::new()),
+            max_sessions,
         }
     }
 
     /// Create a new scan session and return its ID
     /// Also cleans up any stale sessions to prevent memory leaks
     ///
-    /// Returns None if the mutex is poisoned (indicates a serious bug)
-    pub fn create_session(&self) -> Option<(String, CancellationToken)> {
+    /// Fails with `TooManyScans` when the session limit is reached
+    pub fn create_session(
+        &self,
+        kind: SessionKind,
+        path: &str,
+    ) -> Result<(String, CancellationToken), ScanError> {
         let session_id = Uuid::new_v4().to_string();
+        let token = self.create_session_with_id(&session_id, kind, path)?;
+        Ok((session_id, token))
+    }
+
+    /// Create a scan session under an ID chosen by the caller
+    ///
+    /// Lets the frontend cancel a command that returns nothing until it completes.
+    /// Fails with `SessionExists` if the ID is in use, so one operation can't
+    /// take over (or drop) another's cancellation token.
+    pub fn create_session_with_id(
+        &self,
+        session_id: &str,
+        kind: SessionKind,
+        path: &str,
+    ) -> Result<CancellationToken, ScanError> {
         let token = CancellationToken::new();
 
         let mut sessions = match self.sessions.lock() {
@@ -320,15 +538,24 @@ impl ScanState {
         // Clean up stale sessions before adding new one
         Self::cleanup_stale_sessions_internal(&mut sessions);
 
+        if sessions.contains_key(session_id) {
+            return Err(ScanError::SessionExists(session_id.to_string()));
+        }
+        if sessions.len() >= self.max_sessions {
+            return Err(ScanError::TooManyScans(self.max_sessions));
+        }
+
         sessions.insert(
-            session_id.clone(),
+            session_id.to_string(),
             ScanSession {
                 token: token.clone(),
                 created_at: Instant::now(),
+                kind,
+                path: path.to_string(),
             },
         );
 
-        Some((session_id, token))
+        Ok(token)
     }
 
     /// Cancel a scan session by ID
@@ -373,6 +600,30 @@ impl ScanState {
         }
     }
 
+    /// List active sessions, oldest first
+    /// Returns an empty list if mutex is poisoned
+    pub fn list_sessions(&self) -> Vec<ActiveSession> {
+        let sessions = match self.sessions.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => {
+                eprintln!("Warning: Scanner session mutex was poisoned during list");
+                poisoned.into_inner()
+            }
+        };
+
+        let mut active: Vec<ActiveSession> = sessions
+            .iter()
+            .map(|(id, session)| ActiveSession {
+                session_id: id.clone(),
+                kind: session.kind,
+                path: session.path.clone(),
+                age_ms: session.created_at.elapsed().as_millis() as u64,
+            })
+            .collect();
+        active.sort_by_key(|session| std::cmp::Reverse(session.age_ms));
+        active
+    }
+
     /// Clean up sessions that have exceeded their TTL
     /// This prevents memory leaks from crashed or abandoned scans
     /// Returns 0 if mutex is poisoned
@@ -412,8 +663,58 @@ impl Default for ScanState {
     }
 }
 
+/// Convert a filesystem timestamp, falling back to the current time
+///
+/// Returns whether the timestamp is real, so date-based templates can
+/// report missing data instead of silently using the scan time.
+fn timestamp_or_now(time: std::io::Result<std::time::SystemTime>) -> (DateTime<Utc>, bool) {
+    match time {
+        Ok(t) => (DateTime::<Utc>::from(t), true),
+        Err(_) => (Utc::now(), false),
+    }
+}
+
+/// Stable id for a file: its device and inode number
+///
+/// Unlike the path, it survives renames and moves within a volume, and
+/// copies with identical content still get different ids. Reading it costs
+/// one `stat`, not a content hash.
+#[cfg(unix)]
+pub fn stable_file_id(path: &str) -> Option<String> {
+    use std::os::unix::fs::MetadataExt;
+
+    match std::fs::metadata(path) {
+        Ok(metadata) => Some(format!("{}-{}", metadata.dev(), metadata.ino())),
+        Err(_) => path_file_id(path),
+    }
+}
+
+/// Stable id for a file: its volume serial number and file index
+#[cfg(windows)]
+pub fn stable_file_id(path: &str) -> Option<String> {
+    let info = winapi_util::Handle::from_path_any(path)
+        .and_then(|handle| winapi_util::file::information(&handle));
+    match info {
+        Ok(info) => Some(format!("{}-{}", info.volume_serial_number(), info.file_index())),
+        Err(_) => path_file_id(path),
+    }
+}
+
+/// Stable id for a file on platforms without file ids: its canonical path
+#[cfg(not(any(unix, windows)))]
+pub fn stable_file_id(path: &str) -> Option<String> {
+    path_file_id(path)
+}
+
+/// Fallback id when the filesystem id can't be read: the canonical path
+fn path_file_id(path: &str) -> Option<String> {
+    std::fs::canonicalize(path)
+        .ok()
+        .map(|p| format!("path:{}", p.to_string_lossy()))
+}
+
 /// Get category for a file extension
-fn get_category_for_extension(ext: &str) -> FileCategory {
+pub fn get_category_for_extension(ext: &str) -> FileCategory {
     let ext_lower = ext.to_lowercase();
     match ext_lower.as_str() {
         // Images
@@ -463,12 +764,94 @@ fn is_metadata_supported(ext: &str) -> bool {
     !matches!(get_metadata_capability(ext), MetadataCapability::None)
 }
 
+/// A `FileInfo` for `path` as a scan would describe it, without reading disk
+///
+/// Name parts and category come from the path and both dates are now; tests
+/// override the fields they care about with struct update syntax.
+#[cfg(test)]
+pub(crate) fn test_file_info(path: &str, size: u64) -> FileInfo {
+    let full_name = std::path::Path::new(path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let (name, extension) = match full_name.rsplit_once('.') {
+        Some((name, extension)) if !name.is_empty() => (name.to_string(), extension.to_string()),
+        _ => (full_name.clone(), String::new()),
+    };
+    let now = Utc::now();
+
+    FileInfo {
+        path: path.to_string(),
+        category: get_category_for_extension(&extension),
+        metadata_supported: is_metadata_supported(&extension),
+        metadata_capability: get_metadata_capability(&extension),
+        name,
+        extension,
+        full_name: full_name.clone(),
+        size,
+        created_at: now,
+        modified_at: now,
+        relative_path: full_name,
+        has_valid_dates: true,
+        metadata: None,
+        stable_id: None,
+    }
+}
+
 /// Internal scan result with files and skipped info
 struct ScanInternalResult {
     files: Vec<FileInfo>,
     total_size: u64,
     skipped: Vec<SkippedFile>,
     cancelled: bool,
+    directories: Vec<DirectoryInfo>,
+}
+
+/// Path of an entry relative to the scan root, with `/` separators (as
+/// exclusion rules expect)
+fn rule_relative_path(path: &std::path::Path, root: &std::path::Path) -> String {
+    path.strip_prefix(root)
+        .map(|p| p.to_string_lossy().replace('\\', "/"))
+        .unwrap_or_default()
+}
+
+/// Walk a scan root, pruning directories the exclusion rules omit
+///
+/// Excluded directories (e.g. `node_modules/` in .gitignore) are never
+/// descended into; each is recorded once in `pruned` with its rule.
+fn walk_scan_root<'a>(
+    root: &'a std::path::Path,
+    recursive: bool,
+    exclusions: &'a ExclusionRules,
+    pruned: &'a RefCell<Vec<SkippedFile>>,
+) -> impl Iterator<Item = walkdir::DirEntry> + 'a {
+    let walker = if recursive {
+        WalkDir::new(root)
+    } else {
+        WalkDir::new(root).max_depth(1)
+    };
+
+    walker
+        .into_iter()
+        .filter_entry(move |entry| {
+            // Without recursion directories aren't descended into anyway
+            if !recursive || entry.depth() == 0 || !entry.file_type().is_dir() {
+                return true;
+            }
+            match exclusions.check_dir(&rule_relative_path(entry.path(), root)) {
+                Some((reason, rule)) => {
+                    pruned.borrow_mut().push(SkippedFile {
+                        path: entry.path().to_string_lossy().to_string(),
+                        reason,
+                        error: None,
+                        rule,
+                    });
+                    false
+                }
+                None => true,
+            }
+        })
+        .filter_map(|e| e.ok())
 }
 
 /// Internal scan implementation with optional progress reporting and cancellation
@@ -493,16 +876,16 @@ fn scan_folder_internal(
 
     let mut files = Vec::new();
     let mut skipped = Vec::new();
+    let mut directories = Vec::new();
     let mut total_size: u64 = 0;
     let mut discovered: usize = 0;
 
-    // Configure walkdir based on recursive option
+    // Exclusion rules (hidden files, ignore files, globs)
+    let exclusions = ExclusionRules::from_options(&canonical_path, options);
+    let pruned_dirs = RefCell::new(Vec::new());
+
     // Use the canonicalized path to ensure we're scanning the validated directory
-    let walker = if options.recursive {
-        WalkDir::new(&canonical_path)
-    } else {
-        WalkDir::new(&canonical_path).max_depth(1)
-    };
+    let walker = walk_scan_root(&canonical_path, options.recursive, &exclusions, &pruned_dirs);
 
     // Normalize extensions to lowercase for comparison
     let extensions: Option<Vec<String>> = options
@@ -510,39 +893,41 @@ fn scan_folder_internal(
         .as_ref()
         .map(|exts| exts.iter().map(|e| e.to_lowercase()).collect());
 
-    for entry in walker.into_iter().filter_map(|e| e.ok()) {
+    for entry in walker {
         // Check for cancellation
         if let Some(token) = cancel_token {
             if token.is_cancelled() {
+                skipped.extend(pruned_dirs.take());
                 return Ok(ScanInternalResult {
                     files,
                     total_size,
                     skipped,
                     cancelled: true,
+                    directories,
                 });
             }
         }
 
         let entry_path = entry.path();
 
-        // Skip directories
+        // Skip directories (recording them for tree views if requested)
         if entry_path.is_dir() {
+            if options.include_directories && entry.depth() > 0 {
+                directories.extend(directory_info(
+                    entry_path,
+                    entry.depth(),
+                    &canonical_path,
+                    &exclusions,
+                ));
+            }
             continue;
         }
 
         discovered += 1;
 
-        // Report progress with adaptive interval based on discovered count
-        // This reduces IPC overhead for large directories while maintaining responsiveness
+        // Report progress at the configured (or adaptive) interval
         if let Some(callback) = progress_callback {
-            let report_interval = match discovered {
-                0..=100 => 1,        // Report every file for small scans
-                101..=1000 => 10,    // Report every 10 files
-                1001..=10000 => 100, // Report every 100 files
-                _ => 500,            // Report every 500 files for very large scans
-            };
-
-            if discovered == 1 || discovered % report_interval == 0 {
+            if should_report_progress(discovered, options.progress_interval) {
                 let file_name = entry_path
                     .file_name()
                     .and_then(|n| n.to_str())
@@ -551,6 +936,18 @@ fn scan_folder_internal(
             }
         }
 
+        // Apply exclusion rules, recording which rule omitted the file
+        let relative_for_rules = rule_relative_path(entry_path, &canonical_path);
+        if let Some((reason, rule)) = exclusions.check(&relative_for_rules) {
+            skipped.push(SkippedFile {
+                path: entry_path.to_string_lossy().to_string(),
+                reason,
+                error: None,
+                rule,
+            });
+            continue;
+        }
+
         // Get file metadata
         let metadata = match entry_path.metadata() {
             Ok(m) => m,
@@ -565,6 +962,7 @@ fn scan_folder_internal(
                     path: entry_path.to_string_lossy().to_string(),
                     reason,
                     error: Some(e.to_string()),
+                    rule: None,
                 });
                 continue;
             }
@@ -586,6 +984,12 @@ fn scan_folder_internal(
         // Filter by extension if specified
         if let Some(ref exts) = extensions {
             if !exts.is_empty() && !exts.contains(&extension.to_lowercase()) {
+                skipped.push(SkippedFile {
+                    path: entry_path.to_string_lossy().to_string(),
+                    reason: SkipReason::FilteredByExtension,
+                    error: None,
+                    rule: None,
+                });
                 continue;
             }
         }
@@ -604,23 +1008,27 @@ fn scan_folder_internal(
         let size = metadata.len();
         total_size += size;
 
-        // Get timestamps
-        let created_at = metadata
-            .created()
-            .map(|t| DateTime::<Utc>::from(t))
-            .unwrap_or_else(|_| Utc::now());
-
-        let modified_at = metadata
-            .modified()
-            .map(|t| DateTime::<Utc>::from(t))
-            .unwrap_or_else(|_| Utc::now());
+        // Get timestamps (templates use the modification time)
+        let (created_at, _) = timestamp_or_now(metadata.created());
+        let (modified_at, has_valid_dates) = timestamp_or_now(metadata.modified());
 
         let category = get_category_for_extension(&extension);
         let metadata_capability = get_metadata_capability(&extension);
         let metadata_supported = is_metadata_supported(&extension);
+        let path = entry_path.to_string_lossy().to_string();
+        let file_metadata = if options.extract_metadata {
+            extract_file_metadata(&path, &extension, &category)
+        } else {
+            None
+        };
+        let stable_id = if options.compute_stable_ids {
+            stable_file_id(&path)
+        } else {
+            None
+        };
 
         files.push(FileInfo {
-            path: entry_path.to_string_lossy().to_string(),
+            path,
             name,
             extension,
             full_name: file_name,
@@ -631,14 +1039,73 @@ fn scan_folder_internal(
             category,
             metadata_supported,
             metadata_capability,
+            has_valid_dates,
+            metadata: file_metadata,
+            stable_id,
         });
     }
 
+    skipped.extend(pruned_dirs.take());
+    date_directories_from_files(&mut directories, &files);
     Ok(ScanInternalResult {
         files,
         total_size,
         skipped,
         cancelled: false,
+        directories,
+    })
+}
+
+/// Date each directory by the earliest file it contains
+///
+/// A folder's own mtime changes whenever an entry is added or removed, so it
+/// says little about when its contents were created. Directories without
+/// dated files keep the mtime read during the walk.
+fn date_directories_from_files(directories: &mut [DirectoryInfo], files: &[FileInfo]) {
+    if directories.is_empty() {
+        return;
+    }
+
+    let mut earliest: HashMap<&std::path::Path, DateTime<Utc>> = HashMap::new();
+    for file in files.iter().filter(|f| f.has_valid_dates) {
+        for ancestor in std::path::Path::new(&file.path).ancestors().skip(1) {
+            let date = earliest.entry(ancestor).or_insert(file.modified_at);
+            if file.modified_at < *date {
+                *date = file.modified_at;
+            }
+        }
+    }
+
+    for directory in directories.iter_mut() {
+        if let Some(date) = earliest.get(std::path::Path::new(&directory.path)) {
+            directory.modified_at = *date;
+        }
+    }
+}
+
+/// Describe a directory found by the scan, unless exclusion rules omit it
+fn directory_info(
+    path: &std::path::Path,
+    depth: usize,
+    root: &std::path::Path,
+    exclusions: &ExclusionRules,
+) -> Option<DirectoryInfo> {
+    let relative_path = rule_relative_path(path, root);
+    if exclusions.check_dir(&relative_path).is_some() {
+        return None;
+    }
+
+    let (modified_at, _) = timestamp_or_now(path.metadata().and_then(|m| m.modified()));
+
+    Some(DirectoryInfo {
+        path: path.to_string_lossy().to_string(),
+        name: path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        relative_path,
+        depth,
+        modified_at,
     })
 }
 
@@ -663,6 +1130,7 @@ pub async fn scan_folder(
         skipped_count,
         session_id: None,
         cancelled: result.cancelled,
+        directories: result.directories,
     })
 }
 
@@ -682,8 +1150,7 @@ pub async fn scan_folder_with_progress(
     let options = options.unwrap_or_default();
 
     // Create a scan session
-    let (session_id, cancel_token) = scan_state.create_session()
-        .ok_or_else(|| ScanError::InternalError("Failed to create scan session".to_string()))?;
+    let (session_id, cancel_token) = scan_state.create_session(SessionKind::Scan, &path)?;
 
     // Emit starting progress
     let _ = window.emit("scan-progress", ScanProgress {
@@ -742,6 +1209,7 @@ pub async fn scan_folder_with_progress(
                 skipped_count,
                 session_id: Some(session_id),
                 cancelled: scan_result.cancelled,
+                directories: scan_result.directories,
             })
         }
         Err(e) => {
@@ -782,6 +1250,188 @@ pub async fn get_active_scans(
     Ok(scan_state.active_count())
 }
 
+/// List active scan, duplicate and analysis sessions with their ages
+///
+/// For diagnostics: a session much older than its work should take is
+/// likely stuck and can be cancelled with `cancel_scan`.
+///
+/// Command name: list_active_sessions (snake_case per architecture)
+#[tauri::command]
+pub async fn list_active_sessions(
+    scan_state: tauri::State<'_, ScanState>,
+) -> Result<Vec<ActiveSession>, String> {
+    Ok(scan_state.list_sessions())
+}
+
+/// Maximum number of files fully processed by the timed sample
+const ESTIMATE_SAMPLE_FILES: usize = 1000;
+
+/// Maximum duration of the timed sample
+const ESTIMATE_SAMPLE_DURATION: Duration = Duration::from_millis(500);
+
+/// Count the files a scan would keep, without reading their metadata
+///
+/// Applies the same exclusion rules as the scan, so ignored folders such as
+/// `node_modules` aren't walked or counted.
+fn count_scan_entries(
+    root: &std::path::Path,
+    options: &ScanOptions,
+    cancel_token: &CancellationToken,
+) -> (usize, bool) {
+    let exclusions = ExclusionRules::from_options(root, options);
+    let pruned_dirs = RefCell::new(Vec::new());
+
+    let mut count = 0;
+    for entry in walk_scan_root(root, options.recursive, &exclusions, &pruned_dirs) {
+        if cancel_token.is_cancelled() {
+            return (count, true);
+        }
+        if !entry.file_type().is_dir()
+            && exclusions.check(&rule_relative_path(entry.path(), root)).is_none()
+        {
+            count += 1;
+        }
+    }
+    (count, false)
+}
+
+/// Time a sample scan, then extrapolate to the number of files counted
+///
+/// The sample runs the real scan (exclusions, metadata, hashing) and stops
+/// after `sample_files` files or `sample_duration`, whichever comes first.
+fn estimate_scan_time_internal(
+    path: &str,
+    options: &ScanOptions,
+    cancel_token: &CancellationToken,
+    sample_files: usize,
+    sample_duration: Duration,
+) -> Result<ScanTimeEstimate, ScanError> {
+    let canonical_path = validate_scan_path(path)?;
+
+    let sample_options = ScanOptions {
+        progress_interval: Some(1),
+        ..options.clone()
+    };
+    let sample_token = CancellationToken::new();
+    let sampled = std::cell::Cell::new(0);
+    let start = Instant::now();
+    let stop_sample = |discovered: usize, _: &str| {
+        sampled.set(discovered);
+        if discovered >= sample_files
+            || start.elapsed() >= sample_duration
+            || cancel_token.is_cancelled()
+        {
+            sample_token.cancel();
+        }
+    };
+    let sample =
+        scan_folder_internal(path, &sample_options, Some(&sample_token), Some(&stop_sample))?;
+    let elapsed = start.elapsed();
+    let sampled = sampled.get();
+
+    let (file_count, cancelled) = if !sample.cancelled {
+        // The sample covered every file: no need to extrapolate
+        (sampled, false)
+    } else if cancel_token.is_cancelled() {
+        (sampled, true)
+    } else {
+        count_scan_entries(&canonical_path, options, cancel_token)
+    };
+
+    let estimated_seconds = if !sample.cancelled {
+        elapsed.as_secs_f64()
+    } else if sampled == 0 {
+        0.0
+    } else {
+        elapsed.as_secs_f64() / sampled as f64 * file_count as f64
+    };
+
+    Ok(ScanTimeEstimate {
+        estimated_seconds,
+        file_count,
+        sampled_files: sampled,
+        sample_duration_ms: elapsed.as_millis() as u64,
+        cancelled,
+    })
+}
+
+/// Estimate how long scanning a folder would take, before scanning it
+///
+/// Times a short sample scan (up to 1000 files or 500ms) and extrapolates to
+/// the total from a quick file count. Useful before scanning a slow network
+/// drive. Pass a `session_id` to be able to cancel through `cancel_scan`.
+///
+/// Command name: estimate_scan_time (snake_case per architecture)
+#[tauri::command]
+pub async fn estimate_scan_time(
+    scan_state: tauri::State<'_, ScanState>,
+    path: String,
+    options: Option<ScanOptions>,
+    session_id: Option<String>,
+) -> Result<ScanTimeEstimate, ScanError> {
+    let options = options.unwrap_or_default();
+    let cancel_token = match &session_id {
+        Some(id) => scan_state.create_session_with_id(id, SessionKind::Scan, &path)?,
+        None => CancellationToken::new(),
+    };
+
+    let result = estimate_scan_time_internal(
+        &path,
+        &options,
+        &cancel_token,
+        ESTIMATE_SAMPLE_FILES,
+        ESTIMATE_SAMPLE_DURATION,
+    );
+
+    if let Some(id) = &session_id {
+        scan_state.remove_session(id);
+    }
+    result
+}
+
+/// Compare two scans by path
+fn compute_scan_diff(previous: &ScanResult, current: &ScanResult) -> ScanDiff {
+    let previous_by_path: HashMap<&str, &FileInfo> =
+        previous.files.iter().map(|f| (f.path.as_str(), f)).collect();
+    let current_paths: std::collections::HashSet<&str> =
+        current.files.iter().map(|f| f.path.as_str()).collect();
+
+    let mut added = Vec::new();
+    let mut modified = Vec::new();
+    for file in &current.files {
+        match previous_by_path.get(file.path.as_str()) {
+            None => added.push(file.clone()),
+            Some(old) if old.size != file.size || old.modified_at != file.modified_at => {
+                modified.push(file.clone())
+            }
+            Some(_) => {}
+        }
+    }
+
+    let removed = previous
+        .files
+        .iter()
+        .filter(|f| !current_paths.contains(f.path.as_str()))
+        .cloned()
+        .collect();
+
+    ScanDiff {
+        added,
+        removed,
+        modified,
+    }
+}
+
+/// List files added, removed or modified since a previous scan
+///
+/// Lets watch-folder workflows organize only new files after a re-scan.
+///
+/// Command name: diff_scans (snake_case per architecture)
+#[tauri::command]
+pub async fn diff_scans(previous: ScanResult, current: ScanResult) -> Result<ScanDiff, String> {
+    Ok(compute_scan_diff(&previous, &current))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -789,6 +1439,57 @@ mod tests {
     use std::io::Write;
     use tempfile::TempDir;
 
+    #[test]
+    fn test_timestamp_or_now_without_modified_time() {
+        let unsupported = std::io::Error::new(std::io::ErrorKind::Unsupported, "no mtime");
+
+        let (_, valid) = timestamp_or_now(Err(unsupported));
+        assert!(!valid);
+
+        let (date, valid) = timestamp_or_now(Ok(std::time::UNIX_EPOCH));
+        assert!(valid);
+        assert_eq!(date.timestamp(), 0);
+    }
+
+    #[test]
+    fn test_file_info_defaults_to_valid_dates() {
+        let json = r#"{
+            "path": "/test/a.jpg", "name": "a", "extension": "jpg", "fullName": "a.jpg",
+            "size": 1, "createdAt": "2024-01-01T00:00:00Z", "modifiedAt": "2024-01-01T00:00:00Z",
+            "relativePath": "a.jpg", "category": "image", "metadataSupported": true,
+            "metadataCapability": "full"
+        }"#;
+
+        let file: FileInfo = serde_json::from_str(json).unwrap();
+
+        assert!(file.has_valid_dates);
+    }
+
+    #[tokio::test]
+    async fn test_diff_scans() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("kept.txt"), "same").unwrap();
+        fs::write(dir.path().join("edited.txt"), "before").unwrap();
+        fs::write(dir.path().join("deleted.txt"), "gone soon").unwrap();
+        let path = dir.path().to_string_lossy().to_string();
+        let previous = scan_folder(path.clone(), None).await.unwrap();
+
+        fs::write(dir.path().join("edited.txt"), "after the edit").unwrap();
+        fs::remove_file(dir.path().join("deleted.txt")).unwrap();
+        fs::write(dir.path().join("new.txt"), "new").unwrap();
+        let current = scan_folder(path, None).await.unwrap();
+
+        let diff = diff_scans(previous, current).await.unwrap();
+
+        let names = |files: &[FileInfo]| -> Vec<String> {
+            files.iter().map(|f| f.full_name.clone()).collect()
+        };
+        assert_eq!(names(&diff.added), vec!["new.txt"]);
+        assert_eq!(names(&diff.removed), vec!["deleted.txt"]);
+        assert_eq!(names(&diff.modified), vec!["edited.txt"]);
+        assert_eq!(diff.modified[0].size, 14);
+    }
+
     fn create_test_files(dir: &TempDir) -> std::io::Result<()> {
         // Create some test files
         let mut f = File::create(dir.path().join("test.jpg"))?;
@@ -831,6 +1532,7 @@ mod tests {
             Some(ScanOptions {
                 recursive: true,
                 extensions: None,
+                ..Default::default()
             }),
         )
         .await
@@ -850,6 +1552,7 @@ mod tests {
             Some(ScanOptions {
                 recursive: false,
                 extensions: Some(vec!["jpg".to_string()]),
+                ..Default::default()
             }),
         )
         .await
@@ -857,6 +1560,63 @@ mod tests {
 
         assert_eq!(result.total_count, 1);
         assert_eq!(result.files[0].extension, "jpg");
+        // Filtered files are reported rather than silently dropped
+        assert_eq!(result.skipped_count, 2);
+        assert!(result
+            .skipped
+            .iter()
+            .all(|s| matches!(s.reason, SkipReason::FilteredByExtension)));
+    }
+
+    #[tokio::test]
+    async fn test_scan_folder_attributes_exclusion_rules() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join(".gitignore"), "*.log\n").unwrap();
+        fs::write(dir.path().join(".tidyignore"), "drafts/\n").unwrap();
+        fs::create_dir(dir.path().join("drafts")).unwrap();
+        fs::write(dir.path().join("drafts").join("wip.txt"), "wip").unwrap();
+        fs::write(dir.path().join("debug.log"), "log").unwrap();
+        fs::write(dir.path().join("backup.bak"), "bak").unwrap();
+        fs::write(dir.path().join(".hidden.txt"), "hidden").unwrap();
+        fs::write(dir.path().join("notes.md"), "md").unwrap();
+        fs::write(dir.path().join("photo.jpg"), "jpg").unwrap();
+
+        let result = scan_folder(
+            dir.path().to_string_lossy().to_string(),
+            Some(ScanOptions {
+                recursive: true,
+                extensions: Some(vec!["jpg".to_string(), "txt".to_string(), "log".to_string(), "bak".to_string()]),
+                skip_hidden: true,
+                exclude_patterns: Some(vec!["*.bak".to_string()]),
+                respect_gitignore: true,
+                respect_tidyignore: true,
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(result.total_count, 1);
+        assert_eq!(result.files[0].full_name, "photo.jpg");
+
+        let reason_for = |name: &str| {
+            result
+                .skipped
+                .iter()
+                .find(|s| s.path.ends_with(name))
+                .map(|s| format!("{:?}", s.reason))
+        };
+        assert_eq!(reason_for(".hidden.txt").as_deref(), Some("Hidden"));
+        assert_eq!(reason_for(".gitignore").as_deref(), Some("Hidden"));
+        // Excluded directories are skipped whole, without walking their files
+        assert_eq!(reason_for("drafts").as_deref(), Some("Tidyignore"));
+        assert!(reason_for("wip.txt").is_none());
+        assert_eq!(reason_for("debug.log").as_deref(), Some("Gitignore"));
+        assert_eq!(reason_for("backup.bak").as_deref(), Some("Glob"));
+        assert_eq!(reason_for("notes.md").as_deref(), Some("FilteredByExtension"));
+
+        let glob_skip = result.skipped.iter().find(|s| s.path.ends_with("backup.bak")).unwrap();
+        assert_eq!(glob_skip.rule.as_deref(), Some("*.bak"));
     }
 
     #[tokio::test]
@@ -909,15 +1669,55 @@ mod tests {
         let state = ScanState::new();
         assert_eq!(state.active_count(), 0);
 
-        let (session_id, _token) = state.create_session().expect("should create session");
+        let (session_id, _token) = state
+            .create_session(SessionKind::Scan, "/tmp/photos")
+            .expect("should create session");
         assert!(!session_id.is_empty());
         assert_eq!(state.active_count(), 1);
     }
 
+    #[test]
+    fn test_scan_state_refuses_sessions_beyond_limit() {
+        let state = ScanState::with_max_sessions(2);
+        state.create_session(SessionKind::Scan, "/tmp/a").unwrap();
+        let (second, _token) = state.create_session(SessionKind::Duplicates, "/tmp/b").unwrap();
+
+        let error = state
+            .create_session(SessionKind::Scan, "/tmp/c")
+            .err()
+            .expect("third session should be refused");
+        assert!(matches!(error, ScanError::TooManyScans(2)));
+        assert_eq!(error.to_error_response().code, "TOO_MANY_SCANS");
+        assert_eq!(state.active_count(), 2);
+
+        // Finishing a session frees a slot
+        state.remove_session(&second);
+        assert!(state.create_session(SessionKind::Scan, "/tmp/c").is_ok());
+    }
+
+    #[test]
+    fn test_scan_state_refuses_duplicate_session_id() {
+        let state = ScanState::new();
+        let first = state.create_session_with_id("scan-1", SessionKind::Scan, "/tmp/a").unwrap();
+
+        let error = state
+            .create_session_with_id("scan-1", SessionKind::Scan, "/tmp/b")
+            .err()
+            .expect("reused ID should be refused");
+        assert!(matches!(error, ScanError::SessionExists(ref id) if id == "scan-1"));
+        assert_eq!(error.to_error_response().code, "SESSION_EXISTS");
+
+        // The running session keeps its token
+        assert!(state.cancel_session("scan-1"));
+        assert!(first.is_cancelled());
+    }
+
     #[test]
     fn test_scan_state_cancel_session() {
         let state = ScanState::new();
-        let (session_id, token) = state.create_session().expect("should create session");
+        let (session_id, token) = state
+            .create_session(SessionKind::Scan, "/tmp/photos")
+            .expect("should create session");
 
         assert!(!token.is_cancelled());
 
@@ -933,10 +1733,45 @@ mod tests {
         assert!(!cancelled);
     }
 
+    #[test]
+    fn test_scan_state_list_sessions_with_ages() {
+        let state = ScanState::new();
+        state.create_session_with_id("scan", SessionKind::Scan, "/tmp/photos").unwrap();
+        state.create_session_with_id("dupes", SessionKind::Duplicates, "/tmp/music").unwrap();
+        state.create_session_with_id("analysis", SessionKind::Analysis, "/tmp/docs").unwrap();
+
+        // Backdate the analysis session by 90 seconds
+        let started = Instant::now().checked_sub(Duration::from_secs(90)).unwrap();
+        state.sessions.lock().unwrap().get_mut("analysis").unwrap().created_at = started;
+
+        let sessions = state.list_sessions();
+
+        assert_eq!(sessions.len(), 3);
+        assert_eq!(sessions[0].session_id, "analysis");
+        assert_eq!(sessions[0].kind, SessionKind::Analysis);
+        assert_eq!(sessions[0].path, "/tmp/docs");
+        assert!((90_000..95_000).contains(&sessions[0].age_ms));
+
+        for (id, kind, path) in [
+            ("scan", SessionKind::Scan, "/tmp/photos"),
+            ("dupes", SessionKind::Duplicates, "/tmp/music"),
+        ] {
+            let session = sessions.iter().find(|s| s.session_id == id).unwrap();
+            assert_eq!(session.kind, kind);
+            assert_eq!(session.path, path);
+            assert!(session.age_ms < 5_000);
+        }
+
+        state.remove_session("analysis");
+        assert_eq!(state.list_sessions().len(), 2);
+    }
+
     #[test]
     fn test_scan_state_remove_session() {
         let state = ScanState::new();
-        let (session_id, _token) = state.create_session().expect("should create session");
+        let (session_id, _token) = state
+            .create_session(SessionKind::Scan, "/tmp/photos")
+            .expect("should create session");
         assert_eq!(state.active_count(), 1);
 
         state.remove_session(&session_id);
@@ -996,4 +1831,280 @@ mod tests {
         assert!(!result.cancelled);
         assert!(result.session_id.is_none()); // Basic scan_folder doesn't have session
     }
+
+    /// Count progress callbacks for a scan of 10 files with the given interval
+    fn count_progress_reports(progress_interval: Option<usize>) -> usize {
+        let dir = TempDir::new().unwrap();
+        for i in 0..10 {
+            fs::write(dir.path().join(format!("file{}.txt", i)), b"content").unwrap();
+        }
+
+        let calls = std::cell::Cell::new(0);
+        let callback = |_: usize, _: &str| calls.set(calls.get() + 1);
+        let options = ScanOptions {
+            progress_interval,
+            ..Default::default()
+        };
+
+        scan_folder_internal(&dir.path().to_string_lossy(), &options, None, Some(&callback)).unwrap();
+        calls.get()
+    }
+
+    #[tokio::test]
+    async fn test_scan_folder_extract_metadata() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("photo.jpg"),
+            crate::commands::exif::jpeg_with_capture_date("2024:06:01 10:00:00"),
+        )
+        .unwrap();
+        fs::write(dir.path().join("notes.txt"), "no metadata").unwrap();
+        let path = dir.path().to_string_lossy().to_string();
+
+        let without = scan_folder(path.clone(), None).await.unwrap();
+        assert!(without.files.iter().all(|f| f.metadata.is_none()));
+
+        let options = ScanOptions {
+            extract_metadata: true,
+            ..Default::default()
+        };
+        let with = scan_folder(path, Some(options)).await.unwrap();
+        let photo = with.files.iter().find(|f| f.full_name == "photo.jpg").unwrap();
+        let notes = with.files.iter().find(|f| f.full_name == "notes.txt").unwrap();
+        assert_eq!(photo.metadata.as_ref().unwrap()["captureDate"], "2024-06-01");
+        assert!(notes.metadata.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_scan_folder_stable_ids() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("a.txt"), "first").unwrap();
+        fs::write(dir.path().join("b.txt"), "second").unwrap();
+        let path = dir.path().to_string_lossy().to_string();
+        let options = ScanOptions {
+            compute_stable_ids: true,
+            ..Default::default()
+        };
+        let id_of = |files: &[FileInfo], name: &str| {
+            files.iter().find(|f| f.full_name == name).unwrap().stable_id.clone().unwrap()
+        };
+
+        let without = scan_folder(path.clone(), None).await.unwrap();
+        assert!(without.files.iter().all(|f| f.stable_id.is_none()));
+
+        let before = scan_folder(path.clone(), Some(options.clone())).await.unwrap();
+        assert_ne!(id_of(&before.files, "a.txt"), id_of(&before.files, "b.txt"));
+
+        // Identical content must not collapse to one id
+        fs::write(dir.path().join("copy.txt"), "first").unwrap();
+        let with_copy = scan_folder(path.clone(), Some(options.clone())).await.unwrap();
+        assert_ne!(id_of(&with_copy.files, "a.txt"), id_of(&with_copy.files, "copy.txt"));
+
+        fs::rename(dir.path().join("a.txt"), dir.path().join("renamed.txt")).unwrap();
+        let after = scan_folder(path, Some(options)).await.unwrap();
+        assert_eq!(id_of(&before.files, "a.txt"), id_of(&after.files, "renamed.txt"));
+    }
+
+    #[test]
+    fn test_scan_progress_interval() {
+        assert_eq!(count_progress_reports(None), 10); // Adaptive: every file for small scans
+        assert_eq!(count_progress_reports(Some(1)), 10);
+        assert_eq!(count_progress_reports(Some(3)), 3);
+        assert_eq!(count_progress_reports(Some(20)), 0);
+        assert_eq!(count_progress_reports(Some(0)), 0);
+    }
+
+    #[test]
+    fn test_should_report_progress_adaptive() {
+        let reports = |total: usize| (1..=total).filter(|&c| should_report_progress(c, None)).count();
+
+        assert_eq!(reports(100), 100);
+        assert_eq!(reports(1000), 100 + 90);
+        assert!(should_report_progress(1, None));
+        assert!(!should_report_progress(5, Some(0)));
+    }
+
+    fn create_estimate_tree(dir: &TempDir) {
+        for folder in ["a", "b"] {
+            fs::create_dir(dir.path().join(folder)).unwrap();
+            for i in 0..10 {
+                fs::write(dir.path().join(folder).join(format!("{}.txt", i)), "content").unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_estimate_scan_time_extrapolates_from_sample() {
+        let dir = TempDir::new().unwrap();
+        create_estimate_tree(&dir);
+        let options = ScanOptions { recursive: true, ..Default::default() };
+
+        let estimate = estimate_scan_time_internal(
+            &dir.path().to_string_lossy(),
+            &options,
+            &CancellationToken::new(),
+            5,
+            Duration::from_secs(10),
+        )
+        .unwrap();
+
+        assert_eq!(estimate.sampled_files, 5);
+        assert_eq!(estimate.file_count, 20);
+        assert!(!estimate.cancelled);
+        assert!(estimate.estimated_seconds >= 0.0);
+        assert!(estimate.estimated_seconds < 10.0);
+    }
+
+    #[test]
+    fn test_estimate_scan_time_small_folder_is_fully_sampled() {
+        let dir = TempDir::new().unwrap();
+        create_estimate_tree(&dir);
+        let options = ScanOptions { recursive: true, ..Default::default() };
+
+        let estimate = estimate_scan_time_internal(
+            &dir.path().to_string_lossy(),
+            &options,
+            &CancellationToken::new(),
+            ESTIMATE_SAMPLE_FILES,
+            Duration::from_secs(10),
+        )
+        .unwrap();
+
+        // The sample saw every file, so the estimate is the sample's own duration
+        assert_eq!(estimate.sampled_files, 20);
+        assert_eq!(estimate.file_count, 20);
+        assert!(estimate.estimated_seconds * 1000.0 >= estimate.sample_duration_ms as f64);
+        assert!(estimate.estimated_seconds < 10.0);
+    }
+
+    #[test]
+    fn test_count_scan_entries_applies_exclusions() {
+        let dir = TempDir::new().unwrap();
+        create_estimate_tree(&dir);
+        fs::create_dir(dir.path().join("node_modules")).unwrap();
+        fs::write(dir.path().join("node_modules/dep.js"), "module").unwrap();
+        fs::write(dir.path().join(".hidden"), "secret").unwrap();
+        let options = ScanOptions {
+            recursive: true,
+            skip_hidden: true,
+            exclude_patterns: Some(vec!["node_modules".to_string(), "b/*.txt".to_string()]),
+            ..Default::default()
+        };
+
+        let root = dir.path().canonicalize().unwrap();
+
+        let (count, cancelled) = count_scan_entries(&root, &options, &CancellationToken::new());
+
+        assert_eq!(count, 10);
+        assert!(!cancelled);
+    }
+
+    #[test]
+    fn test_estimate_scan_time_cancelled() {
+        let dir = TempDir::new().unwrap();
+        create_estimate_tree(&dir);
+        let options = ScanOptions { recursive: true, ..Default::default() };
+        let token = CancellationToken::new();
+        token.cancel();
+
+        let path = dir.path().to_string_lossy().to_string();
+
+        let estimate =
+            estimate_scan_time_internal(&path, &options, &token, 5, ESTIMATE_SAMPLE_DURATION).unwrap();
+
+        assert!(estimate.cancelled);
+        assert!(estimate.sampled_files <= 1);
+    }
+
+    #[tokio::test]
+    async fn test_scan_include_directories() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join("photos/2024")).unwrap();
+        fs::create_dir(dir.path().join("empty")).unwrap();
+        fs::create_dir(dir.path().join(".cache")).unwrap();
+        File::create(dir.path().join("photos/2024/beach.jpg")).unwrap();
+        let path = dir.path().to_string_lossy().to_string();
+
+        let recursive = ScanOptions { recursive: true, ..Default::default() };
+        let default = scan_folder(path.clone(), Some(recursive)).await.unwrap();
+        assert!(default.directories.is_empty());
+
+        let options = ScanOptions {
+            recursive: true,
+            include_directories: true,
+            skip_hidden: true,
+            // Extension filters apply to files only
+            extensions: Some(vec!["pdf".to_string()]),
+            ..Default::default()
+        };
+        let result = scan_folder(path, Some(options)).await.unwrap();
+
+        let mut relative: Vec<(&str, usize)> = result
+            .directories
+            .iter()
+            .map(|d| (d.relative_path.as_str(), d.depth))
+            .collect();
+        relative.sort();
+        assert_eq!(relative, vec![("empty", 1), ("photos", 1), ("photos/2024", 2)]);
+        assert_eq!(result.total_count, 0);
+        assert_eq!(result.total_size, 0);
+    }
+
+    #[tokio::test]
+    async fn test_scan_dates_directories_from_contained_files() {
+        use std::time::{Duration, SystemTime};
+
+        let dir = TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join("trip/day1")).unwrap();
+        fs::create_dir(dir.path().join("empty")).unwrap();
+        let old = SystemTime::UNIX_EPOCH + Duration::from_secs(1_600_000_000);
+        let older = SystemTime::UNIX_EPOCH + Duration::from_secs(1_500_000_000);
+        File::create(dir.path().join("trip/notes.txt"))
+            .unwrap()
+            .set_modified(old)
+            .unwrap();
+        File::create(dir.path().join("trip/day1/photo.jpg"))
+            .unwrap()
+            .set_modified(older)
+            .unwrap();
+
+        let options = ScanOptions {
+            recursive: true,
+            include_directories: true,
+            ..Default::default()
+        };
+        let path = dir.path().to_string_lossy().to_string();
+        let result = scan_folder(path, Some(options)).await.unwrap();
+
+        let date_of = |relative: &str| {
+            result
+                .directories
+                .iter()
+                .find(|d| d.relative_path == relative)
+                .map(|d| d.modified_at)
+                .unwrap()
+        };
+        let older: DateTime<Utc> = older.into();
+        assert_eq!(date_of("trip"), older);
+        assert_eq!(date_of("trip/day1"), older);
+        // No files inside: the folder's own (recent) mtime is kept
+        assert!(date_of("empty") > older);
+    }
+
+    #[test]
+    fn test_skip_reason_as_str_matches_serde() {
+        for reason in [
+            SkipReason::MetadataError,
+            SkipReason::FilteredByExtension,
+            SkipReason::Hidden,
+            SkipReason::Gitignore,
+            SkipReason::Tidyignore,
+            SkipReason::Glob,
+            SkipReason::PermissionDenied,
+            SkipReason::Other,
+        ] {
+            let serialized = serde_json::to_string(&reason).unwrap();
+            assert_eq!(serialized, format!("\"{}\"", reason.as_str()));
+        }
+    }
 }
@@ -183,6 +183,24 @@ pub fn validate_rename_path(
     original_path: &str,
     proposed_path: &str,
     allowed_base: Option<&Path>,
+) -> Result<PathBuf, SecurityError> {
+    // SEC-P0-001: Check if original file is a symlink
+    let original = Path::new(original_path);
+    if original.is_symlink() {
+        return Err(SecurityError::SymlinkNotAllowed(
+            format!("Original file is a symlink: {}", original.display())
+        ));
+    }
+
+    validate_rename_destination(original_path, proposed_path, allowed_base)
+}
+
+/// Validates a rename/move like [`validate_rename_path`], except that the
+/// original may be a symlink (renaming a link never follows it)
+pub fn validate_rename_destination(
+    original_path: &str,
+    proposed_path: &str,
+    allowed_base: Option<&Path>,
 ) -> Result<PathBuf, SecurityError> {
     // Check for obvious traversal in proposed path
     if proposed_path.contains("..") {
@@ -197,13 +215,6 @@ pub fn validate_rename_path(
     let original = Path::new(original_path);
     let proposed = Path::new(proposed_path);
 
-    // SEC-P0-001: Check if original file is a symlink
-    if original.is_symlink() {
-        return Err(SecurityError::SymlinkNotAllowed(
-            format!("Original file is a symlink: {}", original.display())
-        ));
-    }
-
     // Determine the base directory
     let base_dir = if let Some(base) = allowed_base {
         base.to_path_buf()
@@ -235,6 +246,80 @@ pub fn validate_rename_path(
     validate_path_within_base(proposed, &base_dir)
 }
 
+/// Check whether a path is a Windows path (drive letter or UNC share)
+fn is_windows_style_path(path: &str) -> bool {
+    let bytes = path.as_bytes();
+    path.starts_with("\\\\")
+        || (bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':')
+}
+
+/// Check whether a path uses both `/` and `\` as separators
+pub fn has_mixed_separators(path: &str) -> bool {
+    path.contains('/') && path.contains('\\')
+}
+
+/// Prefix of Windows verbatim paths, as returned by `fs::canonicalize`
+const VERBATIM_PREFIX: &str = "\\\\?\\";
+
+/// Use `/` as the only separator of a Windows path
+///
+/// Frontends on Windows can pass `C:\Photos/2024\a.jpg`; with consistent
+/// separators, paths built from it compare equal to each other. On other
+/// platforms `\` is a valid filename character, so only paths that look like
+/// Windows paths are changed there. Verbatim paths (`\\?\C:\...`) are left
+/// as is, since Windows doesn't accept `/` in them.
+pub fn normalize_path_separators(path: &str) -> String {
+    if path.starts_with(VERBATIM_PREFIX) {
+        path.to_string()
+    } else if cfg!(windows) || is_windows_style_path(path) {
+        path.replace('\\', "/")
+    } else {
+        path.to_string()
+    }
+}
+
+/// Path of `name` inside `dir`, or `name` alone when there is no directory
+///
+/// Proposed paths are always joined with `/`, whatever the platform.
+pub fn join_proposed_path(dir: Option<&Path>, name: &str) -> String {
+    match dir {
+        Some(dir) if !dir.as_os_str().is_empty() => format!("{}/{}", dir.to_string_lossy(), name),
+        _ => name.to_string(),
+    }
+}
+
+/// Normalize a path for prefix comparison ('/' separators, lowercase, no trailing '/')
+///
+/// A verbatim prefix is dropped first (`\\?\UNC\` becomes `\\`), so
+/// canonicalized paths match protected paths written the usual way.
+fn normalize_for_prefix(path: &str) -> String {
+    let path = match path.strip_prefix(VERBATIM_PREFIX) {
+        Some(rest) => match rest.strip_prefix("UNC\\") {
+            Some(share) => format!("\\\\{}", share),
+            None => rest.to_string(),
+        },
+        None => path.to_string(),
+    };
+    normalize_path_separators(&path).trim_end_matches('/').to_lowercase()
+}
+
+/// Find the protected prefix a path falls under, if any
+///
+/// Matching is by whole path components and ignores case, so "/archive"
+/// protects "/Archive/2020/a.jpg" but not "/archived/a.jpg".
+pub fn find_protected_prefix<'a>(path: &str, protected: &'a [String]) -> Option<&'a str> {
+    let path = normalize_for_prefix(path);
+    protected
+        .iter()
+        .find(|prefix| {
+            let prefix = normalize_for_prefix(prefix);
+            !prefix.is_empty()
+                && (path == prefix
+                    || path.strip_prefix(&prefix).is_some_and(|rest| rest.starts_with('/')))
+        })
+        .map(String::as_str)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -398,4 +483,45 @@ mod tests {
         // For same-directory rename, it shouldn't create subdirectories
         assert!(result.is_ok() || matches!(result, Err(SecurityError::InvalidPath(_))));
     }
+
+    #[test]
+    fn test_find_protected_prefix() {
+        let protected = vec!["/Archive/".to_string(), "C:\\Windows".to_string()];
+
+        assert_eq!(find_protected_prefix("/archive/2020/a.jpg", &protected), Some("/Archive/"));
+        assert_eq!(find_protected_prefix("c:/windows/notes.txt", &protected), Some("C:\\Windows"));
+        assert_eq!(find_protected_prefix("/archived/a.jpg", &protected), None);
+        assert_eq!(find_protected_prefix("/photos/a.jpg", &[]), None);
+
+        // Canonicalized Windows paths carry a verbatim prefix
+        let verbatim = "\\\\?\\C:\\Windows\\notes.txt";
+        assert_eq!(find_protected_prefix(verbatim, &protected), Some("C:\\Windows"));
+        let share = vec!["\\\\nas\\archive".to_string()];
+        let verbatim_unc = "\\\\?\\UNC\\nas\\archive\\a.jpg";
+        assert_eq!(find_protected_prefix(verbatim_unc, &share), Some("\\\\nas\\archive"));
+    }
+
+    #[test]
+    fn test_normalize_path_separators() {
+        assert!(has_mixed_separators("C:\\Photos/2024\\a.jpg"));
+        assert!(!has_mixed_separators("C:\\Photos\\a.jpg"));
+        assert_eq!(normalize_path_separators("C:\\Photos/2024\\a.jpg"), "C:/Photos/2024/a.jpg");
+        assert_eq!(normalize_path_separators("\\\\nas\\share/a.jpg"), "//nas/share/a.jpg");
+        assert_eq!(normalize_path_separators("/home/me/a.jpg"), "/home/me/a.jpg");
+    }
+
+    #[test]
+    fn test_normalize_path_separators_keeps_verbatim_paths() {
+        let verbatim = "\\\\?\\C:\\Photos\\a.jpg";
+        assert_eq!(normalize_path_separators(verbatim), verbatim);
+        let verbatim_unc = "\\\\?\\UNC\\nas\\share\\a.jpg";
+        assert_eq!(normalize_path_separators(verbatim_unc), verbatim_unc);
+    }
+
+    #[test]
+    fn test_join_proposed_path() {
+        assert_eq!(join_proposed_path(Some(Path::new("/photos")), "a.jpg"), "/photos/a.jpg");
+        assert_eq!(join_proposed_path(Some(Path::new("")), "a.jpg"), "a.jpg");
+        assert_eq!(join_proposed_path(None, "a.jpg"), "a.jpg");
+    }
 }
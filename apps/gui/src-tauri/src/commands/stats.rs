@@ -0,0 +1,201 @@
+// Directory statistics commands for tidy-app GUI
+// Command names use snake_case per architecture requirements
+//
+// Aggregates scan results into an at-a-glance breakdown for the dashboard
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use ts_rs::TS;
+
+use super::llm::is_low_quality_filename;
+use super::scanner::{scan_folder, FileCategory, FileInfo, ScanError, ScanOptions};
+
+// =============================================================================
+// Types
+// =============================================================================
+
+/// File count and size for one category
+#[derive(Debug, Clone, Serialize, PartialEq, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct CategoryStats {
+    pub category: FileCategory,
+    /// Number of files in this category
+    pub count: usize,
+    /// Total size of files in this category in bytes
+    pub total_size: u64,
+}
+
+/// A file referenced by its modification date
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct DatedFile {
+    pub path: String,
+    pub modified_at: DateTime<Utc>,
+}
+
+/// Aggregate statistics for a scanned directory
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "bindings/")]
+#[serde(rename_all = "camelCase")]
+pub struct DirectoryStats {
+    /// Number of files included in the statistics
+    pub total_files: usize,
+    /// Total size of all files in bytes
+    pub total_size: u64,
+    /// Per-category breakdown, most files first
+    pub categories: Vec<CategoryStats>,
+    /// File with the oldest modification date
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub oldest_file: Option<DatedFile>,
+    /// File with the newest modification date
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub newest_file: Option<DatedFile>,
+    /// Number of files with low-quality names (e.g. "IMG_1234", "Untitled")
+    pub bad_name_count: usize,
+    /// Number of files skipped by the scan
+    pub skipped_count: usize,
+}
+
+// =============================================================================
+// Aggregation
+// =============================================================================
+
+/// Aggregate statistics over scanned files in a single pass
+fn compute_directory_stats(files: &[FileInfo], skipped_count: usize) -> DirectoryStats {
+    let mut categories: HashMap<FileCategory, CategoryStats> = HashMap::new();
+    let mut total_size: u64 = 0;
+    let mut oldest: Option<&FileInfo> = None;
+    let mut newest: Option<&FileInfo> = None;
+    let mut bad_name_count = 0;
+
+    for file in files {
+        total_size += file.size;
+
+        let entry = categories
+            .entry(file.category.clone())
+            .or_insert_with(|| CategoryStats {
+                category: file.category.clone(),
+                count: 0,
+                total_size: 0,
+            });
+        entry.count += 1;
+        entry.total_size += file.size;
+
+        if oldest.is_none_or(|o| file.modified_at < o.modified_at) {
+            oldest = Some(file);
+        }
+        if newest.is_none_or(|n| file.modified_at > n.modified_at) {
+            newest = Some(file);
+        }
+
+        if is_low_quality_filename(&file.full_name) {
+            bad_name_count += 1;
+        }
+    }
+
+    let mut categories: Vec<CategoryStats> = categories.into_values().collect();
+    categories.sort_by(|a, b| {
+        b.count
+            .cmp(&a.count)
+            .then_with(|| b.total_size.cmp(&a.total_size))
+            .then_with(|| format!("{:?}", a.category).cmp(&format!("{:?}", b.category)))
+    });
+
+    let dated = |file: &FileInfo| DatedFile {
+        path: file.path.clone(),
+        modified_at: file.modified_at,
+    };
+
+    DirectoryStats {
+        total_files: files.len(),
+        total_size,
+        categories,
+        oldest_file: oldest.map(dated),
+        newest_file: newest.map(dated),
+        bad_name_count,
+        skipped_count,
+    }
+}
+
+// =============================================================================
+// Tauri Commands
+// =============================================================================
+
+/// Compute directory statistics for the dashboard
+///
+/// Scans the folder with the given options and aggregates file counts and
+/// sizes per category, the oldest/newest files and the number of bad names.
+///
+/// Command name: analyze_directory (snake_case per architecture)
+#[tauri::command]
+pub async fn analyze_directory(
+    path: String,
+    options: Option<ScanOptions>,
+) -> Result<DirectoryStats, ScanError> {
+    let scan = scan_folder(path, options).await?;
+    Ok(compute_directory_stats(&scan.files, scan.skipped_count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::time::{Duration, SystemTime};
+    use tempfile::TempDir;
+
+    fn write_file(dir: &TempDir, name: &str, size: usize, age_days: u64) {
+        let path = dir.path().join(name);
+        fs::write(&path, vec![b'x'; size]).unwrap();
+        let modified = SystemTime::now() - Duration::from_secs(age_days * 24 * 60 * 60);
+        fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_analyze_directory_mixed_files() {
+        let dir = TempDir::new().unwrap();
+        write_file(&dir, "IMG_1234.jpg", 300, 10);
+        write_file(&dir, "Screenshot 2024-01-01.png", 200, 5);
+        write_file(&dir, "vacation-beach-sunset.png", 100, 400);
+        write_file(&dir, "quarterly-report-2024.pdf", 50, 30);
+        write_file(&dir, "untitled.txt", 10, 1);
+        write_file(&dir, "main.rs", 20, 2);
+
+        let stats = analyze_directory(dir.path().to_string_lossy().to_string(), None)
+            .await
+            .unwrap();
+
+        assert_eq!(stats.total_files, 6);
+        assert_eq!(stats.total_size, 680);
+        assert_eq!(
+            stats.categories,
+            vec![
+                CategoryStats { category: FileCategory::Image, count: 3, total_size: 600 },
+                CategoryStats { category: FileCategory::Document, count: 2, total_size: 60 },
+                CategoryStats { category: FileCategory::Code, count: 1, total_size: 20 },
+            ]
+        );
+        // IMG_1234, Screenshot and untitled
+        assert_eq!(stats.bad_name_count, 3);
+        assert!(stats.oldest_file.unwrap().path.ends_with("vacation-beach-sunset.png"));
+        assert!(stats.newest_file.unwrap().path.ends_with("untitled.txt"));
+    }
+
+    #[test]
+    fn test_compute_directory_stats_empty() {
+        let stats = compute_directory_stats(&[], 0);
+
+        assert_eq!(stats.total_files, 0);
+        assert!(stats.categories.is_empty());
+        assert!(stats.oldest_file.is_none());
+        assert!(stats.newest_file.is_none());
+        assert_eq!(stats.bad_name_count, 0);
+    }
+}
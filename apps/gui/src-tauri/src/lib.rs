@@ -4,11 +4,25 @@
 mod commands;
 
 use commands::{
-    analyze_files_with_llm, can_undo_operation, cancel_scan, check_ollama_health, check_openai_health,
-    clear_analysis_cache, clear_history, delete_secret, execute_rename, export_results, generate_preview,
-    get_active_scans, get_cache_stats, get_config, get_history_count, get_history_entry, get_version,
-    load_history, list_ollama_models, list_openai_models, record_operation, reset_config, retrieve_secret,
-    save_config, scan_folder, scan_folder_with_progress, store_secret, undo_operation, ScanState,
+    analysis_cache_preview, analyze_directory, analyze_files_with_llm, analyze_naming_conventions,
+    annotate_with_history, assess_organize_risk, can_undo_operation, cancel_rename, cancel_scan,
+    check_destination_space, check_ollama_health, check_openai_health, clean_folder,
+    clear_analysis_cache, clear_folder_structure_cache, clear_history, delete_secret,
+    detect_rename_chains, diff_scans, estimate_scan_time, execute_ready, execute_rename,
+    explain_preview, export_rename_map, export_rename_script, export_results, extension_histogram,
+    filter_results_by_confidence, find_destination_case_collisions, find_duplicates,
+    find_unused_templates, fix_extensions, flush_config, flush_pending_config,
+    folder_cleanliness_score, generate_preview, generate_preview_with_template, get_active_scans,
+    get_cache_stats, get_config, get_history_count, get_history_entry, get_version, history_stats,
+    list_active_sessions, list_case_styles, list_ollama_models, list_openai_models, load_history,
+    merge_folders, merge_suggestions, minimize_renames, name_reuse_report,
+    normalize_date_formats_preview, normalize_input_paths, organize_from_suggestions,
+    preview_case_change, preview_cleanliness_score, preview_folder_normalization, pull_ollama_model,
+    recategorize_by_content, reconsolidate_folder_suggestions, record_operation, remap_cache_paths,
+    rename_one, request_overwrite_confirmation, reset_config, retrieve_secret, sample_analysis,
+    save_config, scan_folder, scan_folder_with_progress, score_proposed_names, store_secret,
+    suggest_folders_heuristic, undo_operation, undo_recent, undo_since, validate_date_format,
+    validate_template, RenameState, ScanState, DEFAULT_MAX_ACTIVE_SESSIONS,
 };
 use tauri::Manager;
 
@@ -39,42 +53,109 @@ pub fn run() {
             // Linux: vibrancy depends on compositor, we skip it
             // The app will use CSS backdrop-blur as fallback
 
+            // State for managing scan sessions with progress and cancellation,
+            // capped by the configured number of active sessions
+            let max_sessions = tauri::async_runtime::block_on(get_config())
+                .map(|config| config.preferences.max_active_sessions)
+                .unwrap_or_else(|e| {
+                    eprintln!("Warning: Failed to load config, using default session cap: {}", e);
+                    DEFAULT_MAX_ACTIVE_SESSIONS
+                });
+            app.manage(ScanState::with_max_sessions(max_sessions));
+
             Ok(())
         })
-        // State for managing scan sessions with progress and cancellation
-        .manage(ScanState::new())
+        // State for managing rename sessions with cancellation
+        .manage(RenameState::new())
         .invoke_handler(tauri::generate_handler![
             get_version,
             scan_folder,
             scan_folder_with_progress,
             cancel_scan,
             get_active_scans,
+            list_active_sessions,
+            diff_scans,
+            estimate_scan_time,
+            analyze_directory,
+            analyze_naming_conventions,
+            folder_cleanliness_score,
+            preview_cleanliness_score,
+            suggest_folders_heuristic,
+            find_duplicates,
+            fix_extensions,
+            clean_folder,
+            recategorize_by_content,
+            extension_histogram,
             get_config,
             save_config,
+            flush_config,
             reset_config,
             generate_preview,
+            generate_preview_with_template,
+            explain_preview,
+            list_case_styles,
+            preview_case_change,
+            validate_date_format,
+            validate_template,
+            normalize_date_formats_preview,
+            detect_rename_chains,
+            find_destination_case_collisions,
+            name_reuse_report,
+            normalize_input_paths,
+            minimize_renames,
+            merge_folders,
+            assess_organize_risk,
+            check_destination_space,
+            score_proposed_names,
+            request_overwrite_confirmation,
             execute_rename,
+            execute_ready,
+            rename_one,
+            cancel_rename,
             export_results,
+            export_rename_script,
+            export_rename_map,
             check_ollama_health,
             list_ollama_models,
+            pull_ollama_model,
             check_openai_health,
             list_openai_models,
             analyze_files_with_llm,
+            sample_analysis,
             clear_analysis_cache,
+            clear_folder_structure_cache,
             get_cache_stats,
+            analysis_cache_preview,
+            remap_cache_paths,
+            preview_folder_normalization,
+            reconsolidate_folder_suggestions,
+            organize_from_suggestions,
+            merge_suggestions,
+            filter_results_by_confidence,
             // History commands (Story 9.1)
             load_history,
             record_operation,
+            annotate_with_history,
             get_history_entry,
             get_history_count,
+            history_stats,
             undo_operation,
+            undo_recent,
+            undo_since,
             can_undo_operation,
             clear_history,
+            find_unused_templates,
             // Secure secrets storage (SEC-004)
             store_secret,
             retrieve_secret,
             delete_secret
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|_app, event| {
+            // Debounced config saves may still be pending when the app closes
+            if let tauri::RunEvent::Exit = event {
+                flush_pending_config();
+            }
+        });
 }